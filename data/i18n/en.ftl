@@ -0,0 +1,689 @@
+# Text of the UI in English, which other translations fall back to.
+
+## Menus
+
+menu-edit = Edit
+menu-file = File
+menu-plugins = Plugins
+menu-tools = Tools
+
+## File menu
+
+file-apply-patch = Apply Patch
+file-close = Close
+file-close-project = Close Project
+file-close-reference = Close Reference
+file-compress = Yaz0 compress on save
+file-copy-yaml = Copy as YAML
+file-copy-yaml-hover = Copy the whole program to the clipboard as YAML to share
+file-deploy = Deploy
+file-deploy-settings = Deploy Settings
+file-exit = Exit
+file-export-csv = Export CSV
+file-export-csv-hover = Every SInst parameter, one per row, for editing in a spreadsheet
+file-export-description = Export Description
+file-export-description-hover = Describe the tree and every entry as Markdown or HTML, for mod documentation or review
+file-export-graph = Export Graph
+file-export-json = Export JSON
+file-export-json-schema = Export JSON with Schema
+file-export-json-schema-hover = Export JSON naming { $file } as its schema and write the schema beside it, for checking AI programs with other tools
+file-export-patch = Export Patch
+file-import-csv = Import CSV
+file-import-csv-hover = Apply parameter values edited in an exported CSV
+file-merge = Merge Changes
+file-merge-hover = Bring in the changes another version of this file made to the file both started from, matching entries by name
+file-open = Open
+file-open-as-list = Open AS List
+file-open-as-list-hover = Edit an AS list or other AAMP file alongside
+file-open-as-list-pack = Open AS List from Pack
+file-open-as-list-pack-hover = Edit the AS list in the same actor pack as this program
+file-open-clipboard = Open from Clipboard
+file-open-clipboard-hover = Open an AI program copied as YAML, saving it to a file first
+file-open-dump = Open from Game Dump
+file-open-project = Open Project Folder
+file-open-project-hover = List every AI program and actor pack in a mod folder
+file-open-reference = Open Reference
+file-reopen-last = Reopen last file on startup
+file-restore = Restore Previous Version
+file-restore-hover = Go back to a version of this file from before a save
+file-revert-all = Revert All
+file-revert-all-confirm = Are you sure you want to discard every change since the file was loaded?
+file-revert-all-hover = Go back to the file as it was loaded or last saved
+file-save = Save
+file-save-as = Save As
+file-settings = Settings
+
+## Edit menu
+
+edit-back = Back
+edit-forward = Forward
+edit-go-to-parent = Go to Parent
+edit-normalize = Normalize Order
+edit-normalize-hover = Put the AIs and Actions in the order the tree shows them, rewriting every index
+edit-normalize-vanilla = Normalize Order to Vanilla
+edit-normalize-vanilla-hover = Put entries in the order of the vanilla program they came from, with new ones last, so diffs against it stay small
+edit-redo = Redo
+edit-remap = Remap Behaviors
+edit-remap-hover = Rebind the behavior indexes of the selected entries, or of the selected entry and everything below it
+edit-revert-entry = Revert Entry
+edit-revert-entry-hover = Put the selected entry back the way it was loaded
+edit-set-parameter = Set Parameter on Selected
+edit-set-parameter-hover = Ctrl+click entries in the tree to select several
+edit-undo = Undo
+
+## Tools menu
+
+tools-conflicts = Inspect Mod Conflicts
+tools-conflicts-hover = See which entries of an actor's AI program several mods change
+tools-dry-run = Dry Run
+tools-dry-run-hover = Follow the tree down from a root to see what would run
+tools-find-replace = Find and Replace
+tools-find-unused = Find Unused
+tools-fix-group-names = Fix Group Names
+tools-fix-group-names-hover = Set each entry's Name and GroupName from its parent
+tools-hash-names = Hash Names
+tools-hash-names-hover = Name parameter keys which show up as numbers
+tools-port = Port Behavior
+tools-port-hover = Copy an AI and everything it uses from another program
+tools-script-console = Script Console
+tools-search = Search
+tools-show-log = Show Log
+tools-show-log-hover = See what's been opened, saved, and gone wrong, for bug reports
+tools-translations = Translations
+tools-translations-hover = Add or correct English names for Japanese ones
+tools-validate = Validate
+
+## Settings
+
+settings-angles = Angles
+settings-angles-degrees = Degrees
+settings-angles-hover = Unit to edit parameters which look like angles in. They're still saved in the unit the game expects.
+settings-angles-radians = Radians
+settings-backups = Backups
+settings-backups-hover = How many earlier versions of a file to keep in .plasticity_backups beside it when saving over it
+settings-both = Both
+settings-colors = Colors
+settings-colors-colorblind = Colorblind safe
+settings-colors-custom = Custom
+settings-colors-high-contrast = High contrast
+settings-colors-hover = Colors of the markers for passed checks, warnings, errors, and changes. The markers keep their shapes whatever the colors.
+settings-colors-standard = Standard
+settings-entry-numbers = Entry numbers
+settings-entry-numbers-absolute = Absolute
+settings-entry-numbers-hover = Number entries from zero within each category, as YAML dumps do, or across the whole program, as ChildIdx values do
+settings-entry-numbers-per-category = Per category
+settings-flags = GameData flags
+settings-flags-from-dump = From Dump
+settings-flags-from-dump-hover = Use the flags in the game dump's Bootup.pack
+settings-flags-hover = gamedata.ssarc, Bootup.pack, or a text file with a flag on each line, to check parameters naming flags against and suggest flags from
+settings-font-size = Font size
+settings-game-dump = Game dump
+settings-game-dump-hover = Unpacked game files, for opening vanilla programs
+settings-language = Language
+settings-names = Names
+settings-names-english = English
+settings-names-japanese = Japanese
+settings-new-files = New files
+settings-new-files-binary = Binary
+settings-new-files-hover = Format to save programs which have no file yet in
+settings-not-set = Not set
+settings-platform = Platform
+settings-platform-hover = Version of the game mods are made for. Wii U files are big endian and Switch files little endian. Decides how a new mod folder to deploy to is laid out.
+settings-reset = Reset to Defaults
+settings-saved-in = Saved in { $file }
+settings-theme = Theme
+settings-theme-dark = Dark
+settings-theme-light = Light
+settings-ui-scale = UI scale
+
+## Windows
+
+window-add-entry = Add New Entry
+window-ai-def = AI Def: { $class }
+window-busy = Plz Wait
+window-changes-on-disk = Changes on Disk: { $file }
+window-choose-slot = Choose Slot
+window-compare = Compare Entries
+window-confirm = Confirm
+window-conflicts = Inspect Mod Conflicts
+window-convert = Convert to { $category }
+window-delete = Delete Entry
+window-deploy = Deploy
+window-dry-run = Dry Run
+window-error = Error
+window-find-replace = Find and Replace
+window-hash-names = Hash Names
+window-log = Log
+window-merge = Merge Changes
+window-open-dump = Open from Game Dump
+window-port = Port Behavior
+window-problems = Problems Found
+window-project-validation = Project Validation
+window-recover = Recover Unsaved Changes
+window-remap = Remap Behaviors
+window-restore = Restore Previous Version
+window-script-console = Script Console
+window-search = Search
+window-set-parameter = Set Parameter on Selected
+window-settings = Settings
+window-translations = Translations
+window-unsaved = Unsaved Changes
+window-unused = Unused Entries
+window-usages = Usages: { $entry }
+window-validation = Validation
+window-vanilla-examples = Vanilla Examples
+
+## Sections of the entry editor and other windows
+
+section-behaviour-indexes = Behaviour Indexes
+section-children = Children
+section-definition = Definition
+section-minst = Map Unit Instance Parameters
+section-notes = Notes
+section-pinned = Pinned
+section-queries = Queries
+section-sinst = Static Instance Parameters
+section-typical-values = Typical Values
+section-used-by = Used By ({ $count })
+section-uses = Uses
+
+## Find and Replace
+
+replace-apply = Replace { $count }
+replace-done = Replaced { $count } values
+replace-find = Find
+replace-match-case = Match case
+replace-regex = Regular expression
+replace-regex-hover = Use $1, $2 and so on in the replacement for captured groups
+replace-with = Replace with
+replace-would-change = { $count } parameters would change
+
+## Project panel
+
+project-empty = No AI programs or actor packs found
+project-open = (open)
+project-refresh = Refresh
+project-save-all = Save All
+project-save-all-hover = Save every open file in the project with unsaved changes
+project-title = Project: { $name }
+project-validate-hover = Check every file in the project
+
+## Merge Changes
+
+merge-all-ours = All Ours
+merge-all-theirs = All Theirs
+merge-base = Original both were edited from:
+merge-clean = Both versions merged cleanly.
+merge-conflict-demo = Demo { $demo }: ours { $ours }, theirs { $theirs }
+merge-conflict-entry = { $category } { $label }: ours { $ours }, theirs { $theirs }
+merge-conflicts = { $count } things were changed differently in each version. Pick which to keep.
+merge-edit-added = added it
+merge-edit-changed = changed it
+merge-edit-cleared = cleared it
+merge-edit-pointed = pointed it at { $label }
+merge-edit-removed = removed it
+merge-intro = Entries are matched by class, name, and group, so either version may have added, removed, or moved entries.
+merge-merge = Merge
+merge-ours = Ours
+merge-theirs = Other version:
+merge-theirs-side = Theirs
+
+## Inspect Mod Conflicts
+
+conflicts-add-mod = Add Mod
+conflicts-added = Added
+conflicts-changed = Changed
+conflicts-clash = Several mods change the same parameters differently
+conflicts-compare-hover = Needs vanilla and at least two mods
+conflicts-compatible = Changed by several mods, in ways which merge cleanly
+conflicts-mod = Mod { $number }
+conflicts-mod-file = Mod { $number }:
+conflicts-removed = Removed
+conflicts-shared-only = Only entries several mods change
+conflicts-summary = { $changed } entries changed, { $shared } by more than one mod, { $clashes } of those in ways which can't be merged.
+conflicts-vanilla = Vanilla:
+
+## Dry Run
+
+dry-run-cycle = Loops back
+dry-run-first = First child
+dry-run-guess = Only a rough guess: most selectors decide by game state.
+dry-run-no-child = The slot taken has no child set
+dry-run-picked = Picked
+dry-run-query = { $query } is { $result }
+dry-run-query-results = Query Results
+dry-run-reset = Reset Choices
+dry-run-runs = Runs
+dry-run-start = Start from
+
+## Port Behavior
+
+port-attach = Attach to
+port-cannot-copy = This entry can't be copied.
+port-counts = { $ais } AIs, { $actions } Actions, { $behaviors } Behaviors, { $queries } Queries
+port-from = From { $file }
+port-leave-root = [Leave as a root]
+port-port = Port
+port-reuse = The program already has behaviors of these classes:
+port-root = AI to copy
+port-shared-queries = Queries already in the program will be shared: { $queries }
+port-use = Use { $entry }
+
+## Tree
+
+tree-cycle = { $entry } (cycle)
+tree-export-subtree = Export Subtree…
+tree-import-subtree = Import Subtree Here…
+tree-mark-root = Mark as Root
+tree-revert = Revert to Loaded
+tree-unmark-root = Unmark as Root
+
+## Table
+
+table-sort-hover = Sort by this column
+
+## Outline plugin
+
+outline-no-children = Only AIs and Actions have anything below them.
+
+## Deploy
+
+deploy-command = Then run:
+deploy-command-hint = Command to restart the emulator, if any
+deploy-last = Last deployed to:
+deploy-layout = Layout:
+deploy-layout-bcml = BCML mod
+deploy-layout-graphic-pack = Cemu graphic pack
+deploy-layout-layeredfs = Atmosphère layeredfs
+deploy-mod-folder = Mod folder:
+deploy-now = Deploy Now
+deploy-on-save = Deploy whenever a file is saved
+deploy-repack = Put loose AI programs into the mod's actor packs which use them
+
+## Crashes
+
+crash-nothing-unsaved = There were no unsaved changes.
+crash-save-failed = Unsaved changes couldn't be written out.
+crash-save-failed-because = Unsaved changes couldn't be written out: { $error }
+crash-saved = Unsaved changes were written to { $folder }, and will be offered for recovery the next time Plasticity starts.
+crash-title = Plasticity crashed
+
+## Changes on disk
+
+disk-changed = { $file } has been changed on disk.
+disk-diff = Diff
+disk-diff-hover = List the entries which differ from the file on disk
+disk-differs = Differs from the file on disk
+disk-keep-mine = Keep Mine
+disk-keep-mine-hover = Keep the program as it is, to overwrite the file later
+disk-not-on-disk = Not in the file on disk
+disk-only-on-disk = Only in the file on disk
+disk-reload = Reload
+disk-reload-hover = Replace the program with the file on disk
+disk-same = The program is the same as the file on disk.
+
+## Plugins
+
+plugin-export = Export { $format }
+plugin-import = Import { $format }
+
+## Side panel
+
+pin-missing = No entry with this class and name any more
+reference-title = Reference: { $file }
+side-compare-hover = Show the parameters of two entries side by side
+side-delete-hover = Delete the selected entries together
+side-selected = { $count } selected
+side-set-parameter = Set Parameter
+side-table = Table
+side-table-hover = List the entries of the current tab with their details
+side-tree = Tree
+side-unreferenced = Unreferenced ({ $count })
+side-unreferenced-hover = Root AIs which no demo or other entry refers to, so the game never runs them
+
+## Tabs
+
+tab-actions = Actions
+tab-ais = AIs
+tab-behaviours = Behaviours
+tab-demos = Demos
+tab-queries = Queries
+
+## Entry editor
+
+editor-add-new = Add New
+editor-add-param = Add:
+editor-class-name-hover = Game class implementing this entry
+editor-class-name-unknown = No AI def found for this class
+editor-convert = Convert to { $category }
+editor-convert-hover = Move this entry to the { $category } list as one of its classes, keeping everything that uses it
+editor-current-entry = Current Entry
+editor-delete = Delete Selected
+editor-delete-subtree = Delete with Children
+editor-delete-subtree-hover = Delete this entry and every entry under it which nothing else uses
+editor-duplicate = Duplicate
+editor-edit-text = Edit as Text
+editor-edit-text-hover = Edit the entry as AAMP YAML
+editor-find-usages = Find Usages
+editor-group-name-hover = Name of the parent entry
+editor-group-name-mismatch = Does not match the parent entry, should be { $expected }
+editor-jump-hover = Go to this entry (Alt+Left to come back)
+editor-modified = Modified
+editor-modified-hover = Changed since the file was loaded
+editor-move-down = Move Down
+editor-move-up = Move Up
+editor-name-hover = Name of the child slot this entry fills in its parent
+editor-not-index = Not an index
+editor-not-index-hover = Found { $value }
+editor-notes-hint = Why values were chosen, things left to do, …
+editor-notes-hover = Kept in a .plasticity.json file next to the program, which the game never reads
+editor-pin-hover = List the entry at the top of the side panel
+editor-query-gated = Which of these children runs depends on the results:
+editor-query-gated-hover = The Dry Run tool can follow them for chosen results
+editor-query-unused = No AI or Action names this query. Event flows may still use it.
+editor-revert = Revert
+editor-slot-missing = Declared for this class but missing
+editor-text-apply-hover = Replace the entry with the text. Indexes are taken as they are.
+editor-text-reset-hover = Throw away the text and show the entry as it is
+editor-vanilla-examples-hover = See how the game's own AI programs use this class
+editor-view-def = View AI Def
+
+## Demos
+
+demos-add = Add Demo
+demos-key = Key { $key }
+demos-known = Known
+demos-known-hover = Demos seen in programs opened so far
+demos-name-hint = Demo name
+demos-unknown-key = No known name hashes to this key. If you know it, add it under Tools > Hash Names.
+
+## Parameters
+
+param-added = Added since the file was loaded
+param-angle-stored = Stored as { $unit ->
+    [degrees] degrees
+   *[radians] radians
+    }
+param-angle-switch = Edit angles in the other unit
+param-as-found = The actor's ASList has this AS
+param-as-missing = The actor's ASList has no AS by this name, so the actor will T-pose instead of playing it
+param-changed = Changed since the file was loaded, was { $value }. Click to revert.
+param-flag-missing = There is no GameData flag by this name
+param-history = Earlier values:
+param-undefined = Not defined for this class
+param-warning-degrees = { $value }° is more than a full turn
+param-warning-negative = { $value } is negative, which makes no sense for a { $word }
+param-warning-not-finite = { $value } isn't a number the game can use
+param-warning-radians = { $value } radians is more than a full turn, so it may have been entered in degrees
+param-warning-rate = { $value } is unusual for a rate, which is usually between 0 and { $max }
+param-wrong-type = The current value does not have the defined type.
+
+## Set Parameter on Selected
+
+bulk-parameter = SInst parameter
+bulk-selected = { $count } entries selected
+bulk-updated = Updated { $count } entries
+
+## Remap Behaviors
+
+remap-auto-match = Auto-match by ClassName
+remap-empty = No behavior indexes to remap.
+remap-now = Now
+remap-source = Source:
+remap-source-hover = The program the entries were copied from
+remap-was = Was
+
+## Open from Game Dump
+
+dump-actor = Actor:
+dump-game-dump = Game dump:
+dump-no-actors = No actor packs found. Pick the folder containing Actor/Pack.
+
+## Vanilla Examples
+
+vanilla-actors = Actors: { $actors }
+vanilla-count = { $count } of { $total }
+vanilla-count-hover = Uses with this value, of those setting it
+vanilla-no-dump = Set a game dump under File > Open from Game Dump first.
+vanilla-not-read = The game dump has to be read first. This takes a minute or two.
+vanilla-read = Read Game Dump
+vanilla-read-again = Read Again
+vanilla-summary = { $class } is used { $uses } times in { $programs } programs
+vanilla-use = Use
+vanilla-use-hover = Set this value on the selected entry
+
+## Unsaved changes
+
+exit-unsaved = These files have unsaved changes:
+
+## Recover Unsaved Changes
+
+recover-files = Plasticity closed last time with unsaved changes to these files, which can be restored:
+
+## Add New Entry
+
+add-entry-class = Select the AI class for the new entry:
+add-entry-class-hint = AI entry class name
+
+## Compare Entries
+
+compare-diff-only = Only differences
+compare-summary = { $differ } of { $total } parameters differ
+compare-swap = Swap
+
+## Validation
+
+issue-cycle = ChildIdx cycle: { $path }
+issue-empty-class = ClassName is empty
+issue-missing-child-slot = Missing ChildIdx slot { $slot }
+issue-missing-def = Missing Def object
+issue-missing-link = { $class } needs { $needs }, but the actor has no { $link }
+issue-no-such-behavior = BehaviorIdx { $key } points to nonexistent behavior { $index }
+issue-no-such-entry = { $table } { $key } points to nonexistent entry { $index }
+issue-not-integer = { $table } { $key } is not an integer
+issue-odd-value = { $obj } { $name }: { $warning }
+issue-undefined-param = { $obj } { $name } is not defined for { $class }
+issue-unknown-as = SInst { $name }: the ASList has no AS named { $anim }
+issue-unknown-class = No AI def found for class { $class }
+issue-unknown-flag = { $obj } { $name }: there is no GameData flag named { $flag }
+issue-unused-root = Root AI which nothing refers to, so the game will never run it
+issue-wrong-group-name = GroupName "{ $group }" should be { $expected }
+issue-wrong-key = Entry key { $key } should be { $expected }
+issue-wrong-range = { $table } { $key } points into the { $category } range (entry { $index })
+issue-wrong-type = { $obj } { $name } should be { $expected }
+validation-export = Export Report
+validation-export-hover = Save the report as JSON or plain text
+validation-found = { $count ->
+    [one] { $count } problem found:
+   *[other] { $count } problems found:
+    }
+validation-load-failed = Couldn't load it: { $error }
+validation-none = No problems found.
+validation-project-found = { $failed } of { $files } files have problems:
+validation-project-none = No problems found in { $files } files
+validation-save-anyway = Save Anyway
+validation-save-problems = { $file } has { $count ->
+    [one] a problem
+   *[other] { $count } problems
+    } which may stop the game from loading it:
+
+## Delete Entry
+
+delete-children = This entry has children. What should happen to them?
+delete-cleared-behaviors = BehaviorIdx slots which will be cleared:
+delete-cleared-children = ChildIdx slots which will be cleared:
+delete-cleared-demos = Demo slots which will be cleared:
+delete-cleared-others = Slots of other entries which will be cleared:
+delete-confirm = Delete { $entry }?
+delete-confirm-many = Delete { $count } entries?
+delete-orphan = Leave them, clearing the slots which used this entry
+delete-recursive = Delete them too ({ $count } entries which nothing else uses)
+delete-shifted = { $entries ->
+    [one] { $entries } later entry will move down one index, updating { $slots } slots which point at it.
+   *[other] { $entries } later entries will move down one index, updating { $slots } slots which point at them.
+    }
+delete-splice = Put one of them in this entry's place
+delete-subtree-too = These entries under it will be deleted too:
+delete-unreferenced = Nothing refers to this entry.
+
+## Convert
+
+convert-class = Select the { $category } class for { $entry }:
+convert-class-hint = Class name
+convert-convert = Convert
+
+## Restore Previous Version
+
+restore-no-backups = There are no backups of this file yet. One is made on each save.
+restore-unknown-time = Unknown time
+restore-version = Version { $version }
+
+## Translations
+
+translations-reset-hover = Use the bundled translation
+
+## Log
+
+log-copy-all = Copy All
+log-to-file = Also write to file
+
+## Hash Names
+
+hashes-add = Add Name
+hashes-export = Export…
+hashes-export-hover = Save your names to share them
+hashes-import = Import…
+hashes-intro = Try a name to see if it matches a key with no known name.
+hashes-known = Already known
+hashes-matches = Matches an unknown key
+hashes-no-match = No unknown key in this program matches
+hashes-unknown-keys = Unknown Keys ({ $count })
+hashes-user-names = Your Names ({ $count })
+
+## Unused Entries
+
+unused-found = { $count ->
+    [one] { $count } unused entry:
+   *[other] { $count } unused entries:
+    }
+unused-none = No unused entries found.
+unused-remove-all = Remove All
+
+## Script Console
+
+script-clear-output = Clear Output
+script-run = Run
+
+## Search
+
+search-hint = Name, class, or parameter value
+search-results = { $count ->
+    [one] { $count } result
+   *[other] { $count } results
+    }
+
+## Usages
+
+usages-none = This entry is not referenced anywhere.
+
+## Background tasks
+
+task-building-tree = Building tree
+task-checking-project = Checking project
+task-comparing-mods = Comparing mods
+task-files = files
+task-mods-read = mods read
+task-opening-reference = Opening reference
+task-progress = { $done } of { $total } { $status }
+task-roots = roots
+task-working = Working
+
+## Confirmations
+
+confirm-close = { $file } has unsaved changes. Are you sure you want to close it?
+confirm-close-aamp = Close the AS list without saving your changes?
+confirm-migrate-class = Update this entry's parameters and child slots to match { $class }? Existing values with matching names and types will be kept.
+confirm-open-repaired = { $file } could not be opened: { $error }
+    
+    Open it anyway, filling in what's missing?
+confirm-reload = Reloading will lose your unsaved changes, though they can still be undone afterwards. Reload anyway?
+confirm-remove-required = { $param } is required for this entry. Are you sure you want to remove it?
+confirm-remove-unused = Are you sure you want to delete all unused entries?
+confirm-save-problems = These files have problems which may stop the game from loading them:
+    { $files }
+    
+    Are you sure you want to save them?
+
+## Errors
+
+error-aamp-open = Save or close the open AS list before opening another.
+error-autosave = Failed to back up unsaved changes
+error-clipboard = Couldn't read the clipboard: { $error }
+error-clipboard-program = The clipboard doesn't hold an AI program
+error-clipboard-web = Reading the clipboard isn't supported in the browser
+error-cycles = This program contains ChildIdx cycles, which will be cut short in the tree:
+    { $cycles }
+error-deploy-command = Failed to run { $command }
+error-deploy-copy = Failed to copy to { $file }
+error-edited-since = { $file } was edited in the meantime, so this wasn't applied. Try again.
+error-flags = Failed to read GameData flags from { $file }
+error-hash-table = { $file } is not a hash table
+error-log-file = Couldn't open { $file }: { $error }
+error-no-child-slots = { $entry } has no child slots
+error-open = Failed to open { $file }
+error-reference-read-only = The reference program can't be edited
+error-repaired = Repaired { $file } to open it. Save it to keep the repairs:
+    { $repairs }
+error-restore-backup = Failed to restore backup of { $file }
+error-sidecar = { $file } is not a Plasticity sidecar
+error-undefined-params = { $class } does not define these parameters, which may need to be removed:
+    { $params }
+
+## Warnings
+
+warning-link-missing = This class needs { $needs }, but the actor has no { $link }
+warning-link-unverified = Unverified hint: this class may need { $needs }, but the actor has no { $link }
+
+## Text used throughout
+
+behavior-invalid = Behavior_{ $index }. [INVALID]
+column-entry = Entry
+hint-filter = Filter
+hint-needs-desktop = Only in the desktop app, as the browser can't pick files here
+list-or = or
+paths-truncated = Only the first { $count } paths are shown
+slot-choose = Which slot of { $parent } should point at { $child }?
+slot-choose-import = Which slot of { $parent } should point at the imported subtree?
+slot-not-set = [NOT SET]
+time-days-ago = { $count ->
+    [one] { $count } day ago
+   *[other] { $count } days ago
+    }
+time-hours-ago = { $count ->
+    [one] { $count } hour ago
+   *[other] { $count } hours ago
+    }
+time-just-now = just now
+time-minutes-ago = { $count ->
+    [one] { $count } minute ago
+   *[other] { $count } minutes ago
+    }
+
+## Buttons used throughout
+
+button-add = Add
+button-apply = Apply
+button-browse = Browse
+button-cancel = Cancel
+button-clear = Clear
+button-close = Close
+button-compare = Compare
+button-copy = Copy
+button-delete = Delete
+button-discard = Discard
+button-ok = OK
+button-pin = Pin
+button-remove = Remove
+button-reset = Reset
+button-restore = Restore
+button-unpin = Unpin
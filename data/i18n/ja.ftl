@@ -0,0 +1,668 @@
+# Text of the UI in Japanese. Anything missing here is shown in English.
+
+## Menus
+
+menu-edit = 編集
+menu-file = ファイル
+menu-plugins = プラグイン
+menu-tools = ツール
+
+## File menu
+
+file-apply-patch = パッチを適用
+file-close = 閉じる
+file-close-project = プロジェクトを閉じる
+file-close-reference = 参照を閉じる
+file-compress = 保存時に Yaz0 圧縮
+file-copy-yaml = YAML としてコピー
+file-copy-yaml-hover = 共有用にプログラム全体を YAML としてクリップボードにコピーします
+file-deploy = デプロイ
+file-deploy-settings = デプロイ設定
+file-exit = 終了
+file-export-csv = CSV をエクスポート
+file-export-csv-hover = 表計算ソフトで編集できるよう、SInst パラメータを 1 行ずつ書き出します
+file-export-description = 説明をエクスポート
+file-export-description-hover = ツリーと各エントリの説明を Markdown または HTML で書き出します。Mod のドキュメントやレビューに
+file-export-graph = グラフをエクスポート
+file-export-json = JSON をエクスポート
+file-export-json-schema = スキーマ付きで JSON をエクスポート
+file-export-json-schema-hover = { $file } をスキーマとして指定した JSON を書き出し、その横にスキーマも書き出します。他のツールで AI プログラムを検証するために
+file-export-patch = パッチをエクスポート
+file-import-csv = CSV をインポート
+file-import-csv-hover = エクスポートした CSV で編集したパラメータ値を適用します
+file-merge = 変更をマージ
+file-merge-hover = 同じ元ファイルから作られた別バージョンの変更を、エントリを名前で照合して取り込みます
+file-open = 開く
+file-open-as-list = AS リストを開く
+file-open-as-list-hover = AS リストなどの AAMP ファイルを並べて編集します
+file-open-as-list-pack = パックの AS リストを開く
+file-open-as-list-pack-hover = このプログラムと同じアクターパックにある AS リストを編集します
+file-open-clipboard = クリップボードから開く
+file-open-clipboard-hover = YAML としてコピーされた AI プログラムを、先にファイルへ保存してから開きます
+file-open-dump = ゲームダンプから開く
+file-open-project = プロジェクトフォルダを開く
+file-open-project-hover = Mod フォルダ内のすべての AI プログラムとアクターパックを一覧表示します
+file-open-reference = 参照を開く
+file-reopen-last = 起動時に最後のファイルを開く
+file-restore = 以前のバージョンに戻す
+file-restore-hover = 保存する前のバージョンのファイルに戻します
+file-revert-all = すべて元に戻す
+file-revert-all-confirm = ファイルを読み込んでからの変更をすべて破棄してもよろしいですか？
+file-revert-all-hover = 読み込んだとき、または最後に保存したときの状態に戻します
+file-save = 保存
+file-save-as = 名前を付けて保存
+file-settings = 設定
+
+## Edit menu
+
+edit-back = 戻る
+edit-forward = 進む
+edit-go-to-parent = 親へ移動
+edit-normalize = 順序を整理
+edit-normalize-hover = AI と Action をツリーの表示順に並べ、すべてのインデックスを書き換えます
+edit-normalize-vanilla = バニラの順序に整理
+edit-normalize-vanilla-hover = 元のバニラプログラムの順序にエントリを並べ、新しいエントリは最後に置きます。バニラとの差分を小さく保てます
+edit-redo = やり直し
+edit-remap = ビヘイビアを再割り当て
+edit-remap-hover = 選択したエントリ、または選択したエントリとその下すべてのビヘイビアインデックスを割り当て直します
+edit-revert-entry = エントリを元に戻す
+edit-revert-entry-hover = 選択したエントリを読み込んだときの状態に戻します
+edit-set-parameter = 選択したエントリにパラメータを設定
+edit-set-parameter-hover = ツリーで Ctrl+クリックすると複数のエントリを選択できます
+edit-undo = 元に戻す
+
+## Tools menu
+
+tools-conflicts = Mod の競合を調べる
+tools-conflicts-hover = アクターの AI プログラムのどのエントリを複数の Mod が変更しているかを確認します
+tools-dry-run = ドライラン
+tools-dry-run-hover = ルートからツリーをたどり、何が実行されるかを確認します
+tools-find-replace = 検索と置換
+tools-find-unused = 未使用を検索
+tools-fix-group-names = グループ名を修正
+tools-fix-group-names-hover = 各エントリの Name と GroupName を親から設定します
+tools-hash-names = ハッシュ名
+tools-hash-names-hover = 数値で表示されるパラメータキーに名前を付けます
+tools-port = ビヘイビアを移植
+tools-port-hover = 別のプログラムから AI とそれが使うものすべてをコピーします
+tools-script-console = スクリプトコンソール
+tools-search = 検索
+tools-show-log = ログを表示
+tools-show-log-hover = 開いたもの、保存したもの、失敗したことを確認できます。バグ報告に
+tools-translations = 翻訳
+tools-translations-hover = 日本語名に対応する英語名を追加・修正します
+tools-validate = 検証
+
+## Settings
+
+settings-angles = 角度
+settings-angles-degrees = 度
+settings-angles-hover = 角度らしいパラメータを編集する単位です。保存はゲームが期待する単位で行われます。
+settings-angles-radians = ラジアン
+settings-backups = バックアップ
+settings-backups-hover = ファイルを上書き保存するとき、横の .plasticity_backups に残す以前のバージョンの数
+settings-both = 両方
+settings-colors = 色
+settings-colors-colorblind = 色覚多様性対応
+settings-colors-custom = カスタム
+settings-colors-high-contrast = ハイコントラスト
+settings-colors-hover = 問題なし・警告・エラー・変更を示すマーカーの色です。色に関係なくマーカーの形は変わりません。
+settings-colors-standard = 標準
+settings-entry-numbers = エントリ番号
+settings-entry-numbers-absolute = 全体
+settings-entry-numbers-hover = YAML ダンプのようにカテゴリごとに 0 から番号を付けるか、ChildIdx の値のようにプログラム全体で番号を付けます
+settings-entry-numbers-per-category = カテゴリごと
+settings-flags = GameData フラグ
+settings-flags-from-dump = ダンプから
+settings-flags-from-dump-hover = ゲームダンプの Bootup.pack にあるフラグを使います
+settings-flags-hover = gamedata.ssarc、Bootup.pack、または 1 行に 1 つフラグを書いたテキストファイル。フラグ名のパラメータの確認と候補の表示に使います
+settings-font-size = フォントサイズ
+settings-game-dump = ゲームダンプ
+settings-game-dump-hover = バニラのプログラムを開くための、展開済みのゲームファイル
+settings-language = 言語
+settings-names = 名前
+settings-names-english = 英語
+settings-names-japanese = 日本語
+settings-new-files = 新規ファイル
+settings-new-files-binary = バイナリ
+settings-new-files-hover = まだファイルのないプログラムを保存する形式
+settings-not-set = 未設定
+settings-platform = プラットフォーム
+settings-platform-hover = Mod の対象となるゲームのバージョンです。Wii U のファイルはビッグエンディアン、Switch はリトルエンディアンです。デプロイ先の新しい Mod フォルダの構成も決まります。
+settings-reset = デフォルトに戻す
+settings-saved-in = 保存先: { $file }
+settings-theme = テーマ
+settings-theme-dark = ダーク
+settings-theme-light = ライト
+settings-ui-scale = UI の拡大率
+
+## Windows
+
+window-add-entry = 新しいエントリを追加
+window-ai-def = AI 定義: { $class }
+window-busy = お待ちください
+window-changes-on-disk = ディスク上の変更: { $file }
+window-choose-slot = スロットを選択
+window-compare = エントリを比較
+window-confirm = 確認
+window-conflicts = Mod の競合を調べる
+window-convert = { $category } に変換
+window-delete = エントリを削除
+window-deploy = デプロイ
+window-dry-run = ドライラン
+window-error = エラー
+window-find-replace = 検索と置換
+window-hash-names = ハッシュ名
+window-log = ログ
+window-merge = 変更をマージ
+window-open-dump = ゲームダンプから開く
+window-port = ビヘイビアを移植
+window-problems = 問題が見つかりました
+window-project-validation = プロジェクトの検証
+window-recover = 未保存の変更を復元
+window-remap = ビヘイビアを再割り当て
+window-restore = 以前のバージョンに戻す
+window-script-console = スクリプトコンソール
+window-search = 検索
+window-set-parameter = 選択したエントリにパラメータを設定
+window-settings = 設定
+window-translations = 翻訳
+window-unsaved = 未保存の変更
+window-unused = 未使用のエントリ
+window-usages = 使用箇所: { $entry }
+window-validation = 検証
+window-vanilla-examples = バニラの例
+
+## Sections of the entry editor and other windows
+
+section-behaviour-indexes = ビヘイビアインデックス
+section-children = 子
+section-definition = 定義
+section-minst = マップユニットインスタンスパラメータ
+section-notes = メモ
+section-pinned = ピン留め
+section-queries = クエリ
+section-sinst = 静的インスタンスパラメータ
+section-typical-values = よく使われる値
+section-used-by = 使用元 ({ $count })
+section-uses = 使用例
+
+## Find and Replace
+
+replace-apply = { $count } 件を置換
+replace-done = { $count } 個の値を置換しました
+replace-find = 検索
+replace-match-case = 大文字と小文字を区別
+replace-regex = 正規表現
+replace-regex-hover = 置換後の文字列では $1、$2 などでキャプチャしたグループを使えます
+replace-with = 置換後
+replace-would-change = { $count } 個のパラメータが変更されます
+
+## Project panel
+
+project-empty = AI プログラムもアクターパックも見つかりません
+project-open = （開いています）
+project-refresh = 再読み込み
+project-save-all = すべて保存
+project-save-all-hover = プロジェクト内の開いているファイルのうち、未保存の変更があるものをすべて保存します
+project-title = プロジェクト: { $name }
+project-validate-hover = プロジェクト内のすべてのファイルを検証します
+
+## Merge Changes
+
+merge-all-ours = すべてこちら
+merge-all-theirs = すべて相手
+merge-base = 両方の編集元:
+merge-clean = 両方のバージョンを問題なくマージしました。
+merge-conflict-demo = デモ { $demo }: こちらは{ $ours }、相手は{ $theirs }
+merge-conflict-entry = { $category } { $label }: こちらは{ $ours }、相手は{ $theirs }
+merge-conflicts = { $count } 件がそれぞれのバージョンで異なる変更をされています。残す方を選んでください。
+merge-edit-added = 追加
+merge-edit-changed = 変更
+merge-edit-cleared = 空にした
+merge-edit-pointed = { $label } を指すよう変更
+merge-edit-removed = 削除
+merge-intro = エントリはクラス、名前、グループで照合されるため、どちらのバージョンでエントリを追加・削除・移動していても構いません。
+merge-merge = マージ
+merge-ours = こちら
+merge-theirs = 相手のバージョン:
+merge-theirs-side = 相手
+
+## Inspect Mod Conflicts
+
+conflicts-add-mod = Mod を追加
+conflicts-added = 追加
+conflicts-changed = 変更
+conflicts-clash = 複数の Mod が同じパラメータを異なる値に変更しています
+conflicts-compare-hover = バニラと 2 つ以上の Mod が必要です
+conflicts-compatible = 複数の Mod が変更していますが、問題なくマージできます
+conflicts-mod = Mod { $number }
+conflicts-mod-file = Mod { $number }:
+conflicts-removed = 削除
+conflicts-shared-only = 複数の Mod が変更するエントリのみ
+conflicts-summary = { $changed } 個のエントリが変更され、そのうち { $shared } 個は複数の Mod による変更で、{ $clashes } 個はマージできない変更です。
+conflicts-vanilla = バニラ:
+
+## Dry Run
+
+dry-run-cycle = ループ
+dry-run-first = 最初の子
+dry-run-guess = あくまで目安です。ほとんどのセレクターはゲームの状態で判断します。
+dry-run-no-child = 選ばれたスロットに子が設定されていません
+dry-run-picked = 選択
+dry-run-query = { $query } が { $result ->
+    [true] 真
+   *[false] 偽
+    }
+dry-run-query-results = クエリの結果
+dry-run-reset = 選択をリセット
+dry-run-runs = 実行
+dry-run-start = 開始位置
+
+## Port Behavior
+
+port-attach = 接続先
+port-cannot-copy = このエントリはコピーできません。
+port-counts = AI { $ais } 個、アクション { $actions } 個、ビヘイビア { $behaviors } 個、クエリ { $queries } 個
+port-from = コピー元: { $file }
+port-leave-root = [ルートのままにする]
+port-port = 移植
+port-reuse = このプログラムには、すでに次のクラスのビヘイビアがあります:
+port-root = コピーする AI
+port-shared-queries = プログラムにすでにあるクエリは共有されます: { $queries }
+port-use = { $entry } を使う
+
+## Tree
+
+tree-cycle = { $entry }（循環）
+tree-export-subtree = サブツリーをエクスポート…
+tree-import-subtree = ここにサブツリーをインポート…
+tree-mark-root = ルートとしてマーク
+tree-revert = 読み込み時に戻す
+tree-unmark-root = ルートのマークを外す
+
+## Table
+
+table-sort-hover = この列で並べ替え
+
+## Outline plugin
+
+outline-no-children = 下に何かあるのは AI と Action だけです。
+
+## Deploy
+
+deploy-command = その後に実行:
+deploy-command-hint = エミュレーターを再起動するコマンド（あれば）
+deploy-last = 前回のデプロイ先:
+deploy-layout = 構成:
+deploy-layout-bcml = BCML Mod
+deploy-layout-graphic-pack = Cemu グラフィックパック
+deploy-layout-layeredfs = Atmosphère layeredfs
+deploy-mod-folder = Mod フォルダ:
+deploy-now = 今すぐデプロイ
+deploy-on-save = ファイルを保存するたびにデプロイ
+deploy-repack = 単体の AI プログラムを、それを使う Mod のアクターパックに入れる
+
+## Crashes
+
+crash-nothing-unsaved = 未保存の変更はありませんでした。
+crash-save-failed = 未保存の変更を書き出せませんでした。
+crash-save-failed-because = 未保存の変更を書き出せませんでした: { $error }
+crash-saved = 未保存の変更を { $folder } に書き出しました。次に Plasticity を起動したときに復元できます。
+crash-title = Plasticity がクラッシュしました
+
+## Changes on disk
+
+disk-changed = { $file } がディスク上で変更されました。
+disk-diff = 差分
+disk-diff-hover = ディスク上のファイルと異なるエントリを一覧表示します
+disk-differs = ディスク上のファイルと異なります
+disk-keep-mine = こちらを残す
+disk-keep-mine-hover = プログラムをそのままにして、あとでファイルを上書きします
+disk-not-on-disk = ディスク上のファイルにはありません
+disk-only-on-disk = ディスク上のファイルにのみあります
+disk-reload = 再読み込み
+disk-reload-hover = プログラムをディスク上のファイルで置き換えます
+disk-same = プログラムはディスク上のファイルと同じです。
+
+## Plugins
+
+plugin-export = { $format } をエクスポート
+plugin-import = { $format } をインポート
+
+## Side panel
+
+pin-missing = このクラスと名前のエントリはもうありません
+reference-title = 参照: { $file }
+side-compare-hover = 2 つのエントリのパラメータを並べて表示します
+side-delete-hover = 選択したエントリをまとめて削除します
+side-selected = { $count } 個を選択中
+side-set-parameter = パラメータを設定
+side-table = 表
+side-table-hover = 現在のタブのエントリを詳細とともに一覧表示します
+side-tree = ツリー
+side-unreferenced = 参照されていない ({ $count })
+side-unreferenced-hover = デモや他のエントリから参照されていないルート AI です。ゲームが実行することはありません
+
+## Tabs
+
+tab-actions = アクション
+tab-ais = AI
+tab-behaviours = ビヘイビア
+tab-demos = デモ
+tab-queries = クエリ
+
+## Entry editor
+
+editor-add-new = 新規追加
+editor-add-param = 追加:
+editor-class-name-hover = このエントリを実装するゲームのクラス
+editor-class-name-unknown = このクラスの AI 定義が見つかりません
+editor-convert = { $category } に変換
+editor-convert-hover = このエントリを { $category } のリストにそのクラスとして移し、使っているものはそのまま保ちます
+editor-current-entry = 現在のエントリ
+editor-delete = 選択を削除
+editor-delete-subtree = 子ごと削除
+editor-delete-subtree-hover = このエントリと、その下にある他から使われていないエントリをすべて削除します
+editor-duplicate = 複製
+editor-edit-text = テキストとして編集
+editor-edit-text-hover = エントリを AAMP YAML として編集します
+editor-find-usages = 使用箇所を検索
+editor-group-name-hover = 親エントリの名前
+editor-group-name-mismatch = 親エントリと一致しません。{ $expected } であるべきです
+editor-jump-hover = このエントリへ移動します（Alt+← で戻ります）
+editor-modified = 変更あり
+editor-modified-hover = ファイルを読み込んでから変更されています
+editor-move-down = 下へ移動
+editor-move-up = 上へ移動
+editor-name-hover = このエントリが親の中で埋める子スロットの名前
+editor-not-index = インデックスではありません
+editor-not-index-hover = { $value } が見つかりました
+editor-notes-hint = 値を選んだ理由、残っている作業など…
+editor-notes-hover = プログラムの横の .plasticity.json ファイルに保存されます。ゲームが読むことはありません
+editor-pin-hover = サイドパネルの上部にエントリを表示します
+editor-query-gated = 次の子のどれが実行されるかは結果によって決まります:
+editor-query-gated-hover = ドライランツールで、選んだ結果に沿ってたどれます
+editor-query-unused = このクエリを指定している AI や Action はありません。イベントフローで使われている可能性はあります。
+editor-revert = 元に戻す
+editor-slot-missing = このクラスで宣言されていますが、ありません
+editor-text-apply-hover = エントリをテキストの内容で置き換えます。インデックスはそのまま使われます。
+editor-text-reset-hover = テキストを破棄して、現在のエントリを表示します
+editor-vanilla-examples-hover = ゲーム本来の AI プログラムがこのクラスをどう使っているかを確認します
+editor-view-def = AI 定義を表示
+
+## Demos
+
+demos-add = デモを追加
+demos-key = キー { $key }
+demos-known = 既知
+demos-known-hover = これまでに開いたプログラムで見つかったデモ
+demos-name-hint = デモ名
+demos-unknown-key = このキーになる既知の名前はありません。名前がわかる場合は、ツール > ハッシュ名で追加してください。
+
+## Parameters
+
+param-added = ファイルを読み込んでから追加されました
+param-angle-stored = { $unit ->
+    [degrees] 度
+   *[radians] ラジアン
+    }で保存されます
+param-angle-switch = もう一方の単位で角度を編集します
+param-as-found = アクターの ASList にこの AS があります
+param-as-missing = アクターの ASList にこの名前の AS がないため、再生されずに T ポーズになります
+param-changed = ファイルを読み込んでから変更されています（元は { $value }）。クリックで元に戻します。
+param-flag-missing = この名前の GameData フラグはありません
+param-history = 以前の値:
+param-undefined = このクラスでは定義されていません
+param-warning-degrees = { $value }° は一回転を超えています
+param-warning-negative = { $value } は負の値ですが、{ $word } が負になることはありません
+param-warning-not-finite = { $value } はゲームで使える数値ではありません
+param-warning-radians = { $value } ラジアンは一回転を超えています。度で入力された可能性があります
+param-warning-rate = { $value } は率としては珍しい値です。通常は 0 から { $max } の間です
+param-wrong-type = 現在の値は定義された型ではありません。
+
+## Set Parameter on Selected
+
+bulk-parameter = SInst パラメータ
+bulk-selected = { $count } 個のエントリを選択中
+bulk-updated = { $count } 個のエントリを更新しました
+
+## Remap Behaviors
+
+remap-auto-match = ClassName で自動照合
+remap-empty = 再割り当てするビヘイビアインデックスはありません。
+remap-now = 変更後
+remap-source = コピー元:
+remap-source-hover = エントリのコピー元のプログラム
+remap-was = 変更前
+
+## Open from Game Dump
+
+dump-actor = アクター:
+dump-game-dump = ゲームダンプ:
+dump-no-actors = アクターパックが見つかりません。Actor/Pack を含むフォルダを選んでください。
+
+## Vanilla Examples
+
+vanilla-actors = アクター: { $actors }
+vanilla-count = { $count } / { $total }
+vanilla-count-hover = 値を設定している使用例のうち、この値を使っている数
+vanilla-no-dump = 先にファイル > ゲームダンプから開く でゲームダンプを設定してください。
+vanilla-not-read = 先にゲームダンプを読み込む必要があります。1、2 分かかります。
+vanilla-read = ゲームダンプを読み込む
+vanilla-read-again = 再読み込み
+vanilla-summary = { $class } は { $programs } 個のプログラムで { $uses } 回使われています
+vanilla-use = 使う
+vanilla-use-hover = 選択したエントリにこの値を設定します
+
+## Unsaved changes
+
+exit-unsaved = 次のファイルには未保存の変更があります:
+
+## Recover Unsaved Changes
+
+recover-files = 前回 Plasticity は次のファイルに未保存の変更を残したまま終了しました。復元できます:
+
+## Add New Entry
+
+add-entry-class = 新しいエントリの AI クラスを選択してください:
+add-entry-class-hint = AI エントリのクラス名
+
+## Compare Entries
+
+compare-diff-only = 違いのみ
+compare-summary = { $total } 個中 { $differ } 個のパラメータが異なります
+compare-swap = 入れ替え
+
+## Validation
+
+issue-cycle = ChildIdx の循環: { $path }
+issue-empty-class = ClassName が空です
+issue-missing-child-slot = ChildIdx スロット { $slot } がありません
+issue-missing-def = Def オブジェクトがありません
+issue-missing-link = { $class } には { $needs } が必要ですが、アクターに { $link } がありません
+issue-no-such-behavior = BehaviorIdx { $key } が存在しないビヘイビア { $index } を指しています
+issue-no-such-entry = { $table } { $key } が存在しないエントリ { $index } を指しています
+issue-not-integer = { $table } { $key } が整数ではありません
+issue-odd-value = { $obj } { $name }: { $warning }
+issue-undefined-param = { $obj } { $name } は { $class } に定義されていません
+issue-unknown-as = SInst { $name }: ASList に { $anim } という AS はありません
+issue-unknown-class = クラス { $class } の AI 定義が見つかりません
+issue-unknown-flag = { $obj } { $name }: { $flag } という GameData フラグはありません
+issue-unused-root = どこからも参照されていないルート AI のため、ゲームで実行されることはありません
+issue-wrong-group-name = GroupName「{ $group }」は { $expected } であるべきです
+issue-wrong-key = エントリのキー { $key } は { $expected } であるべきです
+issue-wrong-range = { $table } { $key } が { $category } の範囲を指しています（エントリ { $index }）
+issue-wrong-type = { $obj } { $name } は { $expected } であるべきです
+validation-export = レポートをエクスポート
+validation-export-hover = レポートを JSON またはテキストとして保存します
+validation-found = { $count } 件の問題が見つかりました:
+validation-load-failed = 読み込めませんでした: { $error }
+validation-none = 問題は見つかりませんでした。
+validation-project-found = { $files } 個中 { $failed } 個のファイルに問題があります:
+validation-project-none = { $files } 個のファイルに問題は見つかりませんでした
+validation-save-anyway = このまま保存
+validation-save-problems = { $file } には、ゲームが読み込めなくなるおそれのある問題が { $count } 件あります:
+
+## Delete Entry
+
+delete-children = このエントリには子があります。子をどうしますか？
+delete-cleared-behaviors = 空になる BehaviorIdx スロット:
+delete-cleared-children = 空になる ChildIdx スロット:
+delete-cleared-demos = 空になるデモスロット:
+delete-cleared-others = 空になる他のエントリのスロット:
+delete-confirm = { $entry } を削除しますか？
+delete-confirm-many = { $count } 個のエントリを削除しますか？
+delete-orphan = 残して、このエントリを使っていたスロットを空にする
+delete-recursive = 子も削除する（他から使われていないエントリ { $count } 個）
+delete-shifted = 後ろにある { $entries } 個のエントリのインデックスが 1 つずつ下がり、それらを指す { $slots } 個のスロットが更新されます。
+delete-splice = そのうち 1 つをこのエントリの位置に置く
+delete-subtree-too = その下にある次のエントリも削除されます:
+delete-unreferenced = このエントリを参照しているものはありません。
+
+## Convert
+
+convert-class = { $entry } の { $category } クラスを選択してください:
+convert-class-hint = クラス名
+convert-convert = 変換
+
+## Restore Previous Version
+
+restore-no-backups = このファイルのバックアップはまだありません。保存するたびに作成されます。
+restore-unknown-time = 日時不明
+restore-version = バージョン { $version }
+
+## Translations
+
+translations-reset-hover = 同梱の翻訳を使う
+
+## Log
+
+log-copy-all = すべてコピー
+log-to-file = ファイルにも書き出す
+
+## Hash Names
+
+hashes-add = 名前を追加
+hashes-export = エクスポート…
+hashes-export-hover = 共有できるよう、追加した名前を保存します
+hashes-import = インポート…
+hashes-intro = 名前を入力して、名前のわからないキーと一致するか確かめます。
+hashes-known = 既に知られています
+hashes-matches = 不明なキーと一致しました
+hashes-no-match = このプログラムに一致する不明なキーはありません
+hashes-unknown-keys = 不明なキー ({ $count })
+hashes-user-names = 追加した名前 ({ $count })
+
+## Unused Entries
+
+unused-found = 未使用のエントリ { $count } 個:
+unused-none = 未使用のエントリは見つかりませんでした。
+unused-remove-all = すべて削除
+
+## Script Console
+
+script-clear-output = 出力をクリア
+script-run = 実行
+
+## Search
+
+search-hint = 名前、クラス、またはパラメータ値
+search-results = 結果 { $count } 件
+
+## Usages
+
+usages-none = このエントリはどこからも参照されていません。
+
+## Background tasks
+
+task-building-tree = ツリーを構築中
+task-checking-project = プロジェクトを検証中
+task-comparing-mods = Mod を比較中
+task-files = ファイル
+task-mods-read = Mod を読み込み済み
+task-opening-reference = 参照を開いています
+task-progress = { $done } / { $total } { $status }
+task-roots = ルート
+task-working = 処理中
+
+## Confirmations
+
+confirm-close = { $file } には未保存の変更があります。閉じてもよろしいですか？
+confirm-close-aamp = 変更を保存せずに AS リストを閉じますか？
+confirm-migrate-class = このエントリのパラメータと子スロットを { $class } に合わせて更新しますか？名前と型が一致する既存の値は残ります。
+confirm-open-repaired = { $file } を開けませんでした: { $error }
+    
+    足りないものを補って開きますか？
+confirm-reload = 再読み込みすると未保存の変更が失われます（あとで元に戻すことはできます）。再読み込みしますか？
+confirm-remove-required = { $param } はこのエントリに必須です。削除してもよろしいですか？
+confirm-remove-unused = 未使用のエントリをすべて削除してもよろしいですか？
+confirm-save-problems = 次のファイルには、ゲームが読み込めなくなるおそれのある問題があります:
+    { $files }
+    
+    保存してもよろしいですか？
+
+## Errors
+
+error-aamp-open = 別の AS リストを開く前に、開いている AS リストを保存するか閉じてください。
+error-autosave = 未保存の変更をバックアップできませんでした
+error-clipboard = クリップボードを読み取れませんでした: { $error }
+error-clipboard-program = クリップボードの内容は AI プログラムではありません
+error-clipboard-web = ブラウザではクリップボードを読み取れません
+error-cycles = このプログラムには ChildIdx の循環があり、ツリーでは途中で打ち切られます:
+    { $cycles }
+error-deploy-command = { $command } を実行できませんでした
+error-deploy-copy = { $file } にコピーできませんでした
+error-edited-since = その間に { $file } が編集されたため、適用されませんでした。もう一度お試しください。
+error-flags = { $file } から GameData フラグを読み込めませんでした
+error-hash-table = { $file } はハッシュテーブルではありません
+error-log-file = { $file } を開けませんでした: { $error }
+error-no-child-slots = { $entry } には子スロットがありません
+error-open = { $file } を開けませんでした
+error-reference-read-only = 参照用のプログラムは編集できません
+error-repaired = 開くために { $file } を修復しました。修復を残すには保存してください:
+    { $repairs }
+error-restore-backup = { $file } のバックアップを復元できませんでした
+error-sidecar = { $file } は Plasticity のサイドカーファイルではありません
+error-undefined-params = { $class } は次のパラメータを定義していないため、削除が必要かもしれません:
+    { $params }
+
+## Warnings
+
+warning-link-missing = このクラスには{ $needs }が必要ですが、アクターに { $link } がありません
+warning-link-unverified = 未確認のヒント: このクラスには{ $needs }が必要かもしれませんが、アクターに { $link } がありません
+
+## Text used throughout
+
+behavior-invalid = Behavior_{ $index }. [無効]
+column-entry = エントリ
+hint-filter = フィルター
+hint-needs-desktop = ブラウザではここでファイルを選べないため、デスクトップ版でのみ使えます
+list-or = または
+paths-truncated = 最初の { $count } 個のパスだけを表示しています
+slot-choose = { $parent } のどのスロットを { $child } に向けますか？
+slot-choose-import = { $parent } のどのスロットをインポートしたサブツリーに向けますか？
+slot-not-set = [未設定]
+time-days-ago = { $count } 日前
+time-hours-ago = { $count } 時間前
+time-just-now = たった今
+time-minutes-ago = { $count } 分前
+
+## Buttons used throughout
+
+button-add = 追加
+button-apply = 適用
+button-browse = 参照
+button-cancel = キャンセル
+button-clear = クリア
+button-close = 閉じる
+button-compare = 比較
+button-copy = コピー
+button-delete = 削除
+button-discard = 破棄
+button-ok = OK
+button-pin = ピン留め
+button-remove = 削除
+button-reset = リセット
+button-restore = 復元
+button-unpin = ピン留めを外す
@@ -0,0 +1,103 @@
+//! Checks of the animation sequences Action entries play against those the
+//! actor's ASList defines, as an AS name the actor doesn't have leaves it
+//! T-posing in game.
+use crate::{
+    program::{is_actor_pack, path_in_pack, read_sarc, AIProgram, Category},
+    util::try_name,
+    validate::{Issue, IssueKind},
+};
+use anyhow::{Context, Result};
+use roead::aamp::{hash_name, ParamList, Parameter, ParameterIO};
+use std::{collections::BTreeSet, fs, path::Path};
+
+/// Names of the AS an actor's ASList defines.
+pub type AsNames = BTreeSet<String>;
+
+/// Whether a parameter's name says it holds the name of an AS, like `ASName`
+/// or `StartASKey`, going by "AS" standing as a word of its own.
+pub fn names_as(name: &str) -> bool {
+    name.match_indices("AS").any(|(i, _)| {
+        let before = name[..i].chars().last();
+        let after = name[i + 2..].chars().next();
+        !matches!(before, Some(c) if c.is_ascii_uppercase())
+            && !matches!(after, Some(c) if c.is_ascii_lowercase())
+    })
+}
+
+/// Reads the AS names defined by the ASList in an actor pack, if the pack has
+/// one.
+pub fn read_as_names(pack: &Path) -> Result<Option<AsNames>> {
+    let sarc = read_sarc(&fs::read(pack)?)?;
+    let path = match path_in_pack(&sarc, "Actor/ASList/", ".baslist") {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    let pio = ParameterIO::from_binary(
+        sarc.get_file_data(&path)
+            .context("Failed to read ASList from actor pack")?,
+    )?;
+    Ok(Some(
+        pio.list("ASDefines")
+            .map(|defines| {
+                defines
+                    .objects()
+                    .iter()
+                    .filter_map(|(_, define)| define.param("Name"))
+                    .filter_map(|name| name.as_string().ok())
+                    .map(|name| name.to_owned())
+                    .collect()
+            })
+            .unwrap_or_default(),
+    ))
+}
+
+/// Whether the AS a parameter names is in the ASList, or `None` if it doesn't
+/// name one. Empty names are left alone, since they mean no animation.
+pub fn as_exists(name: &str, param: &Parameter, as_names: &AsNames) -> Option<bool> {
+    if !names_as(name) {
+        return None;
+    }
+    let value = param.as_string().ok().filter(|value| !value.is_empty())?;
+    Some(as_names.contains(value))
+}
+
+impl AIProgram {
+    /// Action `SInst` parameters naming an AS the actor's ASList doesn't
+    /// define.
+    pub fn validate_animations(&self, as_names: &AsNames) -> Vec<Issue> {
+        let actions =
+            self.category_offset(Category::Action)..self.category_offset(Category::Behaviour);
+        actions
+            .flat_map(|idx| {
+                self.item_at_index(idx)
+                    .objects()
+                    .get(hash_name("SInst"))
+                    .into_iter()
+                    .flat_map(|obj| obj.params())
+                    .filter_map(move |(k, v)| {
+                        let name = try_name(*k);
+                        (as_exists(&name, v, as_names) == Some(false)).then(|| {
+                            Issue::new(
+                                Some(idx),
+                                IssueKind::UnknownAs {
+                                    anim: v.as_string().unwrap_or_default().to_owned(),
+                                    name,
+                                },
+                            )
+                        })
+                    })
+            })
+            .collect()
+    }
+
+    /// Runs [`AIProgram::validate_animations`] against the ASList of the
+    /// actor pack the program is from, if it's from one which has an ASList.
+    pub fn validate_animations_in(&self, file: &Path) -> Result<Vec<Issue>> {
+        if !is_actor_pack(file) {
+            return Ok(vec![]);
+        }
+        Ok(read_as_names(file)?
+            .map(|as_names| self.validate_animations(&as_names))
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,69 @@
+//! Numbered copies of a file from before each save, kept in a folder beside
+//! it so earlier versions can be restored.
+use anyhow::{Context, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Folder backups are kept in, beside the files they're of
+pub const BACKUP_DIR: &str = ".plasticity_backups";
+
+/// A saved copy of a file. Version 1 is the newest.
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub file: PathBuf,
+    pub version: usize,
+    /// When the copied version was saved
+    pub modified: Option<SystemTime>,
+}
+
+/// Where a version of a file is backed up, with the number before the
+/// extension so the copy still opens as the same kind of file.
+fn backup_path(file: &Path, version: usize) -> Option<PathBuf> {
+    let name = file.file_name()?.to_str()?;
+    let name = match name.split_once('.') {
+        Some((stem, ext)) => format!("{}.{}.{}", stem, version, ext),
+        None => format!("{}.{}", name, version),
+    };
+    Some(file.parent()?.join(BACKUP_DIR).join(name))
+}
+
+/// Copies a file into its backup folder before it's overwritten, keeping
+/// `keep` versions and deleting older ones. Does nothing if the file doesn't
+/// exist yet or `keep` is zero.
+pub fn backup_before_save(file: &Path, keep: usize) -> Result<()> {
+    if keep == 0 || !file.exists() {
+        return Ok(());
+    }
+    let newest = backup_path(file, 1).context("Can't back up a file without a name")?;
+    fs::create_dir_all(newest.parent().unwrap())?;
+    for version in (1..=keep).rev() {
+        let from = backup_path(file, version).unwrap();
+        if from.exists() {
+            if version == keep {
+                fs::remove_file(&from)?;
+            } else {
+                fs::rename(&from, backup_path(file, version + 1).unwrap())?;
+            }
+        }
+    }
+    tracing::debug!("Backing up {} to {}", file.display(), newest.display());
+    fs::copy(file, &newest).with_context(|| format!("Failed to back up {}", file.display()))?;
+    Ok(())
+}
+
+/// Backups of a file, newest first.
+pub fn list_backups(file: &Path) -> Vec<Backup> {
+    (1..)
+        .map_while(|version| {
+            let backup = backup_path(file, version)?;
+            backup.exists().then(|| Backup {
+                modified: fs::metadata(&backup).and_then(|m| m.modified()).ok(),
+                file: backup,
+                version,
+            })
+        })
+        .collect()
+}
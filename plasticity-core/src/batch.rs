@@ -0,0 +1,164 @@
+use crate::{
+    program::{find_programs, AIProgram},
+    validate::IssueKind,
+};
+use anyhow::Result;
+use serde::{Serialize, Serializer};
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+/// A problem found in one file of a batch.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportIssue {
+    /// Absolute index of the offending entry, or `None` for the demo table
+    pub entry: Option<usize>,
+    /// Category, number, and class of the entry, for reading without the file
+    pub label: String,
+    /// What's wrong, written out as English in JSON reports
+    #[serde(rename = "message", serialize_with = "serialize_display")]
+    pub kind: IssueKind,
+}
+
+fn serialize_display<S: Serializer>(kind: &IssueKind, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(kind)
+}
+
+/// Problems found in one AI program or actor pack.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub file: PathBuf,
+    pub issues: Vec<ReportIssue>,
+    /// Why the file couldn't be loaded, if it couldn't
+    pub error: Option<String>,
+}
+
+impl FileReport {
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty() && self.error.is_none()
+    }
+
+    /// Number of problems, counting a load failure as one.
+    pub fn count(&self) -> usize {
+        self.issues.len() + self.error.iter().count()
+    }
+}
+
+/// Problems found in every AI program and actor pack under a folder.
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub root: PathBuf,
+    pub files: Vec<FileReport>,
+}
+
+impl Report {
+    /// Files with at least one problem.
+    pub fn failed(&self) -> impl Iterator<Item = &FileReport> {
+        self.files.iter().filter(|file| !file.is_empty())
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Plain text form, listing only the files with problems.
+    pub fn to_text(&self) -> String {
+        let failed = self.failed().count();
+        let mut out = format!(
+            "Checked {} files in {}: ",
+            self.files.len(),
+            self.root.display()
+        );
+        if failed == 0 {
+            out.push_str("no problems found\n");
+            return out;
+        }
+        writeln!(out, "{} with problems", failed).unwrap();
+        for file in self.failed() {
+            writeln!(
+                out,
+                "\n{} ({})",
+                file.file
+                    .strip_prefix(&self.root)
+                    .unwrap_or(&file.file)
+                    .display(),
+                file.count()
+            )
+            .unwrap();
+            if let Some(error) = &file.error {
+                writeln!(out, "  Couldn't load it: {}", error).unwrap();
+            }
+            for issue in &file.issues {
+                writeln!(out, "  {}: {}", issue.label, issue.kind).unwrap();
+            }
+        }
+        out
+    }
+}
+
+/// Runs every check on each file, loading them with the given function, such
+/// as one which uses unsaved copies of files open in an editor. `progress` is
+/// given how many files are done and how many there are before each one, and
+/// can return false to stop early, leaving the rest out of the report.
+pub fn validate_files(
+    files: &[PathBuf],
+    load: impl Fn(&Path) -> Result<AIProgram>,
+    mut progress: impl FnMut(usize, usize) -> bool,
+) -> Vec<FileReport> {
+    files
+        .iter()
+        .enumerate()
+        .take_while(|(i, _)| progress(*i, files.len()))
+        .map(|(_, file)| match load(file) {
+            Ok(aiprog) => FileReport {
+                file: file.clone(),
+                issues: aiprog
+                    .validate_all()
+                    .into_iter()
+                    .chain(aiprog.validate_animations_in(file).unwrap_or_else(|e| {
+                        tracing::warn!("Couldn't read the ASList of {}: {:#}", file.display(), e);
+                        vec![]
+                    }))
+                    .chain(aiprog.validate_links_in(file).unwrap_or_else(|e| {
+                        tracing::warn!(
+                            "Couldn't read the ActorLink of {}: {:#}",
+                            file.display(),
+                            e
+                        );
+                        vec![]
+                    }))
+                    .map(|issue| ReportIssue {
+                        entry: issue.entry,
+                        label: match issue.entry {
+                            Some(idx) if idx < aiprog.len() => aiprog.entry_label(idx),
+                            _ => String::from("DemoAIActionIdx"),
+                        },
+                        kind: issue.kind,
+                    })
+                    .collect(),
+                error: None,
+            },
+            Err(e) => FileReport {
+                file: file.clone(),
+                issues: vec![],
+                error: Some(format!("{:#}", e)),
+            },
+        })
+        .collect()
+}
+
+/// Checks every AI program under a folder, including those in actor packs.
+pub fn validate_dir(root: &Path) -> Result<Report> {
+    if !root.is_dir() {
+        anyhow::bail!("{} is not a folder", root.display());
+    }
+    Ok(Report {
+        root: root.to_path_buf(),
+        files: validate_files(
+            &find_programs(root),
+            |file| AIProgram::new(file),
+            |_, _| true,
+        ),
+    })
+}
@@ -0,0 +1,48 @@
+use crate::program::AIProgram;
+use roead::aamp::{hash_name, ParamList, Parameter};
+use std::mem::discriminant;
+
+impl AIProgram {
+    /// Keys of every parameter in an object which any of the given entries
+    /// has, in the order they're first seen.
+    pub fn shared_params(&self, entries: &[usize], obj_name: &str) -> Vec<u32> {
+        let mut keys: Vec<u32> = vec![];
+        for idx in entries.iter().filter(|i| **i < self.len()) {
+            if let Some(obj) = self.item_at_index(*idx).objects().get(hash_name(obj_name)) {
+                for key in obj.params().keys() {
+                    if !keys.contains(key) {
+                        keys.push(*key);
+                    }
+                }
+            }
+        }
+        keys
+    }
+
+    /// Writes a value to a parameter of every given entry which already has it
+    /// with the same type. Returns the number of entries changed.
+    pub fn set_param_on(
+        &mut self,
+        entries: &[usize],
+        obj_name: &str,
+        key: u32,
+        value: &Parameter,
+    ) -> usize {
+        let len = self.len();
+        let mut count = 0;
+        for idx in entries.iter().filter(|i| **i < len) {
+            if let Some(param) = self
+                .item_mut_at_index(*idx)
+                .objects_mut()
+                .get_mut(hash_name(obj_name))
+                .and_then(|obj| obj.params_mut().get_mut(&key))
+            {
+                if discriminant(param) == discriminant(value) {
+                    *param = value.clone();
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
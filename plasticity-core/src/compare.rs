@@ -0,0 +1,54 @@
+use crate::program::AIProgram;
+use roead::aamp::{ParamList, Parameter};
+
+/// A parameter of two entries side by side, missing from either if the entry
+/// doesn't have it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamComparison {
+    pub object: u32,
+    pub key: u32,
+    pub left: Option<Parameter>,
+    pub right: Option<Parameter>,
+}
+
+impl ParamComparison {
+    pub fn differs(&self) -> bool {
+        self.left != self.right
+    }
+}
+
+impl AIProgram {
+    /// Lines up the parameters of two entries by object and name, in the
+    /// order of the first entry, followed by any only the second has.
+    pub fn compare_entries(&self, a: usize, b: usize) -> Vec<ParamComparison> {
+        let (left, right) = (self.item_at_index(a), self.item_at_index(b));
+        let mut rows: Vec<ParamComparison> = vec![];
+        for (obj_key, obj) in left.objects().inner() {
+            let other = right.objects().get(*obj_key);
+            for (k, v) in obj.params() {
+                rows.push(ParamComparison {
+                    object: *obj_key,
+                    key: *k,
+                    left: Some(v.clone()),
+                    right: other.and_then(|o| o.params().get(k)).cloned(),
+                });
+            }
+        }
+        for (obj_key, obj) in right.objects().inner() {
+            for (k, v) in obj.params() {
+                if !rows
+                    .iter()
+                    .any(|row| row.object == *obj_key && row.key == *k)
+                {
+                    rows.push(ParamComparison {
+                        object: *obj_key,
+                        key: *k,
+                        left: None,
+                        right: Some(v.clone()),
+                    });
+                }
+            }
+        }
+        rows
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{
+    program::{AIProgram, Category, ReferenceIndex},
+    util::AIDEFS,
+};
+use anyhow::{Context, Result};
+use roead::aamp::{hash_name, ParamList, Parameter};
+
+impl AIProgram {
+    /// Moves an AI to the end of the Action list, or an Action to the end of
+    /// the AI list, as a class of its new category. Every slot pointing at it
+    /// or at entries it moves past follows, and its parameters are fitted to
+    /// the new class as by [`AIProgram::migrate_class`]. Returns the entry's
+    /// new index and any parameters the new class doesn't define.
+    pub fn convert_entry(&mut self, idx: usize, class: &str) -> Result<(usize, Vec<String>)> {
+        let target = match self.category_of(idx) {
+            Category::AI => Category::Action,
+            Category::Action => Category::AI,
+            _ => anyhow::bail!("Only AIs and Actions can be converted"),
+        };
+        AIDEFS
+            .get_def(target, class)
+            .with_context(|| format!("{} is not a known {} class", class, target))?;
+        tracing::debug!("Converting entry {} to {} {}", idx, target, class);
+        let old = idx as i32;
+        let new = match target {
+            Category::AI => self.actions_offset(),
+            _ => self.behaviors_offset() - 1,
+        } as i32;
+        // Entries between the old and new positions move one step toward the
+        // old one
+        ReferenceIndex::new(self).remap(
+            self,
+            |i| {
+                if i == old {
+                    new
+                } else if new < old && i >= new && i < old {
+                    i + 1
+                } else if new > old && i > old && i <= new {
+                    i - 1
+                } else {
+                    i
+                }
+            },
+            |i| i,
+        );
+        let (source, local) = self.locate(idx);
+        let (_, mut entry) = self
+            .category_list_mut(source)
+            .lists_mut()
+            .inner_mut()
+            .shift_remove_index(local)
+            .context("Entry to convert is missing")?;
+        self.rekey_category(source);
+        // AIs and Actions both carry a name and a group name
+        let def = entry
+            .objects_mut()
+            .inner_mut()
+            .entry(hash_name("Def"))
+            .or_default()
+            .params_mut();
+        for key in ["Name", "GroupName"] {
+            def.entry(hash_name(key))
+                .or_insert_with(|| Parameter::StringRef(String::new()));
+        }
+        let target_name = target.to_string();
+        let list = self.category_list_mut(&target_name);
+        let key = format!("{}_{}", target_name, list.lists().len());
+        list.lists_mut().inner_mut().insert(hash_name(&key), entry);
+        let new = new as usize;
+        let undefined = self.migrate_class(new, class)?;
+        Ok((new, undefined))
+    }
+}
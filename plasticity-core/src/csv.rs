@@ -0,0 +1,347 @@
+use crate::{json::key_hash, program::AIProgram, util::try_name};
+use anyhow::{Context, Result};
+use roead::{
+    aamp::{hash_name, ParamList, Parameter},
+    types::{Color, Quat, Vector2f, Vector3f, Vector4f},
+};
+use std::{fs, path::Path};
+
+const HEADER: [&str; 7] = [
+    "Index", "Category", "Class", "Name", "Param", "Type", "Value",
+];
+
+/// Type name and text form of a parameter, with the components of vectors
+/// separated by spaces. Buffers and curves are left out.
+fn param_to_csv(param: &Parameter) -> Option<(&'static str, String)> {
+    let join = |v: &[f32]| {
+        v.iter()
+            .map(|f| f.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    Some(match param {
+        Parameter::Bool(b) => ("Bool", b.to_string()),
+        Parameter::F32(f) => ("F32", f.to_string()),
+        Parameter::Int(i) => ("Int", i.to_string()),
+        Parameter::U32(u) => ("U32", u.to_string()),
+        Parameter::String32(s) => ("String32", s.clone()),
+        Parameter::String64(s) => ("String64", s.clone()),
+        Parameter::String256(s) => ("String256", s.clone()),
+        Parameter::StringRef(s) => ("StringRef", s.clone()),
+        Parameter::Vec2(v) => ("Vec2", join(&[v.x, v.y])),
+        Parameter::Vec3(v) => ("Vec3", join(&[v.x, v.y, v.z])),
+        Parameter::Vec4(v) => ("Vec4", join(&[v.x, v.y, v.z, v.t])),
+        Parameter::Color(c) => ("Color", join(&[c.r, c.g, c.b, c.a])),
+        Parameter::Quat(q) => ("Quat", join(&[q.a, q.b, q.c, q.d])),
+        _ => return None,
+    })
+}
+
+/// Reads a value back as the same type as the parameter it replaces.
+fn param_from_csv(old: &Parameter, value: &str) -> Result<Parameter> {
+    let value = value.trim();
+    let floats = |count: usize| -> Result<Vec<f32>> {
+        let floats = value
+            .split_whitespace()
+            .map(|f| f.parse::<f32>().context("Expected a number"))
+            .collect::<Result<Vec<f32>>>()?;
+        if floats.len() != count {
+            anyhow::bail!("Expected {} numbers separated by spaces", count);
+        }
+        Ok(floats)
+    };
+    Ok(match old {
+        Parameter::Bool(_) => Parameter::Bool(match value.to_lowercase().as_str() {
+            "true" | "1" => true,
+            "false" | "0" => false,
+            _ => anyhow::bail!("Expected true or false"),
+        }),
+        Parameter::F32(_) => Parameter::F32(value.parse().context("Expected a number")?),
+        Parameter::Int(_) => Parameter::Int(value.parse().context("Expected an integer")?),
+        Parameter::U32(_) => {
+            Parameter::U32(value.parse().context("Expected a non-negative integer")?)
+        }
+        Parameter::String32(_) => Parameter::String32(value.to_owned()),
+        Parameter::String64(_) => Parameter::String64(value.to_owned()),
+        Parameter::String256(_) => Parameter::String256(value.to_owned()),
+        Parameter::StringRef(_) => Parameter::StringRef(value.to_owned()),
+        Parameter::Vec2(_) => {
+            let v = floats(2)?;
+            Parameter::Vec2(Vector2f { x: v[0], y: v[1] })
+        }
+        Parameter::Vec3(_) => {
+            let v = floats(3)?;
+            Parameter::Vec3(Vector3f {
+                x: v[0],
+                y: v[1],
+                z: v[2],
+            })
+        }
+        Parameter::Vec4(_) => {
+            let v = floats(4)?;
+            Parameter::Vec4(Vector4f {
+                x: v[0],
+                y: v[1],
+                z: v[2],
+                t: v[3],
+            })
+        }
+        Parameter::Color(_) => {
+            let v = floats(4)?;
+            Parameter::Color(Color {
+                r: v[0],
+                g: v[1],
+                b: v[2],
+                a: v[3],
+            })
+        }
+        Parameter::Quat(_) => {
+            let v = floats(4)?;
+            Parameter::Quat(Quat {
+                a: v[0],
+                b: v[1],
+                c: v[2],
+                d: v[3],
+            })
+        }
+        _ => anyhow::bail!("This parameter type can't be set from a CSV"),
+    })
+}
+
+fn escape(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Splits CSV text into rows of fields, allowing quoted fields with commas,
+/// doubled quotes, and line breaks in them.
+fn parse_rows(text: &str) -> Vec<Vec<String>> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => quoted = true,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            ('\r', false) => (),
+            (c, _) => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+impl AIProgram {
+    /// Flattens the SInst parameters of every entry into CSV rows, one per
+    /// parameter, for editing in a spreadsheet.
+    pub fn to_csv(&self) -> String {
+        let mut out = HEADER.join(",");
+        out.push('\n');
+        for (i, item) in self.items().into_iter().enumerate() {
+            let params = match item.objects().get(hash_name("SInst")) {
+                Some(obj) => obj.params(),
+                None => continue,
+            };
+            let def = item.objects().get(hash_name("Def"));
+            let def_string = |key: &str| {
+                def.and_then(|def| def.params().get(&hash_name(key)))
+                    .and_then(|v| v.as_string().ok())
+                    .unwrap_or_default()
+                    .to_owned()
+            };
+            for (k, v) in params {
+                if let Some((kind, value)) = param_to_csv(v) {
+                    let fields = [
+                        i.to_string(),
+                        self.category_of(i).to_string(),
+                        def_string("ClassName"),
+                        def_string("Name"),
+                        try_name(*k),
+                        kind.to_owned(),
+                        value,
+                    ];
+                    out.push_str(
+                        &fields
+                            .iter()
+                            .map(|f| escape(f))
+                            .collect::<Vec<_>>()
+                            .join(","),
+                    );
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+
+    /// Sets SInst parameters from CSV rows in the layout
+    /// [`AIProgram::to_csv`] writes. Every row is checked against the entry's
+    /// class and the parameter's type first, and nothing is changed if any of
+    /// them are wrong. Returns the number of parameters changed.
+    pub fn apply_csv(&mut self, text: &str) -> Result<usize> {
+        let rows = parse_rows(text);
+        let header = rows.first().context("The file is empty")?;
+        let column = |name: &str| {
+            header
+                .iter()
+                .position(|h| h.trim() == name)
+                .with_context(|| format!("Missing the {} column", name))
+        };
+        let (index, class, param, kind, value) = (
+            column("Index")?,
+            column("Class")?,
+            column("Param")?,
+            column("Type")?,
+            column("Value")?,
+        );
+        let mut changes = vec![];
+        let mut errors = vec![];
+        for (line, row) in rows.iter().enumerate().skip(1) {
+            if row.iter().all(|f| f.trim().is_empty()) {
+                continue;
+            }
+            let field = |i: usize| row.get(i).map(|f| f.as_str()).unwrap_or_default();
+            let change = (|| -> Result<Option<(usize, u32, Parameter)>> {
+                let idx: usize = field(index).trim().parse().context("Bad entry index")?;
+                if idx >= self.len() {
+                    anyhow::bail!("There is no entry {}", idx);
+                }
+                let actual = self.entry_name_from_index(idx).unwrap_or_default();
+                if actual != field(class).trim() {
+                    anyhow::bail!("Entry {} is a {}, not a {}", idx, actual, field(class));
+                }
+                let key = key_hash(field(param).trim());
+                let old = self
+                    .item_at_index(idx)
+                    .objects()
+                    .get(hash_name("SInst"))
+                    .and_then(|obj| obj.params().get(&key))
+                    .with_context(|| format!("Entry {} has no parameter {}", idx, field(param)))?;
+                match param_to_csv(old) {
+                    Some((old_kind, _)) if old_kind == field(kind).trim() => (),
+                    Some((old_kind, _)) => {
+                        anyhow::bail!("{} is a {}, not a {}", field(param), old_kind, field(kind))
+                    }
+                    None => anyhow::bail!("{} can't be set from a CSV", field(param)),
+                }
+                let new = param_from_csv(old, field(value))
+                    .with_context(|| format!("Bad value for {}", field(param)))?;
+                Ok(if new != *old {
+                    Some((idx, key, new))
+                } else {
+                    None
+                })
+            })();
+            match change {
+                Ok(Some(change)) => changes.push(change),
+                Ok(None) => (),
+                Err(e) => errors.push(format!("Line {}: {:#}", line + 1, e)),
+            }
+        }
+        if !errors.is_empty() {
+            anyhow::bail!(
+                "Nothing was changed, because of these problems:\n{}",
+                errors.join("\n")
+            );
+        }
+        let count = changes.len();
+        for (idx, key, new) in changes {
+            if let Some(obj) = self
+                .item_mut_at_index(idx)
+                .objects_mut()
+                .get_mut(hash_name("SInst"))
+            {
+                obj.params_mut().insert(key, new);
+            }
+        }
+        Ok(count)
+    }
+
+    pub fn save_csv(&self, file: &Path) -> Result<()> {
+        fs::write(file, self.to_csv())?;
+        Ok(())
+    }
+
+    pub fn load_csv(&mut self, file: &Path) -> Result<usize> {
+        self.apply_csv(&fs::read_to_string(file)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::tests::{fixture, param};
+
+    #[test]
+    fn parse_quoted_rows() {
+        let text = "a,\"b,\"\"c\"\"\"\r\nd,\"e\nf\"\n,\n";
+        assert_eq!(
+            parse_rows(text),
+            vec![
+                vec!["a".to_owned(), "b,\"c\"".to_owned()],
+                vec!["d".to_owned(), "e\nf".to_owned()],
+                vec![String::new(), String::new()],
+            ]
+        );
+        let field = "say \"hi\", then\nleave";
+        assert_eq!(parse_rows(&escape(field)), vec![vec![field.to_owned()]]);
+    }
+
+    #[test]
+    fn round_trip_unchanged() {
+        let mut aiprog = fixture();
+        let csv = aiprog.to_csv();
+        // The Wait's four parameters and the behavior's one
+        assert_eq!(parse_rows(&csv).len(), 6);
+        assert_eq!(aiprog.apply_csv(&csv).unwrap(), 0);
+        assert_eq!(aiprog, fixture());
+    }
+
+    #[test]
+    fn apply_changed_values() {
+        let mut aiprog = fixture();
+        let csv = aiprog
+            .to_csv()
+            .replace("Time,Int,30", "Time,Int,45")
+            .replace("PosReduceRatio,F32,0.5", "PosReduceRatio,F32,0.25");
+        assert_eq!(aiprog.apply_csv(&csv).unwrap(), 2);
+        assert_eq!(
+            param(&aiprog, 2, "SInst", "Time"),
+            Some(&Parameter::Int(45))
+        );
+        assert_eq!(
+            param(&aiprog, 2, "SInst", "PosReduceRatio"),
+            Some(&Parameter::F32(0.25))
+        );
+    }
+
+    #[test]
+    fn bad_rows_change_nothing() {
+        let mut aiprog = fixture();
+        let csv = aiprog
+            .to_csv()
+            .replace("Time,Int,30", "Time,Int,45")
+            .replace("TimeRand,Int,10", "TimeRand,Int,soon");
+        assert!(aiprog.apply_csv(&csv).is_err());
+        let wrong_class = "Index,Class,Param,Type,Value\n2,IdleAction,Time,Int,45\n";
+        assert!(aiprog.apply_csv(wrong_class).is_err());
+        assert_eq!(aiprog, fixture());
+    }
+}
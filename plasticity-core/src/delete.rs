@@ -0,0 +1,273 @@
+use crate::program::{AIProgram, Category, ReferenceIndex};
+use anyhow::Result;
+use roead::aamp::Parameter;
+use std::collections::{BTreeSet, HashMap};
+
+/// What to do with the children of an entry being deleted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeleteMode {
+    /// Leave them where they are, as roots if nothing else uses them
+    Orphan,
+    /// Delete every descendant nothing outside the subtree uses as well
+    Recursive,
+    /// Point whatever used the entry at this child of it instead
+    Splice(usize),
+}
+
+/// Everything deleting an entry will change elsewhere in the program, worked
+/// out before it's deleted.
+#[derive(Debug, Clone, Default)]
+pub struct DeletePreview {
+    pub entry: usize,
+    /// Holder and key of each `ChildIdx` slot which will be set to -1
+    pub children: Vec<(usize, u32)>,
+    /// Holder and key of each `BehaviorIdx` slot which will be set to -1
+    pub behaviours: Vec<(usize, u32)>,
+    /// Keys of demo slots which will be set to -1
+    pub demos: Vec<u32>,
+    /// How many entries after the deleted one move down an index
+    pub shifted_entries: usize,
+    /// How many slots point at those entries, and so will be changed to follow
+    pub shifted_slots: usize,
+}
+
+impl DeletePreview {
+    /// Whether anything points at the entry, so deleting it leaves empty slots.
+    pub fn breaks_references(&self) -> bool {
+        !(self.children.is_empty() && self.behaviours.is_empty() && self.demos.is_empty())
+    }
+}
+
+/// Everything deleting several entries at once will change elsewhere in the
+/// program. Slots held by the entries themselves aren't counted, as they go
+/// with them.
+#[derive(Debug, Clone, Default)]
+pub struct DeleteEntriesPreview {
+    pub entries: BTreeSet<usize>,
+    /// Holder and key of each `ChildIdx` or `BehaviorIdx` slot of another
+    /// entry which will be set to -1
+    pub slots: Vec<(usize, u32)>,
+    /// Keys of demo slots which will be set to -1
+    pub demos: Vec<u32>,
+}
+
+/// How many slots in part of a [`ReferenceIndex`] hold an index after the
+/// removed one.
+fn slots_after<T>(map: &HashMap<i32, Vec<T>>, removed: i32) -> usize {
+    map.iter()
+        .filter(|(i, _)| **i > removed)
+        .map(|(_, slots)| slots.len())
+        .sum()
+}
+
+impl AIProgram {
+    /// Works out what [`AIProgram::delete_entry`] would change, without
+    /// changing anything.
+    pub fn delete_preview(&self, idx: usize) -> DeletePreview {
+        let refs = self.references(idx);
+        let index = ReferenceIndex::new(self);
+        let mut shifted_slots =
+            slots_after(&index.children, idx as i32) + slots_after(&index.demos, idx as i32);
+        if self.category_of(idx) == Category::Behaviour {
+            shifted_slots += slots_after(&index.behaviours, (idx - self.behaviors_offset()) as i32);
+        }
+        DeletePreview {
+            entry: idx,
+            children: refs.children,
+            behaviours: refs.behaviours,
+            demos: refs.demos,
+            shifted_entries: self.len().saturating_sub(idx + 1),
+            shifted_slots,
+        }
+    }
+}
+
+impl AIProgram {
+    /// Works out what [`AIProgram::delete_entries`] would change, without
+    /// changing anything.
+    pub fn delete_entries_preview(&self, indexes: &BTreeSet<usize>) -> DeleteEntriesPreview {
+        let mut slots = vec![];
+        let mut demos = vec![];
+        for idx in indexes {
+            let refs = self.references(*idx);
+            slots.extend(
+                refs.children
+                    .into_iter()
+                    .chain(refs.behaviours)
+                    .filter(|(holder, _)| !indexes.contains(holder)),
+            );
+            demos.extend(refs.demos);
+        }
+        DeleteEntriesPreview {
+            entries: indexes.clone(),
+            slots,
+            demos,
+        }
+    }
+
+    /// The entry and every descendant through `ChildIdx` which nothing outside
+    /// that set refers to, so they can all go together.
+    pub fn exclusive_subtree(&self, idx: usize) -> BTreeSet<usize> {
+        let index = ReferenceIndex::new(self);
+        let mut subtree = BTreeSet::from([idx]);
+        loop {
+            let added: Vec<usize> = subtree
+                .iter()
+                .flat_map(|i| self.children_of(*i))
+                .filter(|child| !subtree.contains(child))
+                .filter(|child| {
+                    let key = *child as i32;
+                    !index.demos.contains_key(&key)
+                        && index
+                            .children
+                            .get(&key)
+                            .into_iter()
+                            .flatten()
+                            .all(|(holder, _)| subtree.contains(holder))
+                })
+                .collect();
+            if added.is_empty() {
+                return subtree;
+            }
+            subtree.extend(added);
+        }
+    }
+
+    /// Points every `ChildIdx` and demo slot using an entry at one of its
+    /// children instead, then deletes it.
+    pub fn splice_entry(&mut self, idx: usize, child: usize) -> Result<()> {
+        if !self.children_of(idx).contains(&child) {
+            anyhow::bail!(
+                "{} is not a child of {}",
+                self.entry_label(child),
+                self.entry_label(idx)
+            );
+        }
+        let refs = self.references(idx);
+        for (holder, key) in refs.children {
+            self.set_slot(holder, "ChildIdx", key, child as i32);
+        }
+        for key in refs.demos {
+            self.demos_mut()
+                .params_mut()
+                .insert(key, Parameter::Int(child as i32));
+        }
+        self.delete_entry(idx)
+    }
+
+    /// Deletes an entry, dealing with its children as chosen.
+    pub fn delete_with_mode(&mut self, idx: usize, mode: DeleteMode) -> Result<()> {
+        match mode {
+            DeleteMode::Orphan => self.delete_entry(idx),
+            DeleteMode::Splice(child) => self.splice_entry(idx, child),
+            DeleteMode::Recursive => self.delete_entries(&self.exclusive_subtree(idx)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::tests::{demo, fixture, param};
+    use roead::aamp::hash_name;
+
+    #[test]
+    fn preview_delete() {
+        let aiprog = fixture();
+        let preview = aiprog.delete_preview(2);
+        assert_eq!(preview.children, vec![(1, hash_name("Wait"))]);
+        assert!(preview.behaviours.is_empty() && preview.demos.is_empty());
+        assert!(preview.breaks_references());
+        // The IdleAction, behavior, and query move down, and the ForDemo
+        // slot and the demo follow the IdleAction
+        assert_eq!(preview.shifted_entries, 3);
+        assert_eq!(preview.shifted_slots, 2);
+        assert!(!aiprog.delete_preview(5).breaks_references());
+    }
+
+    #[test]
+    fn delete_shifts_indexes() {
+        let mut aiprog = fixture();
+        aiprog.delete_entry(2).unwrap();
+        assert_eq!(aiprog.len(), 5);
+        assert_eq!(aiprog.entry_name_from_index(2).unwrap(), "IdleAction");
+        assert_eq!(
+            param(&aiprog, 1, "ChildIdx", "Wait"),
+            Some(&Parameter::Int(-1))
+        );
+        assert_eq!(
+            param(&aiprog, 0, "ChildIdx", "ForDemo"),
+            Some(&Parameter::Int(2))
+        );
+        assert_eq!(demo(&aiprog, "Demo_Idle"), Some(2));
+        assert!(aiprog.delete_entry(5).is_err());
+    }
+
+    #[test]
+    fn delete_behavior() {
+        let mut aiprog = fixture();
+        assert_eq!(
+            aiprog.delete_preview(4).behaviours,
+            vec![(2, hash_name("DamageCallback"))]
+        );
+        aiprog.delete_entry(4).unwrap();
+        assert_eq!(aiprog.behaviors().len(), 0);
+        assert_eq!(aiprog.queries_offset(), 4);
+        assert_eq!(
+            param(&aiprog, 2, "BehaviorIdx", "DamageCallback"),
+            Some(&Parameter::Int(-1))
+        );
+    }
+
+    #[test]
+    fn delete_several() {
+        let mut aiprog = fixture();
+        let indexes = BTreeSet::from([1, 2]);
+        let preview = aiprog.delete_entries_preview(&indexes);
+        // The SleepBedRoot's slot for the Wait goes with it
+        assert_eq!(preview.slots, vec![(0, hash_name("Normal"))]);
+        assert!(preview.demos.is_empty());
+        aiprog.delete_entries(&indexes).unwrap();
+        assert_eq!(aiprog.len(), 4);
+        assert_eq!(
+            param(&aiprog, 0, "ChildIdx", "Normal"),
+            Some(&Parameter::Int(-1))
+        );
+        assert_eq!(
+            param(&aiprog, 0, "ChildIdx", "ForDemo"),
+            Some(&Parameter::Int(1))
+        );
+        assert_eq!(demo(&aiprog, "Demo_Idle"), Some(1));
+        assert_eq!(aiprog.behaviors_offset(), 2);
+    }
+
+    #[test]
+    fn delete_modes() {
+        let aiprog = fixture();
+        assert_eq!(aiprog.exclusive_subtree(1), BTreeSet::from([1, 2]));
+        // The IdleAction is also a demo's, so it stays
+        assert_eq!(aiprog.exclusive_subtree(0), BTreeSet::from([0, 1, 2]));
+
+        let mut recursive = fixture();
+        recursive
+            .delete_with_mode(1, DeleteMode::Recursive)
+            .unwrap();
+        let mut several = fixture();
+        several.delete_entries(&BTreeSet::from([1, 2])).unwrap();
+        assert_eq!(recursive, several);
+
+        let mut spliced = fixture();
+        assert!(spliced.splice_entry(1, 3).is_err());
+        spliced.delete_with_mode(1, DeleteMode::Splice(2)).unwrap();
+        assert_eq!(spliced.len(), 5);
+        assert_eq!(spliced.entry_name_from_index(1).unwrap(), "Wait");
+        assert_eq!(
+            param(&spliced, 0, "ChildIdx", "Normal"),
+            Some(&Parameter::Int(1))
+        );
+        assert_eq!(
+            param(&spliced, 0, "ChildIdx", "ForDemo"),
+            Some(&Parameter::Int(2))
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{
+    program::{AIProgram, Category},
+    util::*,
+};
+use anyhow::Result;
+use roead::aamp::{hash_name, ParamList, Parameter};
+use std::{collections::BTreeSet, fmt::Write, path::Path};
+
+/// A piece of a program description, written out as Markdown or HTML.
+enum Block {
+    Heading(usize, String),
+    Text(String),
+    /// Lines of a bulleted list with how deeply each is nested
+    List(Vec<(usize, String)>),
+    Table(Vec<String>, Vec<Vec<String>>),
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn to_markdown(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                writeln!(out, "{} {}\n", "#".repeat(*level), text).unwrap()
+            }
+            Block::Text(text) => writeln!(out, "{}\n", text).unwrap(),
+            Block::List(items) => {
+                for (depth, text) in items {
+                    writeln!(out, "{}- {}", "  ".repeat(*depth), text).unwrap();
+                }
+                out.push('\n');
+            }
+            Block::Table(headers, rows) => {
+                writeln!(out, "| {} |", headers.join(" | ")).unwrap();
+                writeln!(out, "|{}", " --- |".repeat(headers.len())).unwrap();
+                for row in rows {
+                    let cells: Vec<String> = row.iter().map(|c| escape_markdown(c)).collect();
+                    writeln!(out, "| {} |", cells.join(" | ")).unwrap();
+                }
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+fn to_html(title: &str, blocks: &[Block]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>body {{ font-family: sans-serif; }} table {{ border-collapse: collapse; }} \
+         td, th {{ border: 1px solid #999; padding: 2px 6px; text-align: left; }}</style>\n\
+         </head>\n<body>\n",
+        escape_html(title)
+    );
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                writeln!(out, "<h{0}>{1}</h{0}>", level, escape_html(text)).unwrap()
+            }
+            Block::Text(text) => writeln!(out, "<p>{}</p>", escape_html(text)).unwrap(),
+            Block::List(items) => {
+                let mut depth = 0;
+                out.push_str("<ul>\n");
+                for (item_depth, text) in items {
+                    while depth < *item_depth {
+                        out.push_str("<ul>\n");
+                        depth += 1;
+                    }
+                    while depth > *item_depth {
+                        out.push_str("</ul>\n");
+                        depth -= 1;
+                    }
+                    writeln!(out, "<li>{}</li>", escape_html(text)).unwrap();
+                }
+                out.push_str(&"</ul>\n".repeat(depth + 1));
+            }
+            Block::Table(headers, rows) => {
+                out.push_str("<table>\n<tr>");
+                for header in headers {
+                    write!(out, "<th>{}</th>", escape_html(header)).unwrap();
+                }
+                out.push_str("</tr>\n");
+                for row in rows {
+                    out.push_str("<tr>");
+                    for cell in row {
+                        write!(out, "<td>{}</td>", escape_html(cell)).unwrap();
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</table>\n");
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+impl AIProgram {
+    /// Lines of the tree below an entry, with the slot each child fills.
+    /// Entries already shown are listed again but not followed, so shared
+    /// subtrees are only written out once.
+    fn tree_lines(
+        &self,
+        idx: usize,
+        depth: usize,
+        shown: &mut BTreeSet<usize>,
+    ) -> Vec<(usize, String)> {
+        let mut lines = vec![];
+        let children: Vec<(u32, usize)> = self
+            .item_at_index(idx)
+            .objects()
+            .get(hash_name("ChildIdx"))
+            .map(|obj| {
+                obj.params()
+                    .iter()
+                    .filter_map(|(k, v)| match v {
+                        Parameter::Int(i) if *i >= 0 && (*i as usize) < self.behaviors_offset() => {
+                            Some((*k, *i as usize))
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        for (slot, child) in children {
+            let seen = !shown.insert(child);
+            lines.push((
+                depth,
+                format!(
+                    "{}: {}{}",
+                    translate(&try_name(slot)),
+                    self.entry_label(child),
+                    if seen { " (see above)" } else { "" }
+                ),
+            ));
+            if !seen {
+                lines.extend(self.tree_lines(child, depth + 1, shown));
+            }
+        }
+        lines
+    }
+
+    fn describe_entry(&self, idx: usize, blocks: &mut Vec<Block>) {
+        let item = self.item_at_index(idx);
+        blocks.push(Block::Heading(3, self.entry_label(idx)));
+        let mut facts = vec![];
+        if let Some(def) = item.objects().get(hash_name("Def")) {
+            for (k, v) in def.params() {
+                facts.push((
+                    0,
+                    format!("{}: {}", try_name(*k), translate(&param_string(v))),
+                ));
+            }
+        }
+        for (obj_name, title, offset) in [
+            ("ChildIdx", "Children", 0),
+            ("BehaviorIdx", "Behaviors", self.behaviors_offset()),
+        ] {
+            if let Some(obj) = item.objects().get(hash_name(obj_name)) {
+                facts.push((0, title.to_owned()));
+                for (k, v) in obj.params() {
+                    let target = match v {
+                        Parameter::Int(i) if *i >= 0 && offset + (*i as usize) < self.len() => {
+                            self.entry_label(offset + *i as usize)
+                        }
+                        _ => String::from("not set"),
+                    };
+                    facts.push((1, format!("{}: {}", translate(&try_name(*k)), target)));
+                }
+            }
+        }
+        let refs = self.references(idx);
+        if !refs.is_empty() {
+            facts.push((0, String::from("Used by")));
+            for key in &refs.demos {
+                facts.push((1, format!("Demo {}", try_name(*key))));
+            }
+            for (holder, key) in refs.children.iter().chain(refs.behaviours.iter()) {
+                facts.push((
+                    1,
+                    format!(
+                        "{} ({})",
+                        self.entry_label(*holder),
+                        translate(&try_name(*key))
+                    ),
+                ));
+            }
+        }
+        blocks.push(Block::List(facts));
+        let skip = [
+            hash_name("Def"),
+            hash_name("ChildIdx"),
+            hash_name("BehaviorIdx"),
+        ];
+        let rows: Vec<Vec<String>> = item
+            .objects()
+            .inner()
+            .iter()
+            .filter(|(k, _)| !skip.contains(k))
+            .flat_map(|(obj_key, obj)| {
+                obj.params()
+                    .iter()
+                    .map(move |(k, v)| vec![try_name(*obj_key), try_name(*k), param_string(v)])
+            })
+            .collect();
+        if !rows.is_empty() {
+            blocks.push(Block::Table(
+                vec!["Object".into(), "Parameter".into(), "Value".into()],
+                rows,
+            ));
+        }
+    }
+
+    fn describe(&self, title: &str) -> Vec<Block> {
+        let mut blocks = vec![
+            Block::Heading(1, title.to_owned()),
+            Block::Text(format!(
+                "{} AIs, {} Actions, {} Behaviors and {} Queries.",
+                self.ais().len(),
+                self.actions().len(),
+                self.behaviors().len(),
+                self.queries().len()
+            )),
+        ];
+        let demos: Vec<(usize, String)> = self
+            .demos()
+            .params()
+            .iter()
+            .filter_map(|(k, v)| match v {
+                Parameter::Int(i) if *i >= 0 && (*i as usize) < self.len() => Some((
+                    0,
+                    format!("{}: {}", try_name(*k), self.entry_label(*i as usize)),
+                )),
+                _ => None,
+            })
+            .collect();
+        if !demos.is_empty() {
+            blocks.push(Block::Heading(2, "Demos".into()));
+            blocks.push(Block::List(demos));
+        }
+        blocks.push(Block::Heading(2, "Tree".into()));
+        let mut shown = BTreeSet::new();
+        let mut lines = vec![];
+        for root in self.roots() {
+            shown.insert(root);
+            lines.push((0, self.entry_label(root)));
+            lines.extend(self.tree_lines(root, 1, &mut shown));
+        }
+        blocks.push(Block::List(lines));
+        for (category, title) in [
+            (Category::AI, "AIs"),
+            (Category::Action, "Actions"),
+            (Category::Behaviour, "Behaviors"),
+            (Category::Query, "Queries"),
+        ] {
+            let start = self.category_offset(category);
+            let end = match category {
+                Category::AI => self.actions_offset(),
+                Category::Action => self.behaviors_offset(),
+                Category::Behaviour => self.queries_offset(),
+                Category::Query => self.len(),
+            };
+            if start < end {
+                blocks.push(Block::Heading(2, title.into()));
+                for idx in start..end {
+                    self.describe_entry(idx, &mut blocks);
+                }
+            }
+        }
+        blocks
+    }
+
+    /// Describes the program for people who don't use the editor: its tree,
+    /// and every entry's class, parameters and what uses it, as Markdown.
+    pub fn describe_markdown(&self, title: &str) -> String {
+        to_markdown(&self.describe(title))
+    }
+
+    /// The description given by [`AIProgram::describe_markdown`] as a
+    /// standalone HTML page.
+    pub fn describe_html(&self, title: &str) -> String {
+        to_html(title, &self.describe(title))
+    }
+
+    /// Writes a description of the program to a file, as HTML for `.html` and
+    /// `.htm` files and as Markdown otherwise.
+    pub fn save_description(&self, file: &Path, title: &str) -> Result<()> {
+        let text = match file.extension().and_then(|ext| ext.to_str()) {
+            Some("html") | Some("htm") => self.describe_html(title),
+            _ => self.describe_markdown(title),
+        };
+        std::fs::write(file, text)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+//! Checks of the GameData flags string parameters name against the flags the
+//! game defines, as a misspelled flag is quietly treated as never set.
+use crate::{
+    program::{dump_content_dir, read_sarc, AIProgram},
+    util::{name_words, try_name},
+    validate::{Issue, IssueKind},
+};
+use anyhow::{Context, Result};
+use roead::{
+    aamp::{hash_name, ParamList, Parameter},
+    byml::Byml,
+    sarc::Sarc,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Where the flag definitions are kept in `Bootup.pack`
+const GAMEDATA_PATH: &str = "GameData/gamedata.ssarc";
+
+/// Names of the GameData flags the game defines, sorted for looking them up.
+#[derive(Debug, Clone, Default)]
+pub struct FlagList(Vec<String>);
+
+impl FlagList {
+    /// Reads flags from `gamedata.ssarc`, from the `Bootup.pack` holding it, or
+    /// from a text file listing a flag on each line.
+    pub fn read(file: &Path) -> Result<Self> {
+        let data = fs::read(file)?;
+        if file.extension().map(|ext| ext == "txt").unwrap_or(false) {
+            return Ok(std::str::from_utf8(&data)
+                .context("Flag list is not UTF-8")?
+                .lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| line.to_owned())
+                .collect());
+        }
+        let sarc = read_sarc(&data)?;
+        match sarc.get_file_data(GAMEDATA_PATH) {
+            Some(gamedata) => Self::from_gamedata(&read_sarc(gamedata)?),
+            None => Self::from_gamedata(&sarc),
+        }
+    }
+
+    /// Reads every flag of every type from the `.bgdata` files of
+    /// `gamedata.ssarc`.
+    fn from_gamedata(sarc: &Sarc) -> Result<Self> {
+        let mut flags = vec![];
+        for file in sarc.files() {
+            if !file.name().map(|n| n.ends_with(".bgdata")).unwrap_or(false) {
+                continue;
+            }
+            // Each file has one list of flags, named after their type
+            if let Byml::Hash(root) = Byml::from_binary(file.data())? {
+                for list in root.values() {
+                    if let Byml::Array(list) = list {
+                        flags.extend(list.iter().filter_map(|flag| match flag {
+                            Byml::Hash(flag) => match flag.get("DataName") {
+                                Some(Byml::String(name)) => Some(name.to_string()),
+                                _ => None,
+                            },
+                            _ => None,
+                        }));
+                    }
+                }
+            }
+        }
+        anyhow::ensure!(!flags.is_empty(), "No GameData flags found");
+        Ok(flags.into_iter().collect())
+    }
+
+    pub fn contains(&self, flag: &str) -> bool {
+        self.0.binary_search_by(|f| f.as_str().cmp(flag)).is_ok()
+    }
+
+    pub fn names(&self) -> &Vec<String> {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromIterator<String> for FlagList {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        let mut flags: Vec<String> = iter.into_iter().collect();
+        flags.sort();
+        flags.dedup();
+        Self(flags)
+    }
+}
+
+/// The `Bootup.pack` of a game dump, which holds the flag definitions.
+pub fn dump_bootup_pack(dump: &Path) -> Result<PathBuf> {
+    let pack = dump_content_dir(dump)?.join("Pack/Bootup.pack");
+    anyhow::ensure!(pack.exists(), "No Bootup.pack found in {}", dump.display());
+    Ok(pack)
+}
+
+/// Whether a parameter's name says it holds the name of a GameData flag, like
+/// `FlagName` or `CheckFlag`.
+pub fn names_flag(name: &str) -> bool {
+    name_words(name).iter().any(|word| word == "flag")
+}
+
+/// Whether the flag a parameter names is defined, or `None` if it doesn't
+/// name one. Empty names are left alone, since they mean no flag.
+pub fn flag_exists(name: &str, param: &Parameter, flags: &FlagList) -> Option<bool> {
+    if !names_flag(name) {
+        return None;
+    }
+    let value = param.as_string().ok().filter(|value| !value.is_empty())?;
+    Some(flags.contains(value))
+}
+
+impl AIProgram {
+    /// `SInst` and `MInst` parameters naming a flag which isn't defined.
+    pub fn validate_flags(&self, flags: &FlagList) -> Vec<Issue> {
+        let mut issues = vec![];
+        for idx in 0..self.len() {
+            let objects = self.item_at_index(idx).objects();
+            for obj_name in ["SInst", "MInst"] {
+                if let Some(obj) = objects.get(hash_name(obj_name)) {
+                    for (k, v) in obj.params() {
+                        let name = try_name(*k);
+                        if flag_exists(&name, v, flags) == Some(false) {
+                            issues.push(Issue::new(
+                                Some(idx),
+                                IssueKind::UnknownFlag {
+                                    obj: obj_name,
+                                    name,
+                                    flag: v.as_string().unwrap_or_default().to_owned(),
+                                },
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        issues
+    }
+}
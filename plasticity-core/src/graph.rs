@@ -0,0 +1,109 @@
+use crate::{program::AIProgram, util::*};
+use anyhow::Result;
+use roead::aamp::{hash_name, ParamList, Parameter};
+use std::{fmt::Write, path::Path};
+
+/// A link between two entries through a `ChildIdx` or `BehaviorIdx` slot.
+struct Edge {
+    from: usize,
+    to: usize,
+    slot: String,
+    behaviour: bool,
+}
+
+fn slot_name(key: u32) -> String {
+    translate(&try_name(key))
+}
+
+impl AIProgram {
+    fn edges(&self) -> Vec<Edge> {
+        let mut edges = vec![];
+        let behaviors_offset = self.behaviors_offset();
+        for (from, item) in self.items().into_iter().take(behaviors_offset).enumerate() {
+            for (obj_name, behaviour) in [("ChildIdx", false), ("BehaviorIdx", true)] {
+                if let Some(obj) = item.objects().get(hash_name(obj_name)) {
+                    for (k, v) in obj.params().iter() {
+                        let to = match v {
+                            Parameter::Int(i) if *i >= 0 && behaviour => {
+                                behaviors_offset + *i as usize
+                            }
+                            Parameter::Int(i) if *i >= 0 => *i as usize,
+                            _ => continue,
+                        };
+                        if to < self.queries_offset() {
+                            edges.push(Edge {
+                                from,
+                                to,
+                                slot: slot_name(*k),
+                                behaviour,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        edges
+    }
+
+    /// Renders the entry hierarchy as a Graphviz DOT graph. Behavior links are
+    /// drawn dashed.
+    pub fn to_dot(&self) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut out = String::from("digraph AIProgram {\n    node [shape=box];\n");
+        for i in 0..self.queries_offset() {
+            writeln!(
+                out,
+                "    n{} [label=\"{}\"];",
+                i,
+                escape(&self.entry_label(i))
+            )
+            .unwrap();
+        }
+        for edge in self.edges() {
+            writeln!(
+                out,
+                "    n{} -> n{} [label=\"{}\"{}];",
+                edge.from,
+                edge.to,
+                escape(&edge.slot),
+                if edge.behaviour { ", style=dashed" } else { "" }
+            )
+            .unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Renders the entry hierarchy as a Mermaid flowchart. Behavior links are
+    /// drawn dashed.
+    pub fn to_mermaid(&self) -> String {
+        let escape = |s: &str| s.replace('"', "#quot;").replace('|', "#124;");
+        let mut out = String::from("graph TD\n");
+        for i in 0..self.queries_offset() {
+            writeln!(out, "    n{}[\"{}\"]", i, escape(&self.entry_label(i))).unwrap();
+        }
+        for edge in self.edges() {
+            writeln!(
+                out,
+                "    n{} {}|\"{}\"| n{}",
+                edge.from,
+                if edge.behaviour { "-.->" } else { "-->" },
+                escape(&edge.slot),
+                edge.to
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    /// Writes the hierarchy to a file, as Mermaid for `.mmd` and `.md` files
+    /// and as DOT otherwise.
+    pub fn save_graph(&self, file: &Path) -> Result<()> {
+        let text = match file.extension().and_then(|ext| ext.to_str()) {
+            Some("mmd") | Some("md") => self.to_mermaid(),
+            _ => self.to_dot(),
+        };
+        std::fs::write(file, text)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{program::AIProgram, util::*};
+use anyhow::{Context, Result};
+use roead::{
+    aamp::{hash_name, ParamList, Parameter, ParameterIO, ParameterList, ParameterObject},
+    types::{Color, Quat, Vector2f, Vector3f, Vector4f},
+};
+use serde_json::{json, Map, Value};
+use std::{fs, path::Path};
+
+/// Names which couldn't be resolved are written as their hash, so read those
+/// back as-is.
+pub(crate) fn key_hash(name: &str) -> u32 {
+    name.parse().unwrap_or_else(|_| hash_name(name))
+}
+
+fn param_to_json(param: &Parameter) -> Result<Value> {
+    Ok(match param {
+        Parameter::Bool(b) => json!({ "Bool": b }),
+        Parameter::F32(f) => json!({ "F32": f }),
+        Parameter::Int(i) => json!({ "Int": i }),
+        Parameter::U32(u) => json!({ "U32": u }),
+        Parameter::String32(s) => json!({ "String32": s }),
+        Parameter::String64(s) => json!({ "String64": s }),
+        Parameter::String256(s) => json!({ "String256": s }),
+        Parameter::StringRef(s) => json!({ "StringRef": s }),
+        Parameter::Vec2(v) => json!({ "Vec2": [v.x, v.y] }),
+        Parameter::Vec3(v) => json!({ "Vec3": [v.x, v.y, v.z] }),
+        Parameter::Vec4(v) => json!({ "Vec4": [v.x, v.y, v.z, v.t] }),
+        Parameter::Color(c) => json!({ "Color": [c.r, c.g, c.b, c.a] }),
+        Parameter::Quat(q) => json!({ "Quat": [q.a, q.b, q.c, q.d] }),
+        Parameter::BufferInt(b) => json!({ "BufferInt": b }),
+        Parameter::BufferF32(b) => json!({ "BufferF32": b }),
+        Parameter::BufferU32(b) => json!({ "BufferU32": b }),
+        Parameter::BufferBinary(b) => json!({ "BufferBinary": b }),
+        _ => anyhow::bail!("Curve parameters cannot be exported to JSON"),
+    })
+}
+
+fn param_from_json(value: &Value) -> Result<Parameter> {
+    let (kind, value) = value
+        .as_object()
+        .and_then(|obj| obj.iter().next())
+        .context("Parameter should be an object with its type as the only key")?;
+    let floats = || -> Result<Vec<f32>> {
+        value
+            .as_array()
+            .context("Expected an array")?
+            .iter()
+            .map(|v| v.as_f64().map(|f| f as f32).context("Expected a number"))
+            .collect()
+    };
+    let int = || value.as_i64().context("Expected an integer");
+    let string = || {
+        value
+            .as_str()
+            .map(|s| s.to_owned())
+            .context("Expected a string")
+    };
+    Ok(match kind.as_str() {
+        "Bool" => Parameter::Bool(value.as_bool().context("Expected a boolean")?),
+        "F32" => Parameter::F32(value.as_f64().context("Expected a number")? as f32),
+        "Int" => Parameter::Int(int()? as i32),
+        "U32" => Parameter::U32(int()? as u32),
+        "String32" => Parameter::String32(string()?),
+        "String64" => Parameter::String64(string()?),
+        "String256" => Parameter::String256(string()?),
+        "StringRef" => Parameter::StringRef(string()?),
+        "Vec2" => match floats()?.as_slice() {
+            [x, y] => Parameter::Vec2(Vector2f { x: *x, y: *y }),
+            _ => anyhow::bail!("Vec2 needs 2 components"),
+        },
+        "Vec3" => match floats()?.as_slice() {
+            [x, y, z] => Parameter::Vec3(Vector3f {
+                x: *x,
+                y: *y,
+                z: *z,
+            }),
+            _ => anyhow::bail!("Vec3 needs 3 components"),
+        },
+        "Vec4" => match floats()?.as_slice() {
+            [x, y, z, t] => Parameter::Vec4(Vector4f {
+                x: *x,
+                y: *y,
+                z: *z,
+                t: *t,
+            }),
+            _ => anyhow::bail!("Vec4 needs 4 components"),
+        },
+        "Color" => match floats()?.as_slice() {
+            [r, g, b, a] => Parameter::Color(Color {
+                r: *r,
+                g: *g,
+                b: *b,
+                a: *a,
+            }),
+            _ => anyhow::bail!("Color needs 4 components"),
+        },
+        "Quat" => match floats()?.as_slice() {
+            [a, b, c, d] => Parameter::Quat(Quat {
+                a: *a,
+                b: *b,
+                c: *c,
+                d: *d,
+            }),
+            _ => anyhow::bail!("Quat needs 4 components"),
+        },
+        "BufferF32" => Parameter::BufferF32(floats()?),
+        "BufferInt" | "BufferU32" | "BufferBinary" => {
+            let ints = value
+                .as_array()
+                .context("Expected an array")?
+                .iter()
+                .map(|v| v.as_i64().context("Expected an integer"))
+                .collect::<Result<Vec<i64>>>()?;
+            match kind.as_str() {
+                "BufferInt" => Parameter::BufferInt(ints.into_iter().map(|i| i as i32).collect()),
+                "BufferU32" => Parameter::BufferU32(ints.into_iter().map(|i| i as u32).collect()),
+                _ => Parameter::BufferBinary(ints.into_iter().map(|i| i as u8).collect()),
+            }
+        }
+        _ => anyhow::bail!("Unknown parameter type {}", kind),
+    })
+}
+
+pub(crate) fn list_to_json(list: &dyn ParamList) -> Result<Value> {
+    let mut objects = Map::new();
+    for (k, obj) in list.objects().inner() {
+        let mut params = Map::new();
+        for (pk, param) in obj.params() {
+            params.insert(try_name(*pk), param_to_json(param)?);
+        }
+        objects.insert(try_name(*k), Value::Object(params));
+    }
+    let mut lists = Map::new();
+    for (k, list) in list.lists().inner() {
+        lists.insert(try_name(*k), list_to_json(list)?);
+    }
+    Ok(json!({ "objects": objects, "lists": lists }))
+}
+
+fn list_from_json(value: &Value, list: &mut dyn ParamList) -> Result<()> {
+    let empty = Map::new();
+    let section = |name: &str| -> Result<&Map<String, Value>> {
+        match value.get(name) {
+            Some(v) => v
+                .as_object()
+                .with_context(|| format!("\"{}\" should be an object", name)),
+            None => Ok(&empty),
+        }
+    };
+    for (k, obj) in section("objects")? {
+        let mut object = ParameterObject::new();
+        for (pk, param) in obj
+            .as_object()
+            .with_context(|| format!("Object {} should be a JSON object", k))?
+        {
+            object.params_mut().insert(
+                key_hash(pk),
+                param_from_json(param).with_context(|| format!("Invalid parameter {}", pk))?,
+            );
+        }
+        list.objects_mut().inner_mut().insert(key_hash(k), object);
+    }
+    for (k, child) in section("lists")? {
+        let mut child_list = ParameterList::new();
+        list_from_json(child, &mut child_list).with_context(|| format!("In list {}", k))?;
+        list.lists_mut().inner_mut().insert(key_hash(k), child_list);
+    }
+    Ok(())
+}
+
+/// Reads JSON as written by [`AIProgram::to_json`], without checking that it
+/// holds an AI program.
+pub(crate) fn pio_from_json(text: &str) -> Result<ParameterIO> {
+    let value: Value = serde_json::from_str(text)?;
+    let mut pio = ParameterIO::new();
+    list_from_json(&value, &mut pio)?;
+    Ok(pio)
+}
+
+impl AIProgram {
+    /// Serializes the program to JSON, with names resolved where they are
+    /// known and each parameter tagged with its type.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&list_to_json(&self.0)?)?)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        Self::from_pio(pio_from_json(text)?)
+    }
+
+    pub fn save_json(&self, file: &Path) -> Result<()> {
+        fs::write(file, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn load_json(file: &Path) -> Result<Self> {
+        Self::from_json(&fs::read_to_string(file)?)
+    }
+}
@@ -0,0 +1,105 @@
+//! Reading YAML from older community tools, which write AAMP with tag
+//! spellings, key forms, and list names roead doesn't accept.
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use roead::aamp::{hash_name, ParamList, ParameterIO, ParameterObject};
+
+/// Tags older tools use, with the one roead expects instead
+static TAG_ALIASES: &[(&str, &str)] = &[
+    ("!parameter_io", "!io"),
+    ("!parameter_list", "!list"),
+    ("!param_list", "!list"),
+    ("!parameter_object", "!obj"),
+    ("!param_obj", "!obj"),
+    ("!string32", "!str32"),
+    ("!string64", "!str64"),
+    ("!string256", "!str256"),
+    ("!u32", "!u"),
+    ("!vector2", "!vec2"),
+    ("!vector3", "!vec3"),
+    ("!vector4", "!vec4"),
+    ("!colour", "!color"),
+];
+
+/// Names older tools give the top-level lists of an AI program, with the
+/// name the game uses
+static LIST_ALIASES: &[(&str, &str)] = &[
+    ("AIs", "AI"),
+    ("Actions", "Action"),
+    ("Behaviors", "Behavior"),
+    ("Behaviour", "Behavior"),
+    ("Behaviours", "Behavior"),
+    ("Queries", "Query"),
+];
+
+lazy_static! {
+    static ref TAG: Regex = Regex::new(r"(?m)(^|[\s\[,])(![A-Za-z_][A-Za-z0-9_]*)").unwrap();
+    /// Keys given as hex, quoted or not
+    static ref HEX_KEY: Regex =
+        Regex::new(r#"(?m)^(\s*(?:-\s+)?)['"]?0x([0-9A-Fa-f]{1,8})['"]?(\s*):"#).unwrap();
+    /// Keys given as quoted decimal numbers
+    static ref QUOTED_KEY: Regex = Regex::new(r#"(?m)^(\s*)['"]([0-9]+)['"](\s*):"#).unwrap();
+}
+
+/// Rewrites YAML in the dialect of older tools into the one roead reads:
+/// tabs in indentation become spaces, old tag spellings are replaced, hex and
+/// quoted numeric keys become plain numbers, and a missing `!io` header is
+/// added.
+pub fn normalize_legacy_yaml(text: &str) -> String {
+    let mut out: String = text
+        .lines()
+        .map(|line| {
+            let indent = line.len() - line.trim_start_matches('\t').len();
+            format!("{}{}\n", "  ".repeat(indent), &line[indent..])
+        })
+        .collect();
+    out = TAG
+        .replace_all(&out, |caps: &Captures<'_>| {
+            let tag = &caps[2];
+            let tag = TAG_ALIASES
+                .iter()
+                .find(|(old, _)| old.eq_ignore_ascii_case(tag))
+                .map(|(_, new)| *new)
+                .unwrap_or(tag);
+            format!("{}{}", &caps[1], tag)
+        })
+        .into_owned();
+    out = HEX_KEY
+        .replace_all(&out, |caps: &Captures<'_>| {
+            format!(
+                "{}{}{}:",
+                &caps[1],
+                u32::from_str_radix(&caps[2], 16).unwrap_or_default(),
+                &caps[3]
+            )
+        })
+        .into_owned();
+    out = QUOTED_KEY.replace_all(&out, "$1$2$3:").into_owned();
+    if !out.trim_start().starts_with("!io") {
+        out = format!("!io\nversion: 0\ntype: xml\n{}", out);
+    }
+    out
+}
+
+/// Gives an AI program's top-level lists the names the game uses, when it
+/// only has them under names older tools wrote, and adds an empty
+/// `DemoAIActionIdx` if it has none. Returns a description of each change.
+pub fn normalize_legacy_lists(pio: &mut ParameterIO) -> Vec<String> {
+    let mut changes = vec![];
+    for (old, new) in LIST_ALIASES {
+        if pio.lists().get(hash_name(new)).is_none() {
+            if let Some(list) = pio.lists_mut().inner_mut().shift_remove(&hash_name(old)) {
+                pio.lists_mut().inner_mut().insert(hash_name(new), list);
+                changes.push(format!("Renamed list {} to {}", old, new));
+            }
+        }
+    }
+    let demos = hash_name("DemoAIActionIdx");
+    if pio.objects().get(demos).is_none() && pio.lists().get(hash_name("AI")).is_some() {
+        pio.objects_mut()
+            .inner_mut()
+            .insert(demos, ParameterObject::new());
+        changes.push(String::from("Added an empty DemoAIActionIdx"));
+    }
+    changes
+}
@@ -0,0 +1,49 @@
+//! Reading, editing, and analysing _The Legend of Zelda: Breath of the Wild_
+//! AI programs (`.baiprog`/`.sbaiprog`), without any UI.
+//!
+//! [`AIProgram`] wraps a parsed program and provides entry editing that keeps
+//! `ChildIdx`, `BehaviorIdx`, and demo indexes consistent, along with
+//! reference analysis ([`AIProgram::references`], [`AIProgram::find_cycles`],
+//! [`AIProgram::unused_entries`], [`AIProgram::validate_references`]). The
+//! class definitions the game expects are available through [`AIDEFS`], and
+//! [`util::try_name`] resolves parameter name hashes.
+#![forbid(unsafe_code)]
+#![warn(clippy::all, rust_2018_idioms)]
+
+pub mod animation;
+pub mod backup;
+pub mod batch;
+pub mod bulk;
+pub mod compare;
+pub mod convert;
+pub mod csv;
+pub mod delete;
+pub mod describe;
+pub mod flags;
+pub mod graph;
+pub mod json;
+pub mod legacy;
+pub mod links;
+pub mod merge;
+pub mod order;
+pub mod patch;
+pub mod program;
+pub mod query;
+pub mod remap;
+pub mod repair;
+pub mod replace;
+pub mod sanity;
+pub mod schema;
+pub mod search;
+pub mod snippet;
+pub mod text;
+pub mod totk;
+pub mod trace;
+pub mod unused;
+pub mod util;
+pub mod validate;
+pub mod vanilla;
+pub mod variant;
+
+pub use program::{AIProgram, Category, References};
+pub use util::{AIDefs, AIDEFS};
@@ -0,0 +1,118 @@
+//! Checks of the entries in an actor's AI program against the components its
+//! ActorLink says it has, as some classes crash the game when used by an
+//! actor without, say, a ragdoll. Which classes need what is kept in
+//! `data/links.json` and is far from complete.
+//!
+//! Most rules there are guesses from class names, so only those with a
+//! `source` saying where they were checked against game data count as
+//! validation issues. The rest are only shown as unverified hints.
+use crate::{
+    program::{is_actor_pack, path_in_pack, read_sarc, AIProgram},
+    validate::{Issue, IssueKind},
+};
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use roead::aamp::{ParamList, ParameterIO, ParameterObject};
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+static LINK_RULES_JSON: &str = include_str!("../data/links.json");
+
+/// A component an AI class needs the actor to have.
+#[derive(Debug, Deserialize)]
+pub struct LinkRule {
+    /// Part of the names of the classes needing the component
+    pub pattern: String,
+    /// The ActorLink parameter naming the file the component is set up in
+    pub link: String,
+    /// What the component is, for telling the user
+    pub needs: String,
+    /// Where the rule was checked against game data, such as vanilla actors
+    /// which crash without the component. Rules without one are guesses.
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl LinkRule {
+    pub fn is_verified(&self) -> bool {
+        self.source.is_some()
+    }
+}
+
+lazy_static! {
+    pub static ref LINK_RULES: Vec<LinkRule> = serde_json::from_str(LINK_RULES_JSON).unwrap();
+}
+
+/// The link targets of an actor, naming the files its components are set up
+/// in.
+#[derive(Debug, Clone)]
+pub struct ActorLink(ParameterObject);
+
+impl ActorLink {
+    /// Reads the ActorLink of an actor pack, if it has one.
+    pub fn read(pack: &Path) -> Result<Option<Self>> {
+        let sarc = read_sarc(&fs::read(pack)?)?;
+        let path = match path_in_pack(&sarc, "Actor/ActorLink/", ".bxml") {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        let pio = ParameterIO::from_binary(
+            sarc.get_file_data(&path)
+                .context("Failed to read ActorLink from actor pack")?,
+        )?;
+        Ok(pio.object("LinkTarget").cloned().map(Self))
+    }
+
+    /// Whether the actor has a component, going by whether its link names a
+    /// file. Actors without one name `Dummy` instead.
+    pub fn has(&self, link: &str) -> bool {
+        matches!(
+            self.0.param(link).and_then(|p| p.as_string().ok()),
+            Some(target) if !target.is_empty() && target != "Dummy"
+        )
+    }
+
+    /// Components a class needs which the actor doesn't have.
+    pub fn missing_for(&self, class: &str) -> Vec<&'static LinkRule> {
+        LINK_RULES
+            .iter()
+            .filter(|rule| class.contains(&rule.pattern) && !self.has(&rule.link))
+            .collect()
+    }
+}
+
+impl AIProgram {
+    /// Entries of classes needing components the actor doesn't have, going
+    /// only by the rules which have been checked against game data.
+    pub fn validate_links(&self, link: &ActorLink) -> Vec<Issue> {
+        (0..self.len())
+            .filter_map(|idx| Some((idx, self.entry_name_from_index(idx).ok()?)))
+            .flat_map(|(idx, class)| {
+                link.missing_for(class)
+                    .into_iter()
+                    .filter(|rule| rule.is_verified())
+                    .map(move |rule| {
+                        Issue::new(
+                            Some(idx),
+                            IssueKind::MissingLink {
+                                class: class.to_owned(),
+                                needs: rule.needs.clone(),
+                                link: rule.link.clone(),
+                            },
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Runs [`AIProgram::validate_links`] against the ActorLink of the actor
+    /// pack the program is from, if it's from one.
+    pub fn validate_links_in(&self, file: &Path) -> Result<Vec<Issue>> {
+        if !is_actor_pack(file) {
+            return Ok(vec![]);
+        }
+        Ok(ActorLink::read(file)?
+            .map(|link| self.validate_links(&link))
+            .unwrap_or_default())
+    }
+}
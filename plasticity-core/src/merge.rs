@@ -0,0 +1,671 @@
+//! Three-way merging of two edited versions of the same AI program, and
+//! checking several for conflicts. Entries are matched by what they are
+//! rather than where they are, so each side may add, remove, or move entries
+//! without the other's indexes going wrong.
+use crate::{
+    order::CATEGORIES,
+    program::{AIProgram, Category},
+    snippet::remap_entry,
+    util::try_name,
+};
+use roead::aamp::{hash_name, ParamList, Parameter, ParameterList, ParameterObject};
+use std::collections::HashMap;
+
+/// Which version of something both sides changed to keep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+impl Side {
+    fn choose<T>(self, ours: T, theirs: T) -> T {
+        match self {
+            Side::Ours => ours,
+            Side::Theirs => theirs,
+        }
+    }
+}
+
+/// What a version of a program did to an entry of the base it came from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl Change {
+    fn of(base: Option<&ParameterList>, version: Option<&ParameterList>) -> Option<Self> {
+        match (base, version) {
+            (None, Some(_)) => Some(Change::Added),
+            (Some(_), None) => Some(Change::Removed),
+            (Some(b), Some(v)) if b != v => Some(Change::Changed),
+            _ => None,
+        }
+    }
+}
+
+/// An entry at least one mod changed, and what each did to it.
+#[derive(Debug, Clone)]
+pub struct Touched {
+    pub category: Category,
+    pub label: String,
+    /// The change each mod made, in the order they were given
+    pub changes: Vec<Option<Change>>,
+    /// Whether the changes of every mod touching the entry can be merged
+    /// without any two changing the same parameter differently
+    pub compatible: bool,
+}
+
+impl Touched {
+    /// How many mods changed the entry.
+    pub fn touched_by(&self) -> usize {
+        self.changes.iter().flatten().count()
+    }
+}
+
+/// Compares several mods' versions of the same program against the base
+/// they all edited, listing every entry any of them changed. Entries are
+/// matched by name, so mods adding or removing entries don't throw the rest
+/// out of line.
+pub fn inspect_conflicts(base: &AIProgram, mods: &[AIProgram]) -> Vec<Touched> {
+    let versions: Vec<&AIProgram> = std::iter::once(base).chain(mods.iter()).collect();
+    let (slots, _) = align(&versions);
+    slots
+        .into_iter()
+        .filter_map(|slot| {
+            let base = slot.versions[0].as_ref();
+            let changes: Vec<Option<Change>> = slot.versions[1..]
+                .iter()
+                .map(|version| Change::of(base, version.as_ref()))
+                .collect();
+            if changes.iter().all(|c| c.is_none()) {
+                return None;
+            }
+            // Fold each mod's version into the others', as merging them would
+            let mut merged = Some(base.cloned());
+            for version in &slot.versions[1..] {
+                merged = merged
+                    .and_then(|merged| merge_versions(base, merged.as_ref(), version.as_ref()));
+            }
+            Some(Touched {
+                category: slot.key.category,
+                label: slot.label,
+                changes,
+                compatible: merged.is_some(),
+            })
+        })
+        .collect()
+}
+
+/// Something both sides changed in different ways.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub subject: Conflicted,
+    pub ours: Edit,
+    pub theirs: Edit,
+    pub take: Side,
+    target: Target,
+}
+
+/// What both sides of a conflict changed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conflicted {
+    /// An entry, by its label
+    Entry { category: Category, label: String },
+    /// A demo slot, by its key
+    Demo(String),
+}
+
+/// What one side of a conflict did.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    Added,
+    Removed,
+    Changed,
+    /// Pointed a demo slot at an entry, by its label
+    PointedAt(String),
+    /// Set a demo slot to an entry which didn't make it into the merge
+    Cleared,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    Entry(usize),
+    Demo(u32),
+}
+
+/// Tells an entry apart from every other in any version of the program: its
+/// category, `ClassName`, `Name`, and `GroupName`, and which of the entries
+/// sharing those it is.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct EntryKey {
+    category: Category,
+    identity: [Option<String>; 3],
+    occurrence: usize,
+}
+
+/// One entry as it is in each version, or `None` where a version lacks it.
+/// Index slots are rewritten to hold the position of what they point at in
+/// the list of entries across all versions, so the versions can be compared
+/// whatever order their entries are in.
+#[derive(Debug, Clone)]
+struct Slot {
+    key: EntryKey,
+    label: String,
+    versions: Vec<Option<ParameterList>>,
+    /// The merged entry, `None` inside if it's removed, or nothing if the
+    /// sides conflict
+    merged: Option<Option<ParameterList>>,
+}
+
+/// One demo slot as it is in each version, pointing into the list of
+/// entries across all versions.
+#[derive(Debug, Clone)]
+struct Demo {
+    key: u32,
+    versions: Vec<Option<i32>>,
+    merged: Option<Option<i32>>,
+}
+
+/// A merge of two programs edited from the same base, ready to be built once
+/// its conflicts have each had a side picked.
+#[derive(Debug, Clone)]
+pub struct Merge {
+    slots: Vec<Slot>,
+    demos: Vec<Demo>,
+    pub conflicts: Vec<Conflict>,
+    /// Where everything apart from the entries and demos is taken from
+    template: AIProgram,
+}
+
+/// Takes whichever side changed something, or `None` if both changed it
+/// differently.
+fn pick<T: PartialEq + Clone>(
+    base: Option<&T>,
+    ours: Option<&T>,
+    theirs: Option<&T>,
+) -> Option<Option<T>> {
+    if ours == theirs || theirs == base {
+        Some(ours.cloned())
+    } else if ours == base {
+        Some(theirs.cloned())
+    } else {
+        None
+    }
+}
+
+/// Keys of two or three maps, in the order they first appear.
+fn union_keys<'a>(maps: impl IntoIterator<Item = Vec<&'a u32>>) -> Vec<u32> {
+    let mut keys = vec![];
+    for key in maps.into_iter().flatten() {
+        if !keys.contains(key) {
+            keys.push(*key);
+        }
+    }
+    keys
+}
+
+fn merge_object(
+    base: &ParameterObject,
+    ours: &ParameterObject,
+    theirs: &ParameterObject,
+) -> Option<ParameterObject> {
+    let mut merged = ours.clone();
+    for key in union_keys([
+        ours.params().keys().collect(),
+        theirs.params().keys().collect(),
+        base.params().keys().collect(),
+    ]) {
+        let value: Option<Parameter> = pick(
+            base.params().get(&key),
+            ours.params().get(&key),
+            theirs.params().get(&key),
+        )?;
+        match value {
+            Some(value) => {
+                merged.params_mut().insert(key, value);
+            }
+            None => {
+                merged.params_mut().shift_remove(&key);
+            }
+        }
+    }
+    Some(merged)
+}
+
+/// Merges the changes each side made to an entry, parameter by parameter, or
+/// gives `None` if both changed the same parameter differently.
+fn merge_entry(
+    base: &ParameterList,
+    ours: &ParameterList,
+    theirs: &ParameterList,
+) -> Option<ParameterList> {
+    let mut merged = ours.clone();
+    for key in union_keys([
+        ours.objects().inner().keys().collect(),
+        theirs.objects().inner().keys().collect(),
+        base.objects().inner().keys().collect(),
+    ]) {
+        let (b, o, t) = (
+            base.objects().get(key),
+            ours.objects().get(key),
+            theirs.objects().get(key),
+        );
+        let obj = match (pick(b, o, t), b, o, t) {
+            (Some(obj), ..) => obj,
+            (None, Some(b), Some(o), Some(t)) => Some(merge_object(b, o, t)?),
+            _ => return None,
+        };
+        match obj {
+            Some(obj) => {
+                merged.objects_mut().inner_mut().insert(key, obj);
+            }
+            None => {
+                merged.objects_mut().inner_mut().shift_remove(&key);
+            }
+        }
+    }
+    for key in union_keys([
+        ours.lists().inner().keys().collect(),
+        theirs.lists().inner().keys().collect(),
+        base.lists().inner().keys().collect(),
+    ]) {
+        match pick(
+            base.lists().get(key),
+            ours.lists().get(key),
+            theirs.lists().get(key),
+        )? {
+            Some(list) => {
+                merged.lists_mut().inner_mut().insert(key, list);
+            }
+            None => {
+                merged.lists_mut().inner_mut().shift_remove(&key);
+            }
+        }
+    }
+    Some(merged)
+}
+
+/// Merges two versions of an entry given the base they came from: whichever
+/// side changed it, or both sides' changes if they're to different
+/// parameters. Gives nothing if they conflict.
+fn merge_versions(
+    base: Option<&ParameterList>,
+    ours: Option<&ParameterList>,
+    theirs: Option<&ParameterList>,
+) -> Option<Option<ParameterList>> {
+    pick(base, ours, theirs).or_else(|| match (base, ours, theirs) {
+        (Some(b), Some(o), Some(t)) => merge_entry(b, o, t).map(Some),
+        _ => None,
+    })
+}
+
+impl AIProgram {
+    /// The [`EntryKey`] of every entry, by index.
+    fn entry_keys(&self) -> Vec<EntryKey> {
+        let mut seen: HashMap<(Category, [Option<String>; 3]), usize> = HashMap::new();
+        (0..self.len())
+            .map(|idx| {
+                let category = self.category_of(idx);
+                let identity = self.identity(idx);
+                let occurrence = seen.entry((category, identity.clone())).or_default();
+                *occurrence += 1;
+                EntryKey {
+                    category,
+                    identity,
+                    occurrence: *occurrence,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Lines up the entries and demo slots of several versions of a program.
+/// Entries of the first come first, then those each later one added, in
+/// their order.
+fn align(versions: &[&AIProgram]) -> (Vec<Slot>, Vec<Demo>) {
+    let mut slots: Vec<Slot> = vec![];
+    let mut positions: HashMap<EntryKey, usize> = HashMap::new();
+    let keys: Vec<Vec<EntryKey>> = versions.iter().map(|v| v.entry_keys()).collect();
+    for (aiprog, keys) in versions.iter().zip(keys.iter()) {
+        for (idx, key) in keys.iter().enumerate() {
+            if !positions.contains_key(key) {
+                positions.insert(key.clone(), slots.len());
+                slots.push(Slot {
+                    key: key.clone(),
+                    label: aiprog.entry_label(idx),
+                    versions: vec![None; versions.len()],
+                    merged: None,
+                });
+            }
+        }
+    }
+    let mut demos: Vec<Demo> = vec![];
+    for (version, (aiprog, keys)) in versions.iter().zip(keys.iter()).enumerate() {
+        let position = |idx: i32| {
+            keys.get(usize::try_from(idx).ok()?)
+                .map(|key| positions[key] as i32)
+        };
+        let behaviors_offset = aiprog.behaviors_offset() as i32;
+        for (idx, key) in keys.iter().enumerate() {
+            slots[positions[key]].versions[version] =
+                Some(remap_entry(aiprog.item_at_index(idx), position, |i| {
+                    position(i + behaviors_offset)
+                }));
+        }
+        for (key, value) in aiprog.demos().params() {
+            let target = match value {
+                Parameter::Int(i) => position(*i).unwrap_or(-1),
+                _ => -1,
+            };
+            let demo = match demos.iter().position(|d| d.key == *key) {
+                Some(i) => &mut demos[i],
+                None => {
+                    demos.push(Demo {
+                        key: *key,
+                        versions: vec![None; versions.len()],
+                        merged: None,
+                    });
+                    demos.last_mut().unwrap()
+                }
+            };
+            demo.versions[version] = Some(target);
+        }
+    }
+    (slots, demos)
+}
+
+impl Merge {
+    /// Merges the changes made in `ours` and `theirs` since `base`. Anything
+    /// only one side changed is taken from it; anything both changed the same
+    /// way is taken once. Entries both changed are merged parameter by
+    /// parameter, and only conflict if both changed the same parameter.
+    pub fn new(base: &AIProgram, ours: &AIProgram, theirs: &AIProgram) -> Self {
+        let (mut slots, mut demos) = align(&[base, ours, theirs]);
+        let mut conflicts = vec![];
+        for (i, slot) in slots.iter_mut().enumerate() {
+            let [base, ours, theirs] = [0, 1, 2].map(|v| slot.versions[v].as_ref());
+            slot.merged = merge_versions(base, ours, theirs);
+            if slot.merged.is_none() {
+                let change = |entry: Option<&ParameterList>| match Change::of(base, entry) {
+                    Some(Change::Added) => Edit::Added,
+                    Some(Change::Removed) => Edit::Removed,
+                    _ => Edit::Changed,
+                };
+                conflicts.push(Conflict {
+                    subject: Conflicted::Entry {
+                        category: slot.key.category,
+                        label: slot.label.clone(),
+                    },
+                    ours: change(ours),
+                    theirs: change(theirs),
+                    take: Side::Ours,
+                    target: Target::Entry(i),
+                });
+            }
+        }
+        for demo in demos.iter_mut() {
+            let [base, ours, theirs] = [0, 1, 2].map(|v| demo.versions[v].as_ref());
+            demo.merged = pick(base, ours, theirs);
+            if demo.merged.is_none() {
+                let target = |value: Option<i32>| match value {
+                    None => Edit::Removed,
+                    Some(i) => match slots.get(i as usize) {
+                        Some(slot) => Edit::PointedAt(slot.label.clone()),
+                        None => Edit::Cleared,
+                    },
+                };
+                conflicts.push(Conflict {
+                    subject: Conflicted::Demo(try_name(demo.key)),
+                    ours: target(demo.versions[1]),
+                    theirs: target(demo.versions[2]),
+                    take: Side::Ours,
+                    target: Target::Demo(demo.key),
+                });
+            }
+        }
+        tracing::info!(
+            "Merged {} entries with {} conflicts",
+            slots.len(),
+            conflicts.len()
+        );
+        Self {
+            slots,
+            demos,
+            conflicts,
+            template: ours.clone(),
+        }
+    }
+
+    /// Puts the merged program together, taking the side picked for each
+    /// conflict. Slots pointing at entries which didn't make it are cleared.
+    pub fn build(&self) -> AIProgram {
+        let mut entries: Vec<Option<&ParameterList>> = self
+            .slots
+            .iter()
+            .map(|slot| slot.merged.as_ref().and_then(|merged| merged.as_ref()))
+            .collect();
+        let mut demos: HashMap<u32, Option<i32>> = self
+            .demos
+            .iter()
+            .filter_map(|demo| Some((demo.key, demo.merged?)))
+            .collect();
+        for conflict in &self.conflicts {
+            match conflict.target {
+                Target::Entry(i) => {
+                    let slot = &self.slots[i];
+                    entries[i] = conflict
+                        .take
+                        .choose(slot.versions[1].as_ref(), slot.versions[2].as_ref());
+                }
+                Target::Demo(key) => {
+                    let demo = self.demos.iter().find(|d| d.key == key).unwrap();
+                    demos.insert(
+                        key,
+                        conflict.take.choose(demo.versions[1], demo.versions[2]),
+                    );
+                }
+            }
+        }
+        // Where each kept entry ends up, as an absolute index
+        let mut positions: HashMap<i32, i32> = HashMap::new();
+        let mut order: Vec<usize> = vec![];
+        for category in CATEGORIES {
+            for (i, slot) in self.slots.iter().enumerate() {
+                if slot.key.category == category && entries[i].is_some() {
+                    positions.insert(i as i32, order.len() as i32);
+                    order.push(i);
+                }
+            }
+        }
+        let behaviors_offset = order
+            .iter()
+            .take_while(|i| {
+                matches!(
+                    self.slots[**i].key.category,
+                    Category::AI | Category::Action
+                )
+            })
+            .count() as i32;
+        let mut aiprog = self.template.clone();
+        for category in CATEGORIES {
+            aiprog
+                .category_list_mut(&category.to_string())
+                .lists_mut()
+                .inner_mut()
+                .clear();
+        }
+        let mut counts: HashMap<Category, usize> = HashMap::new();
+        for i in order {
+            let category = self.slots[i].key.category;
+            let count = counts.entry(category).or_default();
+            let name = format!("{}_{}", category, count);
+            *count += 1;
+            let entry = remap_entry(
+                entries[i].unwrap(),
+                |target| positions.get(&target).copied(),
+                |target| {
+                    positions
+                        .get(&target)
+                        .map(|new| new - behaviors_offset)
+                        .filter(|new| *new >= 0)
+                },
+            );
+            aiprog
+                .category_list_mut(&category.to_string())
+                .lists_mut()
+                .inner_mut()
+                .insert(hash_name(&name), entry);
+        }
+        let demo_params = aiprog.demos_mut().params_mut();
+        demo_params.clear();
+        for demo in &self.demos {
+            if let Some(Some(target)) = demos.get(&demo.key) {
+                let target = positions.get(target).copied().unwrap_or(-1);
+                demo_params.insert(demo.key, Parameter::Int(target));
+            }
+        }
+        aiprog
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::tests::{demo, fixture, param, set_param};
+
+    /// The fixture with one of the Wait's parameters changed.
+    fn with_param(key: &str, value: i32) -> AIProgram {
+        let mut aiprog = fixture();
+        set_param(&mut aiprog, 2, "SInst", key, Parameter::Int(value));
+        aiprog
+    }
+
+    #[test]
+    fn merge_unchanged() {
+        let base = fixture();
+        let merge = Merge::new(&base, &base, &base);
+        assert!(merge.conflicts.is_empty());
+        assert_eq!(merge.build(), base);
+    }
+
+    #[test]
+    fn merge_different_params() {
+        let merge = Merge::new(
+            &fixture(),
+            &with_param("Time", 45),
+            &with_param("TimeRand", 20),
+        );
+        assert!(merge.conflicts.is_empty());
+        let merged = merge.build();
+        assert_eq!(
+            param(&merged, 2, "SInst", "Time"),
+            Some(&Parameter::Int(45))
+        );
+        assert_eq!(
+            param(&merged, 2, "SInst", "TimeRand"),
+            Some(&Parameter::Int(20))
+        );
+    }
+
+    #[test]
+    fn merge_removed_entry() {
+        let mut ours = fixture();
+        ours.delete_entry(3).unwrap();
+        let merge = Merge::new(&fixture(), &ours, &with_param("Time", 45));
+        assert!(merge.conflicts.is_empty());
+        let merged = merge.build();
+        assert_eq!(merged.len(), 5);
+        assert_eq!(
+            param(&merged, 2, "SInst", "Time"),
+            Some(&Parameter::Int(45))
+        );
+        assert_eq!(
+            param(&merged, 0, "ChildIdx", "ForDemo"),
+            Some(&Parameter::Int(-1))
+        );
+        assert_eq!(
+            param(&merged, 2, "BehaviorIdx", "DamageCallback"),
+            Some(&Parameter::Int(0))
+        );
+    }
+
+    #[test]
+    fn merge_conflicting_param() {
+        let base = fixture();
+        let mut merge = Merge::new(&base, &with_param("Time", 45), &with_param("Time", 60));
+        assert_eq!(merge.conflicts.len(), 1);
+        let conflict = &merge.conflicts[0];
+        assert_eq!(
+            conflict.subject,
+            Conflicted::Entry {
+                category: Category::Action,
+                label: base.entry_label(2),
+            }
+        );
+        assert_eq!(
+            (&conflict.ours, &conflict.theirs),
+            (&Edit::Changed, &Edit::Changed)
+        );
+        assert_eq!(conflict.take, Side::Ours);
+        assert_eq!(
+            param(&merge.build(), 2, "SInst", "Time"),
+            Some(&Parameter::Int(45))
+        );
+        merge.conflicts[0].take = Side::Theirs;
+        assert_eq!(
+            param(&merge.build(), 2, "SInst", "Time"),
+            Some(&Parameter::Int(60))
+        );
+    }
+
+    #[test]
+    fn merge_conflicting_demo() {
+        let base = fixture();
+        let mut ours = fixture();
+        ours.demos_mut()
+            .params_mut()
+            .insert(hash_name("Demo_Idle"), Parameter::Int(2));
+        let mut theirs = fixture();
+        theirs
+            .demos_mut()
+            .params_mut()
+            .insert(hash_name("Demo_Idle"), Parameter::Int(-1));
+        let mut merge = Merge::new(&base, &ours, &theirs);
+        assert_eq!(merge.conflicts.len(), 1);
+        let conflict = &merge.conflicts[0];
+        assert!(matches!(conflict.subject, Conflicted::Demo(_)));
+        assert_eq!(conflict.ours, Edit::PointedAt(base.entry_label(2)));
+        assert_eq!(conflict.theirs, Edit::Cleared);
+        assert_eq!(demo(&merge.build(), "Demo_Idle"), Some(2));
+        merge.conflicts[0].take = Side::Theirs;
+        assert_eq!(demo(&merge.build(), "Demo_Idle"), Some(-1));
+    }
+
+    #[test]
+    fn inspect_mods() {
+        let base = fixture();
+        let touched = inspect_conflicts(&base, &[with_param("Time", 45), with_param("Time", 60)]);
+        assert_eq!(touched.len(), 1);
+        assert_eq!(touched[0].category, Category::Action);
+        assert_eq!(touched[0].changes, vec![Some(Change::Changed); 2]);
+        assert_eq!(touched[0].touched_by(), 2);
+        assert!(!touched[0].compatible);
+
+        let touched = inspect_conflicts(
+            &base,
+            &[
+                with_param("Time", 45),
+                fixture(),
+                with_param("TimeRand", 20),
+            ],
+        );
+        assert_eq!(touched.len(), 1);
+        assert_eq!(touched[0].changes[1], None);
+        assert_eq!(touched[0].touched_by(), 2);
+        assert!(touched[0].compatible);
+        assert!(inspect_conflicts(&base, &[fixture()]).is_empty());
+    }
+}
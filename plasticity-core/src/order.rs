@@ -0,0 +1,198 @@
+//! Putting entries into a predictable order, so diffs against vanilla and
+//! merges between versions of a program only show real changes.
+use crate::program::{AIProgram, Category, ReferenceIndex};
+use anyhow::Result;
+use roead::aamp::{hash_name, ParamList};
+use std::collections::{BTreeSet, HashMap};
+
+pub(crate) const CATEGORIES: [Category; 4] = [
+    Category::AI,
+    Category::Action,
+    Category::Behaviour,
+    Category::Query,
+];
+
+impl AIProgram {
+    pub(crate) fn category_range(&self, category: Category) -> std::ops::Range<usize> {
+        let end = match category {
+            Category::AI => self.actions_offset(),
+            Category::Action => self.behaviors_offset(),
+            Category::Behaviour => self.queries_offset(),
+            Category::Query => self.len(),
+        };
+        self.category_offset(category)..end
+    }
+
+    /// The `Name`, `GroupName`, and `ClassName` of an entry, which are what
+    /// tell entries apart between versions of a program.
+    pub(crate) fn identity(&self, idx: usize) -> [Option<String>; 3] {
+        let def = self.item_at_index(idx).objects().get(hash_name("Def"));
+        ["Name", "GroupName", "ClassName"].map(|key| {
+            def.and_then(|def| def.params().get(&hash_name(key)))
+                .and_then(|v| v.as_string().ok())
+                .map(|s| s.to_owned())
+        })
+    }
+
+    /// Moves entries within their categories, rewriting every reference to
+    /// follow. `order` lists the current index of the entry to put at each
+    /// position.
+    pub fn reorder_entries(&mut self, order: &[usize]) -> Result<()> {
+        let distinct: BTreeSet<&usize> = order.iter().filter(|i| **i < self.len()).collect();
+        if order.len() != self.len() || distinct.len() != order.len() {
+            anyhow::bail!("The new order must have every entry exactly once");
+        }
+        if let Some(new) =
+            (0..order.len()).find(|new| self.category_of(order[*new]) != self.category_of(*new))
+        {
+            anyhow::bail!(
+                "{} can't be moved out of its category",
+                self.entry_label(order[new])
+            );
+        }
+        let moved: HashMap<i32, i32> = order
+            .iter()
+            .enumerate()
+            .filter(|(new, old)| *new != **old)
+            .map(|(new, old)| (*old as i32, new as i32))
+            .collect();
+        if moved.is_empty() {
+            return Ok(());
+        }
+        tracing::debug!("Reordering {} entries", moved.len());
+        let offset = self.behaviors_offset() as i32;
+        let child = |i: i32| moved.get(&i).copied().unwrap_or(i);
+        let behaviour = |i: i32| moved.get(&(i + offset)).map_or(i, |new| new - offset);
+        ReferenceIndex::new(self).remap(self, child, behaviour);
+        let entries: Vec<_> = order
+            .iter()
+            .map(|old| self.item_at_index(*old).clone())
+            .collect();
+        for (new, entry) in entries.into_iter().enumerate() {
+            *self.item_mut_at_index(new) = entry;
+        }
+        Ok(())
+    }
+
+    /// Works out a canonical order for the entries. Given a base program,
+    /// such as the vanilla one this was made from, entries it also has come
+    /// in its order, with the rest after them as they are now. Without one,
+    /// AIs and Actions come in the order the tree shows them from the entry
+    /// points, and behaviors and queries keep their order.
+    pub fn canonical_order(&self, base: Option<&AIProgram>) -> Vec<usize> {
+        let mut order = vec![];
+        for category in CATEGORIES {
+            let range = self.category_range(category);
+            let mut placed: Vec<usize> = match base {
+                Some(base) => {
+                    let mut left: Vec<usize> = range.clone().collect();
+                    base.category_range(category)
+                        .filter_map(|b| {
+                            let wanted = base.identity(b);
+                            let pos = left.iter().position(|i| self.identity(*i) == wanted)?;
+                            Some(left.remove(pos))
+                        })
+                        .collect()
+                }
+                None if matches!(category, Category::AI | Category::Action) => self
+                    .tree_order()
+                    .into_iter()
+                    .filter(|i| range.contains(i))
+                    .collect(),
+                None => vec![],
+            };
+            let rest: Vec<usize> = range.filter(|i| !placed.contains(i)).collect();
+            placed.extend(rest);
+            order.extend(placed);
+        }
+        order
+    }
+
+    /// Every AI and Action reachable from the entry points, depth first,
+    /// each where it's first reached.
+    fn tree_order(&self) -> Vec<usize> {
+        fn visit(aiprog: &AIProgram, idx: usize, seen: &mut Vec<usize>) {
+            if seen.contains(&idx) {
+                return;
+            }
+            seen.push(idx);
+            for child in aiprog.children_of(idx) {
+                visit(aiprog, child, seen);
+            }
+        }
+        let mut seen = vec![];
+        for root in self.entry_points() {
+            visit(self, root, &mut seen);
+        }
+        seen
+    }
+
+    /// Puts the entries into their [`AIProgram::canonical_order`], returning
+    /// how many moved.
+    pub fn normalize_order(&mut self, base: Option<&AIProgram>) -> Result<usize> {
+        let order = self.canonical_order(base);
+        let moved = order
+            .iter()
+            .enumerate()
+            .filter(|(new, old)| new != *old)
+            .count();
+        self.reorder_entries(&order)?;
+        Ok(moved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::tests::{demo, fixture, param};
+    use roead::aamp::Parameter;
+
+    /// The fixture with its two Actions swapped.
+    fn swapped() -> AIProgram {
+        let mut aiprog = fixture();
+        aiprog.reorder_entries(&[0, 1, 3, 2, 4, 5]).unwrap();
+        aiprog
+    }
+
+    #[test]
+    fn reorder_follows_references() {
+        let aiprog = swapped();
+        assert_eq!(aiprog.entry_name_from_index(2).unwrap(), "IdleAction");
+        assert_eq!(aiprog.entry_name_from_index(3).unwrap(), "Wait");
+        assert_eq!(
+            param(&aiprog, 0, "ChildIdx", "ForDemo"),
+            Some(&Parameter::Int(2))
+        );
+        assert_eq!(
+            param(&aiprog, 1, "ChildIdx", "Wait"),
+            Some(&Parameter::Int(3))
+        );
+        assert_eq!(demo(&aiprog, "Demo_Idle"), Some(2));
+        assert_eq!(
+            param(&aiprog, 3, "BehaviorIdx", "DamageCallback"),
+            Some(&Parameter::Int(0))
+        );
+    }
+
+    #[test]
+    fn reorder_rejects_bad_orders() {
+        let mut aiprog = fixture();
+        assert!(aiprog.reorder_entries(&[0, 2, 1, 3, 4, 5]).is_err());
+        assert!(aiprog.reorder_entries(&[0, 1, 2, 2, 4, 5]).is_err());
+        assert!(aiprog.reorder_entries(&[0, 1, 2, 3, 4]).is_err());
+        assert_eq!(aiprog, fixture());
+    }
+
+    #[test]
+    fn normalize_restores_order() {
+        let mut aiprog = swapped();
+        assert_eq!(aiprog.canonical_order(None), vec![0, 1, 3, 2, 4, 5]);
+        assert_eq!(
+            aiprog.canonical_order(Some(&fixture())),
+            vec![0, 1, 3, 2, 4, 5]
+        );
+        assert_eq!(aiprog.normalize_order(None).unwrap(), 2);
+        assert_eq!(aiprog, fixture());
+        assert_eq!(aiprog.normalize_order(None).unwrap(), 0);
+    }
+}
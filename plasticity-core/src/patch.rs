@@ -0,0 +1,159 @@
+use crate::{
+    program::{AIProgram, Category},
+    snippet::remap_entry,
+    util::update_name_table_from_pio,
+};
+use anyhow::{Context, Result};
+use roead::aamp::{ParamList, ParameterIO, ParameterList, ParameterObject};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::Path,
+};
+
+impl AIProgram {
+    /// Computes a deep diff against a base program in the same layout BCML
+    /// uses for AAMP merges: only lists, objects, and parameters which were
+    /// added or changed are kept. Removed data is not represented.
+    pub fn diff(&self, base: &AIProgram) -> ParameterIO {
+        let mut patch = self.0.clone();
+        patch.lists_mut().inner_mut().clear();
+        patch.objects_mut().inner_mut().clear();
+        diff_into(&self.0, &base.0, &mut patch);
+        patch
+    }
+
+    /// Deep merges a patch produced by [`AIProgram::diff`] into this program.
+    pub fn apply_patch(&mut self, patch: &ParameterIO) {
+        merge_into(&mut self.0, patch);
+        update_name_table_from_pio(&self.0);
+    }
+
+    /// Finds the entry of a base program, such as the file as it was loaded,
+    /// which an entry of this one corresponds to. Entries keep their position
+    /// while their category has as many entries as the base's; otherwise they
+    /// are matched by name, group, and class.
+    pub fn base_index(&self, base: &AIProgram, idx: usize) -> Option<usize> {
+        let category = self.category_of(idx);
+        let count = |aiprog: &AIProgram| match category {
+            Category::AI => aiprog.ais().len(),
+            Category::Action => aiprog.actions().len(),
+            Category::Behaviour => aiprog.behaviors().len(),
+            Category::Query => aiprog.queries().len(),
+        };
+        let base_offset = base.category_offset(category);
+        if count(self) == count(base) {
+            return Some(base_offset + idx - self.category_offset(category));
+        }
+        let wanted = self.identity(idx);
+        (base_offset..base_offset + count(base)).find(|i| base.identity(*i) == wanted)
+    }
+
+    /// Every entry which differs from its counterpart in a base program, or
+    /// has none.
+    pub fn changed_entries(&self, base: &AIProgram) -> BTreeSet<usize> {
+        (0..self.len())
+            .filter(|i| match self.base_index(base, *i) {
+                Some(b) => self.item_at_index(*i) != base.item_at_index(b),
+                None => true,
+            })
+            .collect()
+    }
+
+    /// Puts an entry back the way it is in a base program. Its `ChildIdx` and
+    /// `BehaviorIdx` values are pointed at wherever the entries they named
+    /// are now, or cleared if those are gone.
+    pub fn revert_entry(&mut self, base: &AIProgram, idx: usize) -> Result<()> {
+        let base_idx = self
+            .base_index(base, idx)
+            .context("This entry was added since the file was loaded")?;
+        let current: HashMap<usize, i32> = (0..self.len())
+            .filter_map(|i| Some((self.base_index(base, i)?, i as i32)))
+            .collect();
+        let behaviors_offset = self.behaviors_offset() as i32;
+        let base_behaviors_offset = base.behaviors_offset();
+        *self.item_mut_at_index(idx) = remap_entry(
+            base.item_at_index(base_idx),
+            |i| current.get(&(i as usize)).copied(),
+            |i| {
+                current
+                    .get(&(base_behaviors_offset + i as usize))
+                    .map(|new| new - behaviors_offset)
+            },
+        );
+        Ok(())
+    }
+
+    pub fn save_patch(&self, base: &AIProgram, file: &Path) -> Result<()> {
+        let patch = self.diff(base);
+        match file.extension().and_then(|ext| ext.to_str()) {
+            Some("yml") => fs::write(file, patch.to_text())?,
+            _ => fs::write(file, patch.to_binary())?,
+        };
+        Ok(())
+    }
+}
+
+pub fn load_patch(file: &Path) -> Result<ParameterIO> {
+    Ok(match file.extension().and_then(|ext| ext.to_str()) {
+        Some("yml") => ParameterIO::from_text(fs::read_to_string(file)?)?,
+        _ => ParameterIO::from_binary(fs::read(file)?)?,
+    })
+}
+
+fn diff_into(modified: &dyn ParamList, base: &dyn ParamList, out: &mut dyn ParamList) {
+    for (k, obj) in modified.objects().inner() {
+        match base.objects().get(*k) {
+            Some(base_obj) => {
+                let mut changed = ParameterObject::new();
+                obj.params()
+                    .iter()
+                    .filter(|(pk, pv)| base_obj.params().get(*pk) != Some(*pv))
+                    .for_each(|(pk, pv)| {
+                        changed.params_mut().insert(*pk, pv.clone());
+                    });
+                if !changed.params().is_empty() {
+                    out.objects_mut().inner_mut().insert(*k, changed);
+                }
+            }
+            None => {
+                out.objects_mut().inner_mut().insert(*k, obj.clone());
+            }
+        }
+    }
+    for (k, list) in modified.lists().inner() {
+        match base.lists().get(*k) {
+            Some(base_list) => {
+                let mut changed = ParameterList::new();
+                diff_into(list, base_list, &mut changed);
+                if !changed.objects().inner().is_empty() || !changed.lists().inner().is_empty() {
+                    out.lists_mut().inner_mut().insert(*k, changed);
+                }
+            }
+            None => {
+                out.lists_mut().inner_mut().insert(*k, list.clone());
+            }
+        }
+    }
+}
+
+fn merge_into(target: &mut dyn ParamList, patch: &dyn ParamList) {
+    for (k, obj) in patch.objects().inner() {
+        match target.objects_mut().get_mut(*k) {
+            Some(target_obj) => obj.params().iter().for_each(|(pk, pv)| {
+                target_obj.params_mut().insert(*pk, pv.clone());
+            }),
+            None => {
+                target.objects_mut().inner_mut().insert(*k, obj.clone());
+            }
+        }
+    }
+    for (k, list) in patch.lists().inner() {
+        match target.lists_mut().get_mut(*k) {
+            Some(target_list) => merge_into(target_list, list),
+            None => {
+                target.lists_mut().inner_mut().insert(*k, list.clone());
+            }
+        }
+    }
+}
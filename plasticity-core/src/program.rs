@@ -0,0 +1,1418 @@
+use crate::{
+    backup::BACKUP_DIR,
+    json::pio_from_json,
+    legacy::{normalize_legacy_lists, normalize_legacy_yaml},
+    totk::{is_totk_data, is_totk_path, unsupported_error},
+    util::*,
+    variant::detect_layout,
+};
+use anyhow::{Context, Result};
+use roead::{
+    self,
+    aamp::{hash_name, ParamList, Parameter, ParameterIO, ParameterList, ParameterObject},
+    sarc::{Sarc, SarcWriter},
+    yaz0,
+};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+static ACTOR_PACK_EXTS: &[&str] = &["sbactorpack", "bactorpack"];
+
+lazy_static::lazy_static! {
+    /// Stands in for the demo slots of a program which has none
+    static ref NO_DEMOS: ParameterObject = ParameterObject::new();
+}
+
+/// The four lists of entries in an AI program. Entries are numbered across all
+/// of them in this order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    AI,
+    Action,
+    Behaviour,
+    Query,
+}
+
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::AI => f.write_fmt(format_args!("AI")),
+            Self::Action => f.write_fmt(format_args!("Action")),
+            Self::Behaviour => f.write_fmt(format_args!("Behavior")),
+            Self::Query => f.write_fmt(format_args!("Query")),
+        }
+    }
+}
+
+/// A parsed AI program. Entries are addressed by their absolute index, counting
+/// up through the AI, Action, Behavior, and Query lists in turn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AIProgram(pub ParameterIO);
+
+/// Every place in an AI program which points at a particular entry.
+#[derive(Debug, Clone, Default)]
+pub struct References {
+    /// Keys of demo slots in `DemoAIActionIdx`
+    pub demos: Vec<u32>,
+    /// Absolute index of the referencing entry and key of its `ChildIdx` slot
+    pub children: Vec<(usize, u32)>,
+    /// Absolute index of the referencing entry and key of its `BehaviorIdx` slot
+    pub behaviours: Vec<(usize, u32)>,
+}
+
+impl References {
+    pub fn is_empty(&self) -> bool {
+        self.demos.is_empty() && self.children.is_empty() && self.behaviours.is_empty()
+    }
+}
+
+/// Every slot holding an entry index, keyed by the index it holds. Built in a
+/// single pass, so shifting a whole range of entries doesn't rescan the
+/// program once per entry.
+#[derive(Debug, Default)]
+pub(crate) struct ReferenceIndex {
+    /// Demo keys by absolute entry index
+    pub(crate) demos: HashMap<i32, Vec<u32>>,
+    /// Holder and `ChildIdx` key by absolute entry index
+    pub(crate) children: HashMap<i32, Vec<(usize, u32)>>,
+    /// Holder and `BehaviorIdx` key by index in the behavior list
+    pub(crate) behaviours: HashMap<i32, Vec<(usize, u32)>>,
+}
+
+impl ReferenceIndex {
+    pub(crate) fn new(aiprog: &AIProgram) -> Self {
+        let mut index = Self::default();
+        for (k, v) in aiprog.demos().params() {
+            if let Parameter::Int(i) = v {
+                index.demos.entry(*i).or_default().push(*k);
+            }
+        }
+        for (holder, item) in aiprog
+            .items()
+            .into_iter()
+            .take(aiprog.behaviors_offset())
+            .enumerate()
+        {
+            for (obj_name, map) in [
+                ("ChildIdx", &mut index.children),
+                ("BehaviorIdx", &mut index.behaviours),
+            ] {
+                if let Some(obj) = item.objects().get(hash_name(obj_name)) {
+                    for (k, v) in obj.params() {
+                        if let Parameter::Int(i) = v {
+                            map.entry(*i).or_default().push((holder, *k));
+                        }
+                    }
+                }
+            }
+        }
+        index
+    }
+
+    /// Points every slot at a new index, given its old one: absolute entry
+    /// indexes through `child` and behavior indexes through `behaviour`.
+    /// Negative values are left alone.
+    pub(crate) fn remap(
+        &self,
+        aiprog: &mut AIProgram,
+        child: impl Fn(i32) -> i32,
+        behaviour: impl Fn(i32) -> i32,
+    ) {
+        for (old, keys) in &self.demos {
+            let new = child(*old);
+            if *old >= 0 && new != *old {
+                for key in keys {
+                    aiprog
+                        .demos_mut()
+                        .params_mut()
+                        .insert(*key, Parameter::Int(new));
+                }
+            }
+        }
+        for (obj_name, map, remap) in [
+            ("ChildIdx", &self.children, &child as &dyn Fn(i32) -> i32),
+            ("BehaviorIdx", &self.behaviours, &behaviour),
+        ] {
+            for (old, slots) in map {
+                let new = remap(*old);
+                if *old >= 0 && new != *old {
+                    for (holder, key) in slots {
+                        aiprog.set_slot(*holder, obj_name, *key, new);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl AIProgram {
+    pub fn new<P: AsRef<Path>>(file: P) -> Result<Self> {
+        let file = file.as_ref();
+        let aiprog = Self::from_pio(Self::read_pio(file)?)?;
+        tracing::info!("Loaded {} with {} entries", file.display(), aiprog.len());
+        Ok(aiprog)
+    }
+
+    /// Reads a program from the contents of a file, going by the file's name
+    /// for its format, for when the file isn't on disk, as in the browser.
+    pub fn from_bytes(file: &Path, data: &[u8]) -> Result<Self> {
+        let aiprog = Self::from_pio(pio_from_bytes(file, data)?)?;
+        tracing::info!("Loaded {} with {} entries", file.display(), aiprog.len());
+        Ok(aiprog)
+    }
+
+    /// Reads the parameter IO of an AI program file, without checking that it
+    /// holds an AI program.
+    pub(crate) fn read_pio(file: &Path) -> Result<ParameterIO> {
+        pio_from_bytes(file, &fs::read(file)?)
+    }
+
+    /// Wraps a parameter IO, checking that it has the structure of an AI
+    /// program.
+    pub fn from_pio(pio: ParameterIO) -> Result<Self> {
+        if [
+            hash_name("AI"),
+            hash_name("Action"),
+            hash_name("Behavior"),
+            hash_name("Query"),
+        ]
+        .into_iter()
+        .any(|k| pio.lists().get(k).is_none())
+            || pio.objects().get(hash_name("DemoAIActionIdx")).is_none()
+        {
+            Err(anyhow::anyhow!("Invalid AI program."))
+        } else {
+            update_name_table_from_pio(&pio);
+            let demos = pio.objects().get(hash_name("DemoAIActionIdx")).unwrap();
+            let names: Vec<String> = demos
+                .params()
+                .keys()
+                .filter(|k| has_name(**k))
+                .map(|k| try_name(*k))
+                .collect();
+            add_demo_names(names.iter().map(|n| n.as_str()));
+            Ok(Self(pio))
+        }
+    }
+
+    /// Saves the program, yaz0 compressing binary output if `compress` is set.
+    /// Actor packs keep whatever compression they already had.
+    pub fn save(&self, file: &Path, compress: bool) -> Result<()> {
+        tracing::info!("Saving {} with {} entries", file.display(), self.len());
+        if is_totk_path(file) {
+            anyhow::bail!("Tears of the Kingdom AI files can't be saved");
+        }
+        if is_actor_pack(file) {
+            return self.save_to_actor_pack(file);
+        }
+        fs::write(file, self.to_bytes(file, compress)?)?;
+        Ok(())
+    }
+
+    /// The program as it would be saved to a file, in the format its
+    /// extension names. Actor packs can't be written this way, as the rest of
+    /// the pack is needed.
+    pub fn to_bytes(&self, file: &Path, compress: bool) -> Result<Vec<u8>> {
+        if is_actor_pack(file) {
+            anyhow::bail!("An actor pack needs the rest of its files to be saved");
+        }
+        if is_totk_path(file) {
+            anyhow::bail!("Tears of the Kingdom AI files can't be saved");
+        }
+        Ok(match file.extension() {
+            Some(ext) if ext == "yml" => self.0.to_text().into_bytes(),
+            Some(ext) if ext == "json" => self.to_json()?.into_bytes(),
+            _ if compress => yaz0::compress(&self.0.to_binary()),
+            _ => self.0.to_binary(),
+        })
+    }
+
+    /// Replaces the AI program inside an existing actor pack, keeping every
+    /// other file in the archive as-is.
+    fn save_to_actor_pack(&self, file: &Path) -> Result<()> {
+        let name = pack_program_path(file)?;
+        write_pack_file(file, &name, self.0.to_binary())
+    }
+
+    pub fn ais(&self) -> Vec<&ParameterList> {
+        self.category_entries("AI")
+    }
+
+    pub fn actions(&self) -> Vec<&ParameterList> {
+        self.category_entries("Action")
+    }
+
+    pub fn behaviors(&self) -> Vec<&ParameterList> {
+        self.category_entries("Behavior")
+    }
+
+    pub fn queries(&self) -> Vec<&ParameterList> {
+        self.category_entries("Query")
+    }
+
+    /// Entries of a top-level list, or none if the program doesn't have it.
+    fn category_entries(&self, name: &str) -> Vec<&ParameterList> {
+        self.0
+            .lists()
+            .get(hash_name(name))
+            .map(|list| list.lists().inner().values().collect())
+            .unwrap_or_default()
+    }
+
+    fn category_len(&self, name: &str) -> usize {
+        self.0
+            .lists()
+            .get(hash_name(name))
+            .map(|list| list.lists().len())
+            .unwrap_or_default()
+    }
+
+    /// A top-level list, added empty if the program doesn't have it.
+    pub(crate) fn category_list_mut(&mut self, name: &str) -> &mut ParameterList {
+        self.0
+            .lists_mut()
+            .inner_mut()
+            .entry(hash_name(name))
+            .or_default()
+    }
+
+    pub fn items(&self) -> Vec<&ParameterList> {
+        self.0
+            .lists()
+            .inner()
+            .values()
+            .flat_map(|cat| cat.lists().inner().values())
+            .collect()
+    }
+
+    pub fn items_mut(&mut self) -> Vec<&mut ParameterList> {
+        self.0
+            .lists_mut()
+            .inner_mut()
+            .values_mut()
+            .flat_map(|cat| cat.lists_mut().inner_mut().values_mut())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0
+            .lists()
+            .inner()
+            .values()
+            .flat_map(|cat| cat.lists().inner().values())
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn actions_offset(&self) -> usize {
+        self.category_len("AI")
+    }
+
+    pub fn behaviors_offset(&self) -> usize {
+        self.actions_offset() + self.category_len("Action")
+    }
+
+    pub fn queries_offset(&self) -> usize {
+        self.behaviors_offset() + self.category_len("Behavior")
+    }
+
+    pub fn category_of(&self, idx: usize) -> Category {
+        if idx < self.actions_offset() {
+            Category::AI
+        } else if idx < self.behaviors_offset() {
+            Category::Action
+        } else if idx < self.queries_offset() {
+            Category::Behaviour
+        } else {
+            Category::Query
+        }
+    }
+
+    pub fn category_offset(&self, category: Category) -> usize {
+        match category {
+            Category::AI => 0,
+            Category::Action => self.actions_offset(),
+            Category::Behaviour => self.behaviors_offset(),
+            Category::Query => self.queries_offset(),
+        }
+    }
+
+    pub fn demos(&self) -> &ParameterObject {
+        self.0
+            .objects()
+            .get(hash_name("DemoAIActionIdx"))
+            .unwrap_or(&NO_DEMOS)
+    }
+
+    pub fn demos_mut(&mut self) -> &mut ParameterObject {
+        self.0
+            .objects_mut()
+            .inner_mut()
+            .entry(hash_name("DemoAIActionIdx"))
+            .or_default()
+    }
+
+    /// Which top-level list holds the entry with an absolute index, and its
+    /// index within that list.
+    pub fn locate(&self, idx: usize) -> (&'static str, usize) {
+        let (actions, behaviors, queries) = (
+            self.actions_offset(),
+            self.behaviors_offset(),
+            self.queries_offset(),
+        );
+        if idx < actions {
+            ("AI", idx)
+        } else if idx < behaviors {
+            ("Action", idx - actions)
+        } else if idx < queries {
+            ("Behavior", idx - behaviors)
+        } else {
+            ("Query", idx - queries)
+        }
+    }
+
+    /// The entry with an absolute index, or an error if there is none.
+    pub fn get_item(&self, idx: usize) -> Result<&ParameterList> {
+        let (category, local) = self.locate(idx);
+        self.0
+            .lists()
+            .get(hash_name(category))
+            .and_then(|list| list.lists().inner().get_index(local))
+            .map(|(_, v)| v)
+            .with_context(|| format!("No entry at index {}", idx))
+    }
+
+    pub fn get_item_mut(&mut self, idx: usize) -> Result<&mut ParameterList> {
+        let (category, local) = self.locate(idx);
+        self.0
+            .lists_mut()
+            .get_mut(hash_name(category))
+            .and_then(|list| list.lists_mut().inner_mut().get_index_mut(local))
+            .map(|(_, v)| v)
+            .with_context(|| format!("No entry at index {}", idx))
+    }
+
+    /// Like [`AIProgram::get_item_mut`], for indexes already known to be in
+    /// range. Panics otherwise.
+    pub fn item_mut_at_index(&mut self, idx: usize) -> &mut ParameterList {
+        self.get_item_mut(idx).unwrap()
+    }
+
+    /// Like [`AIProgram::get_item`], for indexes already known to be in
+    /// range. Panics otherwise.
+    pub fn item_at_index(&self, idx: usize) -> &ParameterList {
+        self.get_item(idx).unwrap()
+    }
+
+    /// Finds every `ChildIdx`, `BehaviorIdx`, and `DemoAIActionIdx` slot
+    /// which points at the entry with the given absolute index.
+    pub fn references(&self, idx: usize) -> References {
+        let mut refs = References {
+            demos: self
+                .demos()
+                .params()
+                .iter()
+                .filter(|(_, v)| points_to(v, idx as i32))
+                .map(|(k, _)| *k)
+                .collect(),
+            children: self.slot_references("ChildIdx", idx as i32),
+            behaviours: vec![],
+        };
+        if self.category_of(idx) == Category::Behaviour {
+            refs.behaviours =
+                self.slot_references("BehaviorIdx", (idx - self.behaviors_offset()) as i32);
+        }
+        refs
+    }
+
+    /// Finds the AI and Action slots in the given object which hold a value.
+    fn slot_references(&self, obj_name: &str, value: i32) -> Vec<(usize, u32)> {
+        self.items()
+            .into_iter()
+            .take(self.behaviors_offset())
+            .enumerate()
+            .filter_map(|(i, item)| item.objects().get(hash_name(obj_name)).map(|obj| (i, obj)))
+            .flat_map(|(i, obj)| {
+                obj.params()
+                    .iter()
+                    .filter(move |(_, v)| points_to(v, value))
+                    .map(move |(k, _)| (i, *k))
+            })
+            .collect()
+    }
+
+    pub(crate) fn set_slot(&mut self, holder: usize, obj_name: &str, key: u32, value: i32) {
+        if let Some(obj) = self
+            .item_mut_at_index(holder)
+            .objects_mut()
+            .get_mut(hash_name(obj_name))
+        {
+            obj.params_mut().insert(key, Parameter::Int(value));
+        }
+    }
+
+    /// Points a `ChildIdx` slot of one entry at another, clearing the slots of
+    /// its previous parent which pointed at it, and renames the child to match
+    /// its new slot.
+    pub fn reparent(
+        &mut self,
+        child: usize,
+        old_parent: Option<usize>,
+        new_parent: usize,
+        slot: u32,
+    ) -> Result<()> {
+        if child == new_parent || self.is_descendant(new_parent, child) {
+            anyhow::bail!(
+                "Cannot move {} under its own descendant {}",
+                self.entry_label(child),
+                self.entry_label(new_parent)
+            );
+        }
+        if let Some(old_parent) = old_parent {
+            for (holder, key) in self.slot_references("ChildIdx", child as i32) {
+                if holder == old_parent {
+                    self.set_slot(holder, "ChildIdx", key, -1);
+                }
+            }
+        }
+        self.set_slot(new_parent, "ChildIdx", slot, child as i32);
+        let parent_name = self
+            .item_at_index(new_parent)
+            .objects()
+            .get(hash_name("Def"))
+            .and_then(|def| def.params().get(&hash_name("Name")))
+            .and_then(|name| name.as_string().ok())
+            .map(|name| name.to_owned());
+        if let Some(parent_name) = parent_name {
+            self.update_names(child, try_name(slot), parent_name)?;
+        }
+        Ok(())
+    }
+
+    /// Whether an entry can be reached from another through `ChildIdx` slots.
+    fn is_descendant(&self, idx: usize, ancestor: usize) -> bool {
+        let mut seen = vec![false; self.behaviors_offset()];
+        let mut stack = self.children_of(ancestor);
+        while let Some(i) = stack.pop() {
+            if i == idx {
+                return true;
+            }
+            if !std::mem::replace(&mut seen[i], true) {
+                stack.extend(self.children_of(i));
+            }
+        }
+        false
+    }
+
+    /// Whether following `ChildIdx` slots down from an entry ever leads back
+    /// to an entry on the way there.
+    fn has_cycle_below(&self, idx: usize) -> bool {
+        // Entries on the current path, and those fully explored
+        let mut on_path = vec![false; self.behaviors_offset()];
+        let mut done = vec![false; self.behaviors_offset()];
+        let mut stack = vec![(idx, false)];
+        while let Some((i, leaving)) = stack.pop() {
+            if leaving {
+                on_path[i] = false;
+                done[i] = true;
+                continue;
+            }
+            if done[i] {
+                continue;
+            }
+            on_path[i] = true;
+            stack.push((i, true));
+            for child in self.children_of(i) {
+                if on_path[child] {
+                    return true;
+                }
+                stack.push((child, false));
+            }
+        }
+        false
+    }
+
+    /// Sets an entry's `Name` and `GroupName`, then names everything below it
+    /// after the slot it fills and its parent. Refuses entries with a
+    /// `ChildIdx` cycle below them, which it would follow forever.
+    pub fn update_names(&mut self, idx: usize, child: String, parent: String) -> Result<()> {
+        if idx < self.behaviors_offset() && self.has_cycle_below(idx) {
+            anyhow::bail!(
+                "There is a ChildIdx cycle below {}, which needs to be fixed before its names can be updated",
+                self.entry_label(idx)
+            );
+        }
+        self.update_names_below(idx, child, parent)
+    }
+
+    /// [`AIProgram::update_names`] for entries already known to have no
+    /// cycles below them.
+    pub(crate) fn update_names_below(
+        &mut self,
+        idx: usize,
+        child: String,
+        parent: String,
+    ) -> Result<()> {
+        let item = self.item_mut_at_index(idx);
+        let defs = item
+            .objects_mut()
+            .get_mut(hash_name("Def"))
+            .context("Entry has no Def")?
+            .params_mut();
+        defs.insert(hash_name("Name"), Parameter::StringRef(child.clone()));
+        defs.insert(hash_name("GroupName"), Parameter::StringRef(parent));
+        let mut child_updates: Vec<(usize, String)> = vec![];
+        if let Some(childs) = item.objects_mut().get_mut(hash_name("ChildIdx")) {
+            childs
+                .params_mut()
+                .iter_mut()
+                .filter(|(_, v)| **v != Parameter::Int(-1))
+                .try_for_each(|(k, v)| -> Result<()> {
+                    child_updates.push((v.as_int()? as usize, try_name(*k)));
+                    Ok(())
+                })?;
+        }
+        child_updates
+            .into_iter()
+            .try_for_each(|(i, s)| -> Result<()> {
+                self.update_names_below(i, s, child.clone())?;
+                Ok(())
+            })?;
+        Ok(())
+    }
+
+    /// Adds a blank entry of a class to the end of its category. Classes which
+    /// use behaviors get their `BehaviorIdx` slots up front, set to -1, taken
+    /// from other entries of the class in the program, as the AI defs don't
+    /// list them.
+    pub fn add_entry(&mut self, category: Category, class: String) -> Result<usize> {
+        let slots: BTreeSet<u32> = self
+            .items()
+            .into_iter()
+            .filter(|item| Self::entry_class(item) == Some(class.as_str()))
+            .filter_map(|item| item.objects().get(hash_name("BehaviorIdx")))
+            .flat_map(|obj| obj.params().keys().copied())
+            .collect();
+        let mut entry = AIDEFS.blank_ai(category, class)?;
+        if !slots.is_empty() {
+            let mut behaviors = ParameterObject::new();
+            for slot in slots {
+                behaviors.params_mut().insert(slot, Parameter::Int(-1));
+            }
+            entry
+                .objects_mut()
+                .inner_mut()
+                .insert(hash_name("BehaviorIdx"), behaviors);
+        }
+        Ok(self.insert_entry(category, entry))
+    }
+
+    fn entry_class(item: &ParameterList) -> Option<&str> {
+        item.objects()
+            .get(hash_name("Def"))?
+            .params()
+            .get(&hash_name("ClassName"))?
+            .as_string()
+            .ok()
+    }
+
+    /// Every string in the parameters of some entries, outside their `Def`.
+    /// Entries name the queries they use this way rather than by index.
+    pub(crate) fn param_strings(&self, entries: impl IntoIterator<Item = usize>) -> HashSet<&str> {
+        entries
+            .into_iter()
+            .flat_map(|i| self.item_at_index(i).objects().inner().iter())
+            .filter(|(k, _)| **k != hash_name("Def"))
+            .flat_map(|(_, obj)| obj.params().values())
+            .filter_map(|v| v.as_string().ok())
+            .collect()
+    }
+
+    /// The `Name` and `ClassName` of a query, either of which a parameter
+    /// can hold to name it.
+    pub(crate) fn query_names(&self, query: usize) -> Vec<&str> {
+        let def = self.item_at_index(query).objects().get(hash_name("Def"));
+        ["Name", "ClassName"]
+            .iter()
+            .filter_map(|key| {
+                def.and_then(|def| def.params().get(&hash_name(key)))
+                    .and_then(|v| v.as_string().ok())
+                    .filter(|name| !name.is_empty())
+            })
+            .collect()
+    }
+
+    /// Whether any of the strings from [`AIProgram::param_strings`] names a
+    /// query.
+    pub(crate) fn query_named_in(&self, query: usize, strings: &HashSet<&str>) -> bool {
+        self.query_names(query)
+            .iter()
+            .any(|name| strings.contains(name))
+    }
+
+    /// Copies an entry to the end of its category, returning the index of the
+    /// copy.
+    pub fn duplicate_entry(&mut self, idx: usize) -> Result<usize> {
+        let new_idx = self.insert_entry(self.category_of(idx), ParameterList::new());
+        // Copy after inserting so the clone picks up any shifted indexes
+        *self.item_mut_at_index(new_idx) = self.item_at_index(idx).clone();
+        Ok(new_idx)
+    }
+
+    /// Changes an entry's class, filling in the SInst parameters and ChildIdx
+    /// slots the new class declares. Parameters whose name and type still
+    /// match are kept, mistyped ones are reset to their default, and any the
+    /// new class doesn't define are left alone but returned by name.
+    pub fn migrate_class(&mut self, idx: usize, class: &str) -> Result<Vec<String>> {
+        let category = self.category_of(idx);
+        let def = AIDEFS
+            .get_def(category, class)
+            .with_context(|| format!("No AI def found for class {}", class))?;
+        let entry = self.item_mut_at_index(idx);
+        entry
+            .objects_mut()
+            .inner_mut()
+            .entry(hash_name("Def"))
+            .or_default()
+            .params_mut()
+            .insert(
+                hash_name("ClassName"),
+                Parameter::String32(class.to_owned()),
+            );
+        let slots = def.child_slots();
+        if !slots.is_empty() {
+            let children = entry
+                .objects_mut()
+                .inner_mut()
+                .entry(hash_name("ChildIdx"))
+                .or_default()
+                .params_mut();
+            for slot in &slots {
+                children
+                    .entry(hash_name(slot))
+                    .or_insert(Parameter::Int(-1));
+            }
+        }
+        let sinst = AIDEFS.sinst_params(category, class);
+        if !sinst.is_empty() {
+            let params = entry
+                .objects_mut()
+                .inner_mut()
+                .entry(hash_name("SInst"))
+                .or_default()
+                .params_mut();
+            for param in sinst.iter().filter(|param| param.has_default()) {
+                let key = hash_name(&param.name);
+                if !params.get(&key).map(|p| param.matches(p)).unwrap_or(false) {
+                    params.insert(
+                        key,
+                        AIDefs::default_parameter(&param.param_type, &param.value),
+                    );
+                }
+            }
+        }
+        let minst = def.map_unit_inst_params.as_deref().unwrap_or_default();
+        let mut undefined = vec![];
+        for (obj_name, defined) in [
+            ("ChildIdx", slots),
+            ("SInst", sinst.iter().map(|p| p.name.as_str()).collect()),
+            ("MInst", minst.iter().map(|p| p.name.as_str()).collect()),
+        ] {
+            if let Some(obj) = entry.objects().get(hash_name(obj_name)) {
+                undefined.extend(
+                    obj.params()
+                        .keys()
+                        .filter(|k| !defined.iter().any(|name| hash_name(name) == **k))
+                        .map(|k| format!("{}.{}", obj_name, try_name(*k))),
+                );
+            }
+        }
+        Ok(undefined)
+    }
+
+    /// Appends an entry to a category, shifting references to later entries.
+    pub(crate) fn insert_entry(&mut self, category: Category, entry: ParameterList) -> usize {
+        tracing::debug!("Adding an entry to {}", category);
+        match category {
+            Category::AI => {
+                let shifted = self.actions_offset() as i32;
+                ReferenceIndex::new(self).remap(
+                    self,
+                    |i| if i >= shifted { i + 1 } else { i },
+                    |i| i,
+                );
+                let new_idx = self.actions_offset();
+                self.category_list_mut("AI")
+                    .lists_mut()
+                    .inner_mut()
+                    .insert_full(hash_name(&format!("AI_{}", new_idx)), entry)
+                    .0
+            }
+            Category::Action => {
+                let shifted = self.behaviors_offset() as i32;
+                ReferenceIndex::new(self).remap(
+                    self,
+                    |i| if i >= shifted { i + 1 } else { i },
+                    |i| i,
+                );
+                let new_idx = self.behaviors_offset() - self.actions_offset();
+                self.category_list_mut("Action")
+                    .lists_mut()
+                    .inner_mut()
+                    .insert_full(hash_name(&format!("Action_{}", new_idx)), entry)
+                    .0
+                    + self.actions_offset()
+            }
+            Category::Behaviour => {
+                let shifted = self.queries_offset() as i32;
+                ReferenceIndex::new(self).remap(
+                    self,
+                    |i| if i >= shifted { i + 1 } else { i },
+                    |i| i,
+                );
+                let new_idx = self.queries_offset() - self.behaviors_offset();
+                self.category_list_mut("Behavior")
+                    .lists_mut()
+                    .inner_mut()
+                    .insert_full(hash_name(&format!("Behavior_{}", new_idx)), entry)
+                    .0
+                    + self.behaviors_offset()
+            }
+            Category::Query => {
+                let new_idx = self.len() - self.queries_offset();
+                self.category_list_mut("Query")
+                    .lists_mut()
+                    .inner_mut()
+                    .insert_full(hash_name(&format!("Query_{}", new_idx)), entry)
+                    .0
+                    + self.queries_offset()
+            }
+        }
+    }
+
+    pub fn delete_entry(&mut self, idx: usize) -> Result<()> {
+        self.delete_entries(&BTreeSet::from([idx]))
+    }
+
+    /// Deletes several entries at once, clearing slots which pointed at them
+    /// and shifting every other index down to match in a single pass.
+    pub fn delete_entries(&mut self, indexes: &BTreeSet<usize>) -> Result<()> {
+        if let Some(last) = indexes.iter().next_back() {
+            if *last >= self.len() {
+                anyhow::bail!("No entry at index {}", last);
+            }
+        }
+        tracing::debug!("Deleting entries {:?}", indexes);
+        let offset = self.behaviors_offset();
+        let behaviours: BTreeSet<usize> = indexes
+            .iter()
+            .filter(|i| self.category_of(**i) == Category::Behaviour)
+            .map(|i| i - offset)
+            .collect();
+        let shift = |removed: &BTreeSet<usize>| {
+            let removed = removed.clone();
+            move |i: i32| {
+                if removed.contains(&(i as usize)) {
+                    -1
+                } else {
+                    i - removed.range(..i as usize).count() as i32
+                }
+            }
+        };
+        ReferenceIndex::new(self).remap(self, shift(indexes), shift(&behaviours));
+        let mut touched = BTreeSet::new();
+        // From the end, so the positions of those still to go stay put
+        for idx in indexes.iter().rev() {
+            let (category, local) = self.locate(*idx);
+            self.category_list_mut(category)
+                .lists_mut()
+                .inner_mut()
+                .shift_remove_index(local);
+            touched.insert(category);
+        }
+        for category in touched {
+            self.rekey_category(category);
+        }
+        Ok(())
+    }
+
+    /// Names the entries of a top-level list `Category_N` again, in order,
+    /// after some have been removed.
+    pub(crate) fn rekey_category(&mut self, category: &str) {
+        let cat = self.category_list_mut(category);
+        let clone = cat.lists().inner().clone();
+        cat.lists_mut().inner_mut().clear();
+        cat.lists_mut()
+            .inner_mut()
+            .extend(clone.into_iter().enumerate().map(|(i, (_, v))| {
+                let key = format!("{}_{}", category, i);
+                (hash_name(&key), v)
+            }));
+    }
+
+    /// Swaps two entries of the same category and every reference to them.
+    pub fn swap_entries(&mut self, a: usize, b: usize) -> Result<()> {
+        for idx in [a, b] {
+            if idx >= self.len() {
+                anyhow::bail!("No entry at index {}", idx);
+            }
+        }
+        let category = self.category_of(a);
+        if self.category_of(b) != category {
+            anyhow::bail!("Entries can only be moved within their category");
+        }
+        let swap = |a: i32, b: i32| {
+            move |i: i32| {
+                if i == a {
+                    b
+                } else if i == b {
+                    a
+                } else {
+                    i
+                }
+            }
+        };
+        let offset = self.behaviors_offset() as i32;
+        let behaviours = if category == Category::Behaviour {
+            swap(a as i32 - offset, b as i32 - offset)
+        } else {
+            swap(-1, -1)
+        };
+        ReferenceIndex::new(self).remap(self, swap(a as i32, b as i32), behaviours);
+        let entry_a = self.item_at_index(a).clone();
+        let entry_b = std::mem::replace(self.item_mut_at_index(b), entry_a);
+        *self.item_mut_at_index(a) = entry_b;
+        Ok(())
+    }
+
+    pub fn roots(&self) -> Vec<usize> {
+        let index = ReferenceIndex::new(self);
+        (0..self.actions_offset())
+            .filter(|i| !index.children.contains_key(&(*i as i32)))
+            .collect()
+    }
+
+    /// Entries the game starts running from: the first AI, and anything a
+    /// demo slot points at.
+    pub fn entry_points(&self) -> Vec<usize> {
+        let mut entries: Vec<usize> = (0..self.actions_offset().min(1))
+            .chain(self.demos().params().values().filter_map(|v| match v {
+                Parameter::Int(i) if *i >= 0 && (*i as usize) < self.behaviors_offset() => {
+                    Some(*i as usize)
+                }
+                _ => None,
+            }))
+            .collect();
+        entries.sort_unstable();
+        entries.dedup();
+        entries
+    }
+
+    /// Roots which are neither entry points nor marked as roots by the user,
+    /// so nothing will ever run them.
+    pub fn orphan_roots(&self, marked: &BTreeSet<usize>) -> Vec<usize> {
+        let entry_points = self.entry_points();
+        self.roots()
+            .into_iter()
+            .filter(|r| !entry_points.contains(r) && !marked.contains(r))
+            .collect()
+    }
+
+    pub fn entry_name(ai: &ParameterList) -> Result<String> {
+        let def = ai
+            .objects()
+            .get(hash_name("Def"))
+            .context("AI missing def")?;
+        Ok(def
+            .params()
+            .get(&hash_name("Name"))
+            .map(|p| p.as_str_ref())
+            .or_else(|| {
+                def.params()
+                    .get(&hash_name("ClassName"))
+                    .map(|p| p.as_string32())
+            })
+            .context("AI missing name or class name")?
+            .map(translate)?)
+    }
+
+    /// Checks that an entry has what the editor expects of it: a `Def` with a
+    /// class name, and only ints in its index slots. Entries which fail can
+    /// still be shown, but not every section of them.
+    pub fn check_entry(&self, idx: usize) -> Result<()> {
+        let entry = self.get_item(idx)?;
+        let def = entry
+            .objects()
+            .get(hash_name("Def"))
+            .with_context(|| format!("{} has no Def", self.index_label(idx)))?;
+        def.params()
+            .get(&hash_name("ClassName"))
+            .and_then(|name| name.as_string().ok())
+            .with_context(|| format!("{} has no class name", self.index_label(idx)))?;
+        for obj_name in ["ChildIdx", "BehaviorIdx"] {
+            if let Some(obj) = entry.objects().get(hash_name(obj_name)) {
+                if let Some(key) = obj
+                    .params()
+                    .iter()
+                    .find(|(_, v)| !matches!(v, Parameter::Int(_)))
+                    .map(|(k, _)| *k)
+                {
+                    anyhow::bail!(
+                        "{} slot {} of {} is not an index",
+                        obj_name,
+                        try_name(key),
+                        self.index_label(idx)
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Label for an entry in the form `Category_N. ClassName`, numbered as
+    /// set by [`set_index_display`].
+    pub fn entry_label(&self, idx: usize) -> String {
+        format!(
+            "{}. {}",
+            self.index_label(idx),
+            self.entry_name_from_index(idx).unwrap_or("???")
+        )
+    }
+
+    /// The position of an entry, as [`index_label`] gives it.
+    pub fn index_label(&self, idx: usize) -> String {
+        let category = self.category_of(idx);
+        index_label(category, idx - self.category_offset(category), idx)
+    }
+
+    pub fn entry_name_from_index(&self, idx: usize) -> Result<&str> {
+        self.get_item(idx)?
+            .objects()
+            .get(hash_name("Def"))
+            .context("Missing defs")?
+            .params()
+            .get(&hash_name("ClassName"))
+            .context("Missing class name")?
+            .as_string()
+            .map_err(|e| e.into())
+    }
+
+    /// Indexes of the AIs and Actions an entry points at through `ChildIdx`.
+    pub fn children_of(&self, idx: usize) -> Vec<usize> {
+        self.item_at_index(idx)
+            .objects()
+            .get(hash_name("ChildIdx"))
+            .map(|obj| {
+                obj.params()
+                    .values()
+                    .filter_map(|v| match v {
+                        Parameter::Int(i) if *i >= 0 && (*i as usize) < self.behaviors_offset() => {
+                            Some(*i as usize)
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Indexes of the AIs and Actions which point at an entry, through
+    /// `ChildIdx` or, for a behavior, `BehaviorIdx`.
+    pub fn parents_of(&self, idx: usize) -> Vec<usize> {
+        let refs = self.references(idx);
+        let mut parents: Vec<usize> = refs
+            .children
+            .into_iter()
+            .chain(refs.behaviours)
+            .map(|(holder, _)| holder)
+            .collect();
+        parents.sort_unstable();
+        parents.dedup();
+        parents
+    }
+
+    /// Every chain of entries leading from a root down to an entry, each
+    /// starting with the root and ending with the entry itself. Stops after
+    /// `limit` paths, since shared entries deep in a program can be reached
+    /// in a great many ways.
+    pub fn ancestor_paths(&self, idx: usize, limit: usize) -> Vec<Vec<usize>> {
+        let mut paths = vec![];
+        let mut stack = vec![vec![idx]];
+        while let Some(path) = stack.pop() {
+            if paths.len() >= limit {
+                break;
+            }
+            let parents: Vec<usize> = self
+                .parents_of(path[0])
+                .into_iter()
+                .filter(|p| !path.contains(p))
+                .collect();
+            if parents.is_empty() {
+                paths.push(path);
+                continue;
+            }
+            for parent in parents {
+                let mut longer = vec![parent];
+                longer.extend(&path);
+                stack.push(longer);
+            }
+        }
+        paths
+    }
+
+    /// Finds every loop in the `ChildIdx` graph, each given as the path of
+    /// entries from the first entry back around to itself.
+    pub fn find_cycles(&self) -> Vec<Vec<usize>> {
+        fn visit(
+            aiprog: &AIProgram,
+            idx: usize,
+            path: &mut Vec<usize>,
+            done: &mut Vec<bool>,
+            cycles: &mut Vec<Vec<usize>>,
+        ) {
+            if let Some(start) = path.iter().position(|i| *i == idx) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(idx);
+                cycles.push(cycle);
+                return;
+            }
+            if done[idx] {
+                return;
+            }
+            path.push(idx);
+            for child in aiprog.children_of(idx) {
+                visit(aiprog, child, path, done, cycles);
+            }
+            path.pop();
+            done[idx] = true;
+        }
+        let mut done = vec![false; self.behaviors_offset()];
+        let mut cycles = vec![];
+        for idx in 0..self.behaviors_offset() {
+            visit(self, idx, &mut vec![], &mut done, &mut cycles);
+        }
+        cycles
+    }
+}
+
+fn points_to(param: &Parameter, idx: i32) -> bool {
+    matches!(param, Parameter::Int(i) if *i == idx)
+}
+
+pub fn is_actor_pack(file: &Path) -> bool {
+    file.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ACTOR_PACK_EXTS.contains(&ext))
+        .unwrap_or(false)
+}
+
+/// Whether a file is yaz0 compressed, or should be by its extension if it
+/// doesn't exist yet.
+pub fn is_compressed(file: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    match fs::File::open(file).and_then(|mut f| f.read_exact(&mut magic)) {
+        Ok(_) => &magic == b"Yaz0",
+        Err(_) => file
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.starts_with('s'))
+            .unwrap_or(false),
+    }
+}
+
+/// Reads a SARC, such as an actor pack, whether or not it is yaz0 compressed.
+pub(crate) fn read_sarc(data: &[u8]) -> Result<Sarc> {
+    Ok(if data.starts_with(b"Yaz0") {
+        Sarc::read(&yaz0::decompress(data)?)?
+    } else {
+        Sarc::read(data)?
+    })
+}
+
+fn aiprog_path_in_pack(sarc: &Sarc) -> Result<String> {
+    path_in_pack(sarc, "Actor/AIProgram/", ".baiprog")
+        .context("Actor pack does not contain an AI program")
+}
+
+pub(crate) fn path_in_pack(sarc: &Sarc, dir: &str, ext: &str) -> Option<String> {
+    sarc.files()
+        .filter_map(|file| file.name())
+        .find(|name| name.starts_with(dir) && name.ends_with(ext))
+        .map(|name| name.to_owned())
+}
+
+/// Reads the parameter IO of an AI program from the contents of a file,
+/// going by the file's name for its format.
+fn pio_from_bytes(file: &Path, data: &[u8]) -> Result<ParameterIO> {
+    if is_totk_path(file) || is_totk_data(data) {
+        return Err(unsupported_error(data));
+    }
+    if file.extension().map(|ext| ext == "json").unwrap_or(false) {
+        return pio_from_json(std::str::from_utf8(data).context("JSON file is not UTF-8")?);
+    }
+    Ok(if is_actor_pack(file) {
+        let sarc = read_sarc(data)?;
+        let name = aiprog_path_in_pack(&sarc)?;
+        ParameterIO::from_binary(
+            sarc.get_file_data(&name)
+                .context("Failed to read AI program from actor pack")?,
+        )?
+    } else {
+        let mut pio = aamp_from_bytes(data)?;
+        for change in normalize_legacy_lists(&mut pio) {
+            tracing::info!("{}: {}", file.display(), change);
+        }
+        if let Some(layout) = detect_layout(&pio) {
+            tracing::info!(
+                "{}: Read a variant layout, with {}",
+                file.display(),
+                layout.describe()
+            );
+            layout.normalize(&mut pio);
+        }
+        pio
+    })
+}
+
+/// Reads an AAMP file, binary, yaz0 compressed, or YAML. Goes by content
+/// rather than extension, so misnamed files still open.
+pub fn read_aamp(file: &Path) -> Result<ParameterIO> {
+    aamp_from_bytes(&fs::read(file)?)
+}
+
+/// Reads AAMP, binary or YAML and yaz0 compressed or not.
+fn aamp_from_bytes(data: &[u8]) -> Result<ParameterIO> {
+    let data = if data.starts_with(b"Yaz0") {
+        yaz0::decompress(data)?
+    } else {
+        data.to_vec()
+    };
+    Ok(if data.starts_with(b"AAMP") {
+        ParameterIO::from_binary(&data)?
+    } else {
+        let text = std::str::from_utf8(&data).context("File is not an AAMP or YAML file")?;
+        pio_from_text(text)?
+    })
+}
+
+/// Parses AAMP YAML, falling back to reading it in the dialect of older tools.
+pub(crate) fn pio_from_text(text: &str) -> Result<ParameterIO> {
+    match ParameterIO::from_text(text) {
+        Ok(pio) => Ok(pio),
+        // Try again in case it's from an older tool, but report the problem
+        // with the text as it was
+        Err(e) => {
+            let pio = ParameterIO::from_text(normalize_legacy_yaml(text)).map_err(|_| e)?;
+            tracing::info!("Read YAML from an older tool");
+            Ok(pio)
+        }
+    }
+}
+
+/// Reads the first AAMP file in a folder of an actor pack with the given
+/// extension, such as the AS list under `Actor/ASList/` ending `.baslist`.
+/// Returns its path in the pack along with it.
+pub fn read_pack_aamp(pack: &Path, dir: &str, ext: &str) -> Result<(String, ParameterIO)> {
+    let sarc = read_sarc(&fs::read(pack)?)?;
+    let name = path_in_pack(&sarc, dir, ext)
+        .with_context(|| format!("Actor pack has no {} file in {}", ext, dir))?;
+    let pio = ParameterIO::from_binary(
+        sarc.get_file_data(&name)
+            .with_context(|| format!("Failed to read {} from actor pack", name))?,
+    )?;
+    Ok((name, pio))
+}
+
+/// Replaces a file inside an existing actor pack, keeping every other file in
+/// the archive as-is and the pack's compression as it was.
+pub fn write_pack_file(pack: &Path, name: &str, data: Vec<u8>) -> Result<()> {
+    let bytes = fs::read(pack).context("Actor pack to save into does not exist")?;
+    let compressed = bytes.starts_with(b"Yaz0");
+    let sarc = read_sarc(&bytes)?;
+    let mut writer = SarcWriter::from_sarc(&sarc);
+    writer.add_file(name, data);
+    let out = writer.write_to_bytes();
+    fs::write(
+        pack,
+        if compressed {
+            yaz0::compress(&out)
+        } else {
+            out
+        },
+    )?;
+    Ok(())
+}
+
+/// Path of the AI program inside an actor pack file, such as
+/// `Actor/AIProgram/Enemy_Lizalfos.baiprog`.
+pub fn pack_program_path(pack: &Path) -> Result<String> {
+    aiprog_path_in_pack(&read_sarc(&fs::read(pack)?)?)
+}
+
+/// Finds the folder in a game dump holding `Actor/Pack`, which may be the dump
+/// itself or its `content` (Wii U) or `romfs` (Switch) folder.
+pub(crate) fn dump_content_dir(dump: &Path) -> Result<PathBuf> {
+    [dump.to_path_buf(), dump.join("content"), dump.join("romfs")]
+        .into_iter()
+        .find(|dir| dir.join("Actor/Pack").is_dir())
+        .with_context(|| format!("No Actor/Pack folder found in {}", dump.display()))
+}
+
+/// Lists the names of the actors with packs in a game dump.
+pub fn dump_actor_names(dump: &Path) -> Vec<String> {
+    let mut names: Vec<String> = dump_content_dir(dump)
+        .and_then(|dir| Ok(fs::read_dir(dir.join("Actor/Pack"))?))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| is_actor_pack(path))
+                .filter_map(|path| path.file_stem()?.to_str().map(|s| s.to_owned()))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Whether a file is a binary AI program or an actor pack holding one.
+pub fn is_program_file(file: &Path) -> bool {
+    is_actor_pack(file)
+        || file
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ["baiprog", "sbaiprog"].contains(&ext))
+            .unwrap_or(false)
+}
+
+/// Finds every AI program and actor pack in a folder and the folders below it,
+/// such as a mod's content folder, sorted by path.
+pub fn find_programs(dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+            if path.is_dir() {
+                // Backups would otherwise show up as copies of every file
+                if !path.ends_with(BACKUP_DIR) {
+                    dirs.push(path);
+                }
+            } else if is_program_file(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Extracts an actor's AI program from a game dump, returning its file name and
+/// binary data. Actors which only exist in `TitleBG.pack` are found there.
+pub fn extract_from_dump(dump: &Path, actor: &str) -> Result<(String, Vec<u8>)> {
+    let content = dump_content_dir(dump)?;
+    let pack_name = format!("Actor/Pack/{}.sbactorpack", actor);
+    let pack = match fs::read(content.join(&pack_name)) {
+        Ok(data) => data,
+        Err(_) => {
+            let title_bg = fs::read(content.join("Pack/TitleBG.pack"))
+                .with_context(|| format!("No actor pack found for {}", actor))?;
+            Sarc::read(&title_bg)?
+                .get_file_data(&pack_name)
+                .with_context(|| format!("No actor pack found for {}", actor))?
+                .to_vec()
+        }
+    };
+    let sarc = read_sarc(&pack)?;
+    let path = aiprog_path_in_pack(&sarc)?;
+    let data = sarc
+        .get_file_data(&path)
+        .context("Failed to read AI program from actor pack")?
+        .to_vec();
+    let name = Path::new(&path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("AIProgram.baiprog")
+        .to_owned();
+    Ok((name, data))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A small program of real classes: a `PlayerRoot` (0) pointing at a
+    /// `SleepBedRoot` (1) and an `IdleAction` (3), the `SleepBedRoot` at a
+    /// `Wait` (2) which uses the only behavior (4), a query (5), and a demo
+    /// pointing at the `IdleAction`.
+    pub(crate) fn fixture() -> AIProgram {
+        AIProgram::from_text(include_str!("../tests/fixture.yml")).unwrap()
+    }
+
+    /// A parameter of an object of an entry, such as a `ChildIdx` slot.
+    pub(crate) fn param<'a>(
+        aiprog: &'a AIProgram,
+        idx: usize,
+        obj: &str,
+        key: &str,
+    ) -> Option<&'a Parameter> {
+        aiprog
+            .item_at_index(idx)
+            .objects()
+            .get(hash_name(obj))?
+            .params()
+            .get(&hash_name(key))
+    }
+
+    /// Sets a parameter of an object an entry already has.
+    pub(crate) fn set_param(
+        aiprog: &mut AIProgram,
+        idx: usize,
+        obj: &str,
+        key: &str,
+        value: Parameter,
+    ) {
+        aiprog
+            .item_mut_at_index(idx)
+            .objects_mut()
+            .get_mut(hash_name(obj))
+            .unwrap()
+            .params_mut()
+            .insert(hash_name(key), value);
+    }
+
+    /// The entry a demo points at.
+    pub(crate) fn demo(aiprog: &AIProgram, key: &str) -> Option<i32> {
+        aiprog
+            .demos()
+            .params()
+            .get(&hash_name(key))
+            .and_then(|v| v.as_int().ok())
+    }
+
+    #[test]
+    fn fixture_layout() {
+        let aiprog = fixture();
+        assert_eq!(aiprog.len(), 6);
+        assert_eq!(
+            [
+                aiprog.actions_offset(),
+                aiprog.behaviors_offset(),
+                aiprog.queries_offset()
+            ],
+            [2, 4, 5]
+        );
+        assert_eq!(aiprog.children_of(0), vec![1, 3]);
+        assert_eq!(aiprog.entry_points(), vec![0, 3]);
+        assert!(aiprog.lint().is_empty());
+    }
+}
@@ -0,0 +1,56 @@
+use crate::program::AIProgram;
+use roead::aamp::{hash_name, ParamList};
+
+/// A parameter of an entry which names a query, by the query's `Name` or
+/// `ClassName`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryLink {
+    /// Key of the object holding the parameter, such as `SInst`
+    pub object: u32,
+    pub param: u32,
+    /// Absolute index of the query
+    pub query: usize,
+}
+
+impl AIProgram {
+    /// Parameters of an entry which name a query, the same way
+    /// [`AIProgram::export_subtree`] finds the queries to bring along.
+    pub fn query_links(&self, idx: usize) -> Vec<QueryLink> {
+        let names: Vec<(usize, Vec<&str>)> = (self.queries_offset()..self.len())
+            .map(|i| (i, self.query_names(i)))
+            .collect();
+        let mut links = vec![];
+        for (obj_key, obj) in self.item_at_index(idx).objects().inner() {
+            if *obj_key == hash_name("Def") {
+                continue;
+            }
+            for (key, value) in obj.params() {
+                if let Ok(s) = value.as_string() {
+                    if let Some((query, _)) = names.iter().find(|(_, names)| names.contains(&s)) {
+                        links.push(QueryLink {
+                            object: *obj_key,
+                            param: *key,
+                            query: *query,
+                        });
+                    }
+                }
+            }
+        }
+        links
+    }
+
+    /// Queries an entry names in any of its parameters.
+    pub fn queries_used_by(&self, idx: usize) -> Vec<usize> {
+        let mut queries: Vec<usize> = self.query_links(idx).iter().map(|l| l.query).collect();
+        queries.sort_unstable();
+        queries.dedup();
+        queries
+    }
+
+    /// AIs and Actions which name a query in their parameters.
+    pub fn query_users(&self, query: usize) -> Vec<usize> {
+        (0..self.behaviors_offset())
+            .filter(|i| self.query_links(*i).iter().any(|l| l.query == query))
+            .collect()
+    }
+}
@@ -0,0 +1,131 @@
+use crate::program::AIProgram;
+use roead::aamp::{hash_name, ParamList, Parameter};
+
+/// One `BehaviorIdx` parameter of an entry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BehaviorSlot {
+    pub entry: usize,
+    pub key: u32,
+    /// Index into the behaviors the parameter currently holds
+    pub index: i32,
+}
+
+impl AIProgram {
+    /// Every `BehaviorIdx` parameter of the given entries.
+    pub fn behavior_slots(&self, entries: &[usize]) -> Vec<BehaviorSlot> {
+        entries
+            .iter()
+            .filter(|i| **i < self.len())
+            .filter_map(|i| {
+                self.item_at_index(*i)
+                    .objects()
+                    .get(hash_name("BehaviorIdx"))
+                    .map(|obj| (*i, obj))
+            })
+            .flat_map(|(entry, obj)| {
+                obj.params().iter().filter_map(move |(k, v)| match v {
+                    Parameter::Int(index) => Some(BehaviorSlot {
+                        entry,
+                        key: *k,
+                        index: *index,
+                    }),
+                    _ => None,
+                })
+            })
+            .collect()
+    }
+
+    /// Class name of the behavior at an index into the behaviors.
+    pub fn behavior_class(&self, index: i32) -> Option<&str> {
+        if index < 0 || index as usize >= self.behaviors().len() {
+            return None;
+        }
+        self.entry_name_from_index(self.behaviors_offset() + index as usize)
+            .ok()
+    }
+
+    /// Finds the behavior in this program which best stands in for one of
+    /// `source`'s: the one with the same class, or the nth of them if the
+    /// source has several of that class.
+    pub fn match_behavior(&self, source: &AIProgram, index: i32) -> Option<i32> {
+        let class = source.behavior_class(index)?;
+        let nth = (0..index)
+            .filter(|i| source.behavior_class(*i) == Some(class))
+            .count();
+        let matches: Vec<i32> = (0..self.behaviors().len() as i32)
+            .filter(|i| self.behavior_class(*i) == Some(class))
+            .collect();
+        matches.get(nth).or_else(|| matches.first()).copied()
+    }
+
+    /// Points a `BehaviorIdx` parameter at another behavior.
+    pub fn set_behavior(&mut self, slot: &BehaviorSlot, index: i32) {
+        self.set_slot(slot.entry, "BehaviorIdx", slot.key, index);
+    }
+
+    /// Every entry reachable from the given one through `ChildIdx`, including
+    /// itself.
+    pub fn descendants(&self, root: usize) -> Vec<usize> {
+        let mut entries = vec![];
+        let mut stack = vec![root];
+        while let Some(idx) = stack.pop() {
+            if idx < self.len() && !entries.contains(&idx) {
+                entries.push(idx);
+                stack.extend(self.children_of(idx));
+            }
+        }
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::tests::{fixture, param};
+
+    #[test]
+    fn find_and_set_behaviors() {
+        let mut aiprog = fixture();
+        let slots = aiprog.behavior_slots(&[0, 1, 2, 3, 99]);
+        assert_eq!(
+            slots,
+            vec![BehaviorSlot {
+                entry: 2,
+                key: hash_name("DamageCallback"),
+                index: 0,
+            }]
+        );
+        assert_eq!(
+            aiprog.behavior_class(0),
+            Some("AcceptLSwordDamageDCCallback")
+        );
+        assert_eq!(aiprog.behavior_class(1), None);
+        assert_eq!(aiprog.behavior_class(-1), None);
+        aiprog.set_behavior(&slots[0], -1);
+        assert_eq!(
+            param(&aiprog, 2, "BehaviorIdx", "DamageCallback"),
+            Some(&Parameter::Int(-1))
+        );
+    }
+
+    #[test]
+    fn match_behaviors_by_class() {
+        let source = fixture();
+        assert_eq!(fixture().match_behavior(&source, 0), Some(0));
+        assert_eq!(fixture().match_behavior(&source, 1), None);
+        let mut without = fixture();
+        without.delete_entry(4).unwrap();
+        assert_eq!(without.match_behavior(&source, 0), None);
+    }
+
+    #[test]
+    fn descendants() {
+        let aiprog = fixture();
+        let mut all = aiprog.descendants(0);
+        all.sort_unstable();
+        assert_eq!(all, vec![0, 1, 2, 3]);
+        assert_eq!(aiprog.descendants(1), vec![1, 2]);
+        assert_eq!(aiprog.descendants(5), vec![5]);
+        assert!(aiprog.descendants(6).is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{program::AIProgram, util::update_name_table_from_pio};
+use anyhow::Result;
+use roead::aamp::{hash_name, ParamList, Parameter, ParameterIO, ParameterList, ParameterObject};
+use std::path::Path;
+
+impl AIProgram {
+    /// Reads an AI program which [`AIProgram::new`] would reject, filling in
+    /// whatever it's missing so it can be opened and fixed. Files which can't
+    /// be read at all still fail. Returns a description of each repair.
+    pub fn new_permissive<P: AsRef<Path>>(file: P) -> Result<(Self, Vec<String>)> {
+        Ok(Self::from_pio_permissive(Self::read_pio(file.as_ref())?))
+    }
+
+    /// Wraps a parameter IO as an AI program, adding any top-level lists and
+    /// objects it lacks, and giving entries without a `Def` or a class name
+    /// of the right type something to show. Returns a description of each
+    /// repair.
+    pub fn from_pio_permissive(mut pio: ParameterIO) -> (Self, Vec<String>) {
+        let mut repairs = vec![];
+        for name in ["AI", "Action", "Behavior", "Query"] {
+            if pio.lists().get(hash_name(name)).is_none() {
+                pio.lists_mut()
+                    .inner_mut()
+                    .insert(hash_name(name), ParameterList::new());
+                repairs.push(format!("Added missing {} list", name));
+            }
+        }
+        if pio.objects().get(hash_name("DemoAIActionIdx")).is_none() {
+            pio.objects_mut()
+                .inner_mut()
+                .insert(hash_name("DemoAIActionIdx"), ParameterObject::new());
+            repairs.push(String::from("Added missing DemoAIActionIdx"));
+        }
+        for name in ["AI", "Action", "Behavior", "Query"] {
+            let list = pio.lists_mut().get_mut(hash_name(name)).unwrap();
+            for (i, entry) in list.lists_mut().inner_mut().values_mut().enumerate() {
+                for fix in repair_entry(entry) {
+                    repairs.push(format!("{}_{}: {}", name, i, fix));
+                }
+            }
+        }
+        for repair in &repairs {
+            tracing::warn!("Repaired program: {}", repair);
+        }
+        update_name_table_from_pio(&pio);
+        (Self(pio), repairs)
+    }
+}
+
+/// Makes sure an entry has a `Def` with a `ClassName`, and that it and any
+/// `Name` have the types the editor reads them as, returning what was
+/// changed.
+fn repair_entry(entry: &mut ParameterList) -> Vec<String> {
+    let mut fixes = vec![];
+    if entry.objects().get(hash_name("Def")).is_none() {
+        entry
+            .objects_mut()
+            .inner_mut()
+            .insert(hash_name("Def"), ParameterObject::new());
+        fixes.push(String::from("added missing Def"));
+    }
+    let def = entry.objects_mut().get_mut(hash_name("Def")).unwrap();
+    let params = def.params_mut();
+    for (key, wrap) in [
+        ("ClassName", Parameter::String32 as fn(String) -> Parameter),
+        ("Name", Parameter::StringRef),
+    ] {
+        let value = params.get(&hash_name(key));
+        let fixed = match value {
+            Some(Parameter::String32(_)) if key == "ClassName" => continue,
+            Some(Parameter::StringRef(_)) if key == "Name" => continue,
+            Some(param) if param.is_string() => {
+                fixes.push(format!("changed the type of {}", key));
+                wrap(param.as_string().unwrap_or_default().to_owned())
+            }
+            Some(_) => {
+                fixes.push(format!("replaced {} which was not a string", key));
+                wrap(String::from("Unknown"))
+            }
+            // Entries without a name go by their class
+            None if key == "Name" => continue,
+            None => {
+                fixes.push(format!("added missing {}", key));
+                wrap(String::from("Unknown"))
+            }
+        };
+        params.insert(hash_name(key), fixed);
+    }
+    fixes
+}
@@ -0,0 +1,125 @@
+use crate::{program::AIProgram, util::try_name};
+use anyhow::{Context, Result};
+use regex::{NoExpand, Regex, RegexBuilder};
+use roead::aamp::{hash_name, ParamList, Parameter};
+
+/// How [`AIProgram::find_replace`] matches text.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReplaceOptions {
+    pub case_sensitive: bool,
+    /// Treat the search as a regular expression, with `$1` and the like
+    /// allowed in the replacement
+    pub regex: bool,
+}
+
+/// A string parameter which a replacement would change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplaceHit {
+    /// Absolute index of the entry holding the parameter
+    pub entry: usize,
+    pub object: u32,
+    pub key: u32,
+    pub before: String,
+    pub after: String,
+}
+
+impl ReplaceHit {
+    /// Where the parameter is, such as `Def.Name`.
+    pub fn field(&self) -> String {
+        format!("{}.{}", try_name(self.object), try_name(self.key))
+    }
+}
+
+fn param_string_mut(param: &mut Parameter) -> Option<&mut String> {
+    match param {
+        Parameter::String32(s)
+        | Parameter::String64(s)
+        | Parameter::String256(s)
+        | Parameter::StringRef(s) => Some(s),
+        _ => None,
+    }
+}
+
+fn build_regex(find: &str, options: ReplaceOptions) -> Result<Regex> {
+    let pattern = if options.regex {
+        find.to_owned()
+    } else {
+        regex::escape(find)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .with_context(|| format!("{} is not a valid regular expression", find))
+}
+
+impl AIProgram {
+    /// Finds every string parameter, in any object but the index ones, which
+    /// replacing `find` with `replace` would change, without changing it.
+    pub fn find_replace(
+        &self,
+        find: &str,
+        replace: &str,
+        options: ReplaceOptions,
+    ) -> Result<Vec<ReplaceHit>> {
+        if find.is_empty() {
+            return Ok(vec![]);
+        }
+        let re = build_regex(find, options)?;
+        let index_objs = [hash_name("ChildIdx"), hash_name("BehaviorIdx")];
+        let mut hits = vec![];
+        for (i, item) in self.items().into_iter().enumerate() {
+            for (obj_key, obj) in item.objects().inner() {
+                if index_objs.contains(obj_key) {
+                    continue;
+                }
+                for (k, v) in obj.params() {
+                    let before = match v {
+                        Parameter::String32(s)
+                        | Parameter::String64(s)
+                        | Parameter::String256(s)
+                        | Parameter::StringRef(s) => s,
+                        _ => continue,
+                    };
+                    let after = if options.regex {
+                        re.replace_all(before, replace)
+                    } else {
+                        re.replace_all(before, NoExpand(replace))
+                    };
+                    if after != before.as_str() {
+                        hits.push(ReplaceHit {
+                            entry: i,
+                            object: *obj_key,
+                            key: *k,
+                            before: before.clone(),
+                            after: after.into_owned(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(hits)
+    }
+
+    /// Makes the changes found by [`AIProgram::find_replace`], skipping any
+    /// parameter which no longer holds the value it was found with. Returns
+    /// how many were changed.
+    pub fn apply_replace(&mut self, hits: &[ReplaceHit]) -> usize {
+        let len = self.len();
+        let mut count = 0;
+        for hit in hits.iter().filter(|hit| hit.entry < len) {
+            if let Some(s) = self
+                .item_mut_at_index(hit.entry)
+                .objects_mut()
+                .get_mut(hit.object)
+                .and_then(|obj| obj.params_mut().get_mut(&hit.key))
+                .and_then(param_string_mut)
+            {
+                if *s == hit.before {
+                    *s = hit.after.clone();
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
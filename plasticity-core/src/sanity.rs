@@ -0,0 +1,127 @@
+//! Warnings for numeric parameters with values the game likely doesn't
+//! expect, going by what their names say they hold.
+use crate::{
+    program::AIProgram,
+    util::{angle_unit, name_words, try_name, AngleUnit},
+    validate::{Issue, IssueKind},
+};
+use roead::aamp::{hash_name, ParamList, Parameter};
+use std::{f32::consts::TAU, fmt};
+
+/// Words naming quantities which can't sensibly be negative
+const NON_NEGATIVE: &[&str] = &[
+    "dist", "distance", "radius", "range", "length", "height", "width", "time", "frame", "frames",
+    "interval", "count", "num",
+];
+
+/// What the game often uses to mean none or forever, which is fine for any
+/// quantity
+const SENTINEL: f32 = -1.0;
+
+/// Highest a rate, such as a speed or animation multiplier, usually goes
+pub const MAX_RATE: f32 = 10.0;
+
+/// What looks wrong with a numeric value. It displays as English, for
+/// reports; the editor words each in the user's language.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueWarning {
+    /// Infinity or NaN
+    NotFinite(f32),
+    /// An angle of more than a full turn in the unit its name gives
+    PastFullTurn(f32, AngleUnit),
+    /// A negative value for a quantity which can't be, named by the word
+    Negative(f32, String),
+    /// A rate outside of 0 to [`MAX_RATE`]
+    UnusualRate(f32),
+}
+
+impl fmt::Display for ValueWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFinite(value) => write!(f, "{} isn't a number the game can use", value),
+            Self::PastFullTurn(value, AngleUnit::Degrees) => {
+                write!(f, "{}° is more than a full turn", value)
+            }
+            Self::PastFullTurn(value, AngleUnit::Radians) => write!(
+                f,
+                "{} radians is more than a full turn, so it may have been entered in degrees",
+                value
+            ),
+            Self::Negative(value, word) => write!(
+                f,
+                "{} is negative, which makes no sense for a {}",
+                value, word
+            ),
+            Self::UnusualRate(value) => write!(
+                f,
+                "{} is unusual for a rate, which is usually between 0 and {}",
+                value, MAX_RATE
+            ),
+        }
+    }
+}
+
+/// Says what looks wrong with a numeric parameter's value, if anything.
+pub fn value_warning(name: &str, param: &Parameter) -> Option<ValueWarning> {
+    let value = match param {
+        Parameter::F32(f) => *f,
+        Parameter::Int(i) => *i as f32,
+        Parameter::U32(u) => *u as f32,
+        _ => return None,
+    };
+    if !value.is_finite() {
+        return Some(ValueWarning::NotFinite(value));
+    }
+    let words = name_words(name);
+    if let Some(unit) = angle_unit(name) {
+        let turn = match unit {
+            AngleUnit::Degrees => 360.0,
+            AngleUnit::Radians => TAU,
+        };
+        if value.abs() > turn + f32::EPSILON {
+            return Some(ValueWarning::PastFullTurn(value, unit));
+        }
+        return None;
+    }
+    if let Some(word) = words.iter().find(|w| NON_NEGATIVE.contains(&w.as_str())) {
+        if value < 0.0 && value != SENTINEL {
+            return Some(ValueWarning::Negative(value, word.clone()));
+        }
+    }
+    if words.iter().any(|w| w == "rate") && !(0.0..=MAX_RATE).contains(&value) {
+        return Some(ValueWarning::UnusualRate(value));
+    }
+    None
+}
+
+impl AIProgram {
+    /// Numeric `SInst` and `MInst` parameters of an entry with values which
+    /// look wrong, by [`value_warning`].
+    pub fn value_issues(&self, idx: usize) -> Vec<Issue> {
+        let objects = self.item_at_index(idx).objects();
+        let mut issues = vec![];
+        for obj_name in ["SInst", "MInst"] {
+            if let Some(obj) = objects.get(hash_name(obj_name)) {
+                for (k, v) in obj.params() {
+                    let name = try_name(*k);
+                    if let Some(warning) = value_warning(&name, v) {
+                        issues.push(Issue::new(
+                            Some(idx),
+                            IssueKind::OddValue {
+                                obj: obj_name,
+                                name,
+                                warning,
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+        issues
+    }
+
+    /// Runs [`AIProgram::value_issues`] on every entry.
+    pub fn validate_values(&self) -> Vec<Issue> {
+        (0..self.len()).flat_map(|i| self.value_issues(i)).collect()
+    }
+}
@@ -0,0 +1,208 @@
+//! A JSON Schema for AI programs as [`AIProgram::to_json`] writes them, made
+//! from the AI defs, so mod repositories can check their programs in CI with
+//! any schema validator. It checks what [`AIProgram::validate_defs`] does:
+//! classes, parameter types, and `ChildIdx` slots.
+use crate::{
+    json::list_to_json,
+    program::{AIProgram, Category},
+    util::{AIDefParam, AIDEFS},
+};
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::{fs, path::Path};
+
+/// Name the schema is written under beside programs exported with it
+pub const SCHEMA_FILE: &str = "aiprogram.schema.json";
+
+const CATEGORIES: [Category; 4] = [
+    Category::AI,
+    Category::Action,
+    Category::Behaviour,
+    Category::Query,
+];
+
+/// A parameter of one type, as an object with the type as its only key.
+fn typed(kind: &str, value: Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": { kind: value },
+        "required": [kind],
+        "additionalProperties": false,
+    })
+}
+
+/// The value of a parameter of the type an AI def declares.
+fn value_schema(param: &AIDefParam, value_type: &str) -> Value {
+    let mut schema = json!({ "type": value_type });
+    if let Some(description) = &param.description {
+        schema["description"] = json!(description);
+    }
+    schema
+}
+
+fn param_schema(param: &AIDefParam) -> Value {
+    match param.param_type.as_str() {
+        "Bool" => typed("Bool", value_schema(param, "boolean")),
+        "Float" => typed("F32", value_schema(param, "number")),
+        "Int" => typed("Int", value_schema(param, "integer")),
+        "String" => {
+            let kinds: Vec<Value> = ["String32", "String64", "String256", "StringRef"]
+                .iter()
+                .map(|kind| typed(kind, value_schema(param, "string")))
+                .collect();
+            json!({ "oneOf": kinds })
+        }
+        "Vec3" => typed(
+            "Vec3",
+            json!({ "type": "array", "items": { "type": "number" }, "minItems": 3, "maxItems": 3 }),
+        ),
+        // Pointers and such have no AAMP type of their own
+        _ => json!({ "$ref": "#/definitions/Parameter" }),
+    }
+}
+
+/// Parameters of an object, limited to those declared when any are, as
+/// [`AIProgram::def_issues`] does.
+fn params_schema(defined: &[AIDefParam]) -> Value {
+    if defined.is_empty() {
+        return json!({ "type": "object", "additionalProperties": { "$ref": "#/definitions/Parameter" } });
+    }
+    let properties: Map<String, Value> = defined
+        .iter()
+        .map(|param| (param.name.clone(), param_schema(param)))
+        .collect();
+    json!({ "type": "object", "properties": properties, "additionalProperties": false })
+}
+
+fn class_schema(category: Category, class: &str) -> Value {
+    let def = match AIDEFS.get_def(category, class) {
+        Some(def) => def,
+        None => return json!({}),
+    };
+    let mut objects = Map::new();
+    objects.insert(
+        "SInst".into(),
+        params_schema(AIDEFS.sinst_params(category, class)),
+    );
+    objects.insert(
+        "MInst".into(),
+        params_schema(def.map_unit_inst_params.as_deref().unwrap_or_default()),
+    );
+    let slots = def.child_slots();
+    if !slots.is_empty() {
+        objects.insert(
+            "ChildIdx".into(),
+            json!({ "type": "object", "required": slots }),
+        );
+    }
+    let mut schema = json!({ "properties": { "objects": { "properties": objects } } });
+    if !slots.is_empty() {
+        schema["properties"]["objects"]["required"] = json!(["ChildIdx"]);
+    }
+    schema
+}
+
+/// Matches entries of a class.
+fn class_is(class: &str) -> Value {
+    json!({
+        "properties": { "objects": { "properties": { "Def": { "properties": {
+            "ClassName": { "properties": { "String32": { "const": class } } }
+        } } } } }
+    })
+}
+
+/// Entries of a category: any of its classes, each checked against its own
+/// def.
+fn category_schema(category: Category) -> Value {
+    let classes: Vec<&String> = AIDEFS.classes(category).collect();
+    let mut all_of = vec![json!({
+        "type": "object",
+        "required": ["objects"],
+        "properties": { "objects": {
+            "type": "object",
+            "required": ["Def"],
+            "properties": { "Def": {
+                "type": "object",
+                "required": ["ClassName"],
+                "properties": { "ClassName": typed("String32", json!({ "enum": classes })) },
+            } },
+        } },
+    })];
+    all_of.extend(classes.iter().map(|class| {
+        json!({
+            "if": class_is(class),
+            "then": { "$ref": format!("#/definitions/{}.{}", category, class) },
+        })
+    }));
+    json!({ "allOf": all_of })
+}
+
+/// The schema of a whole AI program in JSON, with a definition for each
+/// category's entries and each class.
+pub fn json_schema() -> Value {
+    let mut definitions = Map::new();
+    definitions.insert(
+        "Parameter".into(),
+        json!({ "type": "object", "minProperties": 1, "maxProperties": 1 }),
+    );
+    let mut lists = Map::new();
+    for category in CATEGORIES {
+        for class in AIDEFS.classes(category) {
+            definitions.insert(
+                format!("{}.{}", category, class),
+                class_schema(category, class),
+            );
+        }
+        definitions.insert(category.to_string(), category_schema(category));
+        lists.insert(
+            category.to_string(),
+            json!({
+                "type": "object",
+                "properties": { "lists": {
+                    "type": "object",
+                    "additionalProperties": { "$ref": format!("#/definitions/{}", category) },
+                } },
+            }),
+        );
+    }
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "BOTW AI program",
+        "description": "An AI program as Plasticity exports it to JSON",
+        "type": "object",
+        "required": ["objects", "lists"],
+        "properties": {
+            "$schema": { "type": "string" },
+            "objects": {
+                "type": "object",
+                "required": ["DemoAIActionIdx"],
+                "properties": { "DemoAIActionIdx": {
+                    "type": "object",
+                    "additionalProperties": typed("Int", json!({ "type": "integer" })),
+                } },
+            },
+            "lists": {
+                "type": "object",
+                "required": ["AI", "Action", "Behavior", "Query"],
+                "properties": lists,
+            },
+        },
+        "definitions": definitions,
+    })
+}
+
+pub fn save_json_schema(file: &Path) -> Result<()> {
+    fs::write(file, serde_json::to_string_pretty(&json_schema())?)?;
+    Ok(())
+}
+
+impl AIProgram {
+    /// Exports the program to JSON with [`SCHEMA_FILE`] beside it, which the
+    /// JSON names as its schema for editors and validators to pick up.
+    pub fn save_json_with_schema(&self, file: &Path) -> Result<()> {
+        let mut value = list_to_json(&self.0)?;
+        value["$schema"] = json!(format!("./{}", SCHEMA_FILE));
+        fs::write(file, serde_json::to_string_pretty(&value)?)?;
+        save_json_schema(&file.with_file_name(SCHEMA_FILE))
+    }
+}
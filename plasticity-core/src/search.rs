@@ -0,0 +1,61 @@
+use crate::{program::AIProgram, util::*};
+use roead::aamp::{hash_name, ParamList, Parameter};
+
+/// An entry matching a search query.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    /// Absolute index of the matching entry
+    pub entry: usize,
+    /// Description of the matching parameter and its value
+    pub field: String,
+}
+
+/// Text form of a string or numeric parameter, if it has one.
+fn param_text(param: &Parameter) -> Option<String> {
+    match param {
+        Parameter::String32(s)
+        | Parameter::String64(s)
+        | Parameter::String256(s)
+        | Parameter::StringRef(s) => Some(s.clone()),
+        Parameter::Int(i) => Some(i.to_string()),
+        Parameter::U32(u) => Some(u.to_string()),
+        Parameter::F32(f) => Some(f.to_string()),
+        _ => None,
+    }
+}
+
+impl AIProgram {
+    /// Finds every entry with a name, class, or string/numeric parameter value
+    /// containing the query. Index objects are skipped, as their values are
+    /// not meaningful to search.
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let query = query.to_lowercase();
+        if query.is_empty() {
+            return vec![];
+        }
+        let index_objs = [hash_name("ChildIdx"), hash_name("BehaviorIdx")];
+        let matches = |text: &str| {
+            text.to_lowercase().contains(&query)
+                || english(text)
+                    .map(|en| en.to_lowercase().contains(&query))
+                    .unwrap_or(false)
+        };
+        let mut hits = vec![];
+        for (i, item) in self.items().into_iter().enumerate() {
+            for (obj_key, obj) in item.objects().inner() {
+                if index_objs.contains(obj_key) {
+                    continue;
+                }
+                for (k, v) in obj.params() {
+                    if let Some(text) = param_text(v).filter(|text| matches(text)) {
+                        hits.push(SearchHit {
+                            entry: i,
+                            field: format!("{}.{}: {}", try_name(*obj_key), try_name(*k), text),
+                        });
+                    }
+                }
+            }
+        }
+        hits
+    }
+}
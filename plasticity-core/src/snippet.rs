@@ -0,0 +1,209 @@
+use crate::{
+    program::{AIProgram, Category},
+    util::try_name,
+};
+use anyhow::{Context, Result};
+use roead::aamp::{hash_name, ParamList, Parameter, ParameterIO, ParameterList, ParameterObject};
+use std::collections::{BTreeSet, HashMap};
+
+/// Copies an entry with its `ChildIdx` and `BehaviorIdx` values passed through
+/// a mapping, clearing any which aren't in it.
+pub(crate) fn remap_entry(
+    entry: &ParameterList,
+    children: impl Fn(i32) -> Option<i32>,
+    behaviours: impl Fn(i32) -> Option<i32>,
+) -> ParameterList {
+    let mut entry = entry.clone();
+    for (obj_name, remap) in [
+        ("ChildIdx", &children as &dyn Fn(i32) -> Option<i32>),
+        ("BehaviorIdx", &behaviours),
+    ] {
+        if let Some(obj) = entry.objects_mut().get_mut(hash_name(obj_name)) {
+            for v in obj.params_mut().values_mut() {
+                if let Parameter::Int(i) = v {
+                    if *i >= 0 {
+                        *i = remap(*i).unwrap_or(-1);
+                    }
+                }
+            }
+        }
+    }
+    entry
+}
+
+fn def_string<'a>(item: &'a ParameterList, key: &str) -> Option<&'a str> {
+    item.objects()
+        .get(hash_name("Def"))?
+        .params()
+        .get(&hash_name(key))?
+        .as_string()
+        .ok()
+}
+
+impl AIProgram {
+    /// Copies an entry and everything below it into a standalone program, with
+    /// the entry itself first. Behaviors they point at come along, as do any
+    /// queries they name.
+    pub fn export_subtree(&self, root: usize) -> Result<AIProgram> {
+        if root >= self.behaviors_offset() {
+            anyhow::bail!("Only AIs and Actions can be exported as a subtree");
+        }
+        let items = self.items();
+        let behaviors_offset = self.behaviors_offset();
+        let mut entries: Vec<usize> = vec![];
+        let mut behaviours: BTreeSet<usize> = BTreeSet::new();
+        let mut stack = vec![root];
+        while let Some(idx) = stack.pop() {
+            if entries.contains(&idx) {
+                continue;
+            }
+            entries.push(idx);
+            stack.extend(self.children_of(idx));
+            if let Some(obj) = items[idx].objects().get(hash_name("BehaviorIdx")) {
+                behaviours.extend(obj.params().values().filter_map(|v| match v {
+                    Parameter::Int(i) if *i >= 0 => Some(behaviors_offset + *i as usize),
+                    _ => None,
+                }));
+            }
+        }
+        // Keep the root first and everything else in its original order
+        entries[1..].sort_unstable();
+        let (ais, actions): (Vec<usize>, Vec<usize>) = entries
+            .into_iter()
+            .partition(|i| *i < self.actions_offset());
+        let strings = self.param_strings(
+            ais.iter()
+                .chain(actions.iter())
+                .chain(behaviours.iter())
+                .copied(),
+        );
+        let queries: Vec<usize> = (self.queries_offset()..self.len())
+            .filter(|i| self.query_named_in(*i, &strings))
+            .collect();
+
+        let order: Vec<(Category, usize)> = ais
+            .iter()
+            .map(|i| (Category::AI, *i))
+            .chain(actions.iter().map(|i| (Category::Action, *i)))
+            .chain(behaviours.iter().map(|i| (Category::Behaviour, *i)))
+            .chain(queries.iter().map(|i| (Category::Query, *i)))
+            .collect();
+        let new_index: HashMap<usize, i32> = order
+            .iter()
+            .enumerate()
+            .map(|(new, (_, old))| (*old, new as i32))
+            .collect();
+        let new_behaviors_offset = (ais.len() + actions.len()) as i32;
+
+        let mut pio = ParameterIO::new();
+        pio.objects_mut()
+            .inner_mut()
+            .insert(hash_name("DemoAIActionIdx"), ParameterObject::new());
+        for name in ["AI", "Action", "Behavior", "Query"] {
+            pio.lists_mut()
+                .inner_mut()
+                .insert(hash_name(name), ParameterList::new());
+        }
+        for (category, old) in &order {
+            let entry = remap_entry(
+                items[*old],
+                |i| new_index.get(&(i as usize)).copied(),
+                |i| {
+                    new_index
+                        .get(&(behaviors_offset + i as usize))
+                        .map(|new| new - new_behaviors_offset)
+                },
+            );
+            let list = pio
+                .lists_mut()
+                .get_mut(hash_name(&category.to_string()))
+                .unwrap();
+            let key = format!("{}_{}", category, list.lists().len());
+            list.lists_mut().inner_mut().insert(hash_name(&key), entry);
+        }
+        Self::from_pio(pio)
+    }
+
+    /// Adds every entry of a snippet made by [`AIProgram::export_subtree`],
+    /// pointing a `ChildIdx` slot of `parent` at its first entry. Queries this
+    /// program already has a class of are reused rather than added again.
+    /// Returns the new index of the snippet's first entry.
+    pub fn import_subtree(
+        &mut self,
+        snippet: &AIProgram,
+        parent: usize,
+        slot: u32,
+    ) -> Result<usize> {
+        if parent >= self.actions_offset() {
+            anyhow::bail!("Only AIs can have children");
+        }
+        let root = self.merge_snippet(snippet, &HashMap::new())?;
+        let parent_name = def_string(self.item_at_index(parent), "Name")
+            .unwrap_or_default()
+            .to_owned();
+        self.set_slot(parent, "ChildIdx", slot, root as i32);
+        let defs = self
+            .item_mut_at_index(root)
+            .objects_mut()
+            .get_mut(hash_name("Def"))
+            .context("Snippet entry is missing its definition")?
+            .params_mut();
+        defs.insert(hash_name("Name"), Parameter::StringRef(try_name(slot)));
+        defs.insert(hash_name("GroupName"), Parameter::StringRef(parent_name));
+        Ok(root)
+    }
+
+    /// Adds every entry of a snippet made by [`AIProgram::export_subtree`]
+    /// without attaching it to anything. Behaviors in `reuse`, by their index
+    /// in the snippet, are pointed at the given behavior of this program rather
+    /// than copied, and queries this program already has a class of are reused.
+    /// Returns the new index of the snippet's first entry.
+    pub fn merge_snippet(
+        &mut self,
+        snippet: &AIProgram,
+        reuse: &HashMap<i32, i32>,
+    ) -> Result<usize> {
+        if snippet.behaviors_offset() == 0 {
+            anyhow::bail!("Snippet contains no AIs or Actions");
+        }
+        let existing_queries: BTreeSet<String> = (self.queries_offset()..self.len())
+            .filter_map(|i| self.entry_name_from_index(i).ok().map(|s| s.to_owned()))
+            .collect();
+        let snippet_behaviors_offset = snippet.behaviors_offset();
+        // Insert placeholders first, so the final index of every entry is
+        // known before any references are rewritten
+        let mut new_index: HashMap<usize, i32> = HashMap::new();
+        for idx in 0..snippet.len() {
+            let category = snippet.category_of(idx);
+            let skip = match category {
+                Category::Behaviour => {
+                    reuse.contains_key(&((idx - snippet_behaviors_offset) as i32))
+                }
+                Category::Query => snippet
+                    .entry_name_from_index(idx)
+                    .map(|class| existing_queries.contains(class))
+                    .unwrap_or(false),
+                _ => false,
+            };
+            if !skip {
+                let new = self.insert_entry(category, ParameterList::new());
+                new_index.insert(idx, new as i32);
+            }
+        }
+        let behaviors_offset = self.behaviors_offset() as i32;
+        for (old, new) in &new_index {
+            *self.item_mut_at_index(*new as usize) = remap_entry(
+                snippet.item_at_index(*old),
+                |i| new_index.get(&(i as usize)).copied(),
+                |i| {
+                    reuse.get(&i).copied().or_else(|| {
+                        new_index
+                            .get(&(snippet_behaviors_offset + i as usize))
+                            .map(|new| new - behaviors_offset)
+                    })
+                },
+            );
+        }
+        Ok(new_index[&0] as usize)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    legacy::normalize_legacy_lists,
+    program::{pio_from_text, AIProgram},
+};
+use anyhow::{Context, Result};
+use roead::aamp::{hash_name, ParamList, ParameterIO};
+
+impl AIProgram {
+    /// Parses a whole program from AAMP YAML, as pasted from elsewhere.
+    pub fn from_text(text: &str) -> Result<Self> {
+        let mut pio = pio_from_text(text)?;
+        normalize_legacy_lists(&mut pio);
+        Self::from_pio(pio)
+    }
+
+    /// The entry at an index as AAMP YAML, for editing by hand. The entry is
+    /// the only list of an otherwise empty parameter IO, as only whole files
+    /// can be written as YAML.
+    pub fn entry_to_text(&self, idx: usize) -> String {
+        let mut pio = ParameterIO::new();
+        pio.doc_type = self.0.doc_type.clone();
+        pio.version = self.0.version;
+        pio.lists_mut()
+            .inner_mut()
+            .insert(hash_name("Entry"), self.item_at_index(idx).clone());
+        pio.to_text()
+    }
+
+    /// Replaces the entry at an index with one parsed from YAML in the form
+    /// given by [`AIProgram::entry_to_text`]. Indexes in the entry are taken
+    /// as they are, so the rest of the program isn't updated to match.
+    pub fn entry_from_text(&mut self, idx: usize, text: &str) -> Result<()> {
+        let pio = ParameterIO::from_text(text)?;
+        let mut lists = pio.lists().inner().values();
+        let entry = lists.next().context("The text has no entry list")?;
+        if lists.next().is_some() {
+            anyhow::bail!("The text has more than one entry list");
+        }
+        *self.item_mut_at_index(idx) = entry.clone();
+        Ok(())
+    }
+}
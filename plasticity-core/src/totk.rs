@@ -0,0 +1,51 @@
+//! Recognizing AI files from Tears of the Kingdom, which keeps its AI in
+//! node graphs of its own AINB format rather than AAMP programs. They can't
+//! be read or saved yet, but saying so beats calling them invalid.
+use std::path::Path;
+
+/// Magic of an AINB file
+const AINB_MAGIC: &[u8] = b"AIB ";
+
+/// Magic of a zstd compressed file, which is how TOTK ships AINB files on
+/// their own
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xB5, 0x2F, 0xFD];
+
+/// Whether a file is named like a TOTK AI file, such as `Enemy.root.ainb` or
+/// its compressed `.ainb.zs`.
+pub fn is_totk_path(file: &Path) -> bool {
+    let name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    name.ends_with(".ainb") || name.ends_with(".ainb.zs")
+}
+
+/// Whether the contents of a file are from TOTK, by magic or, as zstd is
+/// never used in BOTW, by being zstd compressed.
+pub fn is_totk_data(data: &[u8]) -> bool {
+    data.starts_with(AINB_MAGIC) || data.starts_with(ZSTD_MAGIC)
+}
+
+/// Version of an uncompressed AINB file, which follows the magic.
+pub fn ainb_version(data: &[u8]) -> Option<u32> {
+    if !data.starts_with(AINB_MAGIC) {
+        return None;
+    }
+    let version = data.get(4..8)?;
+    Some(u32::from_le_bytes(version.try_into().ok()?))
+}
+
+/// Why a TOTK file can't be opened, for when one is.
+pub fn unsupported_error(data: &[u8]) -> anyhow::Error {
+    match ainb_version(data) {
+        Some(version) => anyhow::anyhow!(
+            "This is a Tears of the Kingdom AI file (AINB version {:#x}). Plasticity only \
+             edits Breath of the Wild AI programs and can't read these yet.",
+            version
+        ),
+        None => anyhow::anyhow!(
+            "This looks like a compressed Tears of the Kingdom file. Plasticity only edits \
+             Breath of the Wild AI programs and can't read these yet."
+        ),
+    }
+}
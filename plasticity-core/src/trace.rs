@@ -0,0 +1,111 @@
+use crate::{program::AIProgram, util::try_name};
+use roead::aamp::{hash_name, ParamList, Parameter};
+use std::collections::HashMap;
+
+/// `ChildIdx` slot names which selectors take when their condition holds.
+static TRUE_SLOTS: &[&str] = &["True", "成立", "所持", "範囲内", "内側"];
+/// `ChildIdx` slot names which selectors take when their condition fails.
+static FALSE_SLOTS: &[&str] = &["False", "非成立", "非所持", "範囲外", "外側"];
+
+/// Why a step of a trace went the way it did.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepReason {
+    /// The user picked the slot
+    Chosen,
+    /// The entry refers to a query, which was given this result
+    Query(usize, bool),
+    /// No way to tell, so the first slot with a child was taken
+    First,
+    /// Nothing further to follow, so this entry is what runs
+    End,
+    /// The slot points back at an entry already in the trace
+    Cycle,
+}
+
+/// An entry passed through by [`AIProgram::trace`], and the `ChildIdx` slot
+/// followed from it, if any.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceStep {
+    pub entry: usize,
+    pub slot: Option<u32>,
+    pub reason: StepReason,
+}
+
+impl AIProgram {
+    /// Follows `ChildIdx` slots down from an entry to guess which Action would
+    /// end up running. At each AI the slot picked in `choices` is taken if
+    /// there is one. Otherwise, an AI which names a query and has two slots
+    /// goes one way or the other by the query's result in `queries`, `false`
+    /// if not given, and any other AI goes to its first child. This is only a
+    /// rough picture, as the game's selectors mostly decide by things the
+    /// program doesn't record.
+    pub fn trace(
+        &self,
+        root: usize,
+        queries: &HashMap<usize, bool>,
+        choices: &HashMap<usize, u32>,
+    ) -> Vec<TraceStep> {
+        let mut steps: Vec<TraceStep> = vec![];
+        let mut idx = root;
+        while idx < self.behaviors_offset() {
+            let slots: Vec<(u32, i32)> = self
+                .item_at_index(idx)
+                .objects()
+                .get(hash_name("ChildIdx"))
+                .map(|obj| {
+                    obj.params()
+                        .iter()
+                        .filter_map(|(k, v)| match v {
+                            Parameter::Int(i) => Some((*k, *i)),
+                            _ => None,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let set: Vec<(u32, i32)> = slots.iter().copied().filter(|(_, i)| *i >= 0).collect();
+            let query = self.queries_used_by(idx).first().copied();
+            let picked = if let Some(slot) = choices
+                .get(&idx)
+                .and_then(|slot| slots.iter().find(|(k, _)| k == slot))
+            {
+                Some((*slot, StepReason::Chosen))
+            } else if let (Some(query), 2) = (query, slots.len()) {
+                let result = queries.get(&query).copied().unwrap_or(false);
+                let names: Vec<String> = slots.iter().map(|(k, _)| try_name(*k)).collect();
+                let wanted = if result { TRUE_SLOTS } else { FALSE_SLOTS };
+                let i = names
+                    .iter()
+                    .position(|name| wanted.contains(&name.as_str()))
+                    .unwrap_or(if result { 0 } else { 1 });
+                Some((slots[i], StepReason::Query(query, result)))
+            } else {
+                set.first().map(|slot| (*slot, StepReason::First))
+            };
+            match picked {
+                Some(((slot, child), reason)) => {
+                    let cycle = child >= 0
+                        && (child as usize == idx
+                            || steps.iter().any(|step| step.entry == child as usize));
+                    steps.push(TraceStep {
+                        entry: idx,
+                        slot: Some(slot),
+                        reason: if cycle { StepReason::Cycle } else { reason },
+                    });
+                    if child < 0 || cycle {
+                        return steps;
+                    }
+                    idx = child as usize;
+                }
+                None => break,
+            }
+        }
+        if idx < self.len() {
+            steps.push(TraceStep {
+                entry: idx,
+                slot: None,
+                reason: StepReason::End,
+            });
+        }
+        steps
+    }
+}
@@ -0,0 +1,65 @@
+use crate::program::{AIProgram, Category};
+use anyhow::Result;
+use roead::aamp::{hash_name, ParamList, Parameter};
+use std::collections::HashSet;
+
+impl AIProgram {
+    /// Finds the absolute indexes of every entry which nothing uses: AIs and
+    /// Actions which can't be reached from a root or demo slot, behaviors no
+    /// reachable entry points at, and queries whose name no parameter mentions.
+    pub fn unused_entries(&self) -> Vec<usize> {
+        let items = self.items();
+        let behaviors_offset = self.behaviors_offset();
+        let mut reached: HashSet<usize> = HashSet::new();
+        let mut stack: Vec<usize> = self
+            .roots()
+            .into_iter()
+            .chain(self.demos().params().values().filter_map(|v| match v {
+                Parameter::Int(i) if *i >= 0 => Some(*i as usize),
+                _ => None,
+            }))
+            .collect();
+        while let Some(idx) = stack.pop() {
+            if idx >= behaviors_offset || !reached.insert(idx) {
+                continue;
+            }
+            for obj_name in ["ChildIdx", "BehaviorIdx"] {
+                if let Some(obj) = items[idx].objects().get(hash_name(obj_name)) {
+                    for v in obj.params().values() {
+                        match v {
+                            Parameter::Int(i) if *i >= 0 && obj_name == "ChildIdx" => {
+                                stack.push(*i as usize)
+                            }
+                            Parameter::Int(i) if *i >= 0 => {
+                                reached.insert(behaviors_offset + *i as usize);
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+            }
+        }
+        // Queries are referred to by name rather than index
+        let strings = self.param_strings(0..self.queries_offset());
+        (0..self.len())
+            .filter(|i| {
+                if self.category_of(*i) == Category::Query {
+                    !self.query_named_in(*i, &strings)
+                } else {
+                    !reached.contains(i)
+                }
+            })
+            .collect()
+    }
+
+    /// Deletes every unused entry, fixing up indexes as it goes. Returns the
+    /// number of entries removed.
+    pub fn remove_unused(&mut self) -> Result<usize> {
+        let unused = self.unused_entries();
+        // Going from the back keeps the remaining indexes valid
+        for idx in unused.iter().rev() {
+            self.delete_entry(*idx)?;
+        }
+        Ok(unused.len())
+    }
+}
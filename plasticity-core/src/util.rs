@@ -0,0 +1,571 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::{Context, Result};
+use cached::Cached;
+use lazy_static::lazy_static;
+use roead::{
+    aamp::{hash_name, ParamList, Parameter, ParameterIO, ParameterList, ParameterObject},
+    types::Vector3f,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::program::Category;
+
+static JAP_ENG_MAP_JSON: &str = include_str!("../data/jpen.json");
+static AI_DEF_JSON: &str = include_str!("../data/aidef.json");
+static HASHES_JSON: &str = include_str!("../data/hashes.json");
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum AIDefParamValue {
+    Bool(bool),
+    Int(i32),
+    String(String),
+    Float(f32),
+    Vec3([f32; 3]),
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum ChildEntries {
+    Map(BTreeMap<String, Vec<AIDefParam>>),
+    List(Vec<String>),
+    None(String),
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum AIDefEntry {
+    None(String),
+    Some(AIDef),
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AIDefParam {
+    pub name: String,
+    #[serde(rename = "Type")]
+    pub param_type: String,
+    pub value: Option<AIDefParamValue>,
+    pub description: Option<String>,
+}
+
+impl AIDefParam {
+    /// Whether a parameter has the type this definition declares. Types with
+    /// no direct AAMP equivalent always match.
+    pub fn matches(&self, param: &Parameter) -> bool {
+        match self.param_type.as_str() {
+            "Bool" => matches!(param, Parameter::Bool(_)),
+            "Float" => matches!(param, Parameter::F32(_)),
+            "Int" => matches!(param, Parameter::Int(_)),
+            "String" => param.is_string(),
+            "Vec3" => matches!(param, Parameter::Vec3(_)),
+            _ => true,
+        }
+    }
+
+    /// Whether the declared type is a plain value which can be given a
+    /// default, as opposed to a pointer or other runtime type.
+    pub fn has_default(&self) -> bool {
+        matches!(
+            self.param_type.as_str(),
+            "Bool" | "Float" | "Int" | "String" | "Vec3"
+        )
+    }
+
+    pub fn tooltip(&self) -> String {
+        let mut text = format!("Type: {}", self.param_type);
+        if let Some(value) = &self.value {
+            text.push_str(&format!(
+                "\nDefault: {}",
+                serde_json::to_string(value).unwrap_or_default()
+            ));
+        }
+        if let Some(description) = &self.description {
+            text.push_str(&format!("\n\n{}", description));
+        }
+        text
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AIDef {
+    pub map_unit_inst_params: Option<Vec<AIDefParam>>,
+    pub static_inst_params: Option<Vec<AIDefParam>>,
+    pub dynamic_inst_params: Option<Vec<AIDefParam>>,
+    #[serde(rename(deserialize = "childs", serialize = "Children"))]
+    pub childs: Option<ChildEntries>,
+    pub calc_timing: Option<String>,
+}
+
+impl AIDef {
+    /// Names of the `ChildIdx` slots the class declares.
+    pub fn child_slots(&self) -> Vec<&str> {
+        match &self.childs {
+            Some(ChildEntries::List(v)) => v.iter().map(|s| s.as_str()).collect(),
+            Some(ChildEntries::Map(m)) => m.keys().map(|s| s.as_str()).collect(),
+            _ => vec![],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AIDefs {
+    #[serde(rename = "AIs")]
+    pub ais: BTreeMap<String, AIDefEntry>,
+    pub actions: BTreeMap<String, AIDefEntry>,
+    pub behaviors: BTreeMap<String, AIDefEntry>,
+    pub querys: BTreeMap<String, AIDefEntry>,
+}
+
+impl AIDefs {
+    pub fn classes<C: std::borrow::Borrow<Category>>(
+        &self,
+        category: C,
+    ) -> impl Iterator<Item = &String> {
+        match *category.borrow() {
+            Category::AI => self.ais.keys(),
+            Category::Action => self.actions.keys(),
+            Category::Behaviour => self.behaviors.keys(),
+            Category::Query => self.querys.keys(),
+        }
+    }
+
+    pub fn get_classes<C: std::borrow::Borrow<Category>>(&self, category: C) -> Vec<&str> {
+        self.classes(category.borrow())
+            .map(|s| s.as_str())
+            .collect()
+    }
+
+    pub fn get_def<C: std::borrow::Borrow<Category>>(
+        &self,
+        category: C,
+        class: &str,
+    ) -> Option<&AIDef> {
+        match (match *category.borrow() {
+            Category::AI => &self.ais,
+            Category::Action => &self.actions,
+            Category::Behaviour => &self.behaviors,
+            Category::Query => &self.querys,
+        })
+        .get(class)
+        {
+            Some(AIDefEntry::Some(def)) => Some(def),
+            _ => None,
+        }
+    }
+
+    /// Parameters an entry of the given class keeps in `SInst`. Most queries
+    /// declare theirs as dynamic parameters, which the program supplies there
+    /// all the same.
+    pub fn sinst_params<C: std::borrow::Borrow<Category>>(
+        &self,
+        category: C,
+        class: &str,
+    ) -> &[AIDefParam] {
+        let def = match self.get_def(category.borrow(), class) {
+            Some(def) => def,
+            None => return &[],
+        };
+        match (&def.static_inst_params, *category.borrow()) {
+            (Some(params), _) if !params.is_empty() => params,
+            (_, Category::Query) => def.dynamic_inst_params.as_deref().unwrap_or_default(),
+            (params, _) => params.as_deref().unwrap_or_default(),
+        }
+    }
+
+    pub fn default_parameter(param_type: &str, value: &Option<AIDefParamValue>) -> Parameter {
+        if let Some(value) = value {
+            match value {
+                AIDefParamValue::Bool(b) => Parameter::Bool(*b),
+                AIDefParamValue::Float(f) => Parameter::F32(*f),
+                AIDefParamValue::Int(i) => Parameter::Int(*i),
+                AIDefParamValue::String(s) => Parameter::String32(s.clone()),
+                AIDefParamValue::Vec3(v) => Parameter::Vec3(Vector3f {
+                    x: v[0],
+                    y: v[1],
+                    z: v[2],
+                }),
+            }
+        } else {
+            match param_type {
+                "Bool" => Parameter::Bool(false),
+                "Float" => Parameter::F32(0.0),
+                "Int" => Parameter::Int(0),
+                "String" => Parameter::String32(String::new()),
+                "Vec3" => Parameter::Vec3(Vector3f::default()),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// A new entry of a class with the slots and default parameters its AI
+    /// def gives, failing for classes missing from the AI defs.
+    pub fn blank_ai(&self, category: Category, class: String) -> Result<ParameterList> {
+        let mut ai = ParameterList::new();
+        let mut defs = ParameterObject::new();
+        if matches!(category, Category::AI | Category::Action) {
+            defs.params_mut()
+                .insert(hash_name("Name"), Parameter::StringRef("".into()));
+            defs.params_mut()
+                .insert(hash_name("GroupName"), Parameter::StringRef("".into()));
+        }
+        defs.params_mut()
+            .insert(hash_name("ClassName"), Parameter::String32(class.clone()));
+        ai.objects_mut().inner_mut().insert(hash_name("Def"), defs);
+        if let AIDefEntry::Some(ai_def) = (match category {
+            Category::AI => &self.ais,
+            Category::Action => &self.actions,
+            Category::Behaviour => &self.behaviors,
+            Category::Query => &self.querys,
+        })
+        .get(&class)
+        .with_context(|| format!("{} is not a known {} class", class, category))?
+        {
+            if let Some(childs) = &ai_def.childs {
+                let mut children = ParameterObject::new();
+                match childs {
+                    ChildEntries::List(v) => {
+                        v.iter().for_each(|child| {
+                            children
+                                .params_mut()
+                                .insert(hash_name(child.as_str()), Parameter::Int(-1));
+                        });
+                    }
+                    ChildEntries::Map(m) => {
+                        m.keys().for_each(|child| {
+                            children
+                                .params_mut()
+                                .insert(hash_name(child.as_str()), Parameter::Int(-1));
+                        });
+                    }
+                    ChildEntries::None(_) => (),
+                }
+                ai.objects_mut()
+                    .inner_mut()
+                    .insert(hash_name("ChildIdx"), children);
+            }
+            let params = self.sinst_params(category, &class);
+            if ai_def.static_inst_params.is_some() || !params.is_empty() {
+                let mut sinst_params = ParameterObject::new();
+                for sinst in params.iter().filter(|param| param.has_default()) {
+                    sinst_params.params_mut().insert(
+                        hash_name(&sinst.name),
+                        Self::default_parameter(&sinst.param_type, &sinst.value),
+                    );
+                }
+                ai.objects_mut()
+                    .inner_mut()
+                    .insert(hash_name("SInst"), sinst_params);
+            }
+        }
+        Ok(ai)
+    }
+}
+
+lazy_static! {
+    pub static ref JPEN_MAP: std::collections::HashMap<&'static str, String> =
+        serde_json::from_str(JAP_ENG_MAP_JSON).unwrap();
+    pub static ref AIDEFS: AIDefs = serde_json::from_str(AI_DEF_JSON).unwrap();
+    pub static ref NAME_TABLE: std::sync::RwLock<roead::aamp::names::NameTable> = {
+        let mut table = roead::aamp::names::NameTable::new(true);
+        let hashes: std::collections::HashMap<u32, &str> =
+            serde_json::from_str(HASHES_JSON).unwrap();
+        hashes.into_iter().for_each(|(_, string)| {
+            table.add_name(string);
+        });
+        std::sync::RwLock::new(table)
+    };
+}
+
+/// Text form of a parameter for display.
+pub fn param_string(param: &Parameter) -> String {
+    match param {
+        Parameter::Bool(b) => b.to_string(),
+        Parameter::F32(f) => f.to_string(),
+        Parameter::Int(i) => i.to_string(),
+        Parameter::U32(u) => u.to_string(),
+        Parameter::String32(s)
+        | Parameter::String64(s)
+        | Parameter::String256(s)
+        | Parameter::StringRef(s) => s.clone(),
+        Parameter::Vec2(v) => format!("({}, {})", v.x, v.y),
+        Parameter::Vec3(v) => format!("({}, {}, {})", v.x, v.y, v.z),
+        Parameter::Vec4(v) => format!("({}, {}, {}, {})", v.x, v.y, v.z, v.t),
+        Parameter::Color(c) => format!("ARGB({}, {}, {}, {})", c.a, c.r, c.g, c.b),
+        Parameter::Quat(q) => format!("({}, {}, {}, {})", q.a, q.b, q.c, q.d),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Which language names are shown in by [`translate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum NameDisplay {
+    Japanese,
+    English,
+    /// English, with the original Japanese after it
+    Both,
+}
+
+/// How entries are numbered by [`index_label`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum IndexDisplay {
+    /// From zero within each category, as in YAML dumps
+    PerCategory,
+    /// Across the whole program, as `ChildIdx` values are
+    Absolute,
+    Both,
+}
+
+/// Unit an angle is given in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AngleUnit {
+    Degrees,
+    Radians,
+}
+
+impl AngleUnit {
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            AngleUnit::Degrees => "°",
+            AngleUnit::Radians => " rad",
+        }
+    }
+
+    /// Converts an angle in this unit to another.
+    pub fn convert(&self, value: f32, to: AngleUnit) -> f32 {
+        match (self, to) {
+            (AngleUnit::Degrees, AngleUnit::Radians) => value.to_radians(),
+            (AngleUnit::Radians, AngleUnit::Degrees) => value.to_degrees(),
+            _ => value,
+        }
+    }
+}
+
+/// Guesses from its name whether a parameter holds an angle, and the unit the
+/// game expects it in. Names with `Rad` in them, not as part of `Radius`, are
+/// taken to be radians, and ones with `Angle`, `Degree` or `Rot` to be degrees,
+/// as with `TiltAngle` and `InitDgnRotRad`. Rates, times and counts of
+/// rotations aren't angles.
+pub fn angle_unit(name: &str) -> Option<AngleUnit> {
+    let words = name_words(name);
+    let has = |list: &[&str]| words.iter().any(|w| list.contains(&w.as_str()));
+    if has(&["rate", "frame", "frames", "time", "count", "num", "dir"]) {
+        None
+    } else if has(&["rad", "radian", "radians"]) {
+        Some(AngleUnit::Radians)
+    } else if has(&["angle", "degree", "degrees", "deg", "rot", "rotation"]) {
+        Some(AngleUnit::Degrees)
+    } else {
+        None
+    }
+}
+
+/// Splits a parameter name into lowercase words at capitals and underscores,
+/// so `InitDgnRotRad` gives `init`, `dgn`, `rot`, and `rad`.
+pub(crate) fn name_words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = vec![];
+    for c in name.chars() {
+        match words.last_mut() {
+            Some(word) if !c.is_uppercase() && c != '_' => word.push(c),
+            _ if c == '_' => words.push(String::new()),
+            _ => words.push(c.to_string()),
+        }
+    }
+    words.into_iter().map(|w| w.to_lowercase()).collect()
+}
+
+lazy_static! {
+    static ref ANGLE_DISPLAY: std::sync::RwLock<AngleUnit> =
+        std::sync::RwLock::new(AngleUnit::Degrees);
+    static ref NAME_DISPLAY: std::sync::RwLock<NameDisplay> =
+        std::sync::RwLock::new(NameDisplay::English);
+    static ref INDEX_DISPLAY: std::sync::RwLock<IndexDisplay> =
+        std::sync::RwLock::new(IndexDisplay::PerCategory);
+    /// Translations added or corrected by the user, which take precedence
+    /// over [`JPEN_MAP`]
+    static ref USER_JPEN: std::sync::RwLock<HashMap<String, String>> = Default::default();
+}
+
+/// Sets which language [`translate`] shows names in.
+pub fn set_name_display(display: NameDisplay) {
+    *NAME_DISPLAY.write().unwrap() = display;
+}
+
+/// Sets which unit angle parameters are edited in, whatever the unit they're
+/// stored in.
+pub fn set_angle_display(unit: AngleUnit) {
+    *ANGLE_DISPLAY.write().unwrap() = unit;
+}
+
+pub fn angle_display() -> AngleUnit {
+    *ANGLE_DISPLAY.read().unwrap()
+}
+
+/// Sets how [`index_label`] numbers entries.
+pub fn set_index_display(display: IndexDisplay) {
+    *INDEX_DISPLAY.write().unwrap() = display;
+}
+
+/// Label for the position of an entry, given its index within its category
+/// and across the whole program, such as `Action_2` or `#14`.
+pub fn index_label(category: Category, relative: usize, absolute: usize) -> String {
+    match *INDEX_DISPLAY.read().unwrap() {
+        IndexDisplay::PerCategory => format!("{}_{}", category, relative),
+        IndexDisplay::Absolute => format!("#{}", absolute),
+        IndexDisplay::Both => format!("{}_{} (#{})", category, relative, absolute),
+    }
+}
+
+/// Sets the user's translation of a Japanese name, or removes it so the
+/// bundled one applies again.
+pub fn set_translation(name: &str, english: Option<&str>) {
+    let mut user = USER_JPEN.write().unwrap();
+    match english {
+        Some(english) => user.insert(name.to_owned(), english.to_owned()),
+        None => user.remove(name),
+    };
+}
+
+/// English translation of a Japanese name, preferring the user's own over
+/// [`JPEN_MAP`].
+pub fn english(name: &str) -> Option<String> {
+    USER_JPEN
+        .read()
+        .unwrap()
+        .get(name)
+        .or_else(|| JPEN_MAP.get(name))
+        .cloned()
+}
+
+/// A name as it should be shown, in the language chosen with
+/// [`set_name_display`]. Names with no translation are left as they are.
+pub fn translate(name: &str) -> String {
+    let display = *NAME_DISPLAY.read().unwrap();
+    match (english(name), display) {
+        (Some(en), NameDisplay::English) => en,
+        (Some(en), NameDisplay::Both) => format!("{} ({})", en, name),
+        _ => name.to_owned(),
+    }
+}
+
+/// Every name in a parameter IO which is Japanese, whether a string value or
+/// a key, so it could be given a translation.
+pub fn japanese_names(pio: &ParameterIO) -> BTreeSet<String> {
+    fn add_names(list: &dyn ParamList, names: &mut BTreeSet<String>) {
+        for (k, obj) in list.objects().inner() {
+            names.insert(try_name(*k));
+            for (k, param) in obj.params() {
+                names.insert(try_name(*k));
+                if let Ok(value) = param.as_string() {
+                    names.insert(value.to_owned());
+                }
+            }
+        }
+        for (k, list) in list.lists().inner() {
+            names.insert(try_name(*k));
+            add_names(list, names);
+        }
+    }
+    let mut names = BTreeSet::new();
+    add_names(pio, &mut names);
+    names.retain(|name| !name.is_ascii());
+    names
+}
+
+pub fn update_name_table_from_pio(pio: &ParameterIO) {
+    let mut name_table = NAME_TABLE.write().unwrap();
+    fn add_names_from_list(
+        list: &dyn roead::aamp::ParamList,
+        name_table: &mut std::sync::RwLockWriteGuard<'_, roead::aamp::names::NameTable>,
+    ) {
+        list.objects()
+            .inner()
+            .values()
+            .map(|obj| obj.params().values())
+            .flatten()
+            .filter_map(|p| p.as_string().ok())
+            .for_each(|n| name_table.add_name(n));
+        list.lists()
+            .inner()
+            .values()
+            .for_each(|list| add_names_from_list(list, name_table));
+    }
+    add_names_from_list(pio, &mut name_table);
+}
+
+/// Adds names to the table [`try_name`] looks hashes up in, such as ones the
+/// user has found for hashes the bundled table is missing.
+pub fn add_names<'a>(names: impl IntoIterator<Item = &'a str>) {
+    let mut name_table = NAME_TABLE.write().unwrap();
+    names.into_iter().for_each(|n| name_table.add_name(n));
+    // Hashes which were unknown until now may already be cached as numbers
+    TRY_NAME.lock().unwrap().cache_clear();
+    TRY_NUMBERED_NAME.lock().unwrap().cache_clear();
+}
+
+lazy_static! {
+    static ref DEMO_NAMES: std::sync::RwLock<BTreeSet<String>> = Default::default();
+}
+
+/// Records names of demos, for suggesting when adding a demo slot. Every
+/// program loaded adds the names of its demo keys.
+pub fn add_demo_names<'a>(names: impl IntoIterator<Item = &'a str>) {
+    let mut demo_names = DEMO_NAMES.write().unwrap();
+    demo_names.extend(names.into_iter().map(|n| n.to_owned()));
+}
+
+/// Every demo name seen so far, sorted.
+pub fn demo_names() -> Vec<String> {
+    DEMO_NAMES.read().unwrap().iter().cloned().collect()
+}
+
+/// Every object, list, and parameter key in a parameter IO which has no known
+/// name.
+pub fn unknown_hashes(pio: &ParameterIO) -> BTreeSet<u32> {
+    fn add_unknown(list: &dyn ParamList, unknown: &mut BTreeSet<u32>) {
+        for (k, obj) in list.objects().inner() {
+            unknown.insert(*k);
+            unknown.extend(obj.params().keys().copied());
+        }
+        for (k, list) in list.lists().inner() {
+            unknown.insert(*k);
+            add_unknown(list, unknown);
+        }
+    }
+    let mut unknown = BTreeSet::new();
+    add_unknown(pio, &mut unknown);
+    unknown.retain(|k| !has_name(*k));
+    unknown
+}
+
+/// Whether [`try_name`] can find a name for a hash, rather than showing it as
+/// a number.
+pub fn has_name(key: u32) -> bool {
+    try_name(key) != key.to_string()
+}
+
+#[cached::proc_macro::cached]
+pub fn try_name(key: u32) -> String {
+    NAME_TABLE
+        .read()
+        .unwrap()
+        .get_name(key)
+        .map(|n| n.to_owned())
+        .unwrap_or_else(|| try_numbered_name(key))
+}
+
+#[cached::proc_macro::cached]
+fn try_numbered_name(key: u32) -> String {
+    for i in (0..=1000).into_iter().map(|i| i.to_string()) {
+        for prefix in &["AI_", "Action_", "Behavior_", "Query_"] {
+            let test: String = [*prefix, i.as_str()].join("");
+            if hash_name(&test) == key {
+                return test;
+            }
+        }
+    }
+    key.to_string()
+}
@@ -0,0 +1,533 @@
+use crate::{
+    program::{AIProgram, Category},
+    sanity::ValueWarning,
+    util::try_name,
+    AIDEFS,
+};
+use anyhow::Result;
+use roead::aamp::{hash_name, ParamList, ParameterList};
+use std::fmt;
+
+/// A problem found in an AI program.
+#[derive(Debug, Clone)]
+pub struct Issue {
+    /// Absolute index of the offending entry, or `None` for the demo table
+    pub entry: Option<usize>,
+    pub kind: IssueKind,
+}
+
+impl Issue {
+    pub(crate) fn new(entry: Option<usize>, kind: IssueKind) -> Self {
+        Self { entry, kind }
+    }
+}
+
+/// What's wrong, with the names and numbers needed to say so. It displays as
+/// English, for reports; the editor words each kind in the user's language.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IssueKind {
+    /// A `ChildIdx` slot or demo, as `table`, pointing past the last entry
+    NoSuchEntry {
+        table: &'static str,
+        key: String,
+        index: i32,
+    },
+    /// A `ChildIdx` slot or demo pointing at a Behavior or Query
+    WrongRange {
+        table: &'static str,
+        key: String,
+        category: Category,
+        index: i32,
+    },
+    /// A `BehaviorIdx` slot pointing past the last behavior
+    NoSuchBehavior {
+        key: String,
+        index: i32,
+    },
+    /// A `ChildIdx` slot, `BehaviorIdx` slot, or demo which isn't an integer
+    NotInteger {
+        table: &'static str,
+        key: String,
+    },
+    /// `ChildIdx` slots leading back to where they started, as the chain of
+    /// entry labels
+    Cycle(String),
+    MissingDef,
+    EmptyClassName,
+    /// An entry key which doesn't match the entry's position
+    WrongKey {
+        key: String,
+        expected: String,
+    },
+    /// A class with no AI def
+    UnknownClass(String),
+    /// A `ChildIdx` slot the AI def declares which the entry lacks
+    MissingChildSlot(String),
+    /// An `SInst` or `MInst` parameter, as `obj`, of the wrong type
+    WrongType {
+        obj: &'static str,
+        name: String,
+        expected: String,
+    },
+    /// An `SInst` or `MInst` parameter the AI def doesn't declare
+    UndefinedParam {
+        obj: &'static str,
+        name: String,
+        class: String,
+    },
+    /// A `GroupName` which isn't the `Name` of any AI pointing at the entry
+    WrongGroupName {
+        group: String,
+        expected: Vec<String>,
+    },
+    /// A numeric parameter with a value the game likely doesn't expect
+    OddValue {
+        obj: &'static str,
+        name: String,
+        warning: ValueWarning,
+    },
+    /// A parameter naming a GameData flag which doesn't exist
+    UnknownFlag {
+        obj: &'static str,
+        name: String,
+        flag: String,
+    },
+    /// A class needing a component, set up in the ActorLink parameter
+    /// `link`, which the actor lacks
+    MissingLink {
+        class: String,
+        needs: String,
+        link: String,
+    },
+    /// An Action `SInst` parameter naming an AS the actor's ASList lacks
+    UnknownAs {
+        name: String,
+        anim: String,
+    },
+    /// A root AI which nothing refers to, so the game never runs it
+    UnusedRoot,
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchEntry { table, key, index } => {
+                write!(f, "{} {} points to nonexistent entry {}", table, key, index)
+            }
+            Self::WrongRange {
+                table,
+                key,
+                category,
+                index,
+            } => write!(
+                f,
+                "{} {} points into the {} range (entry {})",
+                table, key, category, index
+            ),
+            Self::NoSuchBehavior { key, index } => write!(
+                f,
+                "BehaviorIdx {} points to nonexistent behavior {}",
+                key, index
+            ),
+            Self::NotInteger { table, key } => write!(f, "{} {} is not an integer", table, key),
+            Self::Cycle(path) => write!(f, "ChildIdx cycle: {}", path),
+            Self::MissingDef => f.write_str("Missing Def object"),
+            Self::EmptyClassName => f.write_str("ClassName is empty"),
+            Self::WrongKey { key, expected } => {
+                write!(f, "Entry key {} should be {}", key, expected)
+            }
+            Self::UnknownClass(class) => write!(f, "No AI def found for class {}", class),
+            Self::MissingChildSlot(slot) => write!(f, "Missing ChildIdx slot {}", slot),
+            Self::WrongType {
+                obj,
+                name,
+                expected,
+            } => write!(f, "{} {} should be {}", obj, name, expected),
+            Self::UndefinedParam { obj, name, class } => {
+                write!(f, "{} {} is not defined for {}", obj, name, class)
+            }
+            Self::WrongGroupName { group, expected } => write!(
+                f,
+                "GroupName \"{}\" should be \"{}\"",
+                group,
+                expected.join("\" or \"")
+            ),
+            Self::OddValue { obj, name, warning } => write!(f, "{} {}: {}", obj, name, warning),
+            Self::UnknownFlag { obj, name, flag } => write!(
+                f,
+                "{} {}: there is no GameData flag named {}",
+                obj, name, flag
+            ),
+            Self::MissingLink { class, needs, link } => write!(
+                f,
+                "{} needs {}, but the actor has no {}",
+                class, needs, link
+            ),
+            Self::UnknownAs { name, anim } => {
+                write!(f, "SInst {}: the ASList has no AS named {}", name, anim)
+            }
+            Self::UnusedRoot => {
+                f.write_str("Root AI which nothing refers to, so the game will never run it")
+            }
+        }
+    }
+}
+
+impl AIProgram {
+    /// Checks every `ChildIdx`, `BehaviorIdx`, and `DemoAIActionIdx` value for
+    /// indexes which are out of range or point into the wrong category.
+    pub fn validate_references(&self) -> Vec<Issue> {
+        let len = self.len() as i32;
+        let behaviors_offset = self.behaviors_offset() as i32;
+        let behavior_count = self.behaviors().len() as i32;
+        let mut issues = vec![];
+        for (i, item) in self.items().into_iter().enumerate() {
+            if let Some(children) = item.objects().get(hash_name("ChildIdx")) {
+                for (k, v) in children.params() {
+                    match v.as_int() {
+                        Ok(-1) => (),
+                        Ok(idx) if idx < -1 || idx >= len => issues.push(Issue::new(
+                            Some(i),
+                            IssueKind::NoSuchEntry {
+                                table: "ChildIdx",
+                                key: try_name(*k),
+                                index: idx,
+                            },
+                        )),
+                        Ok(idx) if idx >= behaviors_offset => issues.push(Issue::new(
+                            Some(i),
+                            IssueKind::WrongRange {
+                                table: "ChildIdx",
+                                key: try_name(*k),
+                                category: self.category_of(idx as usize),
+                                index: idx,
+                            },
+                        )),
+                        Ok(_) => (),
+                        Err(_) => issues.push(Issue::new(
+                            Some(i),
+                            IssueKind::NotInteger {
+                                table: "ChildIdx",
+                                key: try_name(*k),
+                            },
+                        )),
+                    }
+                }
+            }
+            if let Some(behaviors) = item.objects().get(hash_name("BehaviorIdx")) {
+                for (k, v) in behaviors.params() {
+                    match v.as_int() {
+                        Ok(-1) => (),
+                        Ok(idx) if idx < -1 || idx >= behavior_count => issues.push(Issue::new(
+                            Some(i),
+                            IssueKind::NoSuchBehavior {
+                                key: try_name(*k),
+                                index: idx,
+                            },
+                        )),
+                        Ok(_) => (),
+                        Err(_) => issues.push(Issue::new(
+                            Some(i),
+                            IssueKind::NotInteger {
+                                table: "BehaviorIdx",
+                                key: try_name(*k),
+                            },
+                        )),
+                    }
+                }
+            }
+        }
+        for (k, v) in self.demos().params() {
+            match v.as_int() {
+                Ok(-1) => (),
+                Ok(idx) if idx < -1 || idx >= len => issues.push(Issue::new(
+                    None,
+                    IssueKind::NoSuchEntry {
+                        table: "Demo",
+                        key: try_name(*k),
+                        index: idx,
+                    },
+                )),
+                Ok(idx) if idx >= behaviors_offset => issues.push(Issue::new(
+                    None,
+                    IssueKind::WrongRange {
+                        table: "Demo",
+                        key: try_name(*k),
+                        category: self.category_of(idx as usize),
+                        index: idx,
+                    },
+                )),
+                Ok(_) => (),
+                Err(_) => issues.push(Issue::new(
+                    None,
+                    IssueKind::NotInteger {
+                        table: "Demo",
+                        key: try_name(*k),
+                    },
+                )),
+            }
+        }
+        for cycle in self.find_cycles() {
+            issues.push(Issue::new(
+                Some(cycle[0]),
+                IssueKind::Cycle(self.cycle_path(&cycle)),
+            ));
+        }
+        issues
+    }
+
+    /// Checks for the mistakes which stop the game from loading a program at
+    /// all: everything [`AIProgram::validate_references`] finds, entries with
+    /// no `Def` object or an empty `ClassName`, and entry keys which don't
+    /// match their position, such as one repeated or left behind by a removal.
+    pub fn lint(&self) -> Vec<Issue> {
+        let mut issues = self.validate_references();
+        for (i, item) in self.items().into_iter().enumerate() {
+            match item.objects().get(hash_name("Def")) {
+                None => issues.push(Issue::new(Some(i), IssueKind::MissingDef)),
+                Some(def) => {
+                    let class = def
+                        .params()
+                        .get(&hash_name("ClassName"))
+                        .and_then(|v| v.as_string().ok());
+                    if class.map(|c| c.is_empty()).unwrap_or(true) {
+                        issues.push(Issue::new(Some(i), IssueKind::EmptyClassName));
+                    }
+                }
+            }
+        }
+        for category in [
+            Category::AI,
+            Category::Action,
+            Category::Behaviour,
+            Category::Query,
+        ] {
+            let offset = self.category_offset(category);
+            let keys = self
+                .0
+                .lists()
+                .get(hash_name(&category.to_string()))
+                .map(|list| list.lists().inner().keys().copied().collect::<Vec<u32>>())
+                .unwrap_or_default();
+            for (i, key) in keys.into_iter().enumerate() {
+                let expected = format!("{}_{}", category, i);
+                if key != hash_name(&expected) {
+                    issues.push(Issue::new(
+                        Some(offset + i),
+                        IssueKind::WrongKey {
+                            key: try_name(key),
+                            expected,
+                        },
+                    ));
+                }
+            }
+        }
+        issues
+    }
+
+    /// Runs every check there is: [`AIProgram::lint`],
+    /// [`AIProgram::validate_group_names`], [`AIProgram::validate_defs`], and
+    /// [`AIProgram::validate_values`].
+    pub fn validate_all(&self) -> Vec<Issue> {
+        let mut issues = self.lint();
+        issues.extend(self.validate_group_names());
+        issues.extend(self.validate_defs());
+        issues.extend(self.validate_values());
+        issues
+    }
+
+    /// Checks an entry against the AI def of its class: `ChildIdx` slots it
+    /// declares which are missing, and `SInst` or `MInst` parameters it doesn't
+    /// declare or which have the wrong type.
+    pub fn def_issues(&self, idx: usize) -> Vec<Issue> {
+        let category = self.category_of(idx);
+        let class = match self.entry_name_from_index(idx) {
+            Ok(class) => class,
+            Err(_) => return vec![],
+        };
+        let def = match AIDEFS.get_def(category, class) {
+            Some(def) => def,
+            None => {
+                return vec![Issue::new(
+                    Some(idx),
+                    IssueKind::UnknownClass(class.to_owned()),
+                )]
+            }
+        };
+        let objects = self.item_at_index(idx).objects();
+        let mut issues: Vec<Issue> = self
+            .missing_child_slots(idx)
+            .into_iter()
+            .map(|slot| Issue::new(Some(idx), IssueKind::MissingChildSlot(slot)))
+            .collect();
+        for (obj_name, defined) in [
+            ("SInst", AIDEFS.sinst_params(category, class)),
+            (
+                "MInst",
+                def.map_unit_inst_params.as_deref().unwrap_or_default(),
+            ),
+        ] {
+            // Some defs leave out their parameters entirely, so only check
+            // against ones which list any
+            if defined.is_empty() {
+                continue;
+            }
+            if let Some(obj) = objects.get(hash_name(obj_name)) {
+                for (k, v) in obj.params() {
+                    match defined.iter().find(|param| hash_name(&param.name) == *k) {
+                        Some(param) if !param.matches(v) => issues.push(Issue::new(
+                            Some(idx),
+                            IssueKind::WrongType {
+                                obj: obj_name,
+                                name: param.name.clone(),
+                                expected: param.param_type.clone(),
+                            },
+                        )),
+                        Some(_) => (),
+                        None => issues.push(Issue::new(
+                            Some(idx),
+                            IssueKind::UndefinedParam {
+                                obj: obj_name,
+                                name: try_name(*k),
+                                class: class.to_owned(),
+                            },
+                        )),
+                    }
+                }
+            }
+        }
+        issues
+    }
+
+    /// The `ChildIdx` slots the AI def of an entry's class declares which the
+    /// entry doesn't have.
+    pub fn missing_child_slots(&self, idx: usize) -> Vec<String> {
+        let def = match self
+            .entry_name_from_index(idx)
+            .ok()
+            .and_then(|class| AIDEFS.get_def(self.category_of(idx), class))
+        {
+            Some(def) => def,
+            None => return vec![],
+        };
+        let children = self.item_at_index(idx).objects().get(hash_name("ChildIdx"));
+        def.child_slots()
+            .into_iter()
+            .filter(|slot| {
+                children
+                    .and_then(|children| children.params().get(&hash_name(slot)))
+                    .is_none()
+            })
+            .map(|slot| slot.to_owned())
+            .collect()
+    }
+
+    /// Runs [`AIProgram::def_issues`] on every entry.
+    pub fn validate_defs(&self) -> Vec<Issue> {
+        (0..self.len()).flat_map(|i| self.def_issues(i)).collect()
+    }
+
+    /// Finds AIs and Actions whose `GroupName` isn't the `Name` of any AI
+    /// which points at them through `ChildIdx`.
+    pub fn validate_group_names(&self) -> Vec<Issue> {
+        let items = self.items();
+        let behaviors_offset = self.behaviors_offset();
+        let mut parents: Vec<Vec<usize>> = vec![vec![]; behaviors_offset];
+        for (i, item) in items.iter().take(behaviors_offset).enumerate() {
+            if let Some(children) = item.objects().get(hash_name("ChildIdx")) {
+                for v in children.params().values() {
+                    if let Ok(child) = v.as_int() {
+                        if child >= 0 && (child as usize) < behaviors_offset {
+                            parents[child as usize].push(i);
+                        }
+                    }
+                }
+            }
+        }
+        parents
+            .into_iter()
+            .enumerate()
+            .filter(|(_, parents)| !parents.is_empty())
+            .filter_map(|(i, parents)| {
+                let group = def_string(items[i], "GroupName")?;
+                let expected: Vec<&str> = parents
+                    .into_iter()
+                    .filter_map(|p| def_string(items[p], "Name"))
+                    .collect();
+                (!expected.is_empty() && !expected.contains(&group)).then(|| {
+                    Issue::new(
+                        Some(i),
+                        IssueKind::WrongGroupName {
+                            group: group.to_owned(),
+                            expected: expected.into_iter().map(|s| s.to_owned()).collect(),
+                        },
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// The names an entry's `GroupName` could have: the `Name` of each AI
+    /// pointing at it.
+    pub fn expected_group_names(&self, idx: usize) -> Vec<String> {
+        self.references(idx)
+            .children
+            .into_iter()
+            .filter_map(|(parent, _)| def_string(self.item_at_index(parent), "Name"))
+            .map(|name| name.to_owned())
+            .collect()
+    }
+
+    /// Renames everything below the root AIs to match the slot it fills and
+    /// the AI it belongs to, using [`AIProgram::update_names`]. Returns the
+    /// number of entries changed.
+    pub fn fix_group_names(&mut self) -> Result<usize> {
+        if !self.find_cycles().is_empty() {
+            anyhow::bail!("ChildIdx cycles need to be fixed before group names can be");
+        }
+        let names = |aiprog: &AIProgram| -> Vec<(Option<String>, Option<String>)> {
+            aiprog
+                .items()
+                .into_iter()
+                .map(|item| {
+                    (
+                        def_string(item, "Name").map(|s| s.to_owned()),
+                        def_string(item, "GroupName").map(|s| s.to_owned()),
+                    )
+                })
+                .collect()
+        };
+        let before = names(self);
+        for root in self.roots() {
+            let item = self.item_at_index(root);
+            if let (Some(name), Some(group)) =
+                (def_string(item, "Name"), def_string(item, "GroupName"))
+            {
+                self.update_names_below(root, name.to_owned(), group.to_owned())?;
+            }
+        }
+        Ok(names(self)
+            .into_iter()
+            .zip(before)
+            .filter(|(after, before)| after != before)
+            .count())
+    }
+
+    /// Describes a `ChildIdx` loop as a chain of entry labels.
+    pub fn cycle_path(&self, cycle: &[usize]) -> String {
+        cycle
+            .iter()
+            .map(|i| self.entry_label(*i))
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+}
+
+fn def_string<'a>(item: &'a ParameterList, key: &str) -> Option<&'a str> {
+    item.objects()
+        .get(hash_name("Def"))?
+        .params()
+        .get(&hash_name(key))?
+        .as_string()
+        .ok()
+}
@@ -0,0 +1,142 @@
+use crate::program::{dump_actor_names, extract_from_dump, AIProgram};
+use anyhow::Result;
+use roead::aamp::{hash_name, ParamList, Parameter, ParameterIO};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+/// An entry of a vanilla AI program using a class.
+#[derive(Debug, Clone)]
+pub struct ClassUse {
+    /// File name of the AI program, without extension
+    pub program: String,
+    /// `Name` of the entry
+    pub name: String,
+    pub sinst: Vec<(u32, Parameter)>,
+}
+
+/// Value of a static parameter across every vanilla use of a class.
+#[derive(Debug, Clone)]
+pub struct TypicalValue {
+    pub key: u32,
+    /// The most common value
+    pub value: Parameter,
+    /// How many uses have the most common value
+    pub count: usize,
+    /// How many uses have the parameter at all
+    pub total: usize,
+}
+
+/// Which vanilla AI programs use each class, and how, built from a game dump.
+#[derive(Debug, Clone, Default)]
+pub struct VanillaIndex {
+    /// Uses of each class, by class name
+    pub classes: BTreeMap<String, Vec<ClassUse>>,
+    /// Actors using each AI program, by program name
+    pub actors: BTreeMap<String, Vec<String>>,
+}
+
+impl VanillaIndex {
+    /// Reads the AI program of every actor in a game dump. Actors sharing a
+    /// program only have it read once. This takes a while, as every actor
+    /// pack has to be decompressed.
+    pub fn build(dump: &Path) -> Result<Self> {
+        let names = dump_actor_names(dump);
+        if names.is_empty() {
+            anyhow::bail!("No actor packs found in {}", dump.display());
+        }
+        let mut index = Self::default();
+        for actor in names {
+            let (file, data) = match extract_from_dump(dump, &actor) {
+                Ok(found) => found,
+                // Plenty of actors have no AI program
+                Err(_) => continue,
+            };
+            let program = Path::new(&file)
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or(file);
+            let seen = index.actors.contains_key(&program);
+            index.actors.entry(program.clone()).or_default().push(actor);
+            if seen {
+                continue;
+            }
+            let aiprog = match ParameterIO::from_binary(&data)
+                .map_err(anyhow::Error::from)
+                .and_then(AIProgram::from_pio)
+            {
+                Ok(aiprog) => aiprog,
+                Err(_) => continue,
+            };
+            index.add_program(&program, &aiprog);
+        }
+        Ok(index)
+    }
+
+    fn add_program(&mut self, program: &str, aiprog: &AIProgram) {
+        for item in aiprog.items() {
+            let def = match item.objects().get(hash_name("Def")) {
+                Some(def) => def,
+                None => continue,
+            };
+            let text = |key: &str| {
+                def.params()
+                    .get(&hash_name(key))
+                    .and_then(|v| v.as_string().ok())
+                    .unwrap_or_default()
+                    .to_owned()
+            };
+            let sinst = item
+                .objects()
+                .get(hash_name("SInst"))
+                .map(|obj| obj.params().iter().map(|(k, v)| (*k, v.clone())).collect())
+                .unwrap_or_default();
+            self.classes
+                .entry(text("ClassName"))
+                .or_default()
+                .push(ClassUse {
+                    program: program.to_owned(),
+                    name: text("Name"),
+                    sinst,
+                });
+        }
+    }
+
+    pub fn uses(&self, class: &str) -> &[ClassUse] {
+        self.classes
+            .get(class)
+            .map(|uses| uses.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The most common value of each static parameter of a class, in the
+    /// order they first appear.
+    pub fn typical_values(&self, class: &str) -> Vec<TypicalValue> {
+        let mut keys: Vec<u32> = vec![];
+        let mut counts: HashMap<u32, Vec<(Parameter, usize)>> = HashMap::new();
+        for (key, value) in self.uses(class).iter().flat_map(|u| u.sinst.iter()) {
+            if !keys.contains(key) {
+                keys.push(*key);
+            }
+            let values = counts.entry(*key).or_default();
+            match values.iter_mut().find(|(v, _)| v == value) {
+                Some((_, count)) => *count += 1,
+                None => values.push((value.clone(), 1)),
+            }
+        }
+        keys.into_iter()
+            .filter_map(|key| {
+                let values = counts.remove(&key)?;
+                let total = values.iter().map(|(_, count)| count).sum();
+                let (value, count) = values.into_iter().max_by_key(|(_, count)| *count)?;
+                Some(TypicalValue {
+                    key,
+                    value,
+                    count,
+                    total,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,163 @@
+//! Reading AI programs from related games, which lay them out like BOTW but
+//! give the top-level lists and demo object other names. Which list is which
+//! is worked out from the classes of the entries in it, so layouts don't need
+//! to be known ahead of time, and the original names are put back on saving.
+use crate::{
+    program::{is_actor_pack, read_aamp, AIProgram, Category},
+    util::{try_name, AIDEFS},
+};
+use anyhow::Result;
+use roead::aamp::{hash_name, ParamList, ParameterIO, ParameterList, ParameterObject};
+use std::path::Path;
+
+const CATEGORIES: [Category; 4] = [
+    Category::AI,
+    Category::Action,
+    Category::Behaviour,
+    Category::Query,
+];
+
+const DEMOS: &str = "DemoAIActionIdx";
+
+/// Where a variant program keeps what BOTW keeps in the top-level lists and
+/// demo object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    /// Key of the list holding each category, in the order of [`Category`]
+    lists: [u32; 4],
+    /// Key of the demo object, or `None` if there isn't one
+    demos: Option<u32>,
+}
+
+/// How many entries of a list have classes of a category, going by the AI
+/// defs.
+fn category_score(list: &ParameterList, category: Category) -> usize {
+    list.lists()
+        .iter()
+        .filter_map(|(_, entry)| {
+            entry
+                .object("Def")?
+                .param("ClassName")?
+                .as_string()
+                .ok()
+                .map(|class| class.to_owned())
+        })
+        .filter(|class| AIDEFS.classes(category).any(|c| c == class))
+        .count()
+}
+
+/// Works out the layout of a program which doesn't have the BOTW lists,
+/// giving each category the list with the most entries of its classes, or
+/// failing that the first list left, as empty lists give nothing to go by.
+pub fn detect_layout(pio: &ParameterIO) -> Option<Layout> {
+    let standard = CATEGORIES.map(|category| hash_name(&category.to_string()));
+    if standard.iter().all(|k| pio.lists().get(*k).is_some()) || pio.lists().len() != 4 {
+        return None;
+    }
+    let mut left: Vec<(u32, &ParameterList)> = pio.lists().iter().map(|(k, l)| (*k, l)).collect();
+    let mut lists = [0; 4];
+    for (i, category) in CATEGORIES.into_iter().enumerate() {
+        let best = left
+            .iter()
+            .enumerate()
+            .map(|(pos, (_, list))| (pos, category_score(list, category)))
+            .filter(|(_, score)| *score > 0)
+            .max_by_key(|(_, score)| *score)
+            .map(|(pos, _)| pos);
+        lists[i] = left.remove(best.unwrap_or(0)).0;
+    }
+    let demos = match pio.objects().get(hash_name(DEMOS)) {
+        Some(_) => Some(hash_name(DEMOS)),
+        None if pio.objects().len() == 1 => pio.objects().iter().next().map(|(k, _)| *k),
+        None => None,
+    };
+    Some(Layout { lists, demos })
+}
+
+/// Changes the keys of top-level lists and objects, keeping their order.
+fn rename_keys(pio: &mut ParameterIO, rename: impl Fn(u32) -> Option<u32>) {
+    let lists = std::mem::take(pio.lists_mut().inner_mut());
+    pio.lists_mut()
+        .inner_mut()
+        .extend(lists.into_iter().map(|(k, v)| (rename(k).unwrap_or(k), v)));
+    let objects = std::mem::take(pio.objects_mut().inner_mut());
+    pio.objects_mut().inner_mut().extend(
+        objects
+            .into_iter()
+            .map(|(k, v)| (rename(k).unwrap_or(k), v)),
+    );
+}
+
+/// Reads the layout of a program file, if it's a variant one.
+pub fn read_layout(file: &Path) -> Result<Option<Layout>> {
+    if is_actor_pack(file) || file.extension().map(|ext| ext == "json").unwrap_or(false) {
+        return Ok(None);
+    }
+    Ok(detect_layout(&read_aamp(file)?))
+}
+
+impl Layout {
+    /// Pairs of the key each list and the demo object has in the variant and
+    /// in BOTW.
+    fn renames(&self) -> Vec<(u32, u32)> {
+        CATEGORIES
+            .iter()
+            .zip(self.lists)
+            .map(|(category, key)| (key, hash_name(&category.to_string())))
+            .chain(self.demos.map(|key| (key, hash_name(DEMOS))))
+            .collect()
+    }
+
+    /// Gives a variant program's lists and demo object their BOTW names,
+    /// adding an empty demo object if it has none.
+    pub fn normalize(&self, pio: &mut ParameterIO) {
+        let renames = self.renames();
+        rename_keys(pio, |k| {
+            renames
+                .iter()
+                .find(|(from, _)| *from == k)
+                .map(|(_, to)| *to)
+        });
+        if self.demos.is_none() {
+            pio.objects_mut()
+                .inner_mut()
+                .insert(hash_name(DEMOS), ParameterObject::new());
+        }
+    }
+
+    /// Puts back the names the variant gives its lists and demo object,
+    /// dropping the demo object if the variant has none.
+    pub fn restore(&self, pio: &mut ParameterIO) {
+        if self.demos.is_none() {
+            pio.objects_mut()
+                .inner_mut()
+                .shift_remove(&hash_name(DEMOS));
+        }
+        let renames = self.renames();
+        rename_keys(pio, |k| {
+            renames
+                .iter()
+                .find(|(_, to)| *to == k)
+                .map(|(from, _)| *from)
+        });
+    }
+
+    /// What the variant calls each list, for telling the user.
+    pub fn describe(&self) -> String {
+        CATEGORIES
+            .iter()
+            .zip(self.lists)
+            .map(|(category, key)| format!("{} as {}", category, try_name(key)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl AIProgram {
+    /// A copy of the program laid out as a variant, for saving it.
+    pub fn in_layout(&self, layout: &Layout) -> Self {
+        let mut pio = self.0.clone();
+        layout.restore(&mut pio);
+        Self(pio)
+    }
+}
@@ -0,0 +1,55 @@
+/*
+ * C API of plasticity-ffi, for reading and editing BOTW AI programs.
+ *
+ * Functions returning int give 0 on success and -1 on failure, those
+ * returning an index give -1 on failure, and those returning a pointer give
+ * NULL on failure. After a failure, plasticity_last_error() says what went
+ * wrong. Programs are freed with plasticity_free() and strings with
+ * plasticity_string_free().
+ *
+ * Entries are addressed by absolute index, counting up through the AI,
+ * Action, Behavior, and Query lists in turn. Categories are named "AI",
+ * "Action", "Behavior", and "Query".
+ */
+#ifndef PLASTICITY_H
+#define PLASTICITY_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct AIProgram AIProgram;
+
+const char *plasticity_last_error(void);
+
+AIProgram *plasticity_open(const char *path);
+AIProgram *plasticity_from_text(const char *text);
+int plasticity_save(const AIProgram *aiprog, const char *path);
+char *plasticity_to_text(const AIProgram *aiprog);
+void plasticity_free(AIProgram *aiprog);
+void plasticity_string_free(char *s);
+
+size_t plasticity_len(const AIProgram *aiprog);
+intptr_t plasticity_category_offset(const AIProgram *aiprog, const char *category);
+char *plasticity_entry_label(const AIProgram *aiprog, size_t idx);
+char *plasticity_entry_text(const AIProgram *aiprog, size_t idx);
+int plasticity_set_entry_text(AIProgram *aiprog, size_t idx, const char *text);
+intptr_t plasticity_children(const AIProgram *aiprog, size_t idx, size_t *out, size_t capacity);
+
+intptr_t plasticity_add_entry(AIProgram *aiprog, const char *category, const char *class_name);
+intptr_t plasticity_duplicate_entry(AIProgram *aiprog, size_t idx);
+int plasticity_delete_entry(AIProgram *aiprog, size_t idx);
+int plasticity_swap_entries(AIProgram *aiprog, size_t a, size_t b);
+
+/* JSON arrays of [entry, message] and [entry, field] pairs */
+char *plasticity_validate_json(const AIProgram *aiprog);
+char *plasticity_search_json(const AIProgram *aiprog, const char *query);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
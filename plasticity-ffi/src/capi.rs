@@ -0,0 +1,343 @@
+//! The C API. Functions returning `int` give 0 on success and -1 on failure,
+//! those returning an index give -1 on failure, and those returning a pointer
+//! give null on failure. After a failure, [`plasticity_last_error`] says what
+//! went wrong.
+use crate::{add_entry, issues, parse_category, search};
+use anyhow::{anyhow, Context, Result};
+use plasticity_core::program::{is_compressed, AIProgram};
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int},
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::Path,
+    ptr,
+};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Runs the body of an exported function, turning errors and panics, which
+/// mustn't unwind into C, into a recorded error.
+fn guard<T>(f: impl FnOnce() -> Result<T>) -> Option<T> {
+    let result = catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|_| Err(anyhow!("Plasticity panicked; see stderr for details")));
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            let message = CString::new(format!("{:#}", e).replace('\0', "")).unwrap();
+            LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+            None
+        }
+    }
+}
+
+fn status(result: Option<()>) -> c_int {
+    if result.is_some() {
+        0
+    } else {
+        -1
+    }
+}
+
+/// An index or -1, as `intptr_t` in C, which unlike `ssize_t` MSVC has too
+fn index(result: Option<usize>) -> isize {
+    result.map_or(-1, |idx| idx as isize)
+}
+
+fn string_out(result: Option<String>) -> *mut c_char {
+    result
+        .and_then(|s| CString::new(s.replace('\0', "")).ok())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+unsafe fn str_arg<'a>(s: *const c_char) -> Result<&'a str> {
+    if s.is_null() {
+        anyhow::bail!("Null string passed");
+    }
+    CStr::from_ptr(s).to_str().context("String is not UTF-8")
+}
+
+unsafe fn program<'a>(aiprog: *const AIProgram) -> Result<&'a AIProgram> {
+    aiprog.as_ref().context("Null program passed")
+}
+
+unsafe fn program_mut<'a>(aiprog: *mut AIProgram) -> Result<&'a mut AIProgram> {
+    aiprog.as_mut().context("Null program passed")
+}
+
+fn check_index(aiprog: &AIProgram, idx: usize) -> Result<()> {
+    if idx >= aiprog.len() {
+        anyhow::bail!("No entry at index {}", idx);
+    }
+    Ok(())
+}
+
+/// The message of the last failure on this thread, or null if there hasn't
+/// been one. It stays valid until the next failure on the thread.
+#[no_mangle]
+pub extern "C" fn plasticity_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Opens an AI program, actor pack, YAML, or JSON file.
+///
+/// # Safety
+/// `path` must be a valid nul-terminated string. The program returned must
+/// be freed with [`plasticity_free`].
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_open(path: *const c_char) -> *mut AIProgram {
+    guard(|| AIProgram::new(str_arg(path)?))
+        .map_or(ptr::null_mut(), |aiprog| Box::into_raw(Box::new(aiprog)))
+}
+
+/// Reads a program from YAML text.
+///
+/// # Safety
+/// `text` must be a valid nul-terminated string. The program returned must
+/// be freed with [`plasticity_free`].
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_from_text(text: *const c_char) -> *mut AIProgram {
+    guard(|| AIProgram::from_text(str_arg(text)?))
+        .map_or(ptr::null_mut(), |aiprog| Box::into_raw(Box::new(aiprog)))
+}
+
+/// Saves a program in the format its file extension names, yaz0 compressing
+/// it if the file being replaced was, or for a new file if its extension
+/// starts with "s".
+///
+/// # Safety
+/// `aiprog` must come from this library and `path` must be a valid
+/// nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_save(aiprog: *const AIProgram, path: *const c_char) -> c_int {
+    status(guard(|| {
+        let path = Path::new(str_arg(path)?);
+        program(aiprog)?.save(path, is_compressed(path))
+    }))
+}
+
+/// The whole program as YAML.
+///
+/// # Safety
+/// `aiprog` must come from this library. The string returned must be freed
+/// with [`plasticity_string_free`].
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_to_text(aiprog: *const AIProgram) -> *mut c_char {
+    string_out(guard(|| Ok(program(aiprog)?.0.to_text())))
+}
+
+/// # Safety
+/// `aiprog` must come from this library, or be null, and not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_free(aiprog: *mut AIProgram) {
+    if !aiprog.is_null() {
+        drop(Box::from_raw(aiprog));
+    }
+}
+
+/// # Safety
+/// `s` must be a string returned by this library, or null, and not be used
+/// again.
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// How many entries the program has, across all four lists.
+///
+/// # Safety
+/// `aiprog` must come from this library.
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_len(aiprog: *const AIProgram) -> usize {
+    guard(|| Ok(program(aiprog)?.len())).unwrap_or_default()
+}
+
+/// The index of the first entry of a category: "AI", "Action", "Behavior",
+/// or "Query".
+///
+/// # Safety
+/// `aiprog` must come from this library and `category` must be a valid
+/// nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_category_offset(
+    aiprog: *const AIProgram,
+    category: *const c_char,
+) -> isize {
+    index(guard(|| {
+        Ok(program(aiprog)?.category_offset(parse_category(str_arg(category)?)?))
+    }))
+}
+
+/// The index, class, and name of an entry, as the editor shows it.
+///
+/// # Safety
+/// `aiprog` must come from this library. The string returned must be freed
+/// with [`plasticity_string_free`].
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_entry_label(
+    aiprog: *const AIProgram,
+    idx: usize,
+) -> *mut c_char {
+    string_out(guard(|| {
+        let aiprog = program(aiprog)?;
+        check_index(aiprog, idx)?;
+        Ok(aiprog.entry_label(idx))
+    }))
+}
+
+/// An entry as YAML.
+///
+/// # Safety
+/// `aiprog` must come from this library. The string returned must be freed
+/// with [`plasticity_string_free`].
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_entry_text(
+    aiprog: *const AIProgram,
+    idx: usize,
+) -> *mut c_char {
+    string_out(guard(|| {
+        let aiprog = program(aiprog)?;
+        check_index(aiprog, idx)?;
+        Ok(aiprog.entry_to_text(idx))
+    }))
+}
+
+/// Replaces an entry with one read from YAML.
+///
+/// # Safety
+/// `aiprog` must come from this library and `text` must be a valid
+/// nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_set_entry_text(
+    aiprog: *mut AIProgram,
+    idx: usize,
+    text: *const c_char,
+) -> c_int {
+    status(guard(|| {
+        let aiprog = program_mut(aiprog)?;
+        check_index(aiprog, idx)?;
+        aiprog.entry_from_text(idx, str_arg(text)?)
+    }))
+}
+
+/// Writes the indexes of an entry's children into `out`, up to `capacity`
+/// of them, returning how many it has, which may be more.
+///
+/// # Safety
+/// `aiprog` must come from this library and `out` must have room for
+/// `capacity` indexes.
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_children(
+    aiprog: *const AIProgram,
+    idx: usize,
+    out: *mut usize,
+    capacity: usize,
+) -> isize {
+    index(guard(|| {
+        let aiprog = program(aiprog)?;
+        check_index(aiprog, idx)?;
+        let children = aiprog.children_of(idx);
+        if !out.is_null() {
+            for (i, child) in children.iter().take(capacity).enumerate() {
+                *out.add(i) = *child;
+            }
+        }
+        Ok(children.len())
+    }))
+}
+
+/// Adds a new entry of a class with its default parameters, returning its
+/// index, or -1 if the AI defs have no such class. Every index after it is
+/// updated to match.
+///
+/// # Safety
+/// `aiprog` must come from this library, and `category` and `class` must be
+/// valid nul-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_add_entry(
+    aiprog: *mut AIProgram,
+    category: *const c_char,
+    class: *const c_char,
+) -> isize {
+    index(guard(|| {
+        add_entry(program_mut(aiprog)?, str_arg(category)?, str_arg(class)?)
+    }))
+}
+
+/// Copies an entry to the end of its category, returning the copy's index.
+///
+/// # Safety
+/// `aiprog` must come from this library.
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_duplicate_entry(aiprog: *mut AIProgram, idx: usize) -> isize {
+    index(guard(|| {
+        let aiprog = program_mut(aiprog)?;
+        check_index(aiprog, idx)?;
+        aiprog.duplicate_entry(idx)
+    }))
+}
+
+/// Deletes an entry, clearing slots which pointed at it and moving every
+/// later index down.
+///
+/// # Safety
+/// `aiprog` must come from this library.
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_delete_entry(aiprog: *mut AIProgram, idx: usize) -> c_int {
+    status(guard(|| program_mut(aiprog)?.delete_entry(idx)))
+}
+
+/// Swaps two entries of the same category along with every reference to
+/// them.
+///
+/// # Safety
+/// `aiprog` must come from this library.
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_swap_entries(
+    aiprog: *mut AIProgram,
+    a: usize,
+    b: usize,
+) -> c_int {
+    status(guard(|| program_mut(aiprog)?.swap_entries(a, b)))
+}
+
+/// Every problem found in the program, as a JSON array of `[entry,
+/// message]` pairs, where the entry is -1 for the demo table.
+///
+/// # Safety
+/// `aiprog` must come from this library. The string returned must be freed
+/// with [`plasticity_string_free`].
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_validate_json(aiprog: *const AIProgram) -> *mut c_char {
+    string_out(guard(|| {
+        Ok(serde_json::to_string(&issues(program(aiprog)?))?)
+    }))
+}
+
+/// Entries with a name, class, or value containing the query, as a JSON
+/// array of `[entry, field]` pairs.
+///
+/// # Safety
+/// `aiprog` must come from this library and `query` must be a valid
+/// nul-terminated string. The string returned must be freed with
+/// [`plasticity_string_free`].
+#[no_mangle]
+pub unsafe extern "C" fn plasticity_search_json(
+    aiprog: *const AIProgram,
+    query: *const c_char,
+) -> *mut c_char {
+    string_out(guard(|| {
+        Ok(serde_json::to_string(&search(
+            program(aiprog)?,
+            str_arg(query)?,
+        ))?)
+    }))
+}
@@ -0,0 +1,58 @@
+//! C and Python bindings to [`plasticity_core`], so mod pipelines in other
+//! languages can load, query, and edit AI programs without redoing the index
+//! bookkeeping every edit needs.
+//!
+//! The C API is declared in `include/plasticity.h`. The Python module,
+//! `plasticity_ffi`, is built with the `python` feature, such as by
+//! `maturin build --features python`.
+#![warn(clippy::all, rust_2018_idioms)]
+use anyhow::Result;
+use plasticity_core::{
+    program::{AIProgram, Category},
+    util::AIDEFS,
+};
+
+mod capi;
+#[cfg(feature = "python")]
+mod python;
+
+/// Reads a category from its name as the program's lists are named.
+fn parse_category(name: &str) -> Result<Category> {
+    Ok(match name {
+        "AI" => Category::AI,
+        "Action" => Category::Action,
+        "Behavior" => Category::Behaviour,
+        "Query" => Category::Query,
+        _ => anyhow::bail!("No category named {}", name),
+    })
+}
+
+/// Adds an entry of a class with its default parameters, refusing classes
+/// the AI defs don't know, which have no defaults to add.
+fn add_entry(aiprog: &mut AIProgram, category: &str, class: &str) -> Result<usize> {
+    let parsed = parse_category(category)?;
+    if AIDEFS.get_def(parsed, class).is_none() {
+        anyhow::bail!("No {} class named {}", category, class);
+    }
+    aiprog.add_entry(parsed, class.to_owned())
+}
+
+/// The problems [`AIProgram::validate_all`] finds, as entry index (or -1 for
+/// the demo table) and message.
+fn issues(aiprog: &AIProgram) -> Vec<(i64, String)> {
+    aiprog
+        .validate_all()
+        .into_iter()
+        .map(|issue| (issue.entry.map_or(-1, |e| e as i64), issue.kind.to_string()))
+        .collect()
+}
+
+/// The entries [`AIProgram::search`] finds, as entry index and matching
+/// field.
+fn search(aiprog: &AIProgram, query: &str) -> Vec<(usize, String)> {
+    aiprog
+        .search(query)
+        .into_iter()
+        .map(|hit| (hit.entry, hit.field))
+        .collect()
+}
@@ -0,0 +1,115 @@
+//! The Python module, a thin wrapper over the same calls as the C API.
+use crate::{add_entry, issues, parse_category, search};
+use plasticity_core::program::{is_compressed, AIProgram};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use std::path::Path;
+
+fn to_py(e: anyhow::Error) -> PyErr {
+    PyValueError::new_err(format!("{:#}", e))
+}
+
+fn check_index(aiprog: &AIProgram, idx: usize) -> PyResult<()> {
+    if idx >= aiprog.len() {
+        return Err(PyValueError::new_err(format!("No entry at index {}", idx)));
+    }
+    Ok(())
+}
+
+/// An AI program. Entries are addressed by absolute index, counting up
+/// through the AI, Action, Behavior, and Query lists in turn, and every edit
+/// keeps the indexes pointing at them correct.
+#[pyclass(name = "Program")]
+struct Program(AIProgram);
+
+#[pymethods]
+impl Program {
+    /// Opens an AI program, actor pack, YAML, or JSON file.
+    #[staticmethod]
+    fn open(path: &str) -> PyResult<Self> {
+        AIProgram::new(path).map(Self).map_err(to_py)
+    }
+
+    #[staticmethod]
+    fn from_text(text: &str) -> PyResult<Self> {
+        AIProgram::from_text(text).map(Self).map_err(to_py)
+    }
+
+    /// Saves in the format the file extension names.
+    fn save(&self, path: &str) -> PyResult<()> {
+        let path = Path::new(path);
+        self.0.save(path, is_compressed(path)).map_err(to_py)
+    }
+
+    fn to_text(&self) -> String {
+        self.0 .0.to_text()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    /// The index of the first entry of "AI", "Action", "Behavior", or
+    /// "Query".
+    fn category_offset(&self, category: &str) -> PyResult<usize> {
+        Ok(self
+            .0
+            .category_offset(parse_category(category).map_err(to_py)?))
+    }
+
+    fn entry_label(&self, idx: usize) -> PyResult<String> {
+        check_index(&self.0, idx)?;
+        Ok(self.0.entry_label(idx))
+    }
+
+    fn entry_text(&self, idx: usize) -> PyResult<String> {
+        check_index(&self.0, idx)?;
+        Ok(self.0.entry_to_text(idx))
+    }
+
+    fn set_entry_text(&mut self, idx: usize, text: &str) -> PyResult<()> {
+        check_index(&self.0, idx)?;
+        self.0.entry_from_text(idx, text).map_err(to_py)
+    }
+
+    fn children(&self, idx: usize) -> PyResult<Vec<usize>> {
+        check_index(&self.0, idx)?;
+        Ok(self.0.children_of(idx))
+    }
+
+    /// Adds an entry of a class with its default parameters, returning its
+    /// index. Raises ValueError for classes the AI defs don't have.
+    fn add_entry(&mut self, category: &str, class: &str) -> PyResult<usize> {
+        add_entry(&mut self.0, category, class).map_err(to_py)
+    }
+
+    fn duplicate_entry(&mut self, idx: usize) -> PyResult<usize> {
+        check_index(&self.0, idx)?;
+        self.0.duplicate_entry(idx).map_err(to_py)
+    }
+
+    fn delete_entry(&mut self, idx: usize) -> PyResult<()> {
+        self.0.delete_entry(idx).map_err(to_py)
+    }
+
+    fn swap_entries(&mut self, a: usize, b: usize) -> PyResult<()> {
+        self.0.swap_entries(a, b).map_err(to_py)
+    }
+
+    /// Every problem found, as (entry, message) pairs, where the entry is -1
+    /// for the demo table.
+    fn validate(&self) -> Vec<(i64, String)> {
+        issues(&self.0)
+    }
+
+    /// Entries with a name, class, or value containing the query, as (entry,
+    /// field) pairs.
+    fn search(&self, query: &str) -> Vec<(usize, String)> {
+        search(&self.0, query)
+    }
+}
+
+#[pymodule]
+fn plasticity_ffi(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<Program>()?;
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use crate::app::App;
+use anyhow::Result;
+use eframe::egui::{self, Ui};
+use plasticity_core::{
+    program::{is_actor_pack, is_compressed, read_aamp, read_pack_aamp, write_pack_file},
+    util::try_name,
+};
+use roead::{
+    aamp::{ParamList, ParameterIO, ParameterObject},
+    yaz0,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Where actor packs keep their AS list
+const ASLIST_DIR: &str = "Actor/ASList/";
+
+/// A plain tree of lists, objects, and parameters for editing AAMP files
+/// other than AI programs, chiefly AS lists, which AI changes often need to
+/// match.
+#[derive(Debug, Clone)]
+pub struct AampEditor {
+    pub file: PathBuf,
+    /// Path of the file inside `file`, when that is an actor pack
+    pub inner: Option<String>,
+    pub pio: ParameterIO,
+    saved: ParameterIO,
+}
+
+impl AampEditor {
+    /// Opens a loose AAMP file, or the AS list of an actor pack.
+    pub fn open(file: &Path) -> Result<Self> {
+        let (inner, pio) = if is_actor_pack(file) {
+            let (inner, pio) = read_pack_aamp(file, ASLIST_DIR, ".baslist")?;
+            (Some(inner), pio)
+        } else {
+            (None, read_aamp(file)?)
+        };
+        Ok(Self {
+            file: file.to_path_buf(),
+            inner,
+            saved: pio.clone(),
+            pio,
+        })
+    }
+
+    pub fn name(&self) -> String {
+        let file = self.file.file_name().unwrap_or_default().to_string_lossy();
+        match &self.inner {
+            Some(inner) => format!("{} ({})", inner.rsplit('/').next().unwrap_or(inner), file),
+            None => file.into_owned(),
+        }
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.pio != self.saved
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        match &self.inner {
+            Some(inner) => write_pack_file(&self.file, inner, self.pio.to_binary())?,
+            None => match self.file.extension() {
+                Some(ext) if ext == "yml" => fs::write(&self.file, self.pio.to_text())?,
+                _ if is_compressed(&self.file) => {
+                    fs::write(&self.file, yaz0::compress(&self.pio.to_binary()))?
+                }
+                _ => fs::write(&self.file, self.pio.to_binary())?,
+            },
+        }
+        self.saved = self.pio.clone();
+        Ok(())
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        egui::ScrollArea::vertical()
+            .max_height(500.0)
+            .show(ui, |ui| list_ui(ui, &mut self.pio, "aamp"));
+    }
+}
+
+/// Draws every object and list under a list, nested as they are in the file.
+/// `path` keeps the IDs of the headers apart from other trees.
+pub fn list_ui<L: ParamList>(ui: &mut Ui, list: &mut L, path: &str) {
+    for (key, obj) in list.objects_mut().inner_mut().iter_mut() {
+        object_ui(ui, *key, obj, path, false);
+    }
+    for (key, child) in list.lists_mut().inner_mut().iter_mut() {
+        let id = format!("{}/{}", path, key);
+        egui::CollapsingHeader::new(try_name(*key))
+            .id_source(&id)
+            .show(ui, |ui| list_ui(ui, child, &id));
+    }
+}
+
+/// Draws a collapsible grid of the parameters in an object.
+pub fn object_ui(ui: &mut Ui, key: u32, obj: &mut ParameterObject, path: &str, open: bool) {
+    let id = format!("{}/{}", path, key);
+    egui::CollapsingHeader::new(try_name(key))
+        .id_source(&id)
+        .default_open(open)
+        .show(ui, |ui| {
+            egui::Grid::new(&id).num_columns(2).show(ui, |ui| {
+                for (k, v) in obj.params_mut().iter_mut() {
+                    ui.label(try_name(*k));
+                    App::render_parameter(ui, v);
+                    ui.end_row();
+                }
+            });
+        });
+}
@@ -1,91 +1,692 @@
-use crate::{auto::*, program::AIProgram, tree::Tree, util::*};
-use anyhow::{Error, Result};
+#[cfg(target_arch = "wasm32")]
+use crate::web::{self, clipboard_text, FileDialog};
+use crate::{
+    aamp::{self, AampEditor},
+    auto::*,
+    autosave::{self, AUTOSAVE_INTERVAL},
+    conflicts::ConflictInspector,
+    crash,
+    deploy::DeployLayout,
+    document::{file_time, DocVersion, Document, EntryText},
+    dryrun::DryRun,
+    i18n::{tr, Language},
+    log::{log_file, Log},
+    merge::{MergeAction, MergeWizard},
+    param_history,
+    plugin::{self, Plugin},
+    port::PortWizard,
+    project::{Project, ProjectAction},
+    reference::Reference,
+    replace::{FindReplace, ReplaceAction},
+    rows,
+    script::run_script,
+    session::Session,
+    settings::{
+        changed_color, error_color, ok_color, warning_color, Palette, Platform, SaveFormat,
+        Settings, Theme,
+    },
+    sidecar::Sidecar,
+    task::{self, Progress, Tasks},
+    tree::{Tree, TreeAction, TreeView},
+};
+use anyhow::{anyhow, Context, Error, Result};
 use eframe::{
-    egui::{self, menu, FontDefinitions, Frame, Ui, Vec2},
+    egui::{self, menu, FontDefinitions, Frame, Key, Ui, Vec2},
     epi,
 };
 use gmod_lzma::decompress;
-use roead::aamp::{hash_name, ParamList, Parameter};
+use instant::Instant;
+use plasticity_core::{
+    animation::{as_exists, read_as_names, AsNames},
+    backup::{list_backups, Backup},
+    batch::{FileReport, Report},
+    delete::{DeleteEntriesPreview, DeleteMode, DeletePreview},
+    flags::{dump_bootup_pack, flag_exists, names_flag, FlagList},
+    links::ActorLink,
+    merge::{inspect_conflicts, Merge, Touched},
+    patch::load_patch,
+    program::{
+        dump_actor_names, extract_from_dump, is_actor_pack, is_compressed, AIProgram, Category,
+    },
+    remap::BehaviorSlot,
+    sanity::{value_warning, ValueWarning, MAX_RATE},
+    schema::SCHEMA_FILE,
+    search::SearchHit,
+    util::*,
+    validate::{Issue, IssueKind},
+    vanilla::VanillaIndex,
+    variant::{read_layout, Layout},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::FileDialog;
+use roead::aamp::{hash_name, ParamList, Parameter, ParameterIO, ParameterList, ParameterObject};
 use std::{
     borrow::Cow,
-    collections::{BTreeSet, HashMap},
-    path::PathBuf,
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
     sync::mpsc::{channel, Receiver, Sender},
+    time::{Duration, SystemTime},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Category {
-    AI,
-    Action,
-    Behaviour,
-    Query,
+/// How often open files are checked for changes made by something else
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Saves a document's program and notes, backing up what was there first.
+#[cfg(not(target_arch = "wasm32"))]
+fn save_document(
+    file: &Path,
+    aiprog: &AIProgram,
+    layout: Option<&Layout>,
+    compress: bool,
+    sidecar: &Sidecar,
+    keep: usize,
+) -> Result<()> {
+    plasticity_core::backup::backup_before_save(file, keep)?;
+    match layout {
+        Some(layout) => aiprog.in_layout(layout).save(file, compress)?,
+        None => aiprog.save(file, compress)?,
+    }
+    sidecar.save(file)
+}
+
+/// Saves a document's program as a download. Notes aren't kept, as the
+/// browser has nowhere to put them.
+#[cfg(target_arch = "wasm32")]
+fn save_document(
+    file: &Path,
+    aiprog: &AIProgram,
+    layout: Option<&Layout>,
+    compress: bool,
+    _sidecar: &Sidecar,
+    _keep: usize,
+) -> Result<()> {
+    match layout {
+        Some(layout) => web::save(file, &aiprog.in_layout(layout), compress),
+        None => web::save(file, aiprog, compress),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn clipboard_text() -> Result<String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|e| anyhow!(tr!("error-clipboard", error = e.to_string())))
+}
+
+/// Whether files and folders can be picked with a dialog. The browser has no
+/// native dialogs, so only opening a program to edit asks it for a file.
+pub const CAN_PICK_FILES: bool = cfg!(not(target_arch = "wasm32"));
+
+/// Adds a button for something which asks for a file or folder, which in the
+/// browser is disabled and says why.
+pub fn dialog_button(ui: &mut Ui, enabled: bool, button: egui::Button) -> egui::Response {
+    let response = ui.add_enabled(enabled && CAN_PICK_FILES, button);
+    if CAN_PICK_FILES {
+        response
+    } else {
+        response.on_disabled_hover_text(tr!("hint-needs-desktop"))
+    }
+}
+
+/// How long ago a time was, roughly, as in "5 minutes ago".
+fn time_ago(time: SystemTime) -> String {
+    let secs = time.elapsed().map(|d| d.as_secs()).unwrap_or_default();
+    match secs {
+        0..=59 => tr!("time-just-now"),
+        60..=3599 => tr!("time-minutes-ago", count = secs / 60),
+        3600..=86399 => tr!("time-hours-ago", count = secs / 3600),
+        _ => tr!("time-days-ago", count = secs / 86400),
+    }
+}
+
+/// Describes a problem found in a program in the user's language.
+fn issue_text(kind: &IssueKind) -> String {
+    match kind {
+        IssueKind::NoSuchEntry { table, key, index } => tr!(
+            "issue-no-such-entry",
+            table = *table,
+            key = key.as_str(),
+            index = *index
+        ),
+        IssueKind::WrongRange {
+            table,
+            key,
+            category,
+            index,
+        } => tr!(
+            "issue-wrong-range",
+            table = *table,
+            key = key.as_str(),
+            category = category.to_string(),
+            index = *index
+        ),
+        IssueKind::NoSuchBehavior { key, index } => {
+            tr!("issue-no-such-behavior", key = key.as_str(), index = *index)
+        }
+        IssueKind::NotInteger { table, key } => {
+            tr!("issue-not-integer", table = *table, key = key.as_str())
+        }
+        IssueKind::Cycle(path) => tr!("issue-cycle", path = path.as_str()),
+        IssueKind::MissingDef => tr!("issue-missing-def"),
+        IssueKind::EmptyClassName => tr!("issue-empty-class"),
+        IssueKind::WrongKey { key, expected } => tr!(
+            "issue-wrong-key",
+            key = key.as_str(),
+            expected = expected.as_str()
+        ),
+        IssueKind::UnknownClass(class) => tr!("issue-unknown-class", class = class.as_str()),
+        IssueKind::MissingChildSlot(slot) => {
+            tr!("issue-missing-child-slot", slot = slot.as_str())
+        }
+        IssueKind::WrongType {
+            obj,
+            name,
+            expected,
+        } => tr!(
+            "issue-wrong-type",
+            obj = *obj,
+            name = name.as_str(),
+            expected = expected.as_str()
+        ),
+        IssueKind::UndefinedParam { obj, name, class } => tr!(
+            "issue-undefined-param",
+            obj = *obj,
+            name = name.as_str(),
+            class = class.as_str()
+        ),
+        IssueKind::WrongGroupName { group, expected } => tr!(
+            "issue-wrong-group-name",
+            group = group.as_str(),
+            expected = expected
+                .iter()
+                .map(|name| format!("\"{}\"", name))
+                .collect::<Vec<_>>()
+                .join(&format!(" {} ", tr!("list-or")))
+        ),
+        IssueKind::OddValue { obj, name, warning } => tr!(
+            "issue-odd-value",
+            obj = *obj,
+            name = name.as_str(),
+            warning = value_warning_text(warning)
+        ),
+        IssueKind::UnknownFlag { obj, name, flag } => tr!(
+            "issue-unknown-flag",
+            obj = *obj,
+            name = name.as_str(),
+            flag = flag.as_str()
+        ),
+        IssueKind::MissingLink { class, needs, link } => tr!(
+            "issue-missing-link",
+            class = class.as_str(),
+            needs = needs.as_str(),
+            link = link.as_str()
+        ),
+        IssueKind::UnknownAs { name, anim } => tr!(
+            "issue-unknown-as",
+            name = name.as_str(),
+            anim = anim.as_str()
+        ),
+        IssueKind::UnusedRoot => tr!("issue-unused-root"),
+    }
+}
+
+/// Describes what looks wrong with a numeric value in the user's language.
+/// Values are passed as text so they show as entered, not rounded.
+fn value_warning_text(warning: &ValueWarning) -> String {
+    match warning {
+        ValueWarning::NotFinite(value) => {
+            tr!("param-warning-not-finite", value = value.to_string())
+        }
+        ValueWarning::PastFullTurn(value, AngleUnit::Degrees) => {
+            tr!("param-warning-degrees", value = value.to_string())
+        }
+        ValueWarning::PastFullTurn(value, AngleUnit::Radians) => {
+            tr!("param-warning-radians", value = value.to_string())
+        }
+        ValueWarning::Negative(value, word) => tr!(
+            "param-warning-negative",
+            value = value.to_string(),
+            word = word.as_str()
+        ),
+        ValueWarning::UnusualRate(value) => tr!(
+            "param-warning-rate",
+            value = value.to_string(),
+            max = MAX_RATE.to_string()
+        ),
+    }
+}
+
+/// Lists what deleting an entry will change, for a confirmation prompt.
+fn delete_text(preview: &DeletePreview, aiprog: &AIProgram) -> String {
+    let mut text = tr!("delete-confirm", entry = aiprog.entry_label(preview.entry));
+    text.push('\n');
+    if !preview.breaks_references() {
+        text.push_str(&format!("\n{}\n", tr!("delete-unreferenced")));
+    }
+    for (title, slots) in [
+        (tr!("delete-cleared-children"), &preview.children),
+        (tr!("delete-cleared-behaviors"), &preview.behaviours),
+    ] {
+        if !slots.is_empty() {
+            text.push_str(&format!("\n{}\n", title));
+            for (holder, key) in slots {
+                text.push_str(&slot_line(aiprog, *holder, *key));
+            }
+        }
+    }
+    if !preview.demos.is_empty() {
+        text.push_str(&format!("\n{}\n", tr!("delete-cleared-demos")));
+        for key in &preview.demos {
+            text.push_str(&format!("  {}\n", try_name(*key)));
+        }
+    }
+    if preview.shifted_entries > 0 {
+        text.push_str(&format!(
+            "\n{}",
+            tr!(
+                "delete-shifted",
+                entries = preview.shifted_entries,
+                slots = preview.shifted_slots
+            )
+        ));
+    }
+    text
 }
 
-impl std::fmt::Display for Category {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            Self::AI => f.write_fmt(format_args!("AI")),
-            Self::Action => f.write_fmt(format_args!("Action")),
-            Self::Behaviour => f.write_fmt(format_args!("Behavior")),
-            Self::Query => f.write_fmt(format_args!("Query")),
+/// Lists what deleting several entries at once will change, for a
+/// confirmation prompt.
+fn delete_entries_text(preview: &DeleteEntriesPreview, aiprog: &AIProgram) -> String {
+    let mut text = tr!("delete-confirm-many", count = preview.entries.len());
+    text.push('\n');
+    for idx in &preview.entries {
+        text.push_str(&format!("  {}\n", aiprog.entry_label(*idx)));
+    }
+    if !preview.slots.is_empty() {
+        text.push_str(&format!("\n{}\n", tr!("delete-cleared-others")));
+        for (holder, key) in &preview.slots {
+            text.push_str(&slot_line(aiprog, *holder, *key));
+        }
+    }
+    if !preview.demos.is_empty() {
+        text.push_str(&format!("\n{}\n", tr!("delete-cleared-demos")));
+        for key in &preview.demos {
+            text.push_str(&format!("  {}\n", try_name(*key)));
         }
     }
+    text
+}
+
+/// A line naming a slot and the entry holding it, for a list of slots.
+fn slot_line(aiprog: &AIProgram, holder: usize, key: u32) -> String {
+    format!(
+        "  {} ({})\n",
+        aiprog.entry_label(holder),
+        translate(&try_name(key))
+    )
+}
+
+/// Label for a `BehaviorIdx` value, given the names of the behaviors.
+fn behavior_label(offset: usize, index: i32, names: &[String]) -> String {
+    match names.get(index as usize) {
+        Some(name) if index >= 0 => format!(
+            "{}. {}",
+            index_label(Category::Behaviour, index as usize, offset + index as usize),
+            name
+        ),
+        _ => tr!("behavior-invalid", index = index),
+    }
+}
+
+/// An entry about to be deleted which others use and which has children, and
+/// what to do with them.
+#[derive(Debug)]
+struct DeleteChoice {
+    doc: DocVersion,
+    entry: usize,
+    mode: DeleteMode,
+    /// What deleting it will change, from [`AIProgram::delete_preview`]
+    text: String,
+    children: Vec<usize>,
+    /// How many entries deleting it with its children removes
+    subtree: usize,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum Message {
-    AIProgram(AIProgram),
-    Tree(Vec<Tree>),
+    AIProgram(PathBuf, AIProgram),
+    /// Rebuilt tree and orphaned roots for the document with the given ID
+    Tree(usize, Vec<Tree>, Vec<Tree>),
     Null,
-    Delete,
+    /// Delete an entry of a document, dealing with its children as given
+    Delete(DocVersion, usize, DeleteMode),
+    /// Delete several entries of a document at once
+    DeleteEntries(DocVersion, BTreeSet<usize>),
+    Merge(Merge),
+    /// Entries changed by the mods being inspected for conflicts
+    ModConflicts(Vec<Touched>),
+    /// A document's program with its entries reordered, and how many moved
+    Normalized(DocVersion, AIProgram, usize),
+    /// Problems found in a document
+    Validation(DocVersion, Vec<Issue>),
+    /// Patch read from a file, to apply to the document with the given ID
+    Patch(usize, ParameterIO),
+    /// Remove a parameter from an object of an entry of a document
+    RemoveParam(DocVersion, usize, u32, u32),
+    CloseDocument(usize),
+    /// Entries nothing uses in a document
+    Unused(DocVersion, Vec<usize>),
+    /// Delete every entry nothing uses from a document
+    RemoveUnused(DocVersion),
+    Reference(PathBuf, AIProgram, Vec<Tree>),
+    /// Program edited by a script, and what the script printed
+    Script(DocVersion, AIProgram, Vec<String>),
+    /// Backups restored after a crash, with the files they belong to as last
+    /// saved
+    Recovered(Vec<(PathBuf, AIProgram, Option<AIProgram>)>),
+    /// Everything is saved, so the app can close
+    Exit,
+    /// File name and data of an AI program extracted from the game dump
+    Extracted(String, Vec<u8>),
+    /// Restructure the entry at an index of a document to suit a new class
+    MigrateClass(DocVersion, usize, String),
+    /// Snippet loaded to be added below the entry at an index of a document
+    Snippet(DocVersion, usize, AIProgram),
+    /// Program entries were merged from, to match their behaviors against
+    RemapSource(PathBuf, AIProgram),
+    /// Program to copy an AI from
+    PortSource(PathBuf, AIProgram),
+    /// Throw away every change since a document was loaded
+    RevertAll(DocVersion),
+    /// The documents with the given IDs were written to disk
+    Saved(Vec<usize>),
+    /// Mod folder scanned for AI programs, to open as a project
+    Project(Project),
+    /// Problems found in each file of the project
+    ProjectValidation(Report),
+    /// Save every open file in the project with unsaved changes
+    SaveProject,
+    /// Files written to the mod folder by deploying
+    Deployed(Vec<PathBuf>),
+    /// Replace the active document with its file as it now is on disk
+    Reload,
+    /// The file of a document as it now is on disk, to replace it with
+    Reloaded(usize, AIProgram),
+    /// The file of a document as it now is on disk, to compare it with
+    DiskVersion(usize, AIProgram),
+    /// AS list or other AAMP file opened in the generic editor
+    Aamp(AampEditor),
+    /// Close the generic AAMP editor, throwing away its changes
+    CloseAamp,
+    /// Uses of each class in the game dump
+    Vanilla(VanillaIndex),
+    /// GameData flags read from the flag list in the settings
+    Flags(FlagList),
+    /// Move an AI of a document to the Action list or the reverse, as the
+    /// given class
+    Convert(DocVersion, usize, String),
+    /// Program read from a backup, to replace the one in the document with
+    /// the given ID
+    RestoreBackup(usize, AIProgram),
+    /// A file which isn't a valid AI program but could be opened by filling
+    /// in what it's missing, and why it isn't valid
+    Repairable(PathBuf, String),
+    /// Open a file, filling in whatever it's missing
+    OpenRepaired(PathBuf),
+    /// Program opened by filling in what its file was missing, and what that
+    /// was
+    Repaired(PathBuf, AIProgram, Vec<String>),
+}
+
+/// Something picked from the Plugins menu, by its position in the plugin's
+/// list of commands or formats.
+#[derive(Debug, Clone, Copy)]
+enum PluginAction {
+    Command(usize),
+    Import(usize),
+    Export(usize),
 }
 
 pub struct App {
-    file: Option<PathBuf>,
-    aiprog: Option<AIProgram>,
-    init_prog: Option<AIProgram>,
-    tree: Vec<Tree>,
-    cache: HashMap<&'static str, Vec<String>>,
-    selected_ai: usize,
-    last_selected: HashMap<Category, usize>,
-    tab: Category,
+    docs: Vec<Document>,
+    /// Read-only program shown alongside the one being edited
+    reference: Option<Reference>,
+    /// Mod folder whose files are listed for opening and checking together
+    project: Option<Project>,
+    project_issues: Option<Report>,
+    /// AS list or other AAMP file open alongside the AI programs
+    aamp: Option<AampEditor>,
+    /// Index of the document being edited
+    active: usize,
+    next_doc_id: usize,
     messengers: (Sender<Result<Message>>, Receiver<Result<Message>>),
     show_error: bool,
     error: Option<String>,
-    show_busy: bool,
+    tasks: Tasks,
     show_add: bool,
     add_ac_state: AcState,
     add_class: String,
+    /// Autocomplete state for whichever parameter field has focus
+    edit_ac_state: AcState,
     show_confirm: bool,
     confirm_text: Option<String>,
     confirm_msg: Option<Message>,
     show_def: bool,
+    show_validation: bool,
+    validation: Vec<Issue>,
+    /// Problems found when saving, which have to be confirmed to save anyway
+    save_issues: Option<Vec<Issue>>,
+    show_unused: bool,
+    /// Entries nothing uses, found in a document as it was
+    unused: Option<(DocVersion, Vec<usize>)>,
+    show_refs: bool,
+    /// Dragged entry, its old parent, and its new parent, while picking a slot
+    reparent_target: Option<(usize, Option<usize>, usize)>,
+    show_hashes: bool,
+    show_settings: bool,
+    delete_choice: Option<DeleteChoice>,
+    log: Log,
+    /// Extensions enabled in this build
+    plugins: Vec<Box<dyn Plugin>>,
+    show_log: bool,
+    /// Entry being moved between the AI and Action lists, the document it's
+    /// in, and its new class
+    convert: Option<(DocVersion, usize, String)>,
+    convert_ac_state: AcState,
+    /// Backups of a document, by ID, to pick one to restore from
+    backups: Option<(usize, Vec<Backup>)>,
+    show_remap: bool,
+    port: Option<PortWizard>,
+    dry_run: Option<DryRun>,
+    find_replace: Option<FindReplace>,
+    /// Two entries whose parameters are shown side by side
+    compare: Option<(usize, usize)>,
+    compare_diff_only: bool,
+    /// Changes from another version of the active program, waiting for its
+    /// conflicts to be settled
+    merge: Option<MergeWizard>,
+    conflicts: Option<ConflictInspector>,
+    show_vanilla: bool,
+    /// Uses of each class in the game dump, once read
+    vanilla: Option<VanillaIndex>,
+    /// GameData flags to check flag parameters against, once read
+    flags: Option<FlagList>,
+    /// Behavior indexes being rebound, with the index each will be set to
+    remap_slots: Vec<(BehaviorSlot, i32)>,
+    remap_source: Option<(PathBuf, AIProgram)>,
+    show_translations: bool,
+    translation_filter: String,
+    /// Japanese names in the active program and any the user has translated
+    translation_names: BTreeSet<String>,
+    settings: Settings,
+    /// Fonts at their base sizes, before the settings scale them
+    fonts: FontDefinitions,
+    native_ppp: Option<f32>,
+    /// Name being tried against unknown hashes
+    hash_input: String,
+    /// Keys in the active program with no known name
+    unknown_hashes: BTreeSet<u32>,
+    /// Snippet waiting for a slot of its new parent, in a document as it was,
+    /// to be picked
+    import_target: Option<(DocVersion, usize, AIProgram)>,
+    new_demo: String,
+    demo_ac_state: AcState,
+    show_script: bool,
+    script: String,
+    script_log: Vec<String>,
+    show_search: bool,
+    search: String,
+    search_results: Vec<SearchHit>,
+    session: Session,
+    /// Entry to select once the file being opened has loaded
+    pending_selection: Option<usize>,
     title: String,
+    last_autosave: Instant,
+    /// History revision of each document when the crash handler's copies
+    /// were last updated
+    unsaved_revisions: Vec<usize>,
+    last_watch: Instant,
+    show_exit: bool,
+    show_dump: bool,
+    show_deploy: bool,
+    /// Whether to deploy the files saved next, whatever the setting
+    deploy_next_save: bool,
+    /// Files written by the last deploy
+    deployed: Vec<PathBuf>,
+    show_bulk: bool,
+    /// SInst parameter being set on every selected entry, and its new value
+    bulk_param: Option<(u32, Parameter)>,
+    /// Number of entries changed by the last bulk edit
+    bulk_result: Option<usize>,
+    dump_actor: String,
+    /// Actors with packs in the game dump, for autocompleting
+    dump_actors: Vec<String>,
+    dump_ac_state: AcState,
+    /// Set once unsaved changes have been dealt with and the app should close
+    exit_confirmed: bool,
+    /// Backups left by a session which didn't exit cleanly, awaiting a
+    /// decision on whether to restore them
+    recovery: Vec<(PathBuf, PathBuf)>,
+}
+
+fn load_fonts() -> FontDefinitions {
+    let mut font_defs = FontDefinitions::default();
+    font_defs.font_data.insert(
+        "Roboto".to_owned(),
+        Cow::Owned(decompress(include_bytes!("../data/Roboto.ttf.lz")).unwrap()),
+    );
+    font_defs.font_data.insert(
+        "NotoSansJP".to_owned(),
+        Cow::Owned(decompress(include_bytes!("../data/NotoSansJP.otf.lz")).unwrap()),
+    );
+    font_defs.font_data.insert(
+        "Ocami".to_owned(),
+        Cow::Owned(decompress(include_bytes!("../data/Ocami.ttf.lz")).unwrap()),
+    );
+    font_defs
+        .fonts_for_family
+        .get_mut(&egui::FontFamily::Proportional)
+        .unwrap()
+        .insert(0, "Roboto".to_owned());
+    font_defs
+        .fonts_for_family
+        .get_mut(&egui::FontFamily::Proportional)
+        .unwrap()
+        .insert(1, "NotoSansJP".to_owned());
+    font_defs
+        .fonts_for_family
+        .get_mut(&egui::FontFamily::Monospace)
+        .unwrap()
+        .insert(0, "Ocami".to_owned());
+    font_defs
 }
 
 impl Default for App {
     fn default() -> Self {
         App {
-            file: None,
-            aiprog: None,
-            init_prog: None,
-            tree: vec![],
-            cache: HashMap::with_capacity(3),
-            selected_ai: 0,
-            last_selected: HashMap::with_capacity(4),
-            tab: Category::AI,
+            docs: vec![],
+            reference: None,
+            project: None,
+            project_issues: None,
+            aamp: None,
+            active: 0,
+            next_doc_id: 0,
             messengers: channel(),
             show_error: false,
             error: None,
-            show_busy: false,
+            tasks: Tasks::default(),
             show_add: false,
             add_class: String::new(),
             add_ac_state: AcState::default(),
+            edit_ac_state: AcState::default(),
             show_confirm: false,
             confirm_text: None,
             confirm_msg: None,
             show_def: false,
+            show_validation: false,
+            validation: vec![],
+            save_issues: None,
+            show_unused: false,
+            unused: None,
+            show_refs: false,
+            reparent_target: None,
+            import_target: None,
+            show_hashes: false,
+            show_settings: false,
+            delete_choice: None,
+            log: Log::init(),
+            plugins: plugin::registered(),
+            show_log: false,
+            convert: None,
+            convert_ac_state: AcState::default(),
+            backups: None,
+            show_remap: false,
+            port: None,
+            dry_run: None,
+            find_replace: None,
+            compare: None,
+            compare_diff_only: false,
+            merge: None,
+            conflicts: None,
+            show_vanilla: false,
+            vanilla: None,
+            flags: None,
+            remap_slots: vec![],
+            remap_source: None,
+            show_translations: false,
+            translation_filter: String::new(),
+            translation_names: BTreeSet::new(),
+            settings: Settings::default(),
+            fonts: FontDefinitions::default(),
+            native_ppp: None,
+            hash_input: String::new(),
+            unknown_hashes: BTreeSet::new(),
+            new_demo: String::new(),
+            demo_ac_state: AcState::default(),
+            show_script: false,
+            script: String::new(),
+            script_log: vec![],
+            show_search: false,
+            search: String::new(),
+            search_results: vec![],
+            session: Session::default(),
+            pending_selection: None,
             title: "Plasticity".into(),
+            last_autosave: Instant::now(),
+            unsaved_revisions: vec![],
+            last_watch: Instant::now(),
+            show_exit: false,
+            show_dump: false,
+            show_deploy: false,
+            deploy_next_save: false,
+            deployed: vec![],
+            show_bulk: false,
+            bulk_param: None,
+            bulk_result: None,
+            dump_actor: String::new(),
+            dump_actors: vec![],
+            dump_ac_state: AcState::default(),
+            exit_confirmed: false,
+            recovery: vec![],
         }
     }
 }
@@ -98,432 +699,3154 @@ impl epi::App for App {
     fn setup(
         &mut self,
         ctx: &egui::CtxRef,
-        _frame: &mut epi::Frame<'_>,
-        _storage: Option<&dyn epi::Storage>,
+        frame: &mut epi::Frame<'_>,
+        storage: Option<&dyn epi::Storage>,
     ) {
-        ctx.set_fonts({
-            let mut font_defs = FontDefinitions::default();
-            font_defs.font_data.insert(
-                "Roboto".to_owned(),
-                Cow::Owned(decompress(include_bytes!("../data/Roboto.ttf.lz")).unwrap()),
-            );
-            font_defs.font_data.insert(
-                "NotoSansJP".to_owned(),
-                Cow::Owned(decompress(include_bytes!("../data/NotoSansJP.otf.lz")).unwrap()),
-            );
-            font_defs.font_data.insert(
-                "Ocami".to_owned(),
-                Cow::Owned(decompress(include_bytes!("../data/Ocami.ttf.lz")).unwrap()),
-            );
-            font_defs
-                .fonts_for_family
-                .get_mut(&egui::FontFamily::Proportional)
-                .unwrap()
-                .insert(0, "Roboto".to_owned());
-            font_defs
-                .fonts_for_family
-                .get_mut(&egui::FontFamily::Proportional)
-                .unwrap()
-                .insert(1, "NotoSansJP".to_owned());
-            font_defs
-                .fonts_for_family
-                .get_mut(&egui::FontFamily::Monospace)
-                .unwrap()
-                .insert(0, "Ocami".to_owned());
-            font_defs
-                .family_and_size
-                .iter_mut()
-                .for_each(|(_, (_, size))| {
-                    *size *= 1.2;
-                });
-            font_defs
-        })
+        self.recovery = autosave::find_backups();
+        if let Some(storage) = storage {
+            self.session = epi::get_value(storage, Session::KEY).unwrap_or_default();
+            add_names(self.session.hash_names.values().map(|n| n.as_str()));
+            for (name, english) in &self.session.translations {
+                set_translation(name, Some(english));
+            }
+        }
+        self.settings = match Settings::load() {
+            Ok(Some(settings)) => settings,
+            Ok(None) => storage
+                .and_then(|storage| epi::get_value(storage, Settings::KEY))
+                .unwrap_or_default(),
+            Err(e) => {
+                tracing::warn!("Couldn't read the settings: {:#}", e);
+                Settings::default()
+            }
+        };
+        if let Some(dump) = self.session.game_dump.take() {
+            self.settings.game_dump.get_or_insert(dump);
+        }
+        self.set_flag_list(self.settings.flag_list.clone());
+        if let Err(e) = self.log.set_write_file(self.settings.log_to_file) {
+            tracing::warn!("Couldn't open {}: {}", log_file().display(), e);
+        }
+        tracing::info!("Plasticity {} started", env!("CARGO_PKG_VERSION"));
+        self.fonts = load_fonts();
+        self.native_ppp = frame.info().native_pixels_per_point;
+        self.settings.apply(ctx, &self.fonts, self.native_ppp);
+        if self.session.reopen_last {
+            if let Some(root) = self.session.project.clone().filter(|f| f.is_dir()) {
+                self.open_project(root);
+            }
+            if let Some(file) = self.session.last_file.clone().filter(|f| f.exists()) {
+                self.pending_selection = Some(self.session.last_selected);
+                self.open_file(file);
+            }
+        }
+    }
+
+    fn save(&mut self, storage: &mut dyn epi::Storage) {
+        if let Some(doc) = self.docs.get(self.active) {
+            self.session.last_file = Some(doc.file.clone());
+            self.session.last_selected = doc.selected_ai;
+        }
+        epi::set_value(storage, Session::KEY, &self.session);
+        // The angle unit can also be switched next to any angle parameter
+        self.settings.angle_display = angle_display();
+        if let Err(e) = self.settings.save() {
+            tracing::warn!("Couldn't save the settings: {:#}", e);
+        }
+    }
+
+    fn on_exit(&mut self) {
+        // Closing the window can't be stopped, so keep unsaved changes around
+        // to offer them again next time
+        let dirty: Vec<(PathBuf, AIProgram)> = self
+            .docs
+            .iter()
+            .filter(|doc| doc.is_dirty())
+            .map(|doc| (doc.file.clone(), doc.aiprog.clone()))
+            .collect();
+        if self.exit_confirmed || dirty.is_empty() || autosave::write_backups(&dirty).is_err() {
+            autosave::clear_backups();
+        }
     }
 
     fn update(&mut self, ctx: &egui::CtxRef, frame: &mut epi::Frame<'_>) {
-        self.render_menu(ctx, frame);
+        if let Some(doc) = self.docs.get_mut(self.active) {
+            doc.index.update(&doc.aiprog);
+        }
+        self.render_menu(ctx);
+        self.render_documents(ctx);
+        self.render_project_panel(ctx);
         self.render_side_panel(ctx);
+        self.render_reference_panel(ctx);
         self.render_main(ctx);
         self.render_error(ctx);
         self.render_busy(ctx);
         self.render_add(ctx);
         self.render_confirm(ctx);
         self.render_def(ctx);
+        self.render_validation(ctx);
+        self.render_dry_run(ctx);
+        self.render_compare(ctx);
+        self.render_vanilla(ctx);
+        self.render_aamp(ctx);
+        self.render_find_replace(ctx);
+        self.render_project_issues(ctx);
+        self.render_save_issues(ctx);
+        self.render_unused(ctx);
+        self.render_references(ctx);
+        self.render_reparent(ctx);
+        self.render_import(ctx);
+        self.render_hashes(ctx);
+        self.render_settings(ctx);
+        self.render_delete_choice(ctx);
+        self.render_log(ctx);
+        self.render_convert(ctx);
+        self.render_backups(ctx);
+        self.render_remap(ctx);
+        self.render_port(ctx);
+        self.render_merge(ctx);
+        self.render_conflicts(ctx);
+        self.render_translations(ctx);
+        self.render_search(ctx);
+        self.render_script(ctx);
+        self.render_recovery(ctx);
+        self.render_exit(ctx);
+        self.render_dump(ctx);
+        self.render_deploy(ctx);
+        self.render_bulk(ctx);
+        self.render_disk_diff(ctx);
+        self.handle_shortcuts(ctx);
+        self.handle_history(ctx);
         self.handle_events(frame);
+        self.autosave();
+        self.watch_files();
+        if self.exit_confirmed {
+            frame.quit();
+        }
     }
 }
 
 impl App {
+    fn start_task<F: FnOnce() -> Result<Message> + Send + 'static>(&mut self, task: F) {
+        self.run_task(&tr!("task-working"), move |_| task());
+    }
+
+    /// Starts a named background task which can report its progress and be
+    /// cancelled.
+    fn run_task<F>(&mut self, name: &str, task: F)
+    where
+        F: FnOnce(&Progress) -> Result<Message> + Send + 'static,
+    {
+        self.tasks.start(name, self.messengers.0.clone(), task);
+    }
+
+    /// Hands the documents with unsaved changes to the panic hook, to write
+    /// out if the app crashes.
+    fn update_unsaved(&self) {
+        crash::set_unsaved(
+            self.docs
+                .iter()
+                .filter(|doc| doc.is_dirty())
+                .map(|doc| (doc.file.clone(), doc.aiprog.clone()))
+                .collect(),
+        );
+    }
+
+    /// Backs up every document with unsaved changes on a background thread,
+    /// once per autosave interval.
     #[allow(unused_must_use)]
-    fn start_task<F: Fn() -> Result<Message> + Send + 'static>(&mut self, task: F) {
-        self.show_busy = true;
+    fn autosave(&mut self) {
+        // The browser has nowhere to write backups
+        if cfg!(target_arch = "wasm32") || self.last_autosave.elapsed() < AUTOSAVE_INTERVAL {
+            return;
+        }
+        self.last_autosave = Instant::now();
+        let dirty: Vec<(PathBuf, AIProgram)> = self
+            .docs
+            .iter()
+            .filter(|doc| doc.is_dirty())
+            .map(|doc| (doc.file.clone(), doc.aiprog.clone()))
+            .collect();
         let sender = self.messengers.0.clone();
-        let task = Box::new(task);
-        std::thread::spawn(move || {
-            sender.send(task());
+        task::spawn(move || {
+            if let Err(e) = autosave::write_backups(&dirty) {
+                sender.send(Err(e.context(tr!("error-autosave"))));
+            }
         });
     }
 
-    fn set_title<S: std::borrow::Borrow<str>>(&mut self, title: S, frame: &mut epi::Frame<'_>) {
-        self.title = title.borrow().to_string();
-        frame.set_window_title(title.borrow());
+    fn doc(&self) -> Option<&Document> {
+        self.docs.get(self.active)
     }
 
-    fn init_hashes(&mut self) {
-        if let Some(aiprog) = self.aiprog.as_ref() {
-            self.cache.insert(
-                "child_names",
-                (0..aiprog.behaviors_offset())
-                    .into_iter()
-                    .map(|i| aiprog.entry_name_from_index(i).unwrap().to_owned())
-                    .collect(),
-            );
-            self.cache.insert(
-                "behaviour_names",
-                aiprog
-                    .behaviors()
-                    .into_iter()
-                    .map(|ai| AIProgram::entry_name(ai).unwrap())
-                    .collect(),
-            );
-            self.cache.insert(
-                "group_names",
-                [String::new()]
-                    .into_iter()
-                    .chain(aiprog.ais().into_iter().map(|ai| {
-                        ai.objects()["Def"]
-                            .params()
-                            .get(&hash_name("Name"))
-                            .unwrap()
-                            .as_string()
-                            .unwrap()
-                            .to_string()
-                    }))
-                    .collect::<BTreeSet<String>>()
-                    .into_iter()
-                    .collect(),
-            );
-        }
+    fn doc_mut(&mut self) -> Option<&mut Document> {
+        self.docs.get_mut(self.active)
     }
 
-    fn handle_events(&mut self, frame: &mut epi::Frame<'_>) {
-        if let Ok(res) = self.messengers.1.try_recv() {
-            self.show_busy = false;
-            match res {
-                Ok(msg) => match msg {
-                    Message::AIProgram(aiprog) => {
-                        self.selected_ai = 0;
-                        self.last_selected = HashMap::with_capacity(4);
-                        self.init_prog = Some(aiprog.clone());
-                        self.aiprog = Some(aiprog.clone());
-                        self.init_hashes();
-                        self.start_task(move || aiprog.to_tree().map(Message::Tree));
-                    }
-                    Message::Tree(tree) => self.tree = tree,
-                    Message::Delete => {
-                        if let Err(e) = self.aiprog.as_mut().unwrap().delete_entry(self.selected_ai)
-                        {
-                            self.show_error(e);
-                        } else {
-                            self.selected_ai = std::cmp::max(0, self.selected_ai - 1);
-                            let aiprog = self.aiprog.clone().unwrap();
-                            self.start_task(move || aiprog.to_tree().map(Message::Tree));
-                        }
-                    }
-                    _ => (),
-                },
-                Err(e) => self.show_error(e),
+    /// Makes the document with an ID active, to apply what a background task
+    /// worked out for it, unless it has been closed since.
+    fn activate(&mut self, id: usize) -> Option<&mut Document> {
+        match self.docs.iter().position(|doc| doc.id == id) {
+            Some(active) => {
+                self.active = active;
+                self.docs.get_mut(active)
+            }
+            None => {
+                tracing::warn!("Document {} was closed before its task finished", id);
+                None
             }
         }
-        if *self.last_selected.get(&self.tab).unwrap_or(&9999) != self.selected_ai
-            && self.aiprog.is_some()
-        {
-            let aiprog = self.aiprog.as_ref().unwrap();
-            self.tab = if self.selected_ai < aiprog.actions_offset() {
-                Category::AI
-            } else if self.selected_ai < aiprog.behaviors_offset() {
-                Category::Action
-            } else if self.selected_ai < aiprog.queries_offset() {
-                Category::Behaviour
-            } else {
-                Category::Query
-            };
-            self.last_selected.insert(self.tab, self.selected_ai);
+    }
+
+    /// Like [`App::activate`], for results worked out from a document's
+    /// program, which are refused if it has been edited since.
+    fn activate_version(&mut self, version: DocVersion) -> Option<&mut Document> {
+        if self.activate(version.id)?.version() == version {
+            return self.doc_mut();
         }
-        if self.init_prog != self.aiprog && !self.title.starts_with('*') {
-            self.set_title(format!("*{}", self.title), frame);
+        let file = self.doc()?.name();
+        tracing::warn!("{} was edited while a task was working on it", file);
+        self.show_error(anyhow!(tr!("error-edited-since", file = file)));
+        None
+    }
+
+    fn update_title(&mut self, frame: &mut epi::Frame<'_>) {
+        let title = match self.doc() {
+            Some(doc) => format!(
+                "{}{} - Plasticity",
+                if doc.is_dirty() { "*" } else { "" },
+                doc.name()
+            ),
+            None => "Plasticity".into(),
+        };
+        if title != self.title {
+            frame.set_window_title(&title);
+            self.title = title;
         }
     }
 
-    fn render_menu(&mut self, ctx: &egui::CtxRef, frame: &mut epi::Frame<'_>) {
-        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
-            menu::bar(ui, |ui| {
-                menu::menu(ui, "File", |ui| {
-                    if ui.button("Open").clicked() {
-                        if let Some(file) = rfd::FileDialog::new()
-                            .add_filter("BOTW Binary AI Program", &["baiprog"])
-                            .add_filter("BOTW YAML AI Program", &["yml"])
-                            .pick_file()
-                        {
-                            self.set_title(
-                                format!(
-                                    "{} - Plasticity",
-                                    file.file_name().unwrap().to_string_lossy()
-                                ),
-                                frame,
-                            );
-                            frame.set_window_title(self.title.as_str());
-                            self.file = Some(file.clone());
-                            self.start_task(move || AIProgram::new(&file).map(Message::AIProgram));
-                        }
-                    }
-                    if ui.button("Save").clicked() && self.aiprog.is_some() && self.file.is_some() {
-                        let file = self.file.clone().unwrap();
-                        self.set_title(
-                            format!(
-                                "{} - Plasticity",
-                                file.file_name().unwrap().to_string_lossy()
-                            ),
-                            frame,
-                        );
-                        let aiprog = self.aiprog.clone().unwrap();
-                        self.start_task(move || aiprog.save(&file).map(|_| Message::Null));
-                        self.init_prog = self.aiprog.clone();
-                    }
-                    if ui.button("Save As").clicked() {
-                        if let Some(file) = rfd::FileDialog::new()
-                            .add_filter("BOTW Binary AI Program", &["baiprog"])
-                            .add_filter("BOTW YAML AI Program", &["yml"])
-                            .save_file()
-                        {
-                            self.set_title(
-                                format!(
-                                    "{} - Plasticity",
-                                    file.file_name().unwrap().to_string_lossy()
-                                ),
-                                frame,
-                            );
-                            let aiprog = self.aiprog.clone().unwrap();
-                            self.start_task(move || aiprog.save(&file).map(|_| Message::Null));
-                            self.init_prog = self.aiprog.clone();
-                        }
-                    }
-                    if ui.button("Exit").clicked() {
-                        std::process::exit(0);
-                    }
-                });
-            });
+    fn open_file(&mut self, file: PathBuf) {
+        tracing::info!("Opening {}", file.display());
+        self.session.add_recent(&file);
+        self.session.last_file = Some(file.clone());
+        if let Some(i) = self.docs.iter().position(|doc| doc.file == file) {
+            self.switch_document(i);
+            return;
+        }
+        self.start_task(move || match AIProgram::new(&file) {
+            Ok(aiprog) => Ok(Message::AIProgram(file.clone(), aiprog)),
+            Err(e) => match AIProgram::new_permissive(&file) {
+                Ok(_) => Ok(Message::Repairable(file.clone(), e.to_string())),
+                Err(_) => Err(e),
+            },
         });
     }
 
-    fn render_side_panel(&mut self, ctx: &egui::CtxRef) {
-        egui::SidePanel::left("tree_panel")
-            .max_width(200.0)
-            .resizable(true)
-            .frame(Frame {
-                margin: Vec2::new(8.0, 2.0),
-                corner_radius: 0.0,
-                fill: ctx.style().visuals.extreme_bg_color,
-                stroke: ctx.style().visuals.window_stroke(),
-                ..Default::default()
-            })
-            .show(ctx, |ui| {
-                egui::ScrollArea::vertical().show(ui, |ui| {
-                    self.tree
-                        .iter_mut()
-                        .for_each(|t| t.ui(ui, &mut self.selected_ai));
-                });
+    /// Opens a file, or switches to it if it's already open, and selects an
+    /// entry.
+    fn open_file_at(&mut self, file: PathBuf, idx: usize) {
+        match self.docs.iter_mut().find(|doc| doc.file == file) {
+            Some(doc) => doc.navigate(idx),
+            None => self.pending_selection = Some(idx),
+        }
+        self.open_file(file);
+    }
+
+    fn open_project(&mut self, root: PathBuf) {
+        self.start_task(move || Project::new(root.clone()).map(Message::Project));
+    }
+
+    fn close_project(&mut self) {
+        self.project = None;
+        self.project_issues = None;
+        self.session.project = None;
+    }
+
+    /// Checks every file in the project, using the open documents as they are
+    /// rather than as saved.
+    fn validate_project(&mut self) {
+        if let Some(project) = self.project.clone() {
+            let open: HashMap<PathBuf, AIProgram> = self
+                .docs
+                .iter()
+                .map(|doc| (doc.file.clone(), doc.aiprog.clone()))
+                .collect();
+            self.run_task(&tr!("task-checking-project"), move |progress| {
+                Ok(Message::ProjectValidation(
+                    project.validate(&open, progress),
+                ))
             });
+        }
     }
 
-    fn render_main(&mut self, ctx: &egui::CtxRef) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            if let Some(aiprog) = self.aiprog.as_ref() {
-                let actions_offset = aiprog.actions_offset();
-                let behaviors_offset = aiprog.behaviors_offset();
-                let queries_offset = aiprog.queries_offset();
-                let show_ais = !aiprog.ais().is_empty();
-                let show_actions = !aiprog.actions().is_empty();
-                let show_behaviours = !aiprog.behaviors().is_empty();
-                let show_queries = !aiprog.queries().is_empty();
-                egui::TopBottomPanel::top("tab_bar").show(ctx, |ui| {
-                    ui.horizontal(|ui| {
-                        if show_ais
-                            && ui
-                                .selectable_label(matches!(self.tab, Category::AI), "AIs")
-                                .clicked()
-                        {
-                            self.tab = Category::AI;
-                            self.selected_ai = *self.last_selected.get(&Category::AI).unwrap_or(&0);
-                        }
-                        if show_actions
-                            && ui
-                                .selectable_label(matches!(self.tab, Category::Action), "Actions")
-                                .clicked()
-                        {
-                            self.tab = Category::Action;
-                            self.selected_ai = self
-                                .last_selected
-                                .get(&Category::Action)
-                                .copied()
-                                .unwrap_or(actions_offset)
-                        }
-                        if show_behaviours
-                            && ui
-                                .selectable_label(
-                                    matches!(self.tab, Category::Behaviour),
-                                    "Behaviours",
-                                )
-                                .clicked()
-                        {
-                            self.tab = Category::Behaviour;
-                            self.selected_ai = self
-                                .last_selected
-                                .get(&Category::Behaviour)
-                                .copied()
-                                .unwrap_or(behaviors_offset)
-                        }
-                        if show_queries
-                            && ui
-                                .selectable_label(matches!(self.tab, Category::Query), "Queries")
-                                .clicked()
-                        {
-                            self.tab = Category::Query;
-                            self.selected_ai = self
-                                .last_selected
-                                .get(&Category::Query)
-                                .copied()
-                                .unwrap_or(queries_offset)
-                        }
-                    })
-                });
-                self.render_editor(ui, ctx);
+    /// Saves every open file in the project with unsaved changes, asking first
+    /// if any of them have problems which may stop the game from loading them.
+    fn request_save_project(&mut self) {
+        let project = match &self.project {
+            Some(project) => project,
+            None => return,
+        };
+        let problems: Vec<String> = self
+            .docs
+            .iter()
+            .filter(|doc| project.contains(&doc.file) && doc.is_dirty())
+            .filter(|doc| !doc.aiprog.lint().is_empty())
+            .map(|doc| doc.name())
+            .collect();
+        if problems.is_empty() {
+            self.save_project();
+        } else {
+            let text = tr!("confirm-save-problems", files = problems.join("\n"));
+            self.show_confirm(&text, Message::SaveProject);
+        }
+    }
+
+    fn save_project(&mut self) {
+        let project = match &self.project {
+            Some(project) => project,
+            None => return,
+        };
+        let dirty: Vec<(usize, PathBuf, bool, AIProgram, Option<Layout>, Sidecar)> = self
+            .docs
+            .iter_mut()
+            .filter(|doc| project.contains(&doc.file) && doc.is_dirty())
+            .map(|doc| {
+                doc.init_prog = doc.aiprog.clone();
+                doc.changed.clear();
+                (
+                    doc.id,
+                    doc.file.clone(),
+                    doc.compress,
+                    doc.aiprog.clone(),
+                    doc.layout.clone(),
+                    doc.sidecar.clone(),
+                )
+            })
+            .collect();
+        if dirty.is_empty() {
+            return;
+        }
+        let keep = self.settings.backup_count;
+        self.start_task(move || {
+            for (_, file, compress, aiprog, layout, sidecar) in &dirty {
+                save_document(file, aiprog, layout.as_ref(), *compress, sidecar, keep)?;
             }
+            Ok(Message::Saved(dirty.iter().map(|(id, ..)| *id).collect()))
         });
     }
 
-    fn render_editor(&mut self, ui: &mut Ui, ctx: &egui::CtxRef) {
-        let mut update_tree = false;
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            if self.aiprog.is_some() {
-                ui.horizontal(|_ui| {
-                    egui::CentralPanel::default().show(ctx, |ui| {
-                        egui::ScrollArea::vertical()
-                            .id_source("editor")
-                            .show(ui, |ui| {
-                                if self.aiprog.is_some() {
-                                    if let Some(aiprog) = self.aiprog.as_mut() {
-                                        egui::ComboBox::from_label("Current Entry")
-                                            .width(ui.available_width() - 125.0)
-                                            .selected_text(format!(
-                                                "{}_{}. {}",
-                                                self.tab,
-                                                self.selected_ai
-                                                    - if self.selected_ai < aiprog.actions_offset()
-                                                    {
-                                                        0
-                                                    } else if self.selected_ai
-                                                        < aiprog.behaviors_offset()
-                                                    {
-                                                        aiprog.actions_offset()
-                                                    } else if self.selected_ai
-                                                        < aiprog.queries_offset()
-                                                    {
-                                                        aiprog.behaviors_offset()
-                                                    } else {
-                                                        aiprog.queries_offset()
-                                                    },
-                                                aiprog
-                                                    .entry_name_from_index(self.selected_ai)
-                                                    .unwrap()
-                                            ))
-                                            .show_ui(ui, |ui| {
-                                                (match self.tab {
-                                                    Category::AI => aiprog.ais(),
-                                                    Category::Action => aiprog.actions(),
-                                                    Category::Behaviour => aiprog.behaviors(),
-                                                    Category::Query => aiprog.queries(),
-                                                })
-                                                .into_iter()
-                                                .enumerate()
-                                                .for_each(|(i, _)| {
-                                                    let idx = i + match self.tab {
-                                                        Category::AI => 0,
-                                                        Category::Action => aiprog.actions_offset(),
-                                                        Category::Behaviour => {
-                                                            aiprog.behaviors_offset()
-                                                        }
-                                                        Category::Query => aiprog.queries_offset(),
-                                                    };
-                                                    ui.selectable_value(
-                                                        &mut self.selected_ai,
-                                                        idx,
-                                                        format!(
-                                                            "{}_{}. {}",
-                                                            self.tab,
-                                                            i,
-                                                            aiprog
-                                                                .entry_name_from_index(i)
-                                                                .unwrap()
-                                                        ),
-                                                    );
-                                                });
-                                            });
-                                    }
-                                    ui.add_space(4.0);
-                                    ui.horizontal(|ui| {
-                                        if ui.small_button("Add New").clicked() {
-                                            self.show_add = true;
-                                        };
-                                        if ui.small_button("Delete Selected").clicked() {
-                                            self.show_confirm(
-                                                "Are you sure you want to delete this AI entry?",
-                                                Message::Delete,
-                                            );
-                                        };
-                                        if ui.small_button("View AI Def").clicked() {
-                                            self.show_def = true;
-                                        }
-                                    });
-                                }
-                                update_tree = update_tree || self.render_definition(ui);
-                                update_tree = update_tree || self.render_ai_children(ui);
-                                self.render_sinst_parameters(ui);
-                                self.render_behaviour_indexes(ui);
-                            });
-                    });
-                });
-            };
+    /// Copies saved files into the mod folder set up for testing.
+    fn deploy(&mut self, files: Vec<PathBuf>) {
+        if self.session.deploy.dir.is_none() {
+            self.show_deploy = true;
+            return;
+        }
+        let deploy = self.session.deploy.clone();
+        self.start_task(move || {
+            let mut written = vec![];
+            for file in &files {
+                written.extend(deploy.deploy(file)?);
+            }
+            Ok(Message::Deployed(written))
         });
-        if update_tree {
-            let aiprog = self.aiprog.clone().unwrap();
-            self.start_task(move || aiprog.to_tree().map(Message::Tree));
+    }
+
+    /// Deploys the active document, saving it first if it has unsaved
+    /// changes.
+    fn deploy_active(&mut self) {
+        if let Some(doc) = self.doc() {
+            if doc.is_dirty() {
+                self.deploy_next_save = true;
+                self.save_file();
+            } else {
+                let file = doc.file.clone();
+                self.deploy(vec![file]);
+            }
         }
     }
 
-    fn render_definition(&mut self, ui: &mut Ui) -> bool {
-        let mut update_tree = false;
-        if let Some(aiprog) = self.aiprog.as_mut() {
-            let ai = aiprog.item_mut_at_index(self.selected_ai);
-            if let Some(defs) = ai.objects_mut().get_mut(hash_name("Def")) {
-                egui::CollapsingHeader::new("Definition")
-                    .default_open(true)
-                    .show(ui, |ui| {
-                        egui::Grid::new("def").num_columns(2).show(ui, |ui| {
-                            if let Some(name) = defs
-                                .params_mut()
-                                .get_mut(&hash_name("Name"))
-                                .and_then(|n| match n {
-                                    Parameter::StringRef(s) => Some(s),
-                                    _ => None,
-                                })
-                            {
-                                ui.label("Name");
-                                if ui.text_edit_singleline(name).changed() {
-                                    update_tree = true;
-                                };
-                                ui.end_row();
-                            };
+    #[cfg(target_arch = "wasm32")]
+    fn open_dialog(&mut self) {
+        web::open_dialog(self.messengers.0.clone());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_dialog(&mut self) {
+        if let Some(file) = FileDialog::new()
+            .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+            .add_filter("BOTW YAML AI Program", &["yml"])
+            .add_filter("BOTW Actor Pack", &["sbactorpack", "bactorpack"])
+            .add_filter("JSON AI Program", &["json"])
+            .pick_file()
+        {
+            self.open_file(file);
+        }
+    }
+
+    /// Saves the active document, asking first if it has root AIs which
+    /// nothing refers to.
+    fn save_file(&mut self) {
+        if let Some(doc) = self.doc() {
+            let mut issues = doc.aiprog.lint();
+            issues.extend(
+                doc.aiprog
+                    .orphan_roots(&doc.marked_root_indexes())
+                    .into_iter()
+                    .map(|i| Issue {
+                        entry: Some(i),
+                        kind: IssueKind::UnusedRoot,
+                    }),
+            );
+            if !issues.is_empty() {
+                self.save_issues = Some(issues);
+                return;
+            }
+        }
+        self.write_file();
+    }
+
+    fn write_file(&mut self) {
+        if let Some(doc) = self.doc_mut() {
+            let id = doc.id;
+            let file = doc.file.clone();
+            let compress = doc.compress;
+            let aiprog = doc.aiprog.clone();
+            let layout = doc.layout.clone();
+            let sidecar = doc.sidecar.clone();
+            doc.init_prog = doc.aiprog.clone();
+            doc.changed.clear();
+            let keep = self.settings.backup_count;
+            self.start_task(move || {
+                save_document(&file, &aiprog, layout.as_ref(), compress, &sidecar, keep)?;
+                Ok(Message::Saved(vec![id]))
+            });
+        }
+    }
+
+    /// Looks for files changed on disk by something else, such as another tool
+    /// or a git checkout.
+    fn watch_files(&mut self) {
+        // Skip while busy, which might be saving one of the files
+        if self.last_watch.elapsed() < WATCH_INTERVAL || self.tasks.is_busy() {
+            return;
+        }
+        self.last_watch = Instant::now();
+        for doc in self.docs.iter_mut() {
+            doc.check_disk();
+        }
+    }
+
+    /// Loads the file of the active document as it now is on disk, either to
+    /// replace the document or to compare it.
+    fn load_disk_version(&mut self, replace: bool) {
+        if let Some(doc) = self.doc() {
+            let id = doc.id;
+            let file = doc.file.clone();
+            self.start_task(move || {
+                let aiprog = AIProgram::new(&file)?;
+                Ok(if replace {
+                    Message::Reloaded(id, aiprog)
+                } else {
+                    Message::DiskVersion(id, aiprog)
+                })
+            });
+        }
+    }
+
+    fn save_as(&mut self) {
+        if self.doc().is_none() {
+            return;
+        }
+        if let Some(file) = FileDialog::new()
+            .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+            .add_filter("BOTW YAML AI Program", &["yml"])
+            .add_filter("JSON AI Program", &["json"])
+            .save_file()
+        {
+            self.session.add_recent(&file);
+            let doc = self.doc_mut().unwrap();
+            doc.compress = is_compressed(&file);
+            doc.file = file;
+            self.save_file();
+        }
+    }
+
+    /// Reads an AI program from YAML on the clipboard, then asks where to save
+    /// it, as every document needs a file.
+    fn open_clipboard(&mut self) {
+        let text = match clipboard_text() {
+            Ok(text) => text,
+            Err(e) => return self.show_error(e),
+        };
+        let aiprog = match AIProgram::from_text(&text) {
+            Ok(aiprog) => aiprog,
+            Err(e) => return self.show_error(e.context(tr!("error-clipboard-program"))),
+        };
+        self.save_new_program(aiprog, "Clipboard");
+    }
+
+    /// Asks where to save a program which has no file yet, such as one
+    /// pasted or imported, then opens it from there. It's offered as `stem`
+    /// in the format set for new files.
+    fn save_new_program(&mut self, aiprog: AIProgram, stem: &str) {
+        let yaml: (&str, &[&str]) = ("BOTW YAML AI Program", &["yml"]);
+        let binary: (&str, &[&str]) = ("BOTW Binary AI Program", &["baiprog", "sbaiprog"]);
+        let (first, second) = match self.settings.save_format {
+            SaveFormat::Yaml => (yaml, binary),
+            SaveFormat::Binary => (binary, yaml),
+        };
+        if let Some(file) = FileDialog::new()
+            .set_file_name(&format!(
+                "{}.{}",
+                stem,
+                self.settings.save_format.extension()
+            ))
+            .add_filter(first.0, first.1)
+            .add_filter(second.0, second.1)
+            .add_filter("JSON AI Program", &["json"])
+            .save_file()
+        {
+            self.session.add_recent(&file);
+            self.start_task(move || {
+                aiprog.save(&file, is_compressed(&file))?;
+                Ok(Message::AIProgram(file, aiprog))
+            });
+        }
+    }
+
+    fn switch_document(&mut self, index: usize) {
+        self.active = index;
+        // Results from another document would point at the wrong entries
+        self.show_validation = false;
+        self.validation.clear();
+        self.show_unused = false;
+        self.unused = None;
+        self.dry_run = None;
+        self.compare = None;
+        if let (Some(find_replace), Some(doc)) = (&mut self.find_replace, self.docs.get(index)) {
+            find_replace.refresh(&doc.aiprog);
+        }
+        if self.show_search {
+            self.open_search();
+        }
+    }
+
+    fn close_document(&mut self, id: usize) {
+        if let Some(i) = self.docs.iter().position(|doc| doc.id == id) {
+            self.docs.remove(i);
+            if self.active > i || self.active >= self.docs.len() {
+                self.active = self.active.saturating_sub(1);
+            }
+            self.switch_document(self.active);
+        }
+    }
+
+    /// Rebuilds the tree of the active document in the background.
+    fn update_tree(&mut self) {
+        if let Some(doc) = self.doc() {
+            let id = doc.id;
+            let aiprog = doc.aiprog.clone();
+            let marked = doc.marked_root_indexes();
+            self.run_task(&tr!("task-building-tree"), move |progress| {
+                Tree::from_program(&aiprog, &marked, progress)
+                    .map(|(tree, orphans)| Message::Tree(id, tree, orphans))
+            });
+        }
+    }
+
+    fn handle_events(&mut self, frame: &mut epi::Frame<'_>) {
+        self.tasks.clean_up();
+        if let Ok(res) = self.messengers.1.try_recv() {
+            match res {
+                Ok(msg) => match msg {
+                    Message::AIProgram(file, aiprog) => {
+                        let cycles = aiprog.find_cycles();
+                        if !cycles.is_empty() {
+                            self.show_error(anyhow!(tr!(
+                                "error-cycles",
+                                cycles = cycles
+                                    .iter()
+                                    .map(|cycle| aiprog.cycle_path(cycle))
+                                    .collect::<Vec<_>>()
+                                    .join("\n")
+                            )));
+                        }
+                        let selected = self
+                            .pending_selection
+                            .take()
+                            .filter(|i| *i < aiprog.len())
+                            .unwrap_or(0);
+                        let mut doc = Document::new(self.next_doc_id, file, aiprog, selected);
+                        match Sidecar::load(&doc.file) {
+                            Ok(sidecar) => doc.sidecar = sidecar,
+                            Err(e) => self.show_error(e),
+                        }
+                        if let Some(marked) = self.session.marked_roots.get(&doc.file) {
+                            doc.marked_roots = marked.clone();
+                        }
+                        match read_layout(&doc.file) {
+                            Ok(layout) => doc.layout = layout,
+                            Err(e) => tracing::warn!(
+                                "Couldn't read the layout of {}: {:#}",
+                                doc.file.display(),
+                                e
+                            ),
+                        }
+                        if is_actor_pack(&doc.file) {
+                            match read_as_names(&doc.file) {
+                                Ok(as_names) => doc.as_names = as_names,
+                                Err(e) => tracing::warn!(
+                                    "Couldn't read the ASList of {}: {:#}",
+                                    doc.file.display(),
+                                    e
+                                ),
+                            }
+                            match ActorLink::read(&doc.file) {
+                                Ok(link) => doc.actor_link = link,
+                                Err(e) => tracing::warn!(
+                                    "Couldn't read the ActorLink of {}: {:#}",
+                                    doc.file.display(),
+                                    e
+                                ),
+                            }
+                        }
+                        self.docs.push(doc);
+                        self.next_doc_id += 1;
+                        self.switch_document(self.docs.len() - 1);
+                        self.update_tree();
+                    }
+                    Message::Tree(id, tree, orphans) => {
+                        if let Some(doc) = self.docs.iter_mut().find(|doc| doc.id == id) {
+                            doc.tree = tree;
+                            doc.orphans = orphans;
+                        }
+                    }
+                    Message::RemoveParam(version, idx, obj_key, key) => {
+                        if let Some(doc) = self.activate_version(version) {
+                            let removed = doc.edit(|aiprog| {
+                                if let Some(obj) =
+                                    aiprog.get_item_mut(idx)?.objects_mut().get_mut(obj_key)
+                                {
+                                    obj.params_mut().shift_remove(&key);
+                                }
+                                Ok(())
+                            });
+                            if let Err(e) = removed {
+                                self.show_error(e);
+                            } else if obj_key == hash_name("Def") {
+                                self.update_tree();
+                            }
+                        }
+                    }
+                    Message::Patch(id, patch) => {
+                        if let Some(doc) = self.activate(id) {
+                            let mut aiprog = doc.aiprog.clone();
+                            aiprog.apply_patch(&patch);
+                            doc.restore(aiprog);
+                            self.update_tree();
+                        }
+                    }
+                    Message::Validation(version, issues)
+                        if self.activate_version(version).is_some() =>
+                    {
+                        self.validation = issues;
+                        self.show_validation = true;
+                    }
+                    Message::Delete(version, idx, mode) => {
+                        if let Some(doc) = self.activate_version(version) {
+                            if let Err(e) = doc.edit(|aiprog| aiprog.delete_with_mode(idx, mode)) {
+                                self.show_error(e);
+                            } else {
+                                doc.selected_ai = idx.saturating_sub(1);
+                                doc.multi_selected.clear();
+                                self.update_tree();
+                            }
+                        }
+                    }
+                    Message::DeleteEntries(version, indexes) => {
+                        if let Some(doc) = self.activate_version(version) {
+                            if let Err(e) = doc.edit(|aiprog| aiprog.delete_entries(&indexes)) {
+                                self.show_error(e);
+                            } else {
+                                let first = indexes.iter().next().copied().unwrap_or_default();
+                                doc.selected_ai = first.saturating_sub(1);
+                                doc.multi_selected.clear();
+                                self.update_tree();
+                            }
+                        }
+                    }
+                    Message::ModConflicts(report) => {
+                        if let Some(inspector) = self.conflicts.as_mut() {
+                            inspector.report = Some(report);
+                        }
+                    }
+                    Message::Merge(merge) => {
+                        if let Some(wizard) = self.merge.as_mut() {
+                            wizard.merge = Some(merge);
+                        }
+                    }
+                    Message::Normalized(version, aiprog, moved) => {
+                        tracing::info!("Normalizing the order moved {} entries", moved);
+                        let doc = match moved {
+                            0 => None,
+                            _ => self.activate_version(version),
+                        };
+                        if let Some(doc) = doc {
+                            doc.restore(aiprog);
+                            self.update_tree();
+                        }
+                    }
+                    Message::CloseDocument(id) => self.close_document(id),
+                    Message::Script(version, aiprog, log) => {
+                        self.script_log.extend(log);
+                        if let Some(doc) = self.activate_version(version) {
+                            doc.restore(aiprog);
+                            self.update_tree();
+                        }
+                    }
+                    Message::Reference(file, aiprog, tree) => {
+                        self.reference = Some(Reference::new(file, aiprog, tree));
+                    }
+                    Message::Unused(version, unused)
+                        if self.activate_version(version).is_some() =>
+                    {
+                        self.unused = Some((version, unused));
+                        self.show_unused = true;
+                    }
+                    Message::MigrateClass(version, idx, class) => {
+                        if let Some(doc) = self.activate_version(version) {
+                            if idx < doc.aiprog.len() {
+                                match doc.edit(|aiprog| aiprog.migrate_class(idx, &class)) {
+                                    Ok(undefined) if !undefined.is_empty() => {
+                                        self.show_error(anyhow!(tr!(
+                                            "error-undefined-params",
+                                            class = class.as_str(),
+                                            params = undefined.join("\n")
+                                        )));
+                                    }
+                                    Ok(_) => (),
+                                    Err(e) => self.show_error(e),
+                                }
+                                self.update_tree();
+                            }
+                        }
+                    }
+                    Message::Exit => self.exit_confirmed = true,
+                    Message::RevertAll(version) => self.revert_all(version),
+                    Message::Reload => self.load_disk_version(true),
+                    Message::Saved(ids) => {
+                        tracing::info!("Saved {} file(s)", ids.len());
+                        self.update_unsaved();
+                        for doc in self.docs.iter_mut().filter(|doc| ids.contains(&doc.id)) {
+                            doc.disk_time = file_time(&doc.file);
+                            doc.disk_change = None;
+                            doc.disk_prog = None;
+                        }
+                        if self.session.deploy.on_save || self.deploy_next_save {
+                            self.deploy_next_save = false;
+                            let files = self
+                                .docs
+                                .iter()
+                                .filter(|doc| ids.contains(&doc.id))
+                                .map(|doc| doc.file.clone())
+                                .collect();
+                            self.deploy(files);
+                        }
+                    }
+                    Message::Deployed(files) => self.deployed = files,
+                    Message::Aamp(editor) => self.aamp = Some(editor),
+                    Message::CloseAamp => self.aamp = None,
+                    Message::Vanilla(index) => self.vanilla = Some(index),
+                    Message::Flags(flags) => self.flags = Some(flags),
+                    Message::Convert(version, idx, class) => {
+                        if let Some(doc) = self.activate_version(version) {
+                            match doc.edit(|aiprog| aiprog.convert_entry(idx, &class)) {
+                                Ok((new, undefined)) => {
+                                    doc.navigate(new);
+                                    if !undefined.is_empty() {
+                                        self.show_error(anyhow!(tr!(
+                                            "error-undefined-params",
+                                            class = class.as_str(),
+                                            params = undefined.join("\n")
+                                        )));
+                                    }
+                                    self.update_tree();
+                                }
+                                Err(e) => self.show_error(e),
+                            }
+                        }
+                    }
+                    Message::RestoreBackup(id, aiprog) => {
+                        if let Some(doc) = self.activate(id) {
+                            doc.restore(aiprog);
+                            self.update_tree();
+                        }
+                    }
+                    Message::Repairable(file, error) => self.show_confirm(
+                        &tr!(
+                            "confirm-open-repaired",
+                            file = file.display().to_string(),
+                            error = error
+                        ),
+                        Message::OpenRepaired(file),
+                    ),
+                    Message::OpenRepaired(file) => self.start_task(move || {
+                        AIProgram::new_permissive(&file).map(|(aiprog, repairs)| {
+                            Message::Repaired(file.clone(), aiprog, repairs)
+                        })
+                    }),
+                    Message::Repaired(file, aiprog, repairs) => {
+                        self.show_error(anyhow!(tr!(
+                            "error-repaired",
+                            file = file.display().to_string(),
+                            repairs = repairs.join("\n")
+                        )));
+                        self.messengers
+                            .0
+                            .send(Ok(Message::AIProgram(file, aiprog)))
+                            .unwrap();
+                    }
+                    Message::Project(project) => {
+                        self.session.project = Some(project.root.clone());
+                        self.project = Some(project);
+                        self.project_issues = None;
+                    }
+                    Message::ProjectValidation(issues) => self.project_issues = Some(issues),
+                    Message::SaveProject => self.save_project(),
+                    Message::Reloaded(id, aiprog) => {
+                        if let Some(doc) = self.docs.iter_mut().find(|doc| doc.id == id) {
+                            doc.disk_time = file_time(&doc.file);
+                            doc.disk_change = None;
+                            doc.disk_prog = None;
+                            doc.init_prog = aiprog.clone();
+                            doc.restore(aiprog);
+                            self.update_tree();
+                        }
+                    }
+                    Message::DiskVersion(id, aiprog) => {
+                        if let Some(doc) = self.docs.iter_mut().find(|doc| doc.id == id) {
+                            doc.disk_prog = Some(aiprog);
+                        }
+                    }
+                    Message::Snippet(version, parent, snippet) => {
+                        self.import_snippet(version, parent, snippet)
+                    }
+                    Message::RemapSource(file, aiprog) => {
+                        self.remap_source = Some((file, aiprog));
+                    }
+                    Message::PortSource(file, aiprog) => {
+                        self.port = Some(PortWizard::new(file, aiprog));
+                    }
+                    Message::Extracted(name, data) => {
+                        if let Some(file) = FileDialog::new()
+                            .set_file_name(&name)
+                            .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+                            .save_file()
+                        {
+                            match std::fs::write(&file, data) {
+                                Ok(()) => self.open_file(file),
+                                Err(e) => self.show_error(e.into()),
+                            }
+                        }
+                    }
+                    Message::Recovered(recovered) => {
+                        for (file, aiprog, saved) in recovered {
+                            let mut doc = Document::new(self.next_doc_id, file, aiprog, 0);
+                            if let Some(saved) = saved {
+                                doc.init_prog = saved;
+                            }
+                            self.docs.push(doc);
+                            self.next_doc_id += 1;
+                            self.switch_document(self.docs.len() - 1);
+                            self.update_tree();
+                        }
+                        if let Err(e) = autosave::discard_backups() {
+                            self.show_error(e);
+                        }
+                    }
+                    Message::RemoveUnused(version) => {
+                        if let Some(doc) = self.activate_version(version) {
+                            let mut aiprog = doc.aiprog.clone();
+                            match aiprog.remove_unused() {
+                                Ok(_) => {
+                                    doc.restore(aiprog);
+                                    self.unused = None;
+                                    self.update_tree();
+                                }
+                                Err(e) => self.show_error(e),
+                            }
+                        }
+                    }
+                    _ => (),
+                },
+                Err(e) => self.show_error(e),
+            }
+        }
+        if let Some(doc) = self.doc_mut() {
+            doc.sync_tab();
+        }
+        self.update_title(frame);
+    }
+
+    fn handle_history(&mut self, ctx: &egui::CtxRef) {
+        // Don't record halfway through a drag or while a text field is being edited
+        let editing = ctx.memory().focus().is_some() || ctx.input().pointer.any_down();
+        if !editing {
+            // Including those just switched away from
+            for doc in self.docs.iter_mut() {
+                doc.commit_editor();
+            }
+        }
+        let revisions: Vec<usize> = self.docs.iter().map(|doc| doc.history.revision()).collect();
+        if revisions != self.unsaved_revisions {
+            self.unsaved_revisions = revisions;
+            self.update_unsaved();
+        }
+        // Text fields handle their own undo while focused
+        if !ctx.wants_keyboard_input() {
+            let (undo, redo) = {
+                let input = ctx.input();
+                let command = input.modifiers.command;
+                (
+                    command && !input.modifiers.shift && input.key_pressed(Key::Z),
+                    command
+                        && (input.key_pressed(Key::Y)
+                            || (input.modifiers.shift && input.key_pressed(Key::Z))),
+                )
+            };
+            if undo {
+                self.undo();
+            } else if redo {
+                self.redo();
+            }
+        }
+    }
+
+    fn handle_shortcuts(&mut self, ctx: &egui::CtxRef) {
+        let (command, shift, alt) = {
+            let input = ctx.input();
+            (
+                input.modifiers.command,
+                input.modifiers.shift,
+                input.modifiers.alt,
+            )
+        };
+        let pressed = |key| ctx.input().key_pressed(key);
+        if command {
+            if pressed(Key::O) {
+                self.open_dialog();
+            } else if pressed(Key::S) && shift && CAN_PICK_FILES {
+                self.save_as();
+            } else if pressed(Key::S) {
+                self.save_file();
+            } else if pressed(Key::F) {
+                self.open_search();
+                ctx.memory().request_focus(egui::Id::new("search_text"));
+            } else if pressed(Key::H) && self.doc().is_some() {
+                self.open_find_replace(ctx);
+            } else if pressed(Key::D) {
+                self.duplicate_selected();
+            } else if pressed(Key::B) {
+                self.deploy_active();
+            } else if pressed(Key::ArrowUp) {
+                self.select_parent();
+            }
+        }
+        // Leave plain keys alone while a text field has focus
+        if ctx.wants_keyboard_input() || command {
+            return;
+        }
+        if pressed(Key::Delete) && matches!(self.doc(), Some(doc) if !doc.show_demos) {
+            self.confirm_delete();
+        }
+        let step: isize = if pressed(Key::ArrowDown) {
+            1
+        } else if pressed(Key::ArrowUp) {
+            -1
+        } else {
+            0
+        };
+        if alt && pressed(Key::ArrowLeft) {
+            if let Some(doc) = self.doc_mut() {
+                doc.go_back();
+            }
+        } else if alt && pressed(Key::ArrowRight) {
+            if let Some(doc) = self.doc_mut() {
+                doc.go_forward();
+            }
+        }
+        if step != 0 && alt {
+            self.move_selected(step);
+        } else if step != 0 {
+            if let Some(doc) = self.doc_mut() {
+                let category = doc.aiprog.category_of(doc.selected_ai);
+                let start = doc.aiprog.category_offset(category);
+                let end = match category {
+                    Category::AI => doc.aiprog.actions_offset(),
+                    Category::Action => doc.aiprog.behaviors_offset(),
+                    Category::Behaviour => doc.aiprog.queries_offset(),
+                    Category::Query => doc.aiprog.len(),
+                };
+                let next = doc.selected_ai as isize + step;
+                if next >= start as isize && next < end as isize {
+                    doc.selected_ai = next as usize;
+                }
+            }
+        }
+    }
+
+    /// Selects the first entry pointing at the selected one.
+    fn select_parent(&mut self) {
+        if let Some(doc) = self.doc_mut() {
+            if let Some(parent) = doc.aiprog.parents_of(doc.selected_ai).first() {
+                doc.navigate(*parent);
+            }
+        }
+    }
+
+    /// Shows each chain of entries leading down to the selected one, returning
+    /// any entry clicked on.
+    fn render_breadcrumbs(ui: &mut Ui, aiprog: &AIProgram, selected: usize) -> Option<usize> {
+        const MAX_PATHS: usize = 8;
+        let paths = aiprog.ancestor_paths(selected, MAX_PATHS);
+        let mut clicked = None;
+        for path in paths.iter().filter(|path| path.len() > 1) {
+            ui.horizontal_wrapped(|ui| {
+                ui.spacing_mut().item_spacing.x = 2.0;
+                for (i, idx) in path.iter().enumerate() {
+                    let name = aiprog
+                        .get_item(*idx)
+                        .and_then(AIProgram::entry_name)
+                        .unwrap_or_else(|_| aiprog.entry_label(*idx));
+                    if i + 1 == path.len() {
+                        ui.strong(name);
+                    } else {
+                        if ui
+                            .small_button(name)
+                            .on_hover_text(aiprog.entry_label(*idx))
+                            .clicked()
+                        {
+                            clicked = Some(*idx);
+                        }
+                        ui.label("›");
+                    }
+                }
+            });
+        }
+        if paths.len() >= MAX_PATHS {
+            ui.label(tr!("paths-truncated", count = MAX_PATHS));
+        }
+        clicked
+    }
+
+    /// Moves the selected entry up or down within its category.
+    fn move_selected(&mut self, step: isize) {
+        if let Some(doc) = self.doc_mut() {
+            let other = doc.selected_ai as isize + step;
+            if other < 0 {
+                return;
+            }
+            let selected = doc.selected_ai;
+            // Fails harmlessly at the edge of the category
+            if doc
+                .edit(|aiprog| aiprog.swap_entries(selected, other as usize))
+                .is_ok()
+            {
+                doc.selected_ai = other as usize;
+                self.update_tree();
+            }
+        }
+    }
+
+    fn duplicate_selected(&mut self) {
+        if let Some(doc) = self.doc_mut() {
+            let selected = doc.selected_ai;
+            match doc.edit(|aiprog| aiprog.duplicate_entry(selected)) {
+                Ok(i) => {
+                    doc.selected_ai = i;
+                    self.update_tree();
+                }
+                Err(e) => self.show_error(e),
+            }
+        }
+    }
+
+    fn revert_entry(&mut self, idx: usize) {
+        if let Some(doc) = self.doc_mut() {
+            let mut aiprog = doc.aiprog.clone();
+            match aiprog.revert_entry(&doc.init_prog, idx) {
+                Ok(()) => {
+                    doc.restore(aiprog);
+                    self.update_tree();
+                }
+                Err(e) => self.show_error(e),
+            }
+        }
+    }
+
+    fn import_csv(&mut self, file: &std::path::Path) {
+        if let Some(doc) = self.doc_mut() {
+            let mut aiprog = doc.aiprog.clone();
+            match aiprog.load_csv(file) {
+                Ok(_) => doc.restore(aiprog),
+                Err(e) => self.show_error(e),
+            }
+        }
+    }
+
+    fn revert_selected(&mut self) {
+        if let Some(idx) = self.doc().map(|doc| doc.selected_ai) {
+            self.revert_entry(idx);
+        }
+    }
+
+    fn revert_all(&mut self, version: DocVersion) {
+        if let Some(doc) = self.activate_version(version) {
+            doc.restore(doc.init_prog.clone());
+            self.update_tree();
+        }
+    }
+
+    fn undo(&mut self) {
+        if self.doc_mut().map(|doc| doc.undo()).unwrap_or(false) {
+            self.update_tree();
+        }
+    }
+
+    fn redo(&mut self) {
+        if self.doc_mut().map(|doc| doc.redo()).unwrap_or(false) {
+            self.update_tree();
+        }
+    }
+
+    fn render_menu(&mut self, ctx: &egui::CtxRef) {
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            menu::bar(ui, |ui| {
+                let has_doc = self.doc().is_some();
+                menu::menu(ui, tr!("menu-file"), |ui| {
+                    if ui
+                        .button(tr!("file-open"))
+                        .on_hover_text("Ctrl+O")
+                        .clicked()
+                    {
+                        self.open_dialog();
+                    }
+                    if ui.button(tr!("file-open-dump")).clicked() {
+                        self.show_dump = true;
+                        if let Some(dump) = &self.settings.game_dump {
+                            self.dump_actors = dump_actor_names(dump);
+                        }
+                    }
+                    let pack = self
+                        .doc()
+                        .filter(|doc| is_actor_pack(&doc.file))
+                        .map(|doc| doc.file.clone());
+                    if ui
+                        .button(tr!("file-open-clipboard"))
+                        .on_hover_text(tr!("file-open-clipboard-hover"))
+                        .clicked()
+                    {
+                        self.open_clipboard();
+                    }
+                    if dialog_button(ui, true, egui::Button::new(tr!("file-open-as-list")))
+                        .on_hover_text(tr!("file-open-as-list-hover"))
+                        .clicked()
+                    {
+                        self.open_aamp_dialog();
+                    }
+                    if ui
+                        .add_enabled(
+                            pack.is_some(),
+                            egui::Button::new(tr!("file-open-as-list-pack")),
+                        )
+                        .on_hover_text(tr!("file-open-as-list-pack-hover"))
+                        .clicked()
+                    {
+                        self.open_aamp(pack.unwrap());
+                    }
+                    if dialog_button(ui, true, egui::Button::new(tr!("file-open-project")))
+                        .on_hover_text(tr!("file-open-project-hover"))
+                        .clicked()
+                    {
+                        if let Some(root) = FileDialog::new().pick_folder() {
+                            self.open_project(root);
+                        }
+                    }
+                    if ui
+                        .add_enabled(
+                            self.project.is_some(),
+                            egui::Button::new(tr!("file-close-project")),
+                        )
+                        .clicked()
+                    {
+                        self.close_project();
+                    }
+                    ui.separator();
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("file-save")))
+                        .on_hover_text("Ctrl+S")
+                        .clicked()
+                    {
+                        self.save_file();
+                    }
+                    if dialog_button(ui, has_doc, egui::Button::new(tr!("file-save-as")))
+                        .on_hover_text("Ctrl+Shift+S")
+                        .clicked()
+                    {
+                        self.save_as();
+                    }
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("file-copy-yaml")))
+                        .on_hover_text(tr!("file-copy-yaml-hover"))
+                        .clicked()
+                    {
+                        ui.output().copied_text = self.doc().unwrap().aiprog.0.to_text();
+                    }
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("file-close")))
+                        .clicked()
+                    {
+                        self.request_close(self.active);
+                    }
+                    let dirty = self.doc().map(|doc| doc.is_dirty()).unwrap_or(false);
+                    if ui
+                        .add_enabled(dirty, egui::Button::new(tr!("file-revert-all")))
+                        .on_hover_text(tr!("file-revert-all-hover"))
+                        .clicked()
+                    {
+                        let doc = self.doc_mut().unwrap();
+                        // So what was typed into the entry before this counts
+                        doc.commit_editor();
+                        let message = Message::RevertAll(doc.version());
+                        self.show_confirm(&tr!("file-revert-all-confirm"), message);
+                    }
+                    if let Some(doc) = self.doc_mut() {
+                        ui.checkbox(&mut doc.compress, tr!("file-compress"));
+                    }
+                    ui.separator();
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("file-deploy")))
+                        .on_hover_text("Ctrl+B")
+                        .clicked()
+                    {
+                        self.deploy_active();
+                    }
+                    if ui.button(tr!("file-deploy-settings")).clicked() {
+                        self.show_deploy = true;
+                    }
+                    ui.separator();
+                    if dialog_button(ui, true, egui::Button::new(tr!("file-open-reference")))
+                        .clicked()
+                    {
+                        if let Some(file) = FileDialog::new()
+                            .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+                            .add_filter("BOTW YAML AI Program", &["yml"])
+                            .add_filter("BOTW Actor Pack", &["sbactorpack", "bactorpack"])
+                            .pick_file()
+                        {
+                            self.run_task(&tr!("task-opening-reference"), move |progress| {
+                                let aiprog = AIProgram::new(&file)?;
+                                let (mut tree, orphans) =
+                                    Tree::from_program(&aiprog, &BTreeSet::new(), progress)?;
+                                tree.extend(orphans);
+                                Ok(Message::Reference(file.clone(), aiprog, tree))
+                            });
+                        }
+                    }
+                    if ui
+                        .add_enabled(
+                            self.reference.is_some(),
+                            egui::Button::new(tr!("file-close-reference")),
+                        )
+                        .clicked()
+                    {
+                        self.reference = None;
+                    }
+                    ui.separator();
+                    if dialog_button(ui, has_doc, egui::Button::new(tr!("file-export-patch")))
+                        .clicked()
+                    {
+                        if let Some(base) = FileDialog::new()
+                            .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+                            .add_filter("BOTW YAML AI Program", &["yml"])
+                            .add_filter("BOTW Actor Pack", &["sbactorpack", "bactorpack"])
+                            .pick_file()
+                        {
+                            if let Some(file) = FileDialog::new()
+                                .add_filter("YAML AAMP Patch", &["yml"])
+                                .add_filter("Binary AAMP Patch", &["aamp"])
+                                .save_file()
+                            {
+                                let aiprog = self.doc().unwrap().aiprog.clone();
+                                self.start_task(move || {
+                                    let base = AIProgram::new(&base)?;
+                                    aiprog.save_patch(&base, &file).map(|_| Message::Null)
+                                });
+                            }
+                        }
+                    }
+                    if dialog_button(ui, has_doc, egui::Button::new(tr!("file-apply-patch")))
+                        .clicked()
+                    {
+                        if let Some(file) = FileDialog::new()
+                            .add_filter("YAML AAMP Patch", &["yml"])
+                            .add_filter("Binary AAMP Patch", &["aamp"])
+                            .pick_file()
+                        {
+                            let id = self.doc().unwrap().id;
+                            self.start_task(move || {
+                                load_patch(&file).map(|patch| Message::Patch(id, patch))
+                            });
+                        }
+                    }
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("file-merge")))
+                        .on_hover_text(tr!("file-merge-hover"))
+                        .clicked()
+                    {
+                        self.merge = Some(MergeWizard::default());
+                    }
+                    if dialog_button(ui, has_doc, egui::Button::new(tr!("file-export-json")))
+                        .clicked()
+                    {
+                        if let Some(file) = FileDialog::new()
+                            .add_filter("JSON AI Program", &["json"])
+                            .save_file()
+                        {
+                            let aiprog = self.doc().unwrap().aiprog.clone();
+                            self.start_task(move || aiprog.save_json(&file).map(|_| Message::Null));
+                        }
+                    }
+                    if dialog_button(
+                        ui,
+                        has_doc,
+                        egui::Button::new(tr!("file-export-json-schema")),
+                    )
+                    .on_hover_text(tr!("file-export-json-schema-hover", file = SCHEMA_FILE))
+                    .clicked()
+                    {
+                        if let Some(file) = FileDialog::new()
+                            .add_filter("JSON AI Program", &["json"])
+                            .save_file()
+                        {
+                            let aiprog = self.doc().unwrap().aiprog.clone();
+                            self.start_task(move || {
+                                aiprog.save_json_with_schema(&file).map(|_| Message::Null)
+                            });
+                        }
+                    }
+                    if dialog_button(ui, has_doc, egui::Button::new(tr!("file-export-csv")))
+                        .on_hover_text(tr!("file-export-csv-hover"))
+                        .clicked()
+                    {
+                        if let Some(file) =
+                            FileDialog::new().add_filter("CSV", &["csv"]).save_file()
+                        {
+                            let aiprog = self.doc().unwrap().aiprog.clone();
+                            self.start_task(move || aiprog.save_csv(&file).map(|_| Message::Null));
+                        }
+                    }
+                    if dialog_button(ui, has_doc, egui::Button::new(tr!("file-import-csv")))
+                        .on_hover_text(tr!("file-import-csv-hover"))
+                        .clicked()
+                    {
+                        if let Some(file) =
+                            FileDialog::new().add_filter("CSV", &["csv"]).pick_file()
+                        {
+                            self.import_csv(&file);
+                        }
+                    }
+                    if dialog_button(ui, has_doc, egui::Button::new(tr!("file-export-graph")))
+                        .clicked()
+                    {
+                        if let Some(file) = FileDialog::new()
+                            .add_filter("Graphviz DOT", &["dot", "gv"])
+                            .add_filter("Mermaid", &["mmd", "md"])
+                            .save_file()
+                        {
+                            let aiprog = self.doc().unwrap().aiprog.clone();
+                            self.start_task(move || {
+                                aiprog.save_graph(&file).map(|_| Message::Null)
+                            });
+                        }
+                    }
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("file-restore")))
+                        .on_hover_text(tr!("file-restore-hover"))
+                        .clicked()
+                    {
+                        let doc = self.doc().unwrap();
+                        self.backups = Some((doc.id, list_backups(&doc.file)));
+                    }
+                    if dialog_button(
+                        ui,
+                        has_doc,
+                        egui::Button::new(tr!("file-export-description")),
+                    )
+                    .on_hover_text(tr!("file-export-description-hover"))
+                    .clicked()
+                    {
+                        let doc = self.doc().unwrap();
+                        if let Some(file) = FileDialog::new()
+                            .set_file_name(&format!(
+                                "{}.md",
+                                doc.file.file_stem().unwrap_or_default().to_string_lossy()
+                            ))
+                            .add_filter("Markdown", &["md"])
+                            .add_filter("HTML", &["html", "htm"])
+                            .save_file()
+                        {
+                            let aiprog = doc.aiprog.clone();
+                            let title = doc.name();
+                            self.start_task(move || {
+                                aiprog
+                                    .save_description(&file, &title)
+                                    .map(|_| Message::Null)
+                            });
+                        }
+                    }
+                    ui.separator();
+                    let mut open = None;
+                    for file in &self.session.recent_files {
+                        if ui
+                            .button(file.file_name().unwrap_or_default().to_string_lossy())
+                            .on_hover_text(file.to_string_lossy())
+                            .clicked()
+                        {
+                            open = Some(file.clone());
+                        }
+                    }
+                    if let Some(file) = open {
+                        self.open_file(file);
+                    }
+                    ui.checkbox(&mut self.session.reopen_last, tr!("file-reopen-last"));
+                    if ui.button(tr!("file-settings")).clicked() {
+                        self.show_settings = true;
+                    }
+                    ui.separator();
+                    if ui.button(tr!("file-exit")).clicked() {
+                        self.request_exit();
+                    }
+                });
+                menu::menu(ui, tr!("menu-edit"), |ui| {
+                    let (can_undo, can_redo) = self
+                        .doc()
+                        .map(|doc| (doc.history.can_undo(), doc.history.can_redo()))
+                        .unwrap_or_default();
+                    if ui
+                        .add_enabled(can_undo, egui::Button::new(tr!("edit-undo")))
+                        .clicked()
+                    {
+                        self.undo();
+                    }
+                    if ui
+                        .add_enabled(can_redo, egui::Button::new(tr!("edit-redo")))
+                        .clicked()
+                    {
+                        self.redo();
+                    }
+                    let modified = self
+                        .doc()
+                        .map(|doc| doc.changed.contains(&doc.selected_ai))
+                        .unwrap_or(false);
+                    if ui
+                        .add_enabled(modified, egui::Button::new(tr!("edit-revert-entry")))
+                        .on_hover_text(tr!("edit-revert-entry-hover"))
+                        .clicked()
+                    {
+                        self.revert_selected();
+                    }
+                    ui.separator();
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("edit-go-to-parent")))
+                        .on_hover_text("Ctrl+Up")
+                        .clicked()
+                    {
+                        self.select_parent();
+                    }
+                    let (can_back, can_forward) = self
+                        .doc()
+                        .map(|doc| (!doc.back.is_empty(), !doc.forward.is_empty()))
+                        .unwrap_or_default();
+                    if ui
+                        .add_enabled(can_back, egui::Button::new(tr!("edit-back")))
+                        .on_hover_text("Alt+Left")
+                        .clicked()
+                    {
+                        self.doc_mut().unwrap().go_back();
+                    }
+                    if ui
+                        .add_enabled(can_forward, egui::Button::new(tr!("edit-forward")))
+                        .on_hover_text("Alt+Right")
+                        .clicked()
+                    {
+                        self.doc_mut().unwrap().go_forward();
+                    }
+                    let multi = self
+                        .doc()
+                        .map(|doc| !doc.multi_selected.is_empty())
+                        .unwrap_or(false);
+                    if ui
+                        .add_enabled(multi, egui::Button::new(tr!("edit-set-parameter")))
+                        .on_hover_text(tr!("edit-set-parameter-hover"))
+                        .clicked()
+                    {
+                        self.open_bulk();
+                    }
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("edit-remap")))
+                        .on_hover_text(tr!("edit-remap-hover"))
+                        .clicked()
+                    {
+                        self.open_remap();
+                    }
+                    ui.separator();
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("edit-normalize")))
+                        .on_hover_text(tr!("edit-normalize-hover"))
+                        .clicked()
+                    {
+                        self.normalize_order(None);
+                    }
+                    if dialog_button(
+                        ui,
+                        has_doc,
+                        egui::Button::new(tr!("edit-normalize-vanilla")),
+                    )
+                    .on_hover_text(tr!("edit-normalize-vanilla-hover"))
+                    .clicked()
+                    {
+                        if let Some(base) = FileDialog::new()
+                            .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+                            .add_filter("BOTW YAML AI Program", &["yml"])
+                            .add_filter("BOTW Actor Pack", &["sbactorpack", "bactorpack"])
+                            .pick_file()
+                        {
+                            self.normalize_order(Some(base));
+                        }
+                    }
+                });
+                menu::menu(ui, tr!("menu-tools"), |ui| {
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("tools-search")))
+                        .clicked()
+                    {
+                        self.open_search();
+                    }
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("tools-find-replace")))
+                        .on_hover_text("Ctrl+H")
+                        .clicked()
+                    {
+                        self.open_find_replace(ui.ctx());
+                    }
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("tools-dry-run")))
+                        .on_hover_text(tr!("tools-dry-run-hover"))
+                        .clicked()
+                    {
+                        self.dry_run = Some(DryRun::new(&self.doc().unwrap().aiprog));
+                    }
+                    if ui
+                        .button(tr!("tools-conflicts"))
+                        .on_hover_text(tr!("tools-conflicts-hover"))
+                        .clicked()
+                    {
+                        self.conflicts = Some(ConflictInspector::default());
+                    }
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("tools-validate")))
+                        .clicked()
+                    {
+                        let doc = self.doc_mut().unwrap();
+                        // So what was typed into the entry before this counts
+                        doc.commit_editor();
+                        let (version, aiprog) = (doc.version(), doc.aiprog.clone());
+                        let as_names = doc.as_names.clone();
+                        let actor_link = doc.actor_link.clone();
+                        let flags = self.flags.clone();
+                        self.start_task(move || {
+                            let mut issues = aiprog.validate_all();
+                            if let Some(as_names) = as_names {
+                                issues.extend(aiprog.validate_animations(&as_names));
+                            }
+                            if let Some(flags) = flags {
+                                issues.extend(aiprog.validate_flags(&flags));
+                            }
+                            if let Some(link) = actor_link {
+                                issues.extend(aiprog.validate_links(&link));
+                            }
+                            Ok(Message::Validation(version, issues))
+                        });
+                    }
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("tools-fix-group-names")))
+                        .on_hover_text(tr!("tools-fix-group-names-hover"))
+                        .clicked()
+                    {
+                        self.fix_group_names();
+                    }
+                    if ui.button(tr!("tools-script-console")).clicked() {
+                        self.show_script = true;
+                    }
+                    if ui
+                        .add_enabled(has_doc, egui::Button::new(tr!("tools-find-unused")))
+                        .clicked()
+                    {
+                        let doc = self.doc_mut().unwrap();
+                        // So what was typed into the entry before this counts
+                        doc.commit_editor();
+                        let (version, aiprog) = (doc.version(), doc.aiprog.clone());
+                        self.start_task(move || {
+                            Ok(Message::Unused(version, aiprog.unused_entries()))
+                        });
+                    }
+                    if dialog_button(ui, has_doc, egui::Button::new(tr!("tools-port")))
+                        .on_hover_text(tr!("tools-port-hover"))
+                        .clicked()
+                    {
+                        self.open_port();
+                    }
+                    if ui
+                        .button(tr!("tools-hash-names"))
+                        .on_hover_text(tr!("tools-hash-names-hover"))
+                        .clicked()
+                    {
+                        self.open_hashes();
+                    }
+                    if ui
+                        .button(tr!("tools-translations"))
+                        .on_hover_text(tr!("tools-translations-hover"))
+                        .clicked()
+                    {
+                        self.open_translations();
+                    }
+                    if ui
+                        .button(tr!("tools-show-log"))
+                        .on_hover_text(tr!("tools-show-log-hover"))
+                        .clicked()
+                    {
+                        self.show_log = true;
+                    }
+                });
+                if !self.plugins.is_empty() {
+                    menu::menu(ui, tr!("menu-plugins"), |ui| {
+                        self.render_plugin_menu(ui, has_doc)
+                    });
+                }
+            });
+        });
+    }
+
+    /// Lists the commands and formats of each plugin.
+    fn render_plugin_menu(&mut self, ui: &mut Ui, has_doc: bool) {
+        let mut chosen = None;
+        for (i, plugin) in self.plugins.iter().enumerate() {
+            if i > 0 {
+                ui.separator();
+            }
+            ui.label(plugin.name());
+            for (command, label) in plugin.commands().iter().enumerate() {
+                if ui.add_enabled(has_doc, egui::Button::new(label)).clicked() {
+                    chosen = Some((i, PluginAction::Command(command)));
+                }
+            }
+            for (format, info) in plugin.formats().iter().enumerate() {
+                if info.can_import
+                    && dialog_button(
+                        ui,
+                        true,
+                        egui::Button::new(tr!("plugin-import", format = info.name)),
+                    )
+                    .clicked()
+                {
+                    chosen = Some((i, PluginAction::Import(format)));
+                }
+                if info.can_export
+                    && dialog_button(
+                        ui,
+                        has_doc,
+                        egui::Button::new(tr!("plugin-export", format = info.name)),
+                    )
+                    .clicked()
+                {
+                    chosen = Some((i, PluginAction::Export(format)));
+                }
+            }
+        }
+        if let Some((i, action)) = chosen {
+            self.run_plugin(i, action);
+        }
+    }
+
+    fn run_plugin(&mut self, i: usize, action: PluginAction) {
+        let plugin = &mut self.plugins[i];
+        let result = match action {
+            PluginAction::Command(command) => match self.docs.get_mut(self.active) {
+                Some(doc) => {
+                    let mut aiprog = doc.aiprog.clone();
+                    plugin
+                        .run_command(command, &mut aiprog, doc.selected_ai)
+                        .map(|_| {
+                            doc.restore(aiprog);
+                            true
+                        })
+                }
+                None => Ok(false),
+            },
+            PluginAction::Import(format) => {
+                let info = &plugin.formats()[format];
+                match FileDialog::new()
+                    .add_filter(info.name, info.extensions)
+                    .pick_file()
+                {
+                    Some(file) => match plugin.import(format, &file) {
+                        Ok(aiprog) => {
+                            let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+                            self.save_new_program(aiprog, &stem);
+                            Ok(false)
+                        }
+                        Err(e) => Err(e),
+                    },
+                    None => Ok(false),
+                }
+            }
+            PluginAction::Export(format) => {
+                let info = &plugin.formats()[format];
+                match (
+                    self.docs.get(self.active),
+                    FileDialog::new()
+                        .add_filter(info.name, info.extensions)
+                        .save_file(),
+                ) {
+                    (Some(doc), Some(file)) => {
+                        plugin.export(format, &doc.aiprog, &file).map(|_| false)
+                    }
+                    _ => Ok(false),
+                }
+            }
+        };
+        match result {
+            Ok(true) => self.update_tree(),
+            Ok(false) => (),
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// Draws the editor sections plugins add, returning whether the tree
+    /// needs rebuilding.
+    fn render_plugin_panels(&mut self, ui: &mut Ui) -> bool {
+        let doc = match self.docs.get_mut(self.active) {
+            Some(doc) => doc,
+            None => return false,
+        };
+        let mut update_tree = false;
+        for plugin in self.plugins.iter_mut() {
+            if let Some(title) = plugin.panel_title().map(|t| t.to_owned()) {
+                egui::CollapsingHeader::new(title).show(ui, |ui| {
+                    update_tree |= plugin.panel_ui(ui, &mut doc.aiprog, doc.selected_ai);
+                });
+            }
+        }
+        update_tree
+    }
+
+    fn fix_group_names(&mut self) {
+        if let Some(doc) = self.doc_mut() {
+            let mut aiprog = doc.aiprog.clone();
+            match aiprog.fix_group_names() {
+                Ok(_) => {
+                    doc.restore(aiprog);
+                    self.validation.clear();
+                    self.update_tree();
+                }
+                Err(e) => self.show_error(e),
+            }
+        }
+    }
+
+    fn render_documents(&mut self, ctx: &egui::CtxRef) {
+        if self.docs.is_empty() {
+            return;
+        }
+        let mut switch = None;
+        let mut close = None;
+        egui::TopBottomPanel::top("document_tabs").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (i, doc) in self.docs.iter().enumerate() {
+                    let label = format!("{}{}", if doc.is_dirty() { "*" } else { "" }, doc.name());
+                    if ui
+                        .selectable_label(i == self.active, label)
+                        .on_hover_text(doc.file.to_string_lossy())
+                        .clicked()
+                    {
+                        switch = Some(i);
+                    }
+                    if ui
+                        .small_button("×")
+                        .on_hover_text(tr!("file-close"))
+                        .clicked()
+                    {
+                        close = Some(i);
+                    }
+                    ui.separator();
+                }
+            });
+        });
+        let (mut reload, mut keep, mut diff) = (false, false, false);
+        if let Some(doc) = self.doc().filter(|doc| doc.disk_change.is_some()) {
+            egui::TopBottomPanel::top("disk_change").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(warning_color(), tr!("disk-changed", file = doc.name()));
+                    reload = ui
+                        .small_button(tr!("disk-reload"))
+                        .on_hover_text(tr!("disk-reload-hover"))
+                        .clicked();
+                    keep = ui
+                        .small_button(tr!("disk-keep-mine"))
+                        .on_hover_text(tr!("disk-keep-mine-hover"))
+                        .clicked();
+                    diff = ui
+                        .small_button(tr!("disk-diff"))
+                        .on_hover_text(tr!("disk-diff-hover"))
+                        .clicked();
+                });
+            });
+        }
+        if let Some(i) = switch {
+            self.switch_document(i);
+        }
+        if let Some(i) = close {
+            self.request_close(i);
+        }
+        if reload {
+            self.reload_from_disk();
+        } else if keep {
+            self.doc_mut().unwrap().keep_mine();
+        } else if diff {
+            self.load_disk_version(false);
+        }
+    }
+
+    fn reload_from_disk(&mut self) {
+        if matches!(self.doc(), Some(doc) if doc.is_dirty()) {
+            self.show_confirm(&tr!("confirm-reload"), Message::Reload);
+        } else {
+            self.load_disk_version(true);
+        }
+    }
+
+    fn render_disk_diff(&mut self, ctx: &egui::CtxRef) {
+        let doc = match self.doc() {
+            Some(doc) => doc,
+            None => return,
+        };
+        let disk = match &doc.disk_prog {
+            Some(disk) => disk,
+            None => return,
+        };
+        let aiprog = &doc.aiprog;
+        let mut show = true;
+        let (mut reload, mut keep) = (false, false);
+        let mut goto = None;
+        egui::Window::new(tr!("window-changes-on-disk", file = doc.name()))
+            .open(&mut show)
+            .default_width(400.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                let changed = aiprog.changed_entries(disk);
+                let disk_only: Vec<usize> = (0..disk.len())
+                    .filter(|i| disk.base_index(aiprog, *i).is_none())
+                    .collect();
+                if changed.is_empty() && disk_only.is_empty() {
+                    ui.label(tr!("disk-same"));
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("disk_diff")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for idx in &changed {
+                                    if ui.small_button(aiprog.entry_label(*idx)).clicked() {
+                                        goto = Some(*idx);
+                                    }
+                                    ui.label(match aiprog.base_index(disk, *idx) {
+                                        Some(_) => tr!("disk-differs"),
+                                        None => tr!("disk-not-on-disk"),
+                                    });
+                                    ui.end_row();
+                                }
+                                for idx in &disk_only {
+                                    ui.label(disk.entry_label(*idx));
+                                    ui.label(tr!("disk-only-on-disk"));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.horizontal(|ui| {
+                    reload = ui.button(tr!("disk-reload")).clicked();
+                    keep = ui.button(tr!("disk-keep-mine")).clicked();
+                });
+            });
+        if let Some(idx) = goto {
+            self.doc_mut().unwrap().navigate(idx);
+        }
+        if reload {
+            self.doc_mut().unwrap().disk_prog = None;
+            self.reload_from_disk();
+        } else if keep {
+            self.doc_mut().unwrap().keep_mine();
+        } else if !show {
+            self.doc_mut().unwrap().disk_prog = None;
+        }
+    }
+
+    /// Closes a document, asking for confirmation first if it has unsaved
+    /// changes.
+    /// Closes the app, first asking what to do with any unsaved changes.
+    fn request_exit(&mut self) {
+        if self.docs.iter().any(|doc| doc.is_dirty()) {
+            self.show_exit = true;
+        } else {
+            self.exit_confirmed = true;
+        }
+    }
+
+    /// Saves every document with unsaved changes and then closes the app.
+    fn save_all_and_exit(&mut self) {
+        let dirty: Vec<(PathBuf, bool, AIProgram)> = self
+            .docs
+            .iter_mut()
+            .filter(|doc| doc.is_dirty())
+            .map(|doc| {
+                doc.init_prog = doc.aiprog.clone();
+                (doc.file.clone(), doc.compress, doc.aiprog.clone())
+            })
+            .collect();
+        self.start_task(move || {
+            for (file, compress, aiprog) in &dirty {
+                aiprog.save(file, *compress)?;
+            }
+            Ok(Message::Exit)
+        });
+    }
+
+    fn request_close(&mut self, index: usize) {
+        if let Some(doc) = self.docs.get(index) {
+            let id = doc.id;
+            if doc.is_dirty() {
+                let text = tr!("confirm-close", file = doc.name());
+                self.show_confirm(&text, Message::CloseDocument(id));
+            } else {
+                self.close_document(id);
+            }
+        }
+    }
+
+    fn open_aamp_dialog(&mut self) {
+        if let Some(file) = FileDialog::new()
+            .add_filter("BOTW AS List", &["baslist"])
+            .add_filter("BOTW Actor Pack", &["sbactorpack", "bactorpack"])
+            .add_filter("BOTW YAML AAMP", &["yml"])
+            .pick_file()
+        {
+            self.open_aamp(file);
+        }
+    }
+
+    fn open_aamp(&mut self, file: PathBuf) {
+        if self.aamp.as_ref().map(|e| e.is_dirty()).unwrap_or(false) {
+            self.show_error(anyhow!(tr!("error-aamp-open")));
+            return;
+        }
+        self.start_task(move || Ok(Message::Aamp(AampEditor::open(&file)?)));
+    }
+
+    fn render_aamp(&mut self, ctx: &egui::CtxRef) {
+        let editor = match self.aamp.as_mut() {
+            Some(editor) => editor,
+            None => return,
+        };
+        let mut show = true;
+        let mut save = false;
+        egui::Window::new(format!(
+            "{}{}",
+            if editor.is_dirty() { "*" } else { "" },
+            editor.name()
+        ))
+        .id(egui::Id::new("aamp_editor"))
+        .open(&mut show)
+        .default_width(450.0)
+        .show(ctx, |ui| {
+            editor.ui(ui);
+            ui.horizontal(|ui| {
+                save = ui
+                    .add_enabled(editor.is_dirty(), egui::Button::new(tr!("file-save")))
+                    .clicked();
+            });
+        });
+        if save {
+            if let Err(e) = editor.save() {
+                self.show_error(e);
+            }
+        } else if !show {
+            if editor.is_dirty() {
+                self.show_confirm(&tr!("confirm-close-aamp"), Message::CloseAamp);
+            } else {
+                self.aamp = None;
+            }
+        }
+    }
+
+    fn render_project_panel(&mut self, ctx: &egui::CtxRef) {
+        let mut action = None;
+        if let Some(project) = self.project.as_mut() {
+            let open: HashMap<PathBuf, bool> = self
+                .docs
+                .iter()
+                .map(|doc| (doc.file.clone(), doc.is_dirty()))
+                .collect();
+            let active = self.docs.get(self.active).map(|doc| doc.file.as_path());
+            egui::SidePanel::left("project_panel")
+                .default_width(180.0)
+                .resizable(true)
+                .show(ctx, |ui| {
+                    action = project.ui(ui, &open, active);
+                });
+        }
+        match action {
+            Some(ProjectAction::Open(file)) => self.open_file(file),
+            Some(ProjectAction::Validate) => self.validate_project(),
+            Some(ProjectAction::SaveAll) => self.request_save_project(),
+            Some(ProjectAction::Refresh) => {
+                if let Some(project) = self.project.as_mut() {
+                    project.refresh();
+                }
+            }
+            Some(ProjectAction::Close) => self.close_project(),
+            None => (),
+        }
+    }
+
+    fn render_side_panel(&mut self, ctx: &egui::CtxRef) {
+        let mut dropped = None;
+        let mut open_bulk = false;
+        let mut delete_selected = false;
+        let mut compare = None;
+        let mut action = None;
+        let mut unpin = None;
+        egui::SidePanel::left("tree_panel")
+            .max_width(200.0)
+            .resizable(true)
+            .frame(Frame {
+                margin: Vec2::new(8.0, 2.0),
+                corner_radius: 0.0,
+                fill: ctx.style().visuals.extreme_bg_color,
+                stroke: ctx.style().visuals.window_stroke(),
+                ..Default::default()
+            })
+            .show(ctx, |ui| {
+                let mut highlighted: HashSet<usize> = if self.show_search {
+                    self.search_results.iter().map(|hit| hit.entry).collect()
+                } else {
+                    HashSet::new()
+                };
+                if let (Some(dry_run), Some(doc)) = (&self.dry_run, self.docs.get(self.active)) {
+                    highlighted.extend(dry_run.steps(&doc.aiprog).iter().map(|step| step.entry));
+                }
+                if let Some(doc) = self.docs.get_mut(self.active) {
+                    doc.drag.target = None;
+                    let pins = doc.pinned_entries();
+                    if !pins.is_empty() {
+                        egui::CollapsingHeader::new(tr!("section-pinned"))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for (key, idx) in &pins {
+                                    ui.horizontal(|ui| {
+                                        if ui
+                                            .small_button("×")
+                                            .on_hover_text(tr!("button-unpin"))
+                                            .clicked()
+                                        {
+                                            unpin = Some(key.clone());
+                                        }
+                                        match idx {
+                                            Some(idx) => {
+                                                if ui
+                                                    .selectable_label(
+                                                        doc.selected_ai == *idx,
+                                                        doc.aiprog.entry_label(*idx),
+                                                    )
+                                                    .clicked()
+                                                {
+                                                    doc.navigate(*idx);
+                                                }
+                                            }
+                                            None => {
+                                                ui.add(egui::Label::new(key).weak())
+                                                    .on_hover_text(tr!("pin-missing"));
+                                            }
+                                        }
+                                    });
+                                }
+                            });
+                        ui.separator();
+                    }
+                    let pinned: BTreeSet<usize> = pins.iter().filter_map(|(_, idx)| *idx).collect();
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut doc.show_table, false, tr!("side-tree"));
+                        ui.selectable_value(&mut doc.show_table, true, tr!("side-table"))
+                            .on_hover_text(tr!("side-table-hover"));
+                    });
+                    if doc.show_table {
+                        if let Some(idx) = doc.table.ui(
+                            ui,
+                            &doc.aiprog,
+                            doc.tab,
+                            doc.selected_ai,
+                            &mut doc.multi_selected,
+                        ) {
+                            doc.navigate(idx);
+                        }
+                    } else {
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut doc.filter.text)
+                                    .hint_text(tr!("hint-filter"))
+                                    .desired_width(100.0),
+                            );
+                            if ui
+                                .selectable_label(doc.filter.show_ais, tr!("tab-ais"))
+                                .clicked()
+                            {
+                                doc.filter.show_ais = !doc.filter.show_ais;
+                            }
+                            if ui
+                                .selectable_label(doc.filter.show_actions, tr!("tab-actions"))
+                                .clicked()
+                            {
+                                doc.filter.show_actions = !doc.filter.show_actions;
+                            }
+                        });
+                        let marked_roots = doc.marked_root_indexes();
+                        let view = TreeView {
+                            highlighted: &highlighted,
+                            filter: &doc.filter,
+                            marked_roots: &marked_roots,
+                            modified: &doc.changed,
+                            pinned: &pinned,
+                        };
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            action = doc.tree.iter_mut().fold(None, |action, t| {
+                                t.ui(
+                                    ui,
+                                    &mut doc.selected_ai,
+                                    &mut doc.multi_selected,
+                                    &mut doc.drag,
+                                    &view,
+                                )
+                                .or(action)
+                            });
+                            if !doc.orphans.is_empty() {
+                                let orphans = egui::CollapsingHeader::new(tr!(
+                                    "side-unreferenced",
+                                    count = doc.orphans.len()
+                                ))
+                                .id_source("orphans")
+                                .show(ui, |ui| {
+                                    doc.orphans.iter_mut().fold(None, |action, t| {
+                                        t.ui(
+                                            ui,
+                                            &mut doc.selected_ai,
+                                            &mut doc.multi_selected,
+                                            &mut doc.drag,
+                                            &view,
+                                        )
+                                        .or(action)
+                                    })
+                                });
+                                orphans
+                                    .header_response
+                                    .on_hover_text(tr!("side-unreferenced-hover"));
+                                action = action.or(orphans.body_returned.flatten());
+                            }
+                        });
+                    }
+                    if !doc.multi_selected.is_empty() {
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            ui.label(tr!("side-selected", count = doc.multi_selected.len()));
+                            if ui.small_button(tr!("side-set-parameter")).clicked() {
+                                open_bulk = true;
+                            }
+                            let pair: Vec<usize> = doc.multi_selected.iter().copied().collect();
+                            if ui
+                                .add_enabled(
+                                    pair.len() == 2,
+                                    egui::Button::new(tr!("button-compare")).small(),
+                                )
+                                .on_hover_text(tr!("side-compare-hover"))
+                                .clicked()
+                            {
+                                compare = Some((pair[0], pair[1]));
+                            }
+                            if ui
+                                .small_button(tr!("button-delete"))
+                                .on_hover_text(tr!("side-delete-hover"))
+                                .clicked()
+                            {
+                                delete_selected = true;
+                            }
+                            if ui.small_button(tr!("button-clear")).clicked() {
+                                doc.multi_selected.clear();
+                            }
+                        });
+                    }
+                    if !ctx.input().pointer.any_down() {
+                        if let (Some((child, old_parent)), Some(target)) =
+                            (doc.drag.source.take(), doc.drag.target)
+                        {
+                            dropped = Some((child, old_parent, target));
+                        }
+                    }
+                }
+            });
+        if let Some((child, old_parent, target)) = dropped {
+            self.drop_entry(child, old_parent, target);
+        }
+        if open_bulk {
+            self.open_bulk();
+        }
+        if delete_selected {
+            self.confirm_delete_selected();
+        }
+        if compare.is_some() {
+            self.compare = compare;
+        }
+        if let Some(key) = unpin {
+            if let Some(doc) = self.doc_mut() {
+                doc.sidecar.pins.retain(|pin| *pin != key);
+            }
+            self.save_sidecar();
+        }
+        match action {
+            Some(TreeAction::ExportSubtree(idx)) => {
+                let aiprog = self.doc().unwrap().aiprog.clone();
+                self.export_subtree(aiprog, idx);
+            }
+            Some(TreeAction::ImportSubtree(parent)) => self.import_dialog(parent),
+            Some(TreeAction::ToggleRoot(idx)) => {
+                let doc = self.docs.get_mut(self.active).unwrap();
+                doc.toggle_root_mark(idx);
+                if doc.marked_roots.is_empty() {
+                    self.session.marked_roots.remove(&doc.file);
+                } else {
+                    self.session
+                        .marked_roots
+                        .insert(doc.file.clone(), doc.marked_roots.clone());
+                }
+                self.update_tree();
+            }
+            Some(TreeAction::RevertEntry(idx)) => self.revert_entry(idx),
+            Some(TreeAction::TogglePin(idx)) => self.toggle_pin(idx),
+            None => (),
+        }
+    }
+
+    /// Asks where to save the entry at an index and everything below it, then
+    /// writes it out as a standalone program.
+    fn export_subtree(&mut self, aiprog: AIProgram, idx: usize) {
+        if let Some(file) = FileDialog::new()
+            .set_file_name(&format!(
+                "{}.yml",
+                aiprog.entry_name_from_index(idx).unwrap_or("Subtree")
+            ))
+            .add_filter("BOTW YAML AI Program", &["yml"])
+            .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+            .add_filter("JSON AI Program", &["json"])
+            .save_file()
+        {
+            self.start_task(move || {
+                aiprog
+                    .export_subtree(idx)?
+                    .save(&file, is_compressed(&file))
+                    .map(|_| Message::Null)
+            });
+        }
+    }
+
+    fn import_dialog(&mut self, parent: usize) {
+        let version = match self.doc_mut() {
+            Some(doc) => {
+                // So what was typed into the entry before this counts
+                doc.commit_editor();
+                doc.version()
+            }
+            None => return,
+        };
+        if let Some(file) = FileDialog::new()
+            .add_filter("BOTW YAML AI Program", &["yml"])
+            .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+            .add_filter("JSON AI Program", &["json"])
+            .pick_file()
+        {
+            self.start_task(move || {
+                AIProgram::new(&file).map(|snippet| Message::Snippet(version, parent, snippet))
+            });
+        }
+    }
+
+    /// Adds a loaded snippet below an entry, asking which slot to use if it has
+    /// more than one.
+    fn import_snippet(&mut self, version: DocVersion, parent: usize, snippet: AIProgram) {
+        let aiprog = match self.activate_version(version) {
+            Some(doc) => &doc.aiprog,
+            None => return,
+        };
+        let slots: Vec<u32> = match aiprog.get_item(parent) {
+            Ok(entry) => entry
+                .objects()
+                .get(hash_name("ChildIdx"))
+                .map(|obj| obj.params().keys().copied().collect())
+                .unwrap_or_default(),
+            Err(e) => return self.show_error(e),
+        };
+        match slots.as_slice() {
+            [] => {
+                let error = anyhow!(tr!(
+                    "error-no-child-slots",
+                    entry = aiprog.entry_label(parent)
+                ));
+                self.show_error(error);
+            }
+            [slot] => self.import_subtree(version, parent, *slot, &snippet),
+            _ => self.import_target = Some((version, parent, snippet)),
+        }
+    }
+
+    fn import_subtree(
+        &mut self,
+        version: DocVersion,
+        parent: usize,
+        slot: u32,
+        snippet: &AIProgram,
+    ) {
+        if let Some(doc) = self.activate_version(version) {
+            let mut aiprog = doc.aiprog.clone();
+            match aiprog.import_subtree(snippet, parent, slot) {
+                Ok(root) => {
+                    doc.restore(aiprog);
+                    doc.selected_ai = root;
+                    doc.multi_selected.clear();
+                    self.update_tree();
+                }
+                Err(e) => self.show_error(e),
+            }
+        }
+    }
+
+    /// Handles a tree node being dropped onto another entry, asking which slot
+    /// to use if the new parent has more than one.
+    fn drop_entry(&mut self, child: usize, old_parent: Option<usize>, target: usize) {
+        let aiprog = &self.doc().unwrap().aiprog;
+        let slots: Vec<u32> = aiprog
+            .item_at_index(target)
+            .objects()
+            .get(hash_name("ChildIdx"))
+            .map(|obj| obj.params().keys().copied().collect())
+            .unwrap_or_default();
+        match slots.as_slice() {
+            [] => {
+                let error = anyhow!(tr!(
+                    "error-no-child-slots",
+                    entry = aiprog.entry_label(target)
+                ));
+                self.show_error(error);
+            }
+            [slot] => self.reparent(child, old_parent, target, *slot),
+            _ => self.reparent_target = Some((child, old_parent, target)),
+        }
+    }
+
+    fn reparent(&mut self, child: usize, old_parent: Option<usize>, target: usize, slot: u32) {
+        if let Some(doc) = self.doc_mut() {
+            match doc.edit(|aiprog| aiprog.reparent(child, old_parent, target, slot)) {
+                Ok(_) => {
+                    self.update_tree();
+                }
+                Err(e) => self.show_error(e),
+            }
+        }
+    }
+
+    fn render_reparent(&mut self, ctx: &egui::CtxRef) {
+        let (child, old_parent, target) = match self.reparent_target {
+            Some(reparent) => reparent,
+            None => return,
+        };
+        let doc = match self.docs.get(self.active) {
+            Some(doc) => doc,
+            None => return,
+        };
+        let aiprog = &doc.aiprog;
+        let mut show = true;
+        let mut chosen = None;
+        egui::Window::new(tr!("window-choose-slot"))
+            .open(&mut show)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.label(tr!(
+                    "slot-choose",
+                    parent = aiprog.entry_label(target),
+                    child = aiprog.entry_label(child)
+                ));
+                chosen = Self::slot_buttons(ui, aiprog, target);
+            });
+        if let Some(slot) = chosen {
+            self.reparent_target = None;
+            self.reparent(child, old_parent, target, slot);
+        } else if !show {
+            self.reparent_target = None;
+        }
+    }
+
+    fn render_import(&mut self, ctx: &egui::CtxRef) {
+        let (version, parent) = match &self.import_target {
+            Some((version, parent, _)) => (*version, *parent),
+            None => return,
+        };
+        // Shown for the document it was picked for, even after switching
+        let doc = match self.docs.iter().find(|doc| doc.id == version.id) {
+            Some(doc) if parent < doc.aiprog.len() => doc,
+            _ => {
+                self.import_target = None;
+                return;
+            }
+        };
+        let aiprog = &doc.aiprog;
+        let mut show = true;
+        let mut chosen = None;
+        egui::Window::new(tr!("window-choose-slot"))
+            .open(&mut show)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.label(tr!(
+                    "slot-choose-import",
+                    parent = aiprog.entry_label(parent)
+                ));
+                chosen = Self::slot_buttons(ui, aiprog, parent);
+            });
+        if let Some(slot) = chosen {
+            let (version, parent, snippet) = self.import_target.take().unwrap();
+            self.import_subtree(version, parent, slot, &snippet);
+        } else if !show {
+            self.import_target = None;
+        }
+    }
+
+    /// Lists the child slots of an entry and what each points at, returning the
+    /// one clicked.
+    fn slot_buttons(ui: &mut Ui, aiprog: &AIProgram, target: usize) -> Option<u32> {
+        let mut chosen = None;
+        egui::Grid::new("child_slots")
+            .num_columns(2)
+            .show(ui, |ui| {
+                let slots = aiprog
+                    .get_item(target)
+                    .ok()
+                    .and_then(|item| item.objects().get(hash_name("ChildIdx")))
+                    .into_iter()
+                    .flat_map(|obj| obj.params().iter());
+                for (k, v) in slots {
+                    let slot = try_name(*k);
+                    if ui.small_button(translate(&slot)).clicked() {
+                        chosen = Some(*k);
+                    }
+                    ui.label(match v {
+                        Parameter::Int(i) if *i >= 0 && (*i as usize) < aiprog.len() => {
+                            aiprog.entry_label(*i as usize)
+                        }
+                        _ => tr!("slot-not-set"),
+                    });
+                    ui.end_row();
+                }
+            });
+        chosen
+    }
+
+    fn render_reference_panel(&mut self, ctx: &egui::CtxRef) {
+        let mut action = None;
+        if let Some(reference) = self.reference.as_mut() {
+            egui::SidePanel::right("reference_panel")
+                .default_width(300.0)
+                .resizable(true)
+                .show(ctx, |ui| {
+                    ui.label(tr!(
+                        "reference-title",
+                        file = reference
+                            .file
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                    ))
+                    .on_hover_text(reference.file.to_string_lossy());
+                    ui.separator();
+                    action = reference.ui(ui);
+                });
+        }
+        match action {
+            Some(TreeAction::ExportSubtree(idx)) => {
+                let aiprog = self.reference.as_ref().unwrap().aiprog().clone();
+                self.export_subtree(aiprog, idx);
+            }
+            Some(
+                TreeAction::ImportSubtree(_)
+                | TreeAction::ToggleRoot(_)
+                | TreeAction::RevertEntry(_)
+                | TreeAction::TogglePin(_),
+            ) => self.show_error(anyhow!(tr!("error-reference-read-only"))),
+            None => (),
+        }
+    }
+
+    fn render_main(&mut self, ctx: &egui::CtxRef) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if let Some(doc) = self.doc_mut() {
+                let aiprog = &doc.aiprog;
+                let actions_offset = aiprog.actions_offset();
+                let behaviors_offset = aiprog.behaviors_offset();
+                let queries_offset = aiprog.queries_offset();
+                let show_ais = !aiprog.ais().is_empty();
+                let show_actions = !aiprog.actions().is_empty();
+                let show_behaviours = !aiprog.behaviors().is_empty();
+                let show_queries = !aiprog.queries().is_empty();
+                egui::TopBottomPanel::top("tab_bar").show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        if show_ais
+                            && ui
+                                .selectable_label(
+                                    !doc.show_demos && matches!(doc.tab, Category::AI),
+                                    tr!("tab-ais"),
+                                )
+                                .clicked()
+                        {
+                            doc.tab = Category::AI;
+                            doc.show_demos = false;
+                            doc.selected_ai = *doc.last_selected.get(&Category::AI).unwrap_or(&0);
+                        }
+                        if show_actions
+                            && ui
+                                .selectable_label(
+                                    !doc.show_demos && matches!(doc.tab, Category::Action),
+                                    tr!("tab-actions"),
+                                )
+                                .clicked()
+                        {
+                            doc.tab = Category::Action;
+                            doc.show_demos = false;
+                            doc.selected_ai = doc
+                                .last_selected
+                                .get(&Category::Action)
+                                .copied()
+                                .unwrap_or(actions_offset)
+                        }
+                        if show_behaviours
+                            && ui
+                                .selectable_label(
+                                    !doc.show_demos && matches!(doc.tab, Category::Behaviour),
+                                    tr!("tab-behaviours"),
+                                )
+                                .clicked()
+                        {
+                            doc.tab = Category::Behaviour;
+                            doc.show_demos = false;
+                            doc.selected_ai = doc
+                                .last_selected
+                                .get(&Category::Behaviour)
+                                .copied()
+                                .unwrap_or(behaviors_offset)
+                        }
+                        if show_queries
+                            && ui
+                                .selectable_label(
+                                    !doc.show_demos && matches!(doc.tab, Category::Query),
+                                    tr!("tab-queries"),
+                                )
+                                .clicked()
+                        {
+                            doc.tab = Category::Query;
+                            doc.show_demos = false;
+                            doc.selected_ai = doc
+                                .last_selected
+                                .get(&Category::Query)
+                                .copied()
+                                .unwrap_or(queries_offset)
+                        }
+                        if ui
+                            .selectable_label(doc.show_demos, tr!("tab-demos"))
+                            .clicked()
+                        {
+                            doc.show_demos = true;
+                        }
+                    })
+                });
+                if doc.show_demos {
+                    self.render_demos(ui);
+                } else {
+                    self.render_editor(ui, ctx);
+                }
+            }
+        });
+    }
+
+    fn render_editor(&mut self, ui: &mut Ui, ctx: &egui::CtxRef) {
+        let mut update_tree = false;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if self.doc().is_some() {
+                ui.horizontal(|_ui| {
+                    egui::CentralPanel::default().show(ctx, |ui| {
+                        egui::ScrollArea::vertical()
+                            .id_source("editor")
+                            .show(ui, |ui| {
+                                if let Some(doc) = self.docs.get_mut(self.active) {
+                                    let aiprog = &doc.aiprog;
+                                    if let Some(idx) =
+                                        Self::render_breadcrumbs(ui, aiprog, doc.selected_ai)
+                                    {
+                                        doc.selected_ai = idx;
+                                    }
+                                    egui::ComboBox::from_label(tr!("editor-current-entry"))
+                                        .width(ui.available_width() - 125.0)
+                                        .selected_text(aiprog.entry_label(doc.selected_ai))
+                                        .show_ui(ui, |ui| {
+                                            let offset = aiprog.category_offset(doc.tab);
+                                            let count = match doc.tab {
+                                                Category::AI => aiprog.ais().len(),
+                                                Category::Action => aiprog.actions().len(),
+                                                Category::Behaviour => aiprog.behaviors().len(),
+                                                Category::Query => aiprog.queries().len(),
+                                            };
+                                            let selected = &mut doc.selected_ai;
+                                            rows::show_rows(
+                                                ui,
+                                                "current_entry_rows",
+                                                ui.spacing().combo_height,
+                                                rows::list_row_height(ui),
+                                                count,
+                                                |ui, range| {
+                                                    for idx in range {
+                                                        ui.selectable_value(
+                                                            selected,
+                                                            offset + idx,
+                                                            aiprog.entry_label(offset + idx),
+                                                        );
+                                                    }
+                                                },
+                                            );
+                                        });
+                                    if let Err(e) = aiprog.check_entry(doc.selected_ai) {
+                                        ui.colored_label(error_color(), format!("⚠ {}", e));
+                                        if aiprog.get_item(doc.selected_ai).is_err() {
+                                            return;
+                                        }
+                                    }
+                                    let modified = doc.changed.contains(&doc.selected_ai);
+                                    let selected = doc.selected_ai;
+                                    let pinned =
+                                        doc.sidecar.pins.contains(&doc.entry_key(selected));
+                                    let target = match aiprog.category_of(selected) {
+                                        Category::AI => Some(Category::Action),
+                                        Category::Action => Some(Category::AI),
+                                        _ => None,
+                                    };
+                                    if modified {
+                                        ui.colored_label(
+                                            changed_color(),
+                                            format!("• {}", tr!("editor-modified")),
+                                        )
+                                        .on_hover_text(tr!("editor-modified-hover"));
+                                    }
+                                    ui.add_space(4.0);
+                                    ui.horizontal(|ui| {
+                                        if ui.small_button(tr!("editor-add-new")).clicked() {
+                                            self.show_add = true;
+                                        };
+                                        if ui
+                                            .small_button(tr!("editor-duplicate"))
+                                            .on_hover_text("Ctrl+D")
+                                            .clicked()
+                                        {
+                                            self.duplicate_selected();
+                                        }
+                                        if ui
+                                            .small_button(tr!("editor-move-up"))
+                                            .on_hover_text("Alt+Up")
+                                            .clicked()
+                                        {
+                                            self.move_selected(-1);
+                                        }
+                                        if ui
+                                            .small_button(tr!("editor-move-down"))
+                                            .on_hover_text("Alt+Down")
+                                            .clicked()
+                                        {
+                                            self.move_selected(1);
+                                        }
+                                        if ui
+                                            .add_enabled(
+                                                modified,
+                                                egui::Button::new(tr!("editor-revert")).small(),
+                                            )
+                                            .on_hover_text(tr!("edit-revert-entry-hover"))
+                                            .clicked()
+                                        {
+                                            self.revert_selected();
+                                        }
+                                        if ui.small_button(tr!("editor-delete")).clicked() {
+                                            self.confirm_delete();
+                                        };
+                                        if ui
+                                            .small_button(tr!("editor-delete-subtree"))
+                                            .on_hover_text(tr!("editor-delete-subtree-hover"))
+                                            .clicked()
+                                        {
+                                            self.confirm_delete_subtree();
+                                        }
+                                        if let Some(target) = target {
+                                            if ui
+                                                .small_button(tr!(
+                                                    "editor-convert",
+                                                    category = target.to_string()
+                                                ))
+                                                .on_hover_text(tr!(
+                                                    "editor-convert-hover",
+                                                    category = target.to_string()
+                                                ))
+                                                .clicked()
+                                            {
+                                                self.start_convert(selected);
+                                            }
+                                        }
+                                        if ui.small_button(tr!("editor-view-def")).clicked() {
+                                            self.show_def = true;
+                                        }
+                                        if ui.small_button(tr!("editor-find-usages")).clicked() {
+                                            self.show_refs = true;
+                                        }
+                                        if ui
+                                            .small_button(tr!("window-vanilla-examples"))
+                                            .on_hover_text(tr!("editor-vanilla-examples-hover"))
+                                            .clicked()
+                                        {
+                                            self.show_vanilla = true;
+                                        }
+                                        if ui
+                                            .small_button(if pinned {
+                                                tr!("button-unpin")
+                                            } else {
+                                                tr!("button-pin")
+                                            })
+                                            .on_hover_text(tr!("editor-pin-hover"))
+                                            .clicked()
+                                        {
+                                            self.toggle_pin(selected);
+                                        }
+                                        let doc = self.docs.get_mut(self.active).unwrap();
+                                        if ui
+                                            .selectable_label(
+                                                doc.entry_text.is_some(),
+                                                tr!("editor-edit-text"),
+                                            )
+                                            .on_hover_text(tr!("editor-edit-text-hover"))
+                                            .clicked()
+                                        {
+                                            doc.entry_text = match doc.entry_text {
+                                                Some(_) => None,
+                                                None => Some(EntryText {
+                                                    entry: selected,
+                                                    text: doc.aiprog.entry_to_text(selected),
+                                                    error: None,
+                                                }),
+                                            };
+                                        }
+                                    });
+                                }
+                                if self
+                                    .doc()
+                                    .map(|doc| doc.entry_text.is_some())
+                                    .unwrap_or(false)
+                                {
+                                    update_tree = update_tree || self.render_entry_text(ui);
+                                } else {
+                                    update_tree = update_tree || self.render_definition(ui);
+                                    update_tree = update_tree || self.render_ai_children(ui);
+                                    self.render_query_links(ui);
+                                    self.render_sinst_parameters(ui);
+                                    self.render_minst_parameters(ui);
+                                    self.render_behaviour_indexes(ui);
+                                    self.render_other_objects(ui);
+                                    update_tree = self.render_plugin_panels(ui) || update_tree;
+                                }
+                                self.render_notes(ui);
+                            });
+                    });
+                });
+            };
+        });
+        if update_tree {
+            self.update_tree();
+        }
+    }
+
+    /// Shows the selected entry as YAML to edit by hand, returning whether
+    /// changes were applied.
+    fn render_entry_text(&mut self, ui: &mut Ui) -> bool {
+        let doc = match self.docs.get_mut(self.active) {
+            Some(doc) => doc,
+            None => return false,
+        };
+        let selected = doc.selected_ai;
+        let aiprog = &mut doc.aiprog;
+        let entry_text = doc.entry_text.as_mut().unwrap();
+        if entry_text.entry != selected {
+            *entry_text = EntryText {
+                entry: selected,
+                text: aiprog.entry_to_text(selected),
+                error: None,
+            };
+        }
+        let mut applied = false;
+        ui.add(
+            egui::TextEdit::multiline(&mut entry_text.text)
+                .code_editor()
+                .desired_width(f32::INFINITY)
+                .desired_rows(20),
+        );
+        if let Some(error) = &entry_text.error {
+            ui.colored_label(error_color(), error);
+        }
+        ui.horizontal(|ui| {
+            if ui
+                .button(tr!("button-apply"))
+                .on_hover_text(tr!("editor-text-apply-hover"))
+                .clicked()
+            {
+                match aiprog.entry_from_text(selected, &entry_text.text) {
+                    Ok(()) => {
+                        entry_text.error = None;
+                        applied = true;
+                    }
+                    Err(e) => entry_text.error = Some(format!("{:?}", e)),
+                }
+            }
+            if ui
+                .button(tr!("button-reset"))
+                .on_hover_text(tr!("editor-text-reset-hover"))
+                .clicked()
+            {
+                entry_text.text = aiprog.entry_to_text(selected);
+                entry_text.error = None;
+            }
+        });
+        applied
+    }
+
+    /// Shows which queries an AI or Action consults and the children they
+    /// decide between, or for a query, what consults it.
+    fn render_query_links(&mut self, ui: &mut Ui) {
+        let mut goto = None;
+        if let Some(doc) = self.docs.get_mut(self.active) {
+            let aiprog = &doc.aiprog;
+            let idx = doc.selected_ai;
+            let param_label =
+                |obj: u32, param: u32| format!("{} / {}", try_name(obj), try_name(param));
+            if aiprog.category_of(idx) == Category::Query {
+                let users = aiprog.query_users(idx);
+                egui::CollapsingHeader::new(tr!("section-used-by", count = users.len()))
+                    .id_source("query_users")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        if users.is_empty() {
+                            ui.label(tr!("editor-query-unused"));
+                        }
+                        egui::Grid::new("query_users_grid")
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                for user in users {
+                                    if ui.small_button(aiprog.entry_label(user)).clicked() {
+                                        goto = Some(user);
+                                    }
+                                    ui.label(
+                                        aiprog
+                                            .query_links(user)
+                                            .iter()
+                                            .filter(|link| link.query == idx)
+                                            .map(|link| param_label(link.object, link.param))
+                                            .collect::<Vec<_>>()
+                                            .join(", "),
+                                    );
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            } else {
+                let links = aiprog.query_links(idx);
+                if links.is_empty() {
+                    return;
+                }
+                egui::CollapsingHeader::new(tr!("section-queries"))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        egui::Grid::new("query_links_grid")
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                for link in &links {
+                                    ui.label(param_label(link.object, link.param));
+                                    if ui.small_button(aiprog.entry_label(link.query)).clicked() {
+                                        goto = Some(link.query);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                        let gated: Vec<String> = aiprog
+                            .item_at_index(idx)
+                            .objects()
+                            .get(hash_name("ChildIdx"))
+                            .map(|obj| {
+                                obj.params()
+                                    .iter()
+                                    .map(|(k, v)| match v {
+                                        Parameter::Int(i)
+                                            if *i >= 0 && (*i as usize) < aiprog.len() =>
+                                        {
+                                            format!(
+                                                "{} → {}",
+                                                translate(&try_name(*k)),
+                                                aiprog.entry_label(*i as usize)
+                                            )
+                                        }
+                                        _ => format!(
+                                            "{} → {}",
+                                            translate(&try_name(*k)),
+                                            tr!("slot-not-set")
+                                        ),
+                                    })
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        if !gated.is_empty() {
+                            ui.label(tr!("editor-query-gated"))
+                                .on_hover_text(tr!("editor-query-gated-hover"));
+                            for line in gated {
+                                ui.label(format!("  {}", line));
+                            }
+                        }
+                    });
+            }
+        }
+        if let Some(idx) = goto {
+            self.docs[self.active].navigate(idx);
+        }
+    }
+
+    fn render_notes(&mut self, ui: &mut Ui) {
+        let mut save = false;
+        if let Some(doc) = self.docs.get_mut(self.active) {
+            let idx = doc.selected_ai;
+            let key = doc.entry_key(idx);
+            let mut note = doc.sidecar.notes.get(&key).cloned().unwrap_or_default();
+            egui::CollapsingHeader::new(tr!("section-notes"))
+                .id_source(("notes", &key))
+                .default_open(!note.is_empty())
+                .show(ui, |ui| {
+                    let response = ui
+                        .add(
+                            egui::TextEdit::multiline(&mut note)
+                                .hint_text(tr!("editor-notes-hint"))
+                                .desired_width(f32::INFINITY),
+                        )
+                        .on_hover_text(tr!("editor-notes-hover"));
+                    if response.changed() {
+                        doc.set_note(idx, note);
+                    }
+                    save = response.lost_focus();
+                });
+        }
+        if save {
+            self.save_sidecar();
+        }
+    }
+
+    /// Writes the notes and pins of the active document out straight away, as
+    /// they aren't part of the program to save with it.
+    fn save_sidecar(&mut self) {
+        if let Some(doc) = self.doc() {
+            if let Err(e) = doc.sidecar.save(&doc.file) {
+                self.show_error(e);
+            }
+        }
+    }
+
+    fn toggle_pin(&mut self, idx: usize) {
+        if let Some(doc) = self.doc_mut() {
+            doc.toggle_pin(idx);
+            self.save_sidecar();
+        }
+    }
+
+    fn render_demos(&mut self, ui: &mut Ui) {
+        if let Some(doc) = self.docs.get_mut(self.active) {
+            let aiprog = &mut doc.aiprog;
+            let labels: Vec<String> = (0..aiprog.behaviors_offset())
+                .map(|i| aiprog.entry_label(i))
+                .collect();
+            let mut remove = None;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("demos").num_columns(3).show(ui, |ui| {
+                    for (k, v) in aiprog.demos_mut().params_mut().iter_mut() {
+                        let idx = match v {
+                            Parameter::Int(i) => i,
+                            _ => continue,
+                        };
+                        if has_name(*k) {
+                            ui.label(try_name(*k));
+                        } else {
+                            ui.add(egui::Label::new(k.to_string()).weak())
+                                .on_hover_text(tr!("demos-unknown-key"));
+                        }
+                        egui::ComboBox::from_id_source(("demo", *k))
+                            .width(ui.spacing().text_edit_width)
+                            .selected_text(
+                                labels
+                                    .get(*idx as usize)
+                                    .cloned()
+                                    .unwrap_or_else(|| tr!("slot-not-set")),
+                            )
+                            .show_ui(ui, |ui| {
+                                labels.iter().enumerate().for_each(|(i, label)| {
+                                    ui.selectable_value(idx, i as i32, label);
+                                });
+                            });
+                        if ui
+                            .small_button("×")
+                            .on_hover_text(tr!("button-remove"))
+                            .clicked()
+                        {
+                            remove = Some(*k);
+                        }
+                        ui.end_row();
+                    }
+                });
+                ui.add_space(4.0);
+                // Demos from every program loaded, less those this one already has
+                let known: Vec<String> = demo_names()
+                    .into_iter()
+                    .filter(|name| !aiprog.demos().params().contains_key(&hash_name(name)))
+                    .collect();
+                ui.horizontal(|ui| {
+                    let res = ui.add(
+                        egui::TextEdit::singleline(&mut self.new_demo)
+                            .hint_text(tr!("demos-name-hint")),
+                    );
+                    let candidates: Vec<&str> = known.iter().map(|n| n.as_str()).collect();
+                    autocomplete_popup(
+                        &mut self.new_demo,
+                        &mut self.demo_ac_state,
+                        &candidates,
+                        ui,
+                        &res,
+                    );
+                    if !known.is_empty() {
+                        egui::ComboBox::from_id_source("known_demos")
+                            .selected_text(tr!("demos-known"))
+                            .show_ui(ui, |ui| {
+                                for name in &known {
+                                    ui.selectable_value(&mut self.new_demo, name.clone(), name);
+                                }
+                            })
+                            .response
+                            .on_hover_text(tr!("demos-known-hover"));
+                    }
+                    let name = self.new_demo.trim();
+                    let hash = hash_name(name);
+                    let add = ui
+                        .add_enabled(
+                            !name.is_empty() && !aiprog.demos().params().contains_key(&hash),
+                            egui::Button::new(tr!("demos-add")),
+                        )
+                        .on_hover_text(tr!("demos-key", key = hash))
+                        .clicked();
+                    if add {
+                        if !has_name(hash) {
+                            self.session.add_hash_name(name);
+                        }
+                        add_demo_names([name]);
+                        aiprog
+                            .demos_mut()
+                            .params_mut()
+                            .insert(hash, Parameter::Int(-1));
+                        self.new_demo.clear();
+                    }
+                });
+            });
+            if let Some(key) = remove {
+                aiprog.demos_mut().params_mut().shift_remove(&key);
+            }
+        }
+    }
+
+    fn render_definition(&mut self, ui: &mut Ui) -> bool {
+        let mut update_tree = false;
+        let mut remove = None;
+        let mut class_changed = None;
+        if let Some(doc) = self.docs.get_mut(self.active) {
+            let aiprog = &mut doc.aiprog;
+            let named = matches!(
+                aiprog.category_of(doc.selected_ai),
+                Category::AI | Category::Action
+            );
+            let expected_groups = aiprog.expected_group_names(doc.selected_ai);
+            let ai = aiprog.item_mut_at_index(doc.selected_ai);
+            if let Some(defs) = ai.objects_mut().get_mut(hash_name("Def")) {
+                egui::CollapsingHeader::new(tr!("section-definition"))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        egui::Grid::new("def").num_columns(3).show(ui, |ui| {
+                            if let Some(name) = defs
+                                .params_mut()
+                                .get_mut(&hash_name("Name"))
+                                .and_then(|n| match n {
+                                    Parameter::StringRef(s) => Some(s),
+                                    _ => None,
+                                })
+                            {
+                                ui.label("Name").on_hover_text(tr!("editor-name-hover"));
+                                let slot_names: Vec<&str> =
+                                    doc.index.slot_names.iter().map(|s| s.as_str()).collect();
+                                if Self::autocomplete_field(
+                                    ui,
+                                    name,
+                                    &slot_names,
+                                    &mut self.edit_ac_state,
+                                )
+                                .changed()
+                                {
+                                    update_tree = true;
+                                };
+                                if ui
+                                    .small_button("×")
+                                    .on_hover_text(tr!("button-remove"))
+                                    .clicked()
+                                {
+                                    remove = Some((hash_name("Name"), named));
+                                }
+                                ui.end_row();
+                            };
                             if let Some(name) = defs
                                 .params_mut()
                                 .get_mut(&hash_name("ClassName"))
@@ -532,456 +3855,3039 @@ impl App {
                                     _ => None,
                                 })
                             {
-                                ui.label("ClassName");
+                                if AIDEFS.get_def(doc.tab, name).is_some() {
+                                    ui.label("ClassName")
+                                        .on_hover_text(tr!("editor-class-name-hover"));
+                                } else {
+                                    ui.colored_label(warning_color(), "⚠ ClassName")
+                                        .on_hover_text(tr!("editor-class-name-unknown"));
+                                }
+                                let old_class = name.clone();
                                 egui::ComboBox::from_id_source("class_name")
                                     .selected_text(name.clone())
                                     .width(ui.spacing().text_edit_width)
                                     .show_ui(ui, |ui| {
-                                        AIDEFS.classes(self.tab).for_each(|class| {
-                                            ui.selectable_value(name, class.to_owned(), class);
-                                        });
+                                        AIDEFS.classes(doc.tab).for_each(|class| {
+                                            ui.selectable_value(name, class.to_owned(), class);
+                                        });
+                                    });
+                                if *name != old_class {
+                                    class_changed = Some((doc.selected_ai, name.clone()));
+                                }
+                                if let Some(link) = &doc.actor_link {
+                                    Self::render_link_warning(ui, name, link);
+                                }
+                                ui.end_row();
+                            };
+                            if let Some(name) = defs
+                                .params_mut()
+                                .get_mut(&hash_name("GroupName"))
+                                .and_then(|n| match n {
+                                    Parameter::StringRef(s) => Some(s),
+                                    _ => None,
+                                })
+                            {
+                                if expected_groups.is_empty() || expected_groups.contains(name) {
+                                    ui.label("GroupName")
+                                        .on_hover_text(tr!("editor-group-name-hover"));
+                                } else {
+                                    ui.colored_label(error_color(), "⚠ GroupName")
+                                        .on_hover_text(tr!(
+                                            "editor-group-name-mismatch",
+                                            expected = expected_groups
+                                                .join(&format!(" {} ", tr!("list-or")))
+                                        ));
+                                }
+                                let group_names: Vec<&str> = doc
+                                    .index
+                                    .group_names
+                                    .iter()
+                                    .map(|s| s.as_str())
+                                    .filter(|s| !s.is_empty())
+                                    .collect();
+                                let translated = english(name);
+                                let response = Self::autocomplete_field(
+                                    ui,
+                                    name,
+                                    &group_names,
+                                    &mut self.edit_ac_state,
+                                );
+                                if let Some(translated) = translated {
+                                    response.on_hover_text(translated);
+                                }
+                                if ui
+                                    .small_button("×")
+                                    .on_hover_text(tr!("button-remove"))
+                                    .clicked()
+                                {
+                                    remove = Some((hash_name("GroupName"), named));
+                                }
+                                ui.end_row();
+                            };
+                        });
+                        let missing: Vec<&str> = ["Name", "GroupName"]
+                            .iter()
+                            .filter(|name| !defs.params().contains_key(&hash_name(name)))
+                            .copied()
+                            .collect();
+                        if named && !missing.is_empty() {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label(tr!("editor-add-param"));
+                                for name in missing {
+                                    if ui.small_button(name).clicked() {
+                                        defs.params_mut().insert(
+                                            hash_name(name),
+                                            Parameter::StringRef("".into()),
+                                        );
+                                        update_tree = true;
+                                    }
+                                }
+                            });
+                        }
+                    });
+            }
+        }
+        if let Some((key, required)) = remove {
+            self.remove_param("Def", key, required);
+        }
+        if let Some((idx, class)) = class_changed {
+            let doc = &mut self.docs[self.active];
+            // The class has already been changed in place, which the
+            // migration goes on from
+            doc.commit_editor();
+            let message = Message::MigrateClass(doc.version(), idx, class.clone());
+            self.show_confirm(
+                &tr!("confirm-migrate-class", class = class.as_str()),
+                message,
+            );
+        }
+        update_tree
+    }
+
+    fn render_ai_children(&mut self, ui: &mut Ui) -> bool {
+        let mut update_tree = false;
+        if let Some(doc) = self.docs.get_mut(self.active) {
+            let mut update: Option<(usize, String)> = None;
+            let mut jump = None;
+            let aiprog = &mut doc.aiprog;
+            let ai_name = match aiprog
+                .get_item(doc.selected_ai)
+                .ok()
+                .and_then(|ai| ai.objects().get(hash_name("Def")))
+                .and_then(|def| def.params().get(&hash_name("Name")))
+                .and_then(|name| name.as_string().ok())
+            {
+                Some(name) => name.to_string(),
+                None => return false,
+            };
+            let ai_count = aiprog.actions_offset();
+            let child_label = |i: usize, name: &str| {
+                let index = if i < ai_count {
+                    index_label(Category::AI, i, i)
+                } else {
+                    index_label(Category::Action, i - ai_count, i)
+                };
+                format!("{}. {}", index, name)
+            };
+            let missing_slots = aiprog.missing_child_slots(doc.selected_ai);
+            if aiprog
+                .item_at_index(doc.selected_ai)
+                .objects()
+                .get(hash_name("ChildIdx"))
+                .is_some()
+                || !missing_slots.is_empty()
+            {
+                egui::CollapsingHeader::new(tr!("section-children"))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        for slot in &missing_slots {
+                            ui.horizontal(|ui| {
+                                ui.colored_label(warning_color(), format!("⚠ {}", translate(slot)))
+                                    .on_hover_text(tr!("editor-slot-missing"));
+                                if ui.small_button(tr!("button-add")).clicked() {
+                                    aiprog
+                                        .item_mut_at_index(doc.selected_ai)
+                                        .objects_mut()
+                                        .inner_mut()
+                                        .entry(hash_name("ChildIdx"))
+                                        .or_default()
+                                        .params_mut()
+                                        .insert(hash_name(slot), Parameter::Int(-1));
+                                }
+                            });
+                        }
+                        if aiprog
+                            .item_at_index(doc.selected_ai)
+                            .objects()
+                            .get(hash_name("ChildIdx"))
+                            .is_none()
+                        {
+                            return;
+                        }
+                        egui::Grid::new("child_idx").num_columns(3).show(ui, |ui| {
+                            let slots = aiprog
+                                .item_mut_at_index(doc.selected_ai)
+                                .objects_mut()
+                                .get_mut(hash_name("ChildIdx"))
+                                .into_iter()
+                                .flat_map(|obj| obj.params_mut().iter_mut());
+                            for (k, v) in slots {
+                                let child_name = try_name(*k);
+                                ui.label(translate(&child_name));
+                                let v = match v.as_mut_int() {
+                                    Ok(v) => v,
+                                    Err(_) => {
+                                        Self::bad_index_label(ui, v);
+                                        ui.end_row();
+                                        continue;
+                                    }
+                                };
+                                let names = &doc.index.child_names;
+                                egui::ComboBox::from_id_source(k)
+                                    .selected_text(
+                                        names
+                                            .get(*v as usize)
+                                            .map(|name| child_label(*v as usize, name))
+                                            .unwrap_or_else(|| tr!("slot-not-set")),
+                                    )
+                                    .width(ui.spacing().text_edit_width)
+                                    .show_ui(ui, |ui| {
+                                        rows::show_rows(
+                                            ui,
+                                            ("child_rows", k),
+                                            ui.spacing().combo_height,
+                                            rows::list_row_height(ui),
+                                            names.len(),
+                                            |ui, range| {
+                                                for i in range {
+                                                    let value = ui.selectable_value(
+                                                        v,
+                                                        i as i32,
+                                                        child_label(i, &names[i]),
+                                                    );
+                                                    if value.changed() {
+                                                        update_tree = true;
+                                                        if value.clicked() {
+                                                            update = Some((i, child_name.clone()));
+                                                        }
+                                                    }
+                                                }
+                                            },
+                                        );
                                     });
+                                if Self::jump_button(ui, *v >= 0 && (*v as usize) < names.len()) {
+                                    jump = Some(*v as usize);
+                                }
                                 ui.end_row();
-                            };
-                            if let Some(name) = defs
-                                .params_mut()
-                                .get_mut(&hash_name("GroupName"))
-                                .and_then(|n| match n {
-                                    Parameter::StringRef(s) => Some(s),
-                                    _ => None,
-                                })
-                            {
-                                ui.label("GroupName");
-                                egui::ComboBox::from_id_source("group_name")
-                                    .selected_text(name.clone())
-                                    .width(ui.spacing().text_edit_width)
+                            }
+                        });
+                    });
+            }
+            if let Some((i, s)) = update {
+                match doc.edit(|aiprog| aiprog.update_names(i, s, ai_name)) {
+                    Ok(_) => (),
+                    Err(e) => {
+                        self.error = Some(e.to_string());
+                        self.show_error = true;
+                    }
+                };
+            };
+            if let Some(idx) = jump {
+                doc.navigate(idx);
+            }
+        }
+        update_tree
+    }
+
+    /// Stands in for an index slot holding something other than an int, which
+    /// can't be edited as one.
+    fn bad_index_label(ui: &mut Ui, value: &Parameter) {
+        ui.colored_label(error_color(), format!("⚠ {}", tr!("editor-not-index")))
+            .on_hover_text(tr!("editor-not-index-hover", value = param_string(value)));
+    }
+
+    /// Draws a button to go to the entry an index refers to, returning whether
+    /// it was clicked.
+    fn jump_button(ui: &mut Ui, enabled: bool) -> bool {
+        ui.add_enabled(enabled, egui::Button::new("→").small())
+            .on_hover_text(tr!("editor-jump-hover"))
+            .clicked()
+    }
+
+    fn render_sinst_parameters(&mut self, ui: &mut Ui) {
+        self.render_inst_parameters(ui, "SInst", tr!("section-sinst"), |category, class| {
+            AIDEFS.sinst_params(category, class)
+        });
+    }
+
+    fn render_minst_parameters(&mut self, ui: &mut Ui) {
+        self.render_inst_parameters(ui, "MInst", tr!("section-minst"), |category, class| {
+            AIDEFS
+                .get_def(category, class)
+                .and_then(|def| def.map_unit_inst_params.as_deref())
+                .unwrap_or_default()
+        });
+    }
+
+    /// Shows the parameters in an object of the selected entry, such as
+    /// `SInst`, in a grid, given where to find those the AI def of a class
+    /// declares for it.
+    fn render_inst_parameters(
+        &mut self,
+        ui: &mut Ui,
+        obj_name: &'static str,
+        title: String,
+        declared: fn(Category, &str) -> &'static [AIDefParam],
+    ) {
+        let mut remove = None;
+        if let Some(doc) = self.docs.get_mut(self.active) {
+            let aiprog = &mut doc.aiprog;
+            let category = aiprog.category_of(doc.selected_ai);
+            let defined: &[AIDefParam] = aiprog
+                .entry_name_from_index(doc.selected_ai)
+                .map(|class| declared(category, class))
+                .unwrap_or_default();
+            let base = aiprog
+                .base_index(&doc.init_prog, doc.selected_ai)
+                .and_then(|i| {
+                    doc.init_prog
+                        .item_at_index(i)
+                        .objects()
+                        .get(hash_name(obj_name))
+                });
+            let selected = doc.selected_ai;
+            // Only Actions' SInst parameters name AS
+            let as_names = doc
+                .as_names
+                .as_ref()
+                .filter(|_| category == Category::Action && obj_name == "SInst");
+            let flags = self.flags.as_ref();
+            let ai = aiprog.item_mut_at_index(selected);
+            if ai.objects().get(hash_name(obj_name)).is_none() && defined.is_empty() {
+                return;
+            }
+            egui::CollapsingHeader::new(title)
+                .id_source(obj_name)
+                .default_open(true)
+                .show(ui, |ui| {
+                    if let Some(params) = ai.objects_mut().get_mut(hash_name(obj_name)) {
+                        let count = params.params().len();
+                        let row_height = rows::grid_row_height(ui);
+                        rows::show_rows(
+                            ui,
+                            (obj_name, "rows"),
+                            row_height * 24.0,
+                            row_height,
+                            count,
+                            |ui, range| {
+                                let mut grid = egui::Grid::new(obj_name).num_columns(3);
+                                if count > rows::MANY_ROWS {
+                                    grid = grid.min_row_height(row_height);
+                                }
+                                grid.show(ui, |ui| {
+                                    for (k, v) in params
+                                        .params_mut()
+                                        .iter_mut()
+                                        .skip(range.start)
+                                        .take(range.len())
+                                    {
+                                        ui.horizontal(|ui| {
+                                            Self::render_param_label(ui, *k, v, defined);
+                                            Self::render_param_change(
+                                                ui,
+                                                v,
+                                                base.and_then(|base| base.params().get(k)),
+                                            );
+                                            Self::render_value_warning(ui, *k, v);
+                                            if let Some(flags) = flags {
+                                                Self::render_flag_check(ui, *k, v, flags);
+                                            }
+                                            if let Some(as_names) = as_names {
+                                                Self::render_as_check(ui, *k, v, as_names);
+                                            }
+                                        });
+                                        let response = Self::render_suggested_parameter(
+                                            ui,
+                                            *k,
+                                            v,
+                                            flags
+                                                .filter(|_| names_flag(&try_name(*k)))
+                                                .map(|flags| flags.names())
+                                                .or_else(|| doc.index.string_values.get(k)),
+                                            &mut self.edit_ac_state,
+                                        );
+                                        if let Some(value) = Self::render_param_history(
+                                            ui,
+                                            &response,
+                                            v,
+                                            doc.param_history.values(selected, obj_name, *k),
+                                        ) {
+                                            *v = value;
+                                        }
+                                        if ui
+                                            .small_button("×")
+                                            .on_hover_text(tr!("button-remove"))
+                                            .clicked()
+                                        {
+                                            let required = defined
+                                                .iter()
+                                                .any(|param| hash_name(&param.name) == *k);
+                                            remove = Some((*k, required));
+                                        }
+                                        ui.end_row();
+                                    }
+                                });
+                            },
+                        );
+                    }
+                    Self::render_missing_params(ui, ai, obj_name, defined);
+                });
+        }
+        if let Some((key, required)) = remove {
+            self.remove_param(obj_name, key, required);
+        }
+    }
+
+    /// Shows any objects the sections above don't cover, and any lists, so
+    /// nothing in an entry is left uneditable.
+    fn render_other_objects(&mut self, ui: &mut Ui) {
+        let known: Vec<u32> = ["Def", "ChildIdx", "SInst", "MInst", "BehaviorIdx"]
+            .iter()
+            .map(|name| hash_name(name))
+            .collect();
+        if let Some(doc) = self.docs.get_mut(self.active) {
+            let ai = doc.aiprog.item_mut_at_index(doc.selected_ai);
+            for (key, obj) in ai
+                .objects_mut()
+                .inner_mut()
+                .iter_mut()
+                .filter(|(k, _)| !known.contains(k))
+            {
+                aamp::object_ui(ui, *key, obj, "entry", true);
+            }
+            // Entries don't normally have lists, but nothing in the file
+            // should be out of reach
+            for (key, list) in ai.lists_mut().inner_mut().iter_mut() {
+                let id = format!("entry/{}", key);
+                egui::CollapsingHeader::new(try_name(*key))
+                    .id_source(&id)
+                    .default_open(true)
+                    .show(ui, |ui| aamp::list_ui(ui, list, &id));
+            }
+        }
+    }
+
+    /// Shows a parameter's name with its AI def details on hover, warning if
+    /// its type differs from the definition or it isn't defined at all.
+    fn render_param_label(ui: &mut Ui, key: u32, param: &Parameter, defined: &[AIDefParam]) {
+        let name = try_name(key);
+        match defined.iter().find(|def| hash_name(&def.name) == key) {
+            Some(def) if def.matches(param) => {
+                ui.label(name).on_hover_text(def.tooltip());
+            }
+            Some(def) => {
+                ui.colored_label(error_color(), format!("⚠ {}", name))
+                    .on_hover_text(format!("{}\n\n{}", def.tooltip(), tr!("param-wrong-type")));
+            }
+            None if !defined.is_empty() => {
+                ui.colored_label(warning_color(), format!("⚠ {}", name))
+                    .on_hover_text(tr!("param-undefined"));
+            }
+            None => {
+                ui.label(name);
+            }
+        }
+    }
+
+    /// Marks a parameter which differs from the file as loaded, with a button
+    /// to put the old value back.
+    fn render_param_change(ui: &mut Ui, param: &mut Parameter, base: Option<&Parameter>) {
+        match base {
+            Some(base) if base == param => (),
+            Some(base) => {
+                if ui
+                    .add(egui::Button::new("↺").small().text_color(changed_color()))
+                    .on_hover_text(tr!("param-changed", value = param_string(base)))
+                    .clicked()
+                {
+                    *param = base.clone();
+                }
+            }
+            None => {
+                ui.colored_label(changed_color(), "•")
+                    .on_hover_text(tr!("param-added"));
+            }
+        }
+    }
+
+    /// Lists the earlier values a parameter had this session when its editor
+    /// is right-clicked, graphing them if it's a single number, and returns
+    /// the one picked to put back.
+    fn render_param_history(
+        ui: &Ui,
+        response: &egui::Response,
+        current: &Parameter,
+        values: &[Parameter],
+    ) -> Option<Parameter> {
+        if values.is_empty() {
+            return None;
+        }
+        let popup_id = response.id.with("param_history");
+        if response.secondary_clicked() {
+            ui.memory().open_popup(popup_id);
+        }
+        egui::popup_below_widget(ui, popup_id, response, |ui| {
+            ui.label(tr!("param-history"));
+            let points: Vec<f32> = values
+                .iter()
+                .chain([current])
+                .filter_map(param_history::scalar)
+                .collect();
+            if points.len() > 1 {
+                Self::sparkline(ui, &points);
+            }
+            let mut picked = None;
+            for value in values.iter().rev() {
+                if ui.button(param_string(value)).clicked() {
+                    picked = Some(value.clone());
+                }
+            }
+            picked
+        })
+        .flatten()
+    }
+
+    /// Draws a small line graph of values, oldest first.
+    fn sparkline(ui: &mut Ui, values: &[f32]) {
+        let (rect, _) = ui.allocate_exact_size(Vec2::new(120.0, 32.0), egui::Sense::hover());
+        let (min, max) = values.iter().fold((f32::MAX, f32::MIN), |(min, max), v| {
+            (min.min(*v), max.max(*v))
+        });
+        let range = (max - min).max(f32::EPSILON);
+        let step = rect.width() / (values.len() - 1) as f32;
+        let points = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                egui::pos2(
+                    rect.left() + i as f32 * step,
+                    rect.bottom() - (v - min) / range * rect.height(),
+                )
+            })
+            .collect();
+        ui.painter().add(egui::Shape::line(
+            points,
+            egui::Stroke::new(1.5, changed_color()),
+        ));
+    }
+
+    /// Warns of a numeric value which looks wrong, such as a negative
+    /// distance.
+    fn render_value_warning(ui: &mut Ui, key: u32, param: &Parameter) {
+        if let Some(warning) = value_warning(&try_name(key), param) {
+            ui.colored_label(warning_color(), "⚠")
+                .on_hover_text(value_warning_text(&warning));
+        }
+    }
+
+    /// Shows whether the AS a parameter names is in the actor's ASList, if it
+    /// names one.
+    fn render_as_check(ui: &mut Ui, key: u32, param: &Parameter, as_names: &AsNames) {
+        match as_exists(&try_name(key), param, as_names) {
+            Some(true) => {
+                ui.colored_label(ok_color(), "✔")
+                    .on_hover_text(tr!("param-as-found"));
+            }
+            Some(false) => {
+                ui.colored_label(error_color(), "❌")
+                    .on_hover_text(tr!("param-as-missing"));
+            }
+            None => (),
+        }
+    }
+
+    /// Warns of components a class needs which the actor doesn't have,
+    /// saying so when the rule is only a guess.
+    fn render_link_warning(ui: &mut Ui, class: &str, link: &ActorLink) {
+        let missing = link.missing_for(class);
+        if !missing.is_empty() {
+            ui.colored_label(warning_color(), "⚠").on_hover_text(
+                missing
+                    .iter()
+                    .map(|rule| {
+                        let (needs, link) = (rule.needs.as_str(), rule.link.as_str());
+                        if rule.is_verified() {
+                            tr!("warning-link-missing", needs = needs, link = link)
+                        } else {
+                            tr!("warning-link-unverified", needs = needs, link = link)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+        }
+    }
+
+    /// Warns of a parameter naming a GameData flag which isn't in the flag
+    /// list.
+    fn render_flag_check(ui: &mut Ui, key: u32, param: &Parameter, flags: &FlagList) {
+        if flag_exists(&try_name(key), param, flags) == Some(false) {
+            ui.colored_label(error_color(), "❌")
+                .on_hover_text(tr!("param-flag-missing"));
+        }
+    }
+
+    /// Offers buttons to add any parameters the AI def declares for an object
+    /// which are not yet present in the entry.
+    fn render_missing_params(
+        ui: &mut Ui,
+        ai: &mut ParameterList,
+        obj_name: &str,
+        defined: &[AIDefParam],
+    ) {
+        let missing: Vec<&AIDefParam> = defined
+            .iter()
+            .filter(|param| {
+                param.has_default()
+                    && ai
+                        .objects()
+                        .get(hash_name(obj_name))
+                        .map(|obj| !obj.params().contains_key(&hash_name(&param.name)))
+                        .unwrap_or(true)
+            })
+            .collect();
+        if !missing.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(tr!("editor-add-param"));
+                for param in missing {
+                    if ui.small_button(&param.name).clicked() {
+                        ai.objects_mut()
+                            .inner_mut()
+                            .entry(hash_name(obj_name))
+                            .or_default()
+                            .params_mut()
+                            .insert(
+                                hash_name(&param.name),
+                                AIDefs::default_parameter(&param.param_type, &param.value),
+                            );
+                    }
+                }
+            });
+        }
+    }
+
+    /// Single line text field which suggests completions while it has focus.
+    fn autocomplete_field(
+        ui: &mut Ui,
+        text: &mut String,
+        candidates: &[&str],
+        state: &mut AcState,
+    ) -> egui::Response {
+        let mut response = ui.add(egui::TextEdit::singleline(text).lock_focus(true));
+        if response.changed() {
+            state.input_changed = true;
+        }
+        if response.has_focus()
+            && !candidates.contains(&text.as_str())
+            && autocomplete_popup(text, state, candidates, ui, &response)
+        {
+            response.mark_changed();
+        }
+        response
+    }
+
+    /// Edits a parameter, suggesting values used elsewhere in the program for
+    /// string parameters of the same name.
+    fn render_suggested_parameter(
+        ui: &mut Ui,
+        key: u32,
+        param: &mut Parameter,
+        values: Option<&Vec<String>>,
+        state: &mut AcState,
+    ) -> egui::Response {
+        if let (Parameter::F32(f), Some(unit)) = (&mut *param, angle_unit(&try_name(key))) {
+            return Self::render_angle(ui, f, unit);
+        }
+        match (param, values) {
+            (
+                Parameter::String256(s)
+                | Parameter::String32(s)
+                | Parameter::String64(s)
+                | Parameter::StringRef(s),
+                Some(values),
+            ) => {
+                let candidates: Vec<&str> = values.iter().map(|s| s.as_str()).collect();
+                Self::autocomplete_field(ui, s, &candidates, state)
+            }
+            (param, _) => Self::render_parameter(ui, param),
+        }
+    }
+
+    /// Edits an angle in the unit chosen in the settings, with a button to
+    /// switch units, keeping it in the unit it's stored in.
+    fn render_angle(ui: &mut Ui, value: &mut f32, stored: AngleUnit) -> egui::Response {
+        let shown = angle_display();
+        ui.horizontal(|ui| {
+            let response = ui
+                .add(
+                    egui::DragValue::from_get_set(|v: Option<f64>| {
+                        if let Some(v) = v {
+                            *value = shown.convert(v as f32, stored);
+                        }
+                        stored.convert(*value, shown) as f64
+                    })
+                    .speed(match shown {
+                        AngleUnit::Degrees => 1.0,
+                        AngleUnit::Radians => 0.01,
+                    })
+                    .suffix(shown.suffix()),
+                )
+                .on_hover_text(tr!(
+                    "param-angle-stored",
+                    unit = match stored {
+                        AngleUnit::Degrees => "degrees",
+                        AngleUnit::Radians => "radians",
+                    }
+                ));
+            if ui
+                .small_button(match shown {
+                    AngleUnit::Degrees => "rad",
+                    AngleUnit::Radians => "°",
+                })
+                .on_hover_text(tr!("param-angle-switch"))
+                .clicked()
+            {
+                set_angle_display(match shown {
+                    AngleUnit::Degrees => AngleUnit::Radians,
+                    AngleUnit::Radians => AngleUnit::Degrees,
+                });
+            }
+            response
+        })
+        .inner
+    }
+
+    pub(crate) fn render_parameter(ui: &mut Ui, param: &mut Parameter) -> egui::Response {
+        match param {
+            Parameter::Bool(b) => ui.checkbox(b, ""),
+            Parameter::Color(c) => {
+                ui.horizontal(|ui| {
+                    ui.label("A");
+                    let a = ui.add(egui::DragValue::new(&mut c.a).speed(0.1));
+                    ui.label("R");
+                    let r = ui.add(egui::DragValue::new(&mut c.r).speed(0.1));
+                    ui.label("G");
+                    let g = ui.add(egui::DragValue::new(&mut c.g).speed(0.1));
+                    ui.label("B");
+                    a | r | g | ui.add(egui::DragValue::new(&mut c.b).speed(0.1))
+                })
+                .inner
+            }
+            Parameter::F32(f) => ui.add(egui::DragValue::new(f).speed(0.1)),
+            Parameter::Int(i) => ui.add(egui::DragValue::new(i).speed(1)),
+            Parameter::Quat(q) => {
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut q.a).speed(0.1))
+                        | ui.add(egui::DragValue::new(&mut q.b).speed(0.1))
+                        | ui.add(egui::DragValue::new(&mut q.c).speed(0.1))
+                        | ui.add(egui::DragValue::new(&mut q.d).speed(0.1))
+                })
+                .inner
+            }
+            Parameter::String256(s)
+            | Parameter::String32(s)
+            | Parameter::String64(s)
+            | Parameter::StringRef(s) => ui.text_edit_singleline(s),
+            Parameter::U32(u) => {
+                ui.add(egui::DragValue::new(u).speed(1).clamp_range(0..=usize::MAX))
+            }
+            Parameter::Vec2(v) => {
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut v.x).speed(0.1))
+                        | ui.add(egui::DragValue::new(&mut v.y).speed(0.1))
+                })
+                .inner
+            }
+            Parameter::Vec3(v) => {
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut v.x).speed(0.1))
+                        | ui.add(egui::DragValue::new(&mut v.y).speed(0.1))
+                        | ui.add(egui::DragValue::new(&mut v.z).speed(0.1))
+                })
+                .inner
+            }
+            Parameter::Vec4(v) => {
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut v.x).speed(0.1))
+                        | ui.add(egui::DragValue::new(&mut v.y).speed(0.1))
+                        | ui.add(egui::DragValue::new(&mut v.z).speed(0.1))
+                        | ui.add(egui::DragValue::new(&mut v.t).speed(0.1))
+                })
+                .inner
+            }
+            _ => ui.label(""),
+        }
+    }
+
+    fn render_behaviour_indexes(&mut self, ui: &mut Ui) {
+        if let Some(doc) = self.docs.get_mut(self.active) {
+            let aiprog = &mut doc.aiprog;
+            let offset = aiprog.behaviors_offset();
+            let mut jump = None;
+            let ai = aiprog.item_mut_at_index(doc.selected_ai);
+            if let Some(behaviours) = ai.objects_mut().get_mut(hash_name("BehaviorIdx")) {
+                egui::CollapsingHeader::new(tr!("section-behaviour-indexes"))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        egui::Grid::new("behaviour_idxs")
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                let names = &doc.index.behaviour_names;
+                                behaviours.params_mut().iter_mut().for_each(|(k, v)| {
+                                    ui.label(try_name(*k));
+                                    let idx = match v.as_mut_int() {
+                                        Ok(idx) => idx,
+                                        Err(_) => {
+                                            Self::bad_index_label(ui, v);
+                                            ui.end_row();
+                                            return;
+                                        }
+                                    };
+                                    egui::ComboBox::from_id_source(k)
+                                        .width(ui.spacing().text_edit_width)
+                                        .selected_text(behavior_label(offset, *idx, names))
+                                        .show_ui(ui, |ui| {
+                                            for i in 0..names.len() as i32 {
+                                                ui.selectable_value(
+                                                    idx,
+                                                    i,
+                                                    behavior_label(offset, i, names),
+                                                );
+                                            }
+                                        });
+                                    if Self::jump_button(
+                                        ui,
+                                        *idx >= 0 && (*idx as usize) < names.len(),
+                                    ) {
+                                        jump = Some(offset + *idx as usize);
+                                    }
+                                    ui.end_row();
+                                });
+                            });
+                    });
+            }
+            if let Some(idx) = jump {
+                doc.navigate(idx);
+            }
+        }
+    }
+
+    fn render_error(&mut self, ctx: &egui::CtxRef) {
+        let mut show = self.show_error;
+        if self.show_error {
+            egui::Window::new(tr!("window-error"))
+                .open(&mut show)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.label(self.error.as_ref().unwrap());
+                    if ui.button(tr!("button-ok")).clicked() {
+                        self.show_error = false;
+                    }
+                });
+            if !show {
+                self.error = None;
+            }
+        }
+    }
+
+    fn render_busy(&mut self, ctx: &egui::CtxRef) {
+        if self.tasks.is_busy() {
+            egui::Window::new(tr!("window-busy"))
+                .default_width(200.0)
+                .collapsible(false)
+                .show(ctx, |ui| self.tasks.ui(ui));
+            // Keep redrawing so progress shows as it's reported
+            ctx.request_repaint();
+        }
+    }
+
+    fn open_bulk(&mut self) {
+        self.show_bulk = true;
+        self.bulk_param = None;
+        self.bulk_result = None;
+    }
+
+    fn render_bulk(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_bulk {
+            return;
+        }
+        let mut show = self.show_bulk;
+        if let Some(doc) = self.docs.get_mut(self.active) {
+            let entries: Vec<usize> = doc.multi_selected.iter().copied().collect();
+            let keys = doc.aiprog.shared_params(&entries, "SInst");
+            let mut apply = false;
+            egui::Window::new(tr!("window-set-parameter"))
+                .open(&mut show)
+                .default_width(350.0)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.spacing_mut().item_spacing.y = 9.0;
+                    ui.label(tr!("bulk-selected", count = entries.len()));
+                    let mut picked = self.bulk_param.as_ref().map(|(k, _)| *k);
+                    egui::ComboBox::from_label(tr!("bulk-parameter"))
+                        .selected_text(picked.map(try_name).unwrap_or_default())
+                        .show_ui(ui, |ui| {
+                            for key in &keys {
+                                ui.selectable_value(&mut picked, Some(*key), try_name(*key));
+                            }
+                        });
+                    if picked != self.bulk_param.as_ref().map(|(k, _)| *k) {
+                        // Start from the first selected entry's value
+                        self.bulk_param = picked.and_then(|key| {
+                            entries.iter().find_map(|i| {
+                                doc.aiprog
+                                    .item_at_index(*i)
+                                    .objects()
+                                    .get(hash_name("SInst"))?
+                                    .params()
+                                    .get(&key)
+                                    .map(|v| (key, v.clone()))
+                            })
+                        });
+                        self.bulk_result = None;
+                    }
+                    if let Some((_, value)) = &mut self.bulk_param {
+                        Self::render_parameter(ui, value);
+                        apply = ui.button(tr!("button-apply")).clicked();
+                    }
+                    if let Some(count) = self.bulk_result {
+                        ui.label(tr!("bulk-updated", count = count));
+                    }
+                });
+            if apply {
+                if let Some((key, value)) = &self.bulk_param {
+                    let count =
+                        doc.edit(|aiprog| Ok(aiprog.set_param_on(&entries, "SInst", *key, value)));
+                    self.bulk_result = count.ok();
+                }
+            }
+        }
+        self.show_bulk = show;
+    }
+
+    fn open_port(&mut self) {
+        if let Some(file) = FileDialog::new()
+            .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+            .add_filter("BOTW YAML AI Program", &["yml"])
+            .add_filter("BOTW Actor Pack", &["sbactorpack", "bactorpack"])
+            .add_filter("JSON AI Program", &["json"])
+            .pick_file()
+        {
+            self.start_task(move || {
+                AIProgram::new(&file).map(|aiprog| Message::PortSource(file.clone(), aiprog))
+            });
+        }
+    }
+
+    fn render_port(&mut self, ctx: &egui::CtxRef) {
+        let (port, doc) = match (self.port.as_mut(), self.docs.get_mut(self.active)) {
+            (Some(port), Some(doc)) => (port, doc),
+            _ => return,
+        };
+        let mut show = true;
+        let mut apply = false;
+        egui::Window::new(tr!("window-port"))
+            .open(&mut show)
+            .default_width(400.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                apply = port.ui(ui, &doc.aiprog);
+            });
+        if apply {
+            let mut aiprog = doc.aiprog.clone();
+            match port.apply(&mut aiprog) {
+                Ok(root) => {
+                    doc.restore(aiprog);
+                    doc.selected_ai = root;
+                    self.update_tree();
+                    show = false;
+                }
+                Err(e) => self.show_error(e),
+            }
+        }
+        if !show {
+            self.port = None;
+        }
+    }
+
+    fn render_merge(&mut self, ctx: &egui::CtxRef) {
+        let wizard = match self.merge.as_mut() {
+            Some(wizard) => wizard,
+            None => return,
+        };
+        let mut show = true;
+        let mut action = None;
+        egui::Window::new(tr!("window-merge"))
+            .open(&mut show)
+            .default_width(450.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                action = wizard.ui(ui);
+            });
+        match action {
+            Some(MergeAction::Start(base, theirs)) => {
+                if let Some(doc) = self.docs.get(self.active) {
+                    wizard.doc = Some(doc.version());
+                    let aiprog = doc.aiprog.clone();
+                    self.start_task(move || {
+                        let base = AIProgram::new(&base)?;
+                        let theirs = AIProgram::new(&theirs)?;
+                        Ok(Message::Merge(Merge::new(&base, &aiprog, &theirs)))
+                    });
+                }
+            }
+            Some(MergeAction::Apply) => {
+                let aiprog = wizard.merge.as_ref().map(|merge| merge.build());
+                if let (Some(aiprog), Some(version)) = (aiprog, wizard.doc) {
+                    if let Some(doc) = self.activate_version(version) {
+                        doc.restore(aiprog);
+                        self.update_tree();
+                    }
+                }
+                show = false;
+            }
+            None => (),
+        }
+        if !show {
+            self.merge = None;
+        }
+    }
+
+    fn render_conflicts(&mut self, ctx: &egui::CtxRef) {
+        let inspector = match self.conflicts.as_mut() {
+            Some(inspector) => inspector,
+            None => return,
+        };
+        let mut show = true;
+        let mut compare = None;
+        egui::Window::new(tr!("window-conflicts"))
+            .open(&mut show)
+            .default_width(450.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                compare = inspector.ui(ui);
+            });
+        if let Some((base, mods)) = compare {
+            self.run_task(&tr!("task-comparing-mods"), move |progress| {
+                let base = AIProgram::new(&base)?;
+                let mut programs = vec![];
+                for (i, file) in mods.iter().enumerate() {
+                    progress.check()?;
+                    progress.set(i, mods.len(), tr!("task-mods-read"));
+                    programs.push(AIProgram::new(file)?);
+                }
+                Ok(Message::ModConflicts(inspect_conflicts(&base, &programs)))
+            });
+        }
+        if !show {
+            self.conflicts = None;
+        }
+    }
+
+    fn open_remap(&mut self) {
+        if let Some(doc) = self.doc() {
+            let entries: Vec<usize> = if doc.multi_selected.is_empty() {
+                doc.aiprog.descendants(doc.selected_ai)
+            } else {
+                doc.multi_selected.iter().copied().collect()
+            };
+            self.remap_slots = doc
+                .aiprog
+                .behavior_slots(&entries)
+                .into_iter()
+                .map(|slot| (slot, slot.index))
+                .collect();
+            self.show_remap = true;
+        }
+    }
+
+    fn render_remap(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_remap {
+            return;
+        }
+        let doc = match self.docs.get(self.active) {
+            Some(doc) => doc,
+            None => return,
+        };
+        let aiprog = &doc.aiprog;
+        let names = &doc.index.behaviour_names;
+        let offset = aiprog.behaviors_offset();
+        let mut show = self.show_remap;
+        let mut pick_source = false;
+        let mut apply = false;
+        egui::Window::new(tr!("window-remap"))
+            .open(&mut show)
+            .default_width(500.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.horizontal(|ui| {
+                    ui.label(tr!("remap-source"));
+                    ui.label(
+                        self.remap_source
+                            .as_ref()
+                            .and_then(|(file, _)| file.file_name())
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_else(|| tr!("settings-not-set")),
+                    );
+                    pick_source = dialog_button(ui, true, egui::Button::new(tr!("button-browse")))
+                        .on_hover_text(tr!("remap-source-hover"))
+                        .clicked();
+                });
+                if ui
+                    .add_enabled(
+                        self.remap_source.is_some(),
+                        egui::Button::new(tr!("remap-auto-match")),
+                    )
+                    .clicked()
+                {
+                    let (_, source) = self.remap_source.as_ref().unwrap();
+                    for (slot, index) in self.remap_slots.iter_mut() {
+                        if let Some(matched) = aiprog.match_behavior(source, slot.index) {
+                            *index = matched;
+                        }
+                    }
+                }
+                if self.remap_slots.is_empty() {
+                    ui.label(tr!("remap-empty"));
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("remap_grid").num_columns(3).show(ui, |ui| {
+                            ui.strong(tr!("column-entry"));
+                            ui.strong(tr!("remap-was"));
+                            ui.strong(tr!("remap-now"));
+                            ui.end_row();
+                            let source = self.remap_source.as_ref().map(|(_, s)| s);
+                            for (slot, index) in self.remap_slots.iter_mut() {
+                                ui.label(format!(
+                                    "{} / {}",
+                                    aiprog.entry_label(slot.entry),
+                                    try_name(slot.key)
+                                ));
+                                ui.label(format!(
+                                    "{}. {}",
+                                    slot.index,
+                                    source
+                                        .and_then(|s| s.behavior_class(slot.index))
+                                        .unwrap_or("?")
+                                ));
+                                egui::ComboBox::from_id_source(("remap", slot.entry, slot.key))
+                                    .width(200.0)
+                                    .selected_text(behavior_label(offset, *index, names))
                                     .show_ui(ui, |ui| {
-                                        let group_names = &self.cache["group_names"];
-                                        group_names.iter().for_each(|ai_name| {
+                                        for i in 0..names.len() as i32 {
                                             ui.selectable_value(
-                                                name,
-                                                ai_name.clone(),
-                                                JPEN_MAP
-                                                    .get(ai_name.as_str())
-                                                    .map(|s| format!("{} ({})", s, &ai_name))
-                                                    .unwrap_or_else(|| ai_name.to_string()),
+                                                index,
+                                                i,
+                                                behavior_label(offset, i, names),
                                             );
-                                        });
+                                        }
                                     });
                                 ui.end_row();
-                            };
+                            }
+                        });
+                    });
+                apply = ui
+                    .add_enabled(
+                        !self.remap_slots.is_empty(),
+                        egui::Button::new(tr!("button-apply")),
+                    )
+                    .clicked();
+            });
+        if pick_source {
+            if let Some(file) = FileDialog::new()
+                .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+                .add_filter("BOTW YAML AI Program", &["yml"])
+                .add_filter("JSON AI Program", &["json"])
+                .pick_file()
+            {
+                self.start_task(move || {
+                    AIProgram::new(&file).map(|aiprog| Message::RemapSource(file.clone(), aiprog))
+                });
+            }
+        }
+        if apply {
+            let doc = self.docs.get_mut(self.active).unwrap();
+            let mut aiprog = doc.aiprog.clone();
+            for (slot, index) in &self.remap_slots {
+                aiprog.set_behavior(slot, *index);
+            }
+            doc.restore(aiprog);
+            self.update_tree();
+            show = false;
+        }
+        self.show_remap = show;
+    }
+
+    fn render_dump(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_dump {
+            return;
+        }
+        let mut show = self.show_dump;
+        let mut open = false;
+        egui::Window::new(tr!("window-open-dump"))
+            .open(&mut show)
+            .default_width(350.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.horizontal(|ui| {
+                    ui.label(tr!("dump-game-dump"));
+                    ui.label(
+                        self.settings
+                            .game_dump
+                            .as_ref()
+                            .map(|dump| dump.display().to_string())
+                            .unwrap_or_else(|| tr!("settings-not-set")),
+                    );
+                    if dialog_button(ui, true, egui::Button::new(tr!("button-browse"))).clicked() {
+                        if let Some(dump) = FileDialog::new().pick_folder() {
+                            self.set_game_dump(Some(dump));
+                        }
+                    }
+                });
+                if self.settings.game_dump.is_some() && self.dump_actors.is_empty() {
+                    ui.colored_label(error_color(), tr!("dump-no-actors"));
+                }
+                ui.horizontal(|ui| {
+                    ui.label(tr!("dump-actor"));
+                    let actors: Vec<&str> = self.dump_actors.iter().map(|s| s.as_str()).collect();
+                    Self::autocomplete_field(
+                        ui,
+                        &mut self.dump_actor,
+                        &actors,
+                        &mut self.dump_ac_state,
+                    );
+                });
+                open = ui
+                    .add_enabled(
+                        self.settings.game_dump.is_some() && !self.dump_actor.is_empty(),
+                        egui::Button::new(tr!("file-open")),
+                    )
+                    .clicked();
+            });
+        if open {
+            if let Some(dump) = self.settings.game_dump.clone() {
+                let actor = self.dump_actor.clone();
+                show = false;
+                self.start_task(move || {
+                    extract_from_dump(&dump, &actor)
+                        .map(|(name, data)| Message::Extracted(name, data))
+                });
+            }
+        }
+        self.show_dump = show;
+    }
+
+    /// Switches to another game dump, forgetting what was read from the last.
+    fn set_game_dump(&mut self, dump: Option<PathBuf>) {
+        self.dump_actors = dump.as_deref().map(dump_actor_names).unwrap_or_default();
+        self.vanilla = None;
+        self.settings.game_dump = dump;
+    }
+
+    /// Switches to another list of GameData flags, reading it in the
+    /// background.
+    fn set_flag_list(&mut self, file: Option<PathBuf>) {
+        self.flags = None;
+        self.settings.flag_list = file.clone();
+        if let Some(file) = file {
+            self.start_task(move || {
+                Ok(Message::Flags(FlagList::read(&file).with_context(
+                    || tr!("error-flags", file = file.display().to_string()),
+                )?))
+            });
+        }
+    }
+
+    fn build_vanilla_index(&mut self) {
+        if let Some(dump) = self.settings.game_dump.clone() {
+            self.start_task(move || Ok(Message::Vanilla(VanillaIndex::build(&dump)?)));
+        }
+    }
+
+    /// Shows the typical static parameters of the selected entry's class in
+    /// the game's own programs, and every entry using it.
+    fn render_vanilla(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_vanilla {
+            return;
+        }
+        let mut show = true;
+        let mut build = false;
+        let mut set = None;
+        let doc = match self.docs.get(self.active) {
+            Some(doc) => doc,
+            None => return,
+        };
+        let class = doc
+            .aiprog
+            .entry_name_from_index(doc.selected_ai)
+            .unwrap_or_default()
+            .to_owned();
+        egui::Window::new(tr!("window-vanilla-examples"))
+            .open(&mut show)
+            .default_width(450.0)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                let index = match &self.vanilla {
+                    Some(index) => index,
+                    None => {
+                        if self.settings.game_dump.is_some() {
+                            ui.label(tr!("vanilla-not-read"));
+                            build = ui.button(tr!("vanilla-read")).clicked();
+                        } else {
+                            ui.label(tr!("vanilla-no-dump"));
+                        }
+                        return;
+                    }
+                };
+                let uses = index.uses(&class);
+                let programs: BTreeSet<&str> = uses.iter().map(|u| u.program.as_str()).collect();
+                ui.horizontal(|ui| {
+                    ui.label(tr!(
+                        "vanilla-summary",
+                        class = class.as_str(),
+                        uses = uses.len(),
+                        programs = programs.len()
+                    ));
+                    if ui.small_button(tr!("vanilla-read-again")).clicked() {
+                        build = true;
+                    }
+                });
+                let typical = index.typical_values(&class);
+                if !typical.is_empty() {
+                    egui::CollapsingHeader::new(tr!("section-typical-values"))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            egui::Grid::new("vanilla_typical")
+                                .num_columns(4)
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for value in typical {
+                                        ui.label(try_name(value.key));
+                                        ui.label(param_string(&value.value));
+                                        ui.label(tr!(
+                                            "vanilla-count",
+                                            count = value.count,
+                                            total = value.total
+                                        ))
+                                        .on_hover_text(tr!("vanilla-count-hover"));
+                                        if ui
+                                            .small_button(tr!("vanilla-use"))
+                                            .on_hover_text(tr!("vanilla-use-hover"))
+                                            .clicked()
+                                        {
+                                            set = Some((value.key, value.value.clone()));
+                                        }
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                }
+                egui::CollapsingHeader::new(tr!("section-uses")).show(ui, |ui| {
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            for (i, class_use) in uses.iter().enumerate() {
+                                let actors = index
+                                    .actors
+                                    .get(&class_use.program)
+                                    .map(|actors| actors.join(", "))
+                                    .unwrap_or_default();
+                                egui::CollapsingHeader::new(format!(
+                                    "{}: {}",
+                                    class_use.program, class_use.name
+                                ))
+                                .id_source(("vanilla_use", i))
+                                .show(ui, |ui| {
+                                    ui.label(tr!("vanilla-actors", actors = actors));
+                                    egui::Grid::new(("vanilla_use_grid", i))
+                                        .num_columns(2)
+                                        .show(ui, |ui| {
+                                            for (k, v) in &class_use.sinst {
+                                                ui.label(try_name(*k));
+                                                ui.label(param_string(v));
+                                                ui.end_row();
+                                            }
+                                        });
+                                });
+                            }
+                        });
+                });
+            });
+        if build {
+            self.build_vanilla_index();
+        }
+        if let Some((key, value)) = set {
+            if let Some(doc) = self.doc_mut() {
+                let selected = doc.selected_ai;
+                let objects = doc.aiprog.item_mut_at_index(selected).objects_mut();
+                if objects.get(hash_name("SInst")).is_none() {
+                    objects
+                        .inner_mut()
+                        .insert(hash_name("SInst"), ParameterObject::new());
+                }
+                if let Some(sinst) = objects.get_mut(hash_name("SInst")) {
+                    sinst.params_mut().insert(key, value);
+                }
+            }
+        }
+        self.show_vanilla = show;
+    }
+
+    fn render_deploy(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_deploy {
+            return;
+        }
+        let mut show = self.show_deploy;
+        let mut deploy_now = false;
+        let has_doc = self.doc().is_some();
+        let platform = self.settings.platform;
+        let deploy = &mut self.session.deploy;
+        egui::Window::new(tr!("window-deploy"))
+            .open(&mut show)
+            .default_width(400.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.horizontal(|ui| {
+                    ui.label(tr!("deploy-mod-folder"));
+                    ui.label(
+                        deploy
+                            .dir
+                            .as_ref()
+                            .map(|dir| dir.display().to_string())
+                            .unwrap_or_else(|| tr!("settings-not-set")),
+                    );
+                    if dialog_button(ui, true, egui::Button::new(tr!("button-browse"))).clicked() {
+                        if let Some(dir) = FileDialog::new().pick_folder() {
+                            if deploy.dir.is_none() {
+                                deploy.layout = platform.deploy_layout();
+                            }
+                            deploy.dir = Some(dir);
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr!("deploy-layout"));
+                    for layout in [
+                        DeployLayout::Bcml,
+                        DeployLayout::GraphicPack,
+                        DeployLayout::LayeredFs,
+                    ] {
+                        ui.radio_value(&mut deploy.layout, layout, layout.name());
+                    }
+                });
+                ui.checkbox(&mut deploy.on_save, tr!("deploy-on-save"));
+                ui.checkbox(&mut deploy.repack, tr!("deploy-repack"));
+                ui.horizontal(|ui| {
+                    ui.label(tr!("deploy-command"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut deploy.command)
+                            .hint_text(tr!("deploy-command-hint")),
+                    );
+                });
+                if !self.deployed.is_empty() {
+                    ui.label(tr!("deploy-last"));
+                    for file in &self.deployed {
+                        ui.label(file.display().to_string());
+                    }
+                }
+                deploy_now = ui
+                    .add_enabled(
+                        has_doc && deploy.dir.is_some(),
+                        egui::Button::new(tr!("deploy-now")),
+                    )
+                    .on_hover_text("Ctrl+B")
+                    .clicked();
+            });
+        if deploy_now {
+            self.deploy_active();
+        }
+        self.show_deploy = show;
+    }
+
+    fn render_exit(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_exit {
+            return;
+        }
+        let (mut save, mut discard, mut cancel) = (false, false, false);
+        egui::Window::new(tr!("window-unsaved"))
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.label(tr!("exit-unsaved"));
+                for doc in self.docs.iter().filter(|doc| doc.is_dirty()) {
+                    ui.label(doc.name());
+                }
+                ui.horizontal(|ui| {
+                    save = ui.button(tr!("file-save")).clicked();
+                    discard = ui.button(tr!("button-discard")).clicked();
+                    cancel = ui.button(tr!("button-cancel")).clicked();
+                });
+            });
+        if save {
+            self.show_exit = false;
+            self.save_all_and_exit();
+        } else if discard {
+            self.show_exit = false;
+            self.exit_confirmed = true;
+        } else if cancel {
+            self.show_exit = false;
+        }
+    }
+
+    fn render_recovery(&mut self, ctx: &egui::CtxRef) {
+        if self.recovery.is_empty() {
+            return;
+        }
+        let mut restore = false;
+        let mut discard = false;
+        egui::Window::new(tr!("window-recover"))
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.label(tr!("recover-files"));
+                for (file, _) in &self.recovery {
+                    ui.label(file.display().to_string());
+                }
+                ui.horizontal(|ui| {
+                    restore = ui.button(tr!("button-restore")).clicked();
+                    discard = ui.button(tr!("button-discard")).clicked();
+                });
+            });
+        if restore {
+            let backups = std::mem::take(&mut self.recovery);
+            self.start_task(move || autosave::load_backups(&backups).map(Message::Recovered));
+        } else if discard {
+            self.recovery.clear();
+            if let Err(e) = autosave::discard_backups() {
+                self.show_error(e);
+            }
+        }
+    }
+
+    fn render_add(&mut self, ctx: &egui::CtxRef) {
+        let mut show = self.show_add;
+        let tab = match self.doc() {
+            Some(doc) => doc.tab,
+            None => return,
+        };
+        if self.show_add {
+            let mut add = false;
+            egui::Window::new(tr!("window-add-entry"))
+                .open(&mut show)
+                .default_width(250.0)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.spacing_mut().item_spacing.y = 9.0;
+                    ui.label(tr!("add-entry-class"));
+                    let text_class = egui::TextEdit::singleline(&mut self.add_class)
+                        .lock_focus(true)
+                        .hint_text(tr!("add-entry-class-hint"));
+                    let res = ui.add(text_class);
+                    let classes = AIDEFS.get_classes(&tab);
+                    if !classes.contains(&self.add_class.as_str()) {
+                        autocomplete_popup(
+                            &mut self.add_class,
+                            &mut self.add_ac_state,
+                            classes.as_slice(),
+                            ui,
+                            &res,
+                        );
+                    }
+                    // egui::ComboBox::from_label("ClassName")
+                    //     .selected_text(self.add_class.clone())
+                    //     .width(ui.spacing().text_edit_width)
+                    //     .show_ui(ui, |ui| {
+                    //         AIDEFS.classes(&doc.tab).for_each(|class| {
+                    //             ui.selectable_value(
+                    //                 &mut self.add_class,
+                    //                 class.clone(),
+                    //                 class.clone(),
+                    //             );
+                    //         });
+                    //     });
+                    ui.horizontal(|ui| {
+                        if ui.button(tr!("button-close")).clicked() {
+                            self.show_add = false;
+                        }
+                        if ui
+                            .add_enabled(
+                                !self.add_class.is_empty(),
+                                egui::Button::new(tr!("button-ok")),
+                            )
+                            .clicked()
+                        {
+                            add = true;
+                        }
+                    });
+                });
+            if add {
+                let doc = self.docs.get_mut(self.active).unwrap();
+                match doc.edit(|aiprog| aiprog.add_entry(tab, self.add_class.clone())) {
+                    Ok(i) => {
+                        doc.selected_ai = i;
+                        self.update_tree();
+                    }
+                    Err(e) => self.show_error(e),
+                };
+                self.show_add = false;
+                self.add_class = String::new();
+            }
+            if !show {
+                self.show_add = false;
+            }
+        }
+    }
+
+    #[allow(unused_must_use)]
+    fn render_confirm(&mut self, ctx: &egui::CtxRef) {
+        let mut show = self.show_confirm;
+        if self.show_confirm {
+            egui::Window::new(tr!("window-confirm"))
+                .open(&mut show)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.spacing_mut().item_spacing.y = 9.0;
+                    ui.label(self.confirm_text.as_ref().unwrap());
+                    ui.horizontal(|ui| {
+                        if ui.button(tr!("button-close")).clicked() {
+                            self.show_confirm = false;
+                        }
+                        if ui.button(tr!("button-ok")).clicked() {
+                            self.show_confirm = false;
+                            self.messengers.0.send(Ok(self
+                                .confirm_msg
+                                .as_ref()
+                                .unwrap()
+                                .to_owned()));
+                        }
+                    });
+                });
+            if !show || !self.show_confirm {
+                self.confirm_msg = None;
+                self.confirm_text = None;
+            }
+        }
+    }
+
+    fn render_def(&mut self, ctx: &egui::CtxRef) {
+        if self.show_def {
+            let mut show = self.show_def;
+            let doc = match self.docs.get(self.active) {
+                Some(doc) => doc,
+                None => return,
+            };
+            let aiprog = &doc.aiprog;
+            let class = aiprog
+                .entry_name_from_index(doc.selected_ai)
+                .unwrap_or("???");
+            egui::Window::new(tr!("window-ai-def", class = class))
+                .open(&mut show)
+                .fixed_size(egui::vec2(425.0, 400.0))
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.spacing_mut().item_spacing.y = 9.0;
+                    if let Some(def) = match doc.tab {
+                        Category::AI => &AIDEFS.ais,
+                        Category::Action => &AIDEFS.actions,
+                        Category::Behaviour => &AIDEFS.behaviors,
+                        Category::Query => &AIDEFS.querys,
+                    }
+                    .get(class)
+                    {
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            let mut text = serde_json::to_string_pretty(def).unwrap();
+                            ui.add_enabled(
+                                false,
+                                egui::TextEdit::multiline(&mut text)
+                                    .code_editor()
+                                    .desired_rows(20)
+                                    .desired_width(ui.available_width()),
+                            );
                         });
+                    }
+                    if ui.button(tr!("button-close")).clicked() {
+                        self.show_def = false;
+                    }
+                });
+            if !show {
+                self.show_def = false;
+            }
+        }
+    }
+
+    fn render_dry_run(&mut self, ctx: &egui::CtxRef) {
+        let (dry_run, doc) = match (self.dry_run.as_mut(), self.docs.get_mut(self.active)) {
+            (Some(dry_run), Some(doc)) => (dry_run, doc),
+            _ => return,
+        };
+        let mut show = true;
+        let mut goto = None;
+        egui::Window::new(tr!("window-dry-run"))
+            .open(&mut show)
+            .default_width(450.0)
+            .show(ctx, |ui| {
+                goto = dry_run.ui(ui, &doc.aiprog);
+            });
+        if let Some(idx) = goto {
+            doc.navigate(idx);
+        }
+        if !show {
+            self.dry_run = None;
+        }
+    }
+
+    fn render_compare(&mut self, ctx: &egui::CtxRef) {
+        let ((a, b), doc) = match (self.compare, self.docs.get_mut(self.active)) {
+            (Some(pair), Some(doc)) if pair.0.max(pair.1) < doc.aiprog.len() => (pair, doc),
+            _ => {
+                self.compare = None;
+                return;
+            }
+        };
+        let mut show = true;
+        let mut goto = None;
+        let mut swap = false;
+        let diff_only = &mut self.compare_diff_only;
+        egui::Window::new(tr!("window-compare"))
+            .open(&mut show)
+            .default_width(550.0)
+            .show(ctx, |ui| {
+                let aiprog = &doc.aiprog;
+                let rows = aiprog.compare_entries(a, b);
+                ui.horizontal(|ui| {
+                    ui.checkbox(diff_only, tr!("compare-diff-only"));
+                    swap = ui.button(tr!("compare-swap")).clicked();
+                    ui.label(tr!(
+                        "compare-summary",
+                        differ = rows.iter().filter(|row| row.differs()).count(),
+                        total = rows.len()
+                    ));
+                });
+                egui::ScrollArea::vertical()
+                    .max_height(450.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("compare_grid")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.label("");
+                                for idx in [a, b] {
+                                    if ui.small_button(aiprog.entry_label(idx)).clicked() {
+                                        goto = Some(idx);
+                                    }
+                                }
+                                ui.end_row();
+                                for row in rows.iter().filter(|row| !*diff_only || row.differs()) {
+                                    ui.label(format!(
+                                        "{}.{}",
+                                        try_name(row.object),
+                                        try_name(row.key)
+                                    ));
+                                    for value in [&row.left, &row.right] {
+                                        let text = value
+                                            .as_ref()
+                                            .map(param_string)
+                                            .unwrap_or_else(|| String::from("—"));
+                                        if row.differs() {
+                                            ui.colored_label(changed_color(), text);
+                                        } else {
+                                            ui.label(text);
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
                     });
+            });
+        if let Some(idx) = goto {
+            doc.navigate(idx);
+        }
+        if swap {
+            self.compare = Some((b, a));
+        }
+        if !show {
+            self.compare = None;
+        }
+    }
+
+    fn open_find_replace(&mut self, ctx: &egui::CtxRef) {
+        let mut find_replace = self.find_replace.take().unwrap_or_default();
+        if let Some(doc) = self.doc() {
+            find_replace.refresh(&doc.aiprog);
+        }
+        self.find_replace = Some(find_replace);
+        ctx.memory().request_focus(egui::Id::new("replace_find"));
+    }
+
+    fn render_find_replace(&mut self, ctx: &egui::CtxRef) {
+        let (find_replace, doc) = match (self.find_replace.as_mut(), self.docs.get_mut(self.active))
+        {
+            (Some(find_replace), Some(doc)) => (find_replace, doc),
+            _ => return,
+        };
+        let mut show = true;
+        let mut action = None;
+        egui::Window::new(tr!("window-find-replace"))
+            .open(&mut show)
+            .default_width(450.0)
+            .show(ctx, |ui| {
+                action = find_replace.ui(ui, &doc.aiprog);
+            });
+        match action {
+            Some(ReplaceAction::Goto(idx)) => doc.navigate(idx),
+            Some(ReplaceAction::Apply(hits)) => {
+                let mut aiprog = doc.aiprog.clone();
+                find_replace.applied = Some(aiprog.apply_replace(&hits));
+                find_replace.refresh(&aiprog);
+                doc.restore(aiprog);
+                self.update_tree();
             }
+            None => (),
+        }
+        if !show {
+            self.find_replace = None;
         }
-        update_tree
     }
 
-    fn render_ai_children(&mut self, ui: &mut Ui) -> bool {
-        let mut update_tree = false;
-        if self.aiprog.is_some() {
-            let mut update: Option<(usize, String)> = None;
-            let aiprog = self.aiprog.as_mut().unwrap();
-            let ai_name = match aiprog
-                .item_at_index(self.selected_ai)
-                .objects()
-                .get(hash_name("Def"))
-                .unwrap()
-                .params()
-                .get(&hash_name("Name"))
-            {
-                Some(n) => n,
-                None => return false,
+    fn render_validation(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_validation {
+            return;
+        }
+        if let Some(doc) = self.docs.get(self.active) {
+            let aiprog = &doc.aiprog;
+            let mut show = self.show_validation;
+            let mut goto = None;
+            egui::Window::new(tr!("window-validation"))
+                .open(&mut show)
+                .default_width(450.0)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.spacing_mut().item_spacing.y = 9.0;
+                    if self.validation.is_empty() {
+                        ui.label(tr!("validation-none"));
+                    } else {
+                        ui.label(tr!("validation-found", count = self.validation.len()));
+                        goto = Self::render_issues(ui, "validation", aiprog, &self.validation);
+                    }
+                    if ui.button(tr!("button-close")).clicked() {
+                        self.show_validation = false;
+                    }
+                });
+            if let Some(idx) = goto {
+                self.docs[self.active].selected_ai = idx;
             }
-            .as_string()
-            .unwrap()
-            .to_string();
-            let ai_count = aiprog.actions_offset();
-            if aiprog
-                .item_at_index(self.selected_ai)
-                .objects()
-                .get(hash_name("ChildIdx"))
-                .is_some()
-            {
-                egui::CollapsingHeader::new("Children")
-                    .default_open(true)
+            if !show {
+                self.show_validation = false;
+            }
+        }
+    }
+
+    /// Lists problems found in a program, returning an entry whose button was
+    /// clicked to go to it.
+    fn render_issues(ui: &mut Ui, id: &str, aiprog: &AIProgram, issues: &[Issue]) -> Option<usize> {
+        let mut goto = None;
+        egui::ScrollArea::vertical()
+            .id_source(id)
+            .max_height(300.0)
+            .show(ui, |ui| {
+                egui::Grid::new(id)
+                    .num_columns(2)
+                    .striped(true)
                     .show(ui, |ui| {
-                        egui::Grid::new("child_idx").num_columns(2).show(ui, |ui| {
-                            for (k, v) in aiprog
-                                .item_mut_at_index(self.selected_ai)
-                                .objects_mut()
-                                .get_mut(hash_name("ChildIdx"))
-                                .unwrap()
-                                .params_mut()
-                                .iter_mut()
-                                .map(|(k, v)| (k, v.as_mut_int().unwrap()))
-                            {
-                                let child_name = try_name(*k);
-                                ui.label(
-                                    JPEN_MAP
-                                        .get(&child_name.as_str())
-                                        .unwrap_or(&child_name)
-                                        .to_owned(),
-                                );
-                                let names = &self.cache["child_names"];
-                                egui::ComboBox::from_id_source(k)
-                                    .selected_text(
-                                        names
-                                            .get(*v as usize)
-                                            .map(|name| {
-                                                format!(
-                                                    "{}_{}. {}",
-                                                    if *v < ai_count as i32 {
-                                                        "AI"
-                                                    } else {
-                                                        "Action"
-                                                    },
-                                                    v,
-                                                    name
-                                                )
-                                            })
-                                            .unwrap_or_else(|| String::from("[NOT SET]"))
-                                            .clone(),
-                                    )
-                                    .width(ui.spacing().text_edit_width)
-                                    .show_ui(ui, |ui| {
-                                        names.iter().enumerate().for_each(|(i, name)| {
-                                            let value = ui.selectable_value(v, i as i32, {
-                                                if i < ai_count {
-                                                    format!("AI_{}. {}", i, name)
-                                                } else {
-                                                    format!("Action_{}. {}", i - ai_count, name)
+                        for issue in issues {
+                            match issue.entry {
+                                Some(idx) if idx < aiprog.len() => {
+                                    if ui.small_button(aiprog.entry_label(idx)).clicked() {
+                                        goto = Some(idx);
+                                    }
+                                }
+                                _ => {
+                                    ui.label("DemoAIActionIdx");
+                                }
+                            }
+                            ui.label(issue_text(&issue.kind));
+                            ui.end_row();
+                        }
+                    });
+            });
+        goto
+    }
+
+    fn render_project_issues(&mut self, ctx: &egui::CtxRef) {
+        let (project, report) = match (&self.project, &self.project_issues) {
+            (Some(project), Some(report)) => (project, report),
+            _ => return,
+        };
+        let mut show = true;
+        let mut goto = None;
+        let mut export = false;
+        egui::Window::new(tr!("window-project-validation"))
+            .open(&mut show)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                let failed: Vec<&FileReport> = report.failed().collect();
+                if failed.is_empty() {
+                    ui.label(tr!("validation-project-none", files = report.files.len()));
+                } else {
+                    ui.label(tr!(
+                        "validation-project-found",
+                        failed = failed.len(),
+                        files = report.files.len()
+                    ));
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        for file in failed {
+                            egui::CollapsingHeader::new(format!(
+                                "{} ({})",
+                                project.relative(&file.file),
+                                file.count()
+                            ))
+                            .id_source(&file.file)
+                            .show(ui, |ui| {
+                                if let Some(error) = &file.error {
+                                    ui.label(tr!("validation-load-failed", error = error.as_str()));
+                                }
+                                egui::Grid::new(("project_issues", &file.file))
+                                    .num_columns(2)
+                                    .striped(true)
+                                    .show(ui, |ui| {
+                                        for issue in &file.issues {
+                                            match issue.entry {
+                                                Some(idx) => {
+                                                    if ui.small_button(&issue.label).clicked() {
+                                                        goto = Some((file.file.clone(), idx));
+                                                    }
                                                 }
-                                            });
-                                            if value.changed() {
-                                                update_tree = true;
-                                                if value.clicked() {
-                                                    update = Some((i, child_name.clone()));
+                                                None => {
+                                                    ui.label(&issue.label);
                                                 }
                                             }
-                                        });
+                                            ui.label(issue_text(&issue.kind));
+                                            ui.end_row();
+                                        }
                                     });
-                                ui.end_row();
+                            });
+                        }
+                    });
+                export = dialog_button(ui, true, egui::Button::new(tr!("validation-export")))
+                    .on_hover_text(tr!("validation-export-hover"))
+                    .clicked();
+            });
+        if export {
+            if let Some(file) = FileDialog::new()
+                .add_filter("JSON", &["json"])
+                .add_filter("Text", &["txt"])
+                .save_file()
+            {
+                let report = self.project_issues.as_ref().unwrap();
+                let text = if file.extension().map(|ext| ext == "json").unwrap_or(false) {
+                    report.to_json()
+                } else {
+                    Ok(report.to_text())
+                };
+                if let Err(e) = text.and_then(|text| Ok(std::fs::write(&file, text)?)) {
+                    self.show_error(e);
+                }
+            }
+        }
+        if !show {
+            self.project_issues = None;
+        }
+        if let Some((file, idx)) = goto {
+            self.open_file_at(file, idx);
+        }
+    }
+
+    fn render_save_issues(&mut self, ctx: &egui::CtxRef) {
+        let (issues, doc) = match (&self.save_issues, self.docs.get(self.active)) {
+            (Some(issues), Some(doc)) => (issues, doc),
+            _ => return,
+        };
+        let mut show = true;
+        let mut goto = None;
+        let mut save = false;
+        let mut cancel = false;
+        egui::Window::new(tr!("window-problems"))
+            .open(&mut show)
+            .default_width(450.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.label(tr!(
+                    "validation-save-problems",
+                    file = doc.name(),
+                    count = issues.len()
+                ));
+                goto = Self::render_issues(ui, "save_issues", &doc.aiprog, issues);
+                ui.horizontal(|ui| {
+                    save = ui.button(tr!("validation-save-anyway")).clicked();
+                    cancel = ui.button(tr!("button-cancel")).clicked();
+                });
+            });
+        if let Some(idx) = goto {
+            self.docs[self.active].selected_ai = idx;
+        }
+        if save {
+            self.write_file();
+        }
+        if cancel || !show {
+            self.deploy_next_save = false;
+        }
+        if save || cancel || !show {
+            self.save_issues = None;
+        }
+    }
+
+    fn render_settings(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_settings {
+            return;
+        }
+        let mut show = self.show_settings;
+        self.settings.angle_display = angle_display();
+        let mut settings = self.settings.clone();
+        let mut error = None;
+        egui::Window::new(tr!("window-settings"))
+            .open(&mut show)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("settings_grid")
+                    .num_columns(2)
+                    .spacing([8.0, 9.0])
+                    .show(ui, |ui| {
+                        ui.label(tr!("settings-language"));
+                        ui.horizontal(|ui| {
+                            for language in Language::ALL {
+                                ui.radio_value(&mut settings.language, language, language.name());
+                            }
+                        });
+                        ui.end_row();
+                        ui.label(tr!("settings-theme"));
+                        ui.horizontal(|ui| {
+                            ui.radio_value(
+                                &mut settings.theme,
+                                Theme::Dark,
+                                tr!("settings-theme-dark"),
+                            );
+                            ui.radio_value(
+                                &mut settings.theme,
+                                Theme::Light,
+                                tr!("settings-theme-light"),
+                            );
+                        });
+                        ui.end_row();
+                        ui.label(tr!("settings-colors"))
+                            .on_hover_text(tr!("settings-colors-hover"));
+                        ui.vertical(|ui| {
+                            ui.horizontal(|ui| {
+                                for palette in Palette::ALL {
+                                    ui.radio_value(&mut settings.palette, palette, palette.name());
+                                }
+                            });
+                            ui.horizontal(|ui| match settings.palette.colors(settings.theme) {
+                                Some(colors) => {
+                                    for (rgb, marker) in [
+                                        (colors.ok, "✔"),
+                                        (colors.warning, "⚠"),
+                                        (colors.error, "❌"),
+                                        (colors.changed, "•"),
+                                    ] {
+                                        let [r, g, b] = rgb;
+                                        ui.colored_label(egui::Color32::from_rgb(r, g, b), marker);
+                                    }
+                                }
+                                None => {
+                                    let colors = &mut settings.custom_colors;
+                                    for (rgb, marker) in [
+                                        (&mut colors.ok, "✔"),
+                                        (&mut colors.warning, "⚠"),
+                                        (&mut colors.error, "❌"),
+                                        (&mut colors.changed, "•"),
+                                    ] {
+                                        ui.color_edit_button_srgb(rgb);
+                                        ui.label(marker);
+                                    }
+                                }
+                            });
+                        });
+                        ui.end_row();
+                        ui.label(tr!("settings-font-size"));
+                        ui.add(egui::Slider::new(&mut settings.font_scale, 0.8..=2.0));
+                        ui.end_row();
+                        ui.label(tr!("settings-ui-scale"));
+                        ui.add(egui::Slider::new(&mut settings.ui_scale, 0.5..=2.0));
+                        ui.end_row();
+                        ui.label(tr!("settings-names"));
+                        ui.horizontal(|ui| {
+                            ui.radio_value(
+                                &mut settings.name_display,
+                                NameDisplay::English,
+                                tr!("settings-names-english"),
+                            );
+                            ui.radio_value(
+                                &mut settings.name_display,
+                                NameDisplay::Japanese,
+                                tr!("settings-names-japanese"),
+                            );
+                            ui.radio_value(
+                                &mut settings.name_display,
+                                NameDisplay::Both,
+                                tr!("settings-both"),
+                            );
+                        });
+                        ui.end_row();
+                        ui.label(tr!("settings-entry-numbers"))
+                            .on_hover_text(tr!("settings-entry-numbers-hover"));
+                        ui.horizontal(|ui| {
+                            ui.radio_value(
+                                &mut settings.index_display,
+                                IndexDisplay::PerCategory,
+                                tr!("settings-entry-numbers-per-category"),
+                            );
+                            ui.radio_value(
+                                &mut settings.index_display,
+                                IndexDisplay::Absolute,
+                                tr!("settings-entry-numbers-absolute"),
+                            );
+                            ui.radio_value(
+                                &mut settings.index_display,
+                                IndexDisplay::Both,
+                                tr!("settings-both"),
+                            );
+                        });
+                        ui.end_row();
+                        ui.label(tr!("settings-angles"))
+                            .on_hover_text(tr!("settings-angles-hover"));
+                        ui.horizontal(|ui| {
+                            ui.radio_value(
+                                &mut settings.angle_display,
+                                AngleUnit::Degrees,
+                                tr!("settings-angles-degrees"),
+                            );
+                            ui.radio_value(
+                                &mut settings.angle_display,
+                                AngleUnit::Radians,
+                                tr!("settings-angles-radians"),
+                            );
+                        });
+                        ui.end_row();
+                        ui.label(tr!("settings-backups"))
+                            .on_hover_text(tr!("settings-backups-hover"));
+                        ui.add(
+                            egui::DragValue::new(&mut settings.backup_count).clamp_range(0..=50),
+                        );
+                        ui.end_row();
+                        ui.label(tr!("settings-game-dump"))
+                            .on_hover_text(tr!("settings-game-dump-hover"));
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                settings
+                                    .game_dump
+                                    .as_ref()
+                                    .map(|dump| dump.display().to_string())
+                                    .unwrap_or_else(|| tr!("settings-not-set")),
+                            );
+                            if dialog_button(ui, true, egui::Button::new(tr!("button-browse")))
+                                .clicked()
+                            {
+                                if let Some(dump) = FileDialog::new().pick_folder() {
+                                    settings.game_dump = Some(dump);
+                                }
+                            }
+                            if settings.game_dump.is_some()
+                                && ui.button(tr!("button-clear")).clicked()
+                            {
+                                settings.game_dump = None;
+                            }
+                        });
+                        ui.end_row();
+                        ui.label(tr!("settings-flags"))
+                            .on_hover_text(tr!("settings-flags-hover"));
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                settings
+                                    .flag_list
+                                    .as_ref()
+                                    .map(|file| file.display().to_string())
+                                    .unwrap_or_else(|| tr!("settings-not-set")),
+                            );
+                            if dialog_button(ui, true, egui::Button::new(tr!("button-browse")))
+                                .clicked()
+                            {
+                                if let Some(file) = FileDialog::new()
+                                    .add_filter("Game Data", &["ssarc", "sarc", "pack"])
+                                    .add_filter("Flag List", &["txt"])
+                                    .pick_file()
+                                {
+                                    settings.flag_list = Some(file);
+                                }
+                            }
+                            if ui
+                                .add_enabled(
+                                    settings.game_dump.is_some(),
+                                    egui::Button::new(tr!("settings-flags-from-dump")),
+                                )
+                                .on_hover_text(tr!("settings-flags-from-dump-hover"))
+                                .clicked()
+                            {
+                                match dump_bootup_pack(settings.game_dump.as_ref().unwrap()) {
+                                    Ok(pack) => settings.flag_list = Some(pack),
+                                    Err(e) => error = Some(e),
+                                }
+                            }
+                            if settings.flag_list.is_some()
+                                && ui.button(tr!("button-clear")).clicked()
+                            {
+                                settings.flag_list = None;
                             }
                         });
+                        ui.end_row();
+                        ui.label(tr!("settings-new-files"))
+                            .on_hover_text(tr!("settings-new-files-hover"));
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut settings.save_format, SaveFormat::Yaml, "YAML");
+                            ui.radio_value(
+                                &mut settings.save_format,
+                                SaveFormat::Binary,
+                                tr!("settings-new-files-binary"),
+                            );
+                        });
+                        ui.end_row();
+                        ui.label(tr!("settings-platform"))
+                            .on_hover_text(tr!("settings-platform-hover"));
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut settings.platform, Platform::WiiU, "Wii U");
+                            ui.radio_value(&mut settings.platform, Platform::Switch, "Switch");
+                        });
+                        ui.end_row();
                     });
+                if let Ok(file) = Settings::file() {
+                    ui.add(
+                        egui::Label::new(tr!(
+                            "settings-saved-in",
+                            file = file.display().to_string()
+                        ))
+                        .weak(),
+                    );
+                }
+                if ui.button(tr!("settings-reset")).clicked() {
+                    // Finding the dump again would be a chore, and the language is
+                    // kept so the window can still be read
+                    settings = Settings {
+                        language: settings.language,
+                        game_dump: settings.game_dump.take(),
+                        flag_list: settings.flag_list.take(),
+                        ..Settings::default()
+                    };
+                }
+            });
+        if settings.game_dump != self.settings.game_dump {
+            self.set_game_dump(settings.game_dump.clone());
+        }
+        if let Some(e) = error {
+            self.show_error(e);
+        }
+        if settings.flag_list != self.settings.flag_list {
+            self.set_flag_list(settings.flag_list.clone());
+        }
+        if settings != self.settings {
+            let names_changed = settings.name_display != self.settings.name_display
+                || settings.index_display != self.settings.index_display;
+            self.settings = settings;
+            self.settings.apply(ctx, &self.fonts, self.native_ppp);
+            if names_changed {
+                self.refresh_names();
             }
-            if let Some((i, s)) = update {
-                match aiprog.update_names(i, s, ai_name) {
-                    Ok(_) => (),
-                    Err(e) => {
-                        self.error = Some(e.to_string());
-                        self.show_error = true;
-                    }
-                };
-            };
-            if update_tree {
-                self.cache.insert(
-                    "child_names",
-                    (0..aiprog.behaviors_offset())
-                        .into_iter()
-                        .map(|i| aiprog.entry_name_from_index(i).unwrap().to_owned())
-                        .collect(),
-                );
+        }
+        if !show {
+            self.show_settings = false;
+        }
+    }
+
+    /// Asks before deleting the selected entry, listing the slots which will
+    /// be cleared and how many indexes will shift.
+    fn confirm_delete(&mut self) {
+        if let Some(doc) = self.doc() {
+            let idx = doc.selected_ai;
+            let preview = doc.aiprog.delete_preview(idx);
+            let text = delete_text(&preview, &doc.aiprog);
+            let children = doc.aiprog.children_of(idx);
+            if preview.breaks_references() && !children.is_empty() {
+                self.delete_choice = Some(DeleteChoice {
+                    doc: doc.version(),
+                    entry: idx,
+                    mode: DeleteMode::Orphan,
+                    text,
+                    children,
+                    subtree: doc.aiprog.exclusive_subtree(idx).len(),
+                });
+            } else {
+                let message = Message::Delete(doc.version(), idx, DeleteMode::Orphan);
+                self.show_confirm(&text, message);
+            }
+        }
+    }
+
+    /// Asks before deleting the selected entry along with its descendants
+    /// which nothing else uses, listing them.
+    fn confirm_delete_subtree(&mut self) {
+        if let Some(doc) = self.doc() {
+            let idx = doc.selected_ai;
+            let subtree = doc.aiprog.exclusive_subtree(idx);
+            let mut text = delete_text(&doc.aiprog.delete_preview(idx), &doc.aiprog);
+            if subtree.len() > 1 {
+                text.push_str(&format!("\n\n{}\n", tr!("delete-subtree-too")));
+                for i in subtree.iter().filter(|i| **i != idx) {
+                    text.push_str(&format!("  {}\n", doc.aiprog.entry_label(*i)));
+                }
             }
+            let message = Message::Delete(doc.version(), idx, DeleteMode::Recursive);
+            self.show_confirm(&text, message);
+        }
+    }
+
+    /// Reorders the entries of the active program, following a base program
+    /// read from a file if given.
+    fn normalize_order(&mut self, base: Option<PathBuf>) {
+        if let Some(doc) = self.doc() {
+            let (version, mut aiprog) = (doc.version(), doc.aiprog.clone());
+            self.start_task(move || {
+                let base = base.map(AIProgram::new).transpose()?;
+                let moved = aiprog.normalize_order(base.as_ref())?;
+                Ok(Message::Normalized(version, aiprog, moved))
+            });
+        }
+    }
+
+    /// Asks before deleting every selected entry in one go.
+    fn confirm_delete_selected(&mut self) {
+        if let Some(doc) = self.doc() {
+            let indexes = doc.multi_selected.clone();
+            let text =
+                delete_entries_text(&doc.aiprog.delete_entries_preview(&indexes), &doc.aiprog);
+            let message = Message::DeleteEntries(doc.version(), indexes);
+            self.show_confirm(&text, message);
+        }
+    }
+
+    /// Opens the window to move an entry of the active document between the
+    /// AI and Action lists.
+    fn start_convert(&mut self, idx: usize) {
+        if let Some(doc) = self.doc_mut() {
+            // So what was typed into the entry before this counts as before
+            doc.commit_editor();
+            self.convert = Some((doc.version(), idx, String::new()));
+        }
+    }
+
+    /// Asks for the class an AI or Action takes on when moved to the other
+    /// list.
+    fn render_convert(&mut self, ctx: &egui::CtxRef) {
+        let (version, idx, class) = match &mut self.convert {
+            Some(convert) => convert,
+            None => return,
+        };
+        // Shown for the document it was opened from, even after switching
+        let aiprog = match self.docs.iter().find(|doc| doc.id == version.id) {
+            Some(doc) if *idx < doc.aiprog.len() => &doc.aiprog,
+            _ => {
+                self.convert = None;
+                return;
+            }
+        };
+        let target = match aiprog.category_of(*idx) {
+            Category::AI => Category::Action,
+            _ => Category::AI,
+        };
+        let mut show = true;
+        let mut convert = false;
+        egui::Window::new(tr!("window-convert", category = target.to_string()))
+            .open(&mut show)
+            .default_width(250.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.label(tr!(
+                    "convert-class",
+                    category = target.to_string(),
+                    entry = aiprog.entry_label(*idx)
+                ));
+                let res = ui.add(
+                    egui::TextEdit::singleline(class)
+                        .lock_focus(true)
+                        .hint_text(tr!("convert-class-hint")),
+                );
+                let classes = AIDEFS.get_classes(target);
+                if !classes.contains(&class.as_str()) {
+                    autocomplete_popup(
+                        class,
+                        &mut self.convert_ac_state,
+                        classes.as_slice(),
+                        ui,
+                        &res,
+                    );
+                }
+                convert = ui
+                    .add_enabled(
+                        classes.contains(&class.as_str()),
+                        egui::Button::new(tr!("convert-convert")),
+                    )
+                    .clicked();
+            });
+        if convert {
+            let (version, idx, class) = self.convert.take().unwrap();
+            self.messengers
+                .0
+                .send(Ok(Message::Convert(version, idx, class)))
+                .unwrap();
+        } else if !show {
+            self.convert = None;
+        }
+    }
+
+    /// Offers what to do with the children of an entry being deleted when
+    /// other entries use it, so the tree isn't just cut apart.
+    fn render_delete_choice(&mut self, ctx: &egui::CtxRef) {
+        let aiprog = match (&self.delete_choice, self.docs.get(self.active)) {
+            (Some(_), Some(doc)) => &doc.aiprog,
+            _ => return,
+        };
+        let choice = self.delete_choice.as_mut().unwrap();
+        let mut show = true;
+        let mut delete = false;
+        let mut cancel = false;
+        egui::Window::new(tr!("window-delete"))
+            .open(&mut show)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.label(&choice.text);
+                ui.separator();
+                ui.label(tr!("delete-children"));
+                ui.radio_value(&mut choice.mode, DeleteMode::Orphan, tr!("delete-orphan"));
+                ui.radio_value(
+                    &mut choice.mode,
+                    DeleteMode::Recursive,
+                    tr!("delete-recursive", count = choice.subtree),
+                );
+                let splicing = matches!(choice.mode, DeleteMode::Splice(_));
+                if ui.radio(splicing, tr!("delete-splice")).clicked() && !splicing {
+                    choice.mode = DeleteMode::Splice(choice.children[0]);
+                }
+                if let DeleteMode::Splice(child) = &mut choice.mode {
+                    egui::ComboBox::from_id_source("splice_child")
+                        .selected_text(aiprog.entry_label(*child))
+                        .show_ui(ui, |ui| {
+                            for i in &choice.children {
+                                ui.selectable_value(child, *i, aiprog.entry_label(*i));
+                            }
+                        });
+                }
+                ui.horizontal(|ui| {
+                    cancel = ui.button(tr!("button-cancel")).clicked();
+                    delete = ui.button(tr!("button-delete")).clicked();
+                });
+            });
+        if delete {
+            let choice = self.delete_choice.take().unwrap();
+            self.messengers
+                .0
+                .send(Ok(Message::Delete(choice.doc, choice.entry, choice.mode)))
+                .unwrap();
+        } else if cancel || !show {
+            self.delete_choice = None;
         }
-        update_tree
     }
 
-    fn render_sinst_parameters(&mut self, ui: &mut Ui) {
-        if let Some(aiprog) = self.aiprog.as_mut() {
-            let ai = aiprog.item_mut_at_index(self.selected_ai);
-            if let Some(params) = ai.objects_mut().get_mut(hash_name("SInst")) {
-                egui::CollapsingHeader::new("Static Instance Parameters")
-                    .default_open(true)
+    /// Lists the backups of a document with when each was saved, to restore
+    /// one in place of the document's contents. Restoring can be undone.
+    fn render_backups(&mut self, ctx: &egui::CtxRef) {
+        let (id, backups) = match &self.backups {
+            Some(backups) => backups,
+            None => return,
+        };
+        let mut show = true;
+        let mut restore = None;
+        egui::Window::new(tr!("window-restore"))
+            .open(&mut show)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                if backups.is_empty() {
+                    ui.label(tr!("restore-no-backups"));
+                }
+                egui::Grid::new("backups_grid")
+                    .num_columns(3)
+                    .striped(true)
                     .show(ui, |ui| {
-                        egui::Grid::new("sinst").num_columns(2).show(ui, |ui| {
-                            for (k, v) in params.params_mut().iter_mut() {
-                                ui.label(try_name(*k));
-                                Self::render_parameter(ui, v);
-                                ui.end_row();
+                        for backup in backups {
+                            ui.label(tr!("restore-version", version = backup.version));
+                            ui.label(
+                                backup
+                                    .modified
+                                    .map(time_ago)
+                                    .unwrap_or_else(|| tr!("restore-unknown-time")),
+                            )
+                            .on_hover_text(backup.file.display().to_string());
+                            if ui.small_button(tr!("button-restore")).clicked() {
+                                restore = Some(backup.file.clone());
                             }
-                        });
+                            ui.end_row();
+                        }
                     });
-            }
+            });
+        if let Some(file) = restore {
+            let id = *id;
+            self.backups = None;
+            self.start_task(move || Ok(Message::RestoreBackup(id, AIProgram::new(&file)?)));
+        } else if !show {
+            self.backups = None;
         }
     }
 
-    fn render_parameter(ui: &mut Ui, param: &mut Parameter) {
-        match param {
-            Parameter::Bool(b) => {
-                ui.checkbox(b, "");
-            }
-            Parameter::Color(c) => {
-                ui.horizontal(|ui| {
-                    ui.label("A");
-                    ui.add(egui::DragValue::new(&mut c.a).speed(0.1));
-                    ui.label("R");
-                    ui.add(egui::DragValue::new(&mut c.r).speed(0.1));
-                    ui.label("G");
-                    ui.add(egui::DragValue::new(&mut c.g).speed(0.1));
-                    ui.label("B");
-                    ui.add(egui::DragValue::new(&mut c.b).speed(0.1));
-                });
-            }
-            Parameter::F32(f) => {
-                ui.add(egui::DragValue::new(f).speed(0.1));
-            }
-            Parameter::Int(i) => {
-                ui.add(egui::DragValue::new(i).speed(1));
-            }
-            Parameter::Quat(q) => {
-                ui.horizontal(|ui| {
-                    ui.add(egui::DragValue::new(&mut q.a).speed(0.1));
-                    ui.add(egui::DragValue::new(&mut q.b).speed(0.1));
-                    ui.add(egui::DragValue::new(&mut q.c).speed(0.1));
-                    ui.add(egui::DragValue::new(&mut q.d).speed(0.1));
-                });
-            }
-            Parameter::String256(s)
-            | Parameter::String32(s)
-            | Parameter::String64(s)
-            | Parameter::StringRef(s) => {
-                ui.text_edit_singleline(s);
-            }
-            Parameter::U32(u) => {
-                ui.add(egui::DragValue::new(u).speed(1).clamp_range(0..=usize::MAX));
-            }
-            Parameter::Vec2(v) => {
-                ui.horizontal(|ui| {
-                    ui.add(egui::DragValue::new(&mut v.x).speed(0.1));
-                    ui.add(egui::DragValue::new(&mut v.y).speed(0.1));
-                });
-            }
-            Parameter::Vec3(v) => {
-                ui.horizontal(|ui| {
-                    ui.add(egui::DragValue::new(&mut v.x).speed(0.1));
-                    ui.add(egui::DragValue::new(&mut v.y).speed(0.1));
-                    ui.add(egui::DragValue::new(&mut v.z).speed(0.1));
-                });
-            }
-            Parameter::Vec4(v) => {
-                ui.horizontal(|ui| {
-                    ui.add(egui::DragValue::new(&mut v.x).speed(0.1));
-                    ui.add(egui::DragValue::new(&mut v.y).speed(0.1));
-                    ui.add(egui::DragValue::new(&mut v.z).speed(0.1));
-                    ui.add(egui::DragValue::new(&mut v.t).speed(0.1));
-                });
-            }
-            _ => (),
+    /// Rebuilds everything showing translated names in every document.
+    fn refresh_names(&mut self) {
+        for doc in self.docs.iter_mut() {
+            doc.index.invalidate();
+        }
+        for doc in &self.docs {
+            let id = doc.id;
+            let aiprog = doc.aiprog.clone();
+            let marked = doc.marked_root_indexes();
+            let sender = self.messengers.0.clone();
+            task::spawn(move || {
+                sender
+                    .send(
+                        Tree::from_program(&aiprog, &marked, &Progress::default())
+                            .map(|(tree, orphans)| Message::Tree(id, tree, orphans)),
+                    )
+                    .ok();
+            });
         }
     }
 
-    fn render_behaviour_indexes(&mut self, ui: &mut Ui) {
-        if let Some(aiprog) = self.aiprog.as_mut() {
-            let ai = aiprog.item_mut_at_index(self.selected_ai);
-            if let Some(behaviours) = ai.objects_mut().get_mut(hash_name("BehaviorIdx")) {
-                egui::CollapsingHeader::new("Behaviour Indexes")
-                    .default_open(true)
+    fn open_translations(&mut self) {
+        self.translation_names = self
+            .doc()
+            .map(|doc| japanese_names(&doc.aiprog.0))
+            .unwrap_or_default();
+        self.translation_names
+            .extend(self.session.translations.keys().cloned());
+        self.show_translations = true;
+    }
+
+    fn render_translations(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_translations {
+            return;
+        }
+        let mut show = self.show_translations;
+        let mut refresh = false;
+        egui::Window::new(tr!("window-translations"))
+            .open(&mut show)
+            .default_width(400.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.horizontal(|ui| {
+                    ui.label(tr!("hint-filter"));
+                    ui.text_edit_singleline(&mut self.translation_filter);
+                });
+                let filter = self.translation_filter.to_lowercase();
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
                     .show(ui, |ui| {
-                        egui::Grid::new("behaviour_idxs")
-                            .num_columns(2)
+                        egui::Grid::new("translations_grid")
+                            .num_columns(3)
                             .show(ui, |ui| {
-                                let names = &self.cache["behaviour_names"];
-                                behaviours.params_mut().iter_mut().for_each(|(k, v)| {
-                                    let idx = v.as_mut_int().unwrap();
-                                    ui.label(try_name(*k));
-                                    egui::ComboBox::from_id_source(k)
-                                        .width(ui.spacing().text_edit_width)
-                                        .selected_text(format!(
-                                            "Behavior_{}. {}",
-                                            idx,
-                                            &names[(*idx) as usize]
-                                        ))
-                                        .show_ui(ui, |ui| {
-                                            names.iter().enumerate().for_each(|(i, name)| {
-                                                ui.selectable_value(
-                                                    idx,
-                                                    i as i32,
-                                                    format!("Behavior_{}. {}", i, name),
-                                                );
-                                            });
-                                        });
+                                for name in &self.translation_names {
+                                    let mut translation = english(name).unwrap_or_default();
+                                    if !filter.is_empty()
+                                        && !name.contains(&filter)
+                                        && !translation.to_lowercase().contains(&filter)
+                                    {
+                                        continue;
+                                    }
+                                    ui.label(name);
+                                    let response = ui.text_edit_singleline(&mut translation);
+                                    if response.changed() {
+                                        self.session.set_translation(name, &translation);
+                                    }
+                                    refresh |= response.lost_focus();
+                                    if self.session.translations.contains_key(name) {
+                                        if ui
+                                            .small_button("↺")
+                                            .on_hover_text(tr!("translations-reset-hover"))
+                                            .clicked()
+                                        {
+                                            self.session.set_translation(name, "");
+                                            refresh = true;
+                                        }
+                                    } else {
+                                        ui.label("");
+                                    }
                                     ui.end_row();
-                                });
+                                }
                             });
                     });
-            }
+            });
+        if refresh {
+            self.refresh_names();
+        }
+        if !show {
+            self.show_translations = false;
         }
     }
 
-    fn render_error(&mut self, ctx: &egui::CtxRef) {
-        let mut show = self.show_error;
-        if self.show_error {
-            egui::Window::new("Error")
-                .open(&mut show)
-                .collapsible(false)
-                .show(ctx, |ui| {
-                    ui.label(self.error.as_ref().unwrap());
-                    if ui.button("OK").clicked() {
-                        self.show_error = false;
+    /// Shows everything logged this run, with buttons to copy it for a bug
+    /// report and to write it to a file.
+    fn render_log(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_log {
+            return;
+        }
+        let mut show = true;
+        let mut to_file = self.settings.log_to_file;
+        egui::Window::new(tr!("window-log"))
+            .open(&mut show)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("log-copy-all")).clicked() {
+                        ui.output().copied_text = self.log.text();
+                    }
+                    if ui.button(tr!("button-clear")).clicked() {
+                        self.log.clear();
                     }
+                    ui.checkbox(&mut to_file, tr!("log-to-file"))
+                        .on_hover_text(log_file().display().to_string());
                 });
-            if !show {
-                self.error = None;
+                ui.separator();
+                self.log.ui(ui);
+            });
+        if to_file != self.settings.log_to_file {
+            self.settings.log_to_file = to_file;
+            if let Err(e) = self.log.set_write_file(to_file) {
+                self.show_error(anyhow!(tr!(
+                    "error-log-file",
+                    file = log_file().display().to_string(),
+                    error = e.to_string()
+                )));
             }
         }
+        self.show_log = show;
     }
 
-    fn render_busy(&mut self, ctx: &egui::CtxRef) {
-        if self.show_busy {
-            egui::Window::new("Plz Wait")
-                .default_width(200.0)
-                .collapsible(false)
-                .show(ctx, |ui| {
-                    ui.add(egui::widgets::ProgressBar::new(0.99).animate(true));
+    fn open_hashes(&mut self) {
+        self.unknown_hashes = self
+            .doc()
+            .map(|doc| unknown_hashes(&doc.aiprog.0))
+            .unwrap_or_default();
+        self.show_hashes = true;
+    }
+
+    fn render_hashes(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_hashes {
+            return;
+        }
+        let mut show = self.show_hashes;
+        let mut import = false;
+        let mut export = false;
+        egui::Window::new(tr!("window-hash-names"))
+            .open(&mut show)
+            .default_width(350.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.label(tr!("hashes-intro"));
+                let hash = hash_name(&self.hash_input);
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.hash_input);
+                    ui.label(hash.to_string());
+                });
+                if self.hash_input.is_empty() {
+                    ui.label("");
+                } else if self.unknown_hashes.contains(&hash) {
+                    ui.colored_label(ok_color(), format!("✔ {}", tr!("hashes-matches")));
+                } else if has_name(hash) {
+                    ui.label(tr!("hashes-known"));
+                } else {
+                    ui.label(tr!("hashes-no-match"));
+                }
+                if ui
+                    .add_enabled(
+                        !self.hash_input.is_empty(),
+                        egui::Button::new(tr!("hashes-add")),
+                    )
+                    .clicked()
+                {
+                    self.session.add_hash_name(&self.hash_input);
+                    self.unknown_hashes.remove(&hash);
+                    self.hash_input.clear();
+                }
+                egui::CollapsingHeader::new(tr!(
+                    "hashes-unknown-keys",
+                    count = self.unknown_hashes.len()
+                ))
+                .show(ui, |ui| {
+                    egui::ScrollArea::vertical()
+                        .id_source("unknown_hashes")
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for hash in &self.unknown_hashes {
+                                ui.label(hash.to_string());
+                            }
+                        });
+                });
+                egui::CollapsingHeader::new(tr!(
+                    "hashes-user-names",
+                    count = self.session.hash_names.len()
+                ))
+                .show(ui, |ui| {
+                    egui::ScrollArea::vertical()
+                        .id_source("user_hashes")
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("user_hashes_grid")
+                                .num_columns(2)
+                                .show(ui, |ui| {
+                                    for (hash, name) in &self.session.hash_names {
+                                        ui.label(hash.to_string());
+                                        ui.label(name);
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                });
+                ui.horizontal(|ui| {
+                    if dialog_button(ui, true, egui::Button::new(tr!("hashes-import"))).clicked() {
+                        import = true;
+                    }
+                    if dialog_button(
+                        ui,
+                        !self.session.hash_names.is_empty(),
+                        egui::Button::new(tr!("hashes-export")),
+                    )
+                    .on_hover_text(tr!("hashes-export-hover"))
+                    .clicked()
+                    {
+                        export = true;
+                    }
                 });
+            });
+        if import {
+            if let Some(file) = FileDialog::new()
+                .add_filter("JSON Hash Table", &["json"])
+                .pick_file()
+            {
+                match self.session.import_hash_names(&file) {
+                    Ok(()) => self.unknown_hashes.retain(|k| !has_name(*k)),
+                    Err(e) => self.show_error(e),
+                }
+            }
+        }
+        if export {
+            if let Some(file) = FileDialog::new()
+                .set_file_name("hashes.json")
+                .add_filter("JSON Hash Table", &["json"])
+                .save_file()
+            {
+                if let Err(e) = self.session.export_hash_names(&file) {
+                    self.show_error(e);
+                }
+            }
+        }
+        if !show {
+            self.show_hashes = false;
         }
     }
 
-    fn render_add(&mut self, ctx: &egui::CtxRef) {
-        let mut show = self.show_add;
-        if self.show_add {
-            egui::Window::new("Add New Entry")
+    fn render_unused(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_unused {
+            return;
+        }
+        let (version, unused) = match &self.unused {
+            Some((version, unused)) => (*version, unused),
+            None => return,
+        };
+        if let Some(doc) = self.docs.iter().find(|doc| doc.id == version.id) {
+            let aiprog = &doc.aiprog;
+            let mut show = self.show_unused;
+            let mut close = false;
+            let mut goto = None;
+            let mut remove = false;
+            egui::Window::new(tr!("window-unused"))
                 .open(&mut show)
-                .default_width(250.0)
+                .default_width(350.0)
                 .collapsible(false)
                 .show(ctx, |ui| {
                     ui.spacing_mut().item_spacing.y = 9.0;
-                    ui.label("Select the AI class for the new entry:");
-                    let text_class = egui::TextEdit::singleline(&mut self.add_class)
-                        .lock_focus(true)
-                        .hint_text("AI entry class name");
-                    let res = ui.add(text_class);
-                    let classes = AIDEFS.get_classes(&self.tab);
-                    if !classes.contains(&self.add_class.as_str()) {
-                        autocomplete_popup(
-                            &mut self.add_class,
-                            &mut self.add_ac_state,
-                            classes.as_slice(),
-                            ui,
-                            &res,
-                        );
+                    if unused.is_empty() {
+                        ui.label(tr!("unused-none"));
+                    } else {
+                        ui.label(tr!("unused-found", count = unused.len()));
+                        egui::ScrollArea::vertical()
+                            .max_height(300.0)
+                            .show(ui, |ui| {
+                                for idx in unused.iter().filter(|i| **i < aiprog.len()) {
+                                    if ui.small_button(aiprog.entry_label(*idx)).clicked() {
+                                        goto = Some(*idx);
+                                    }
+                                }
+                            });
                     }
-                    // egui::ComboBox::from_label("ClassName")
-                    //     .selected_text(self.add_class.clone())
-                    //     .width(ui.spacing().text_edit_width)
-                    //     .show_ui(ui, |ui| {
-                    //         AIDEFS.classes(&self.tab).for_each(|class| {
-                    //             ui.selectable_value(
-                    //                 &mut self.add_class,
-                    //                 class.clone(),
-                    //                 class.clone(),
-                    //             );
-                    //         });
-                    //     });
                     ui.horizontal(|ui| {
-                        if ui.button("Close").clicked() {
-                            self.show_add = false;
+                        if ui.button(tr!("button-close")).clicked() {
+                            close = true;
                         }
                         if ui
-                            .add_enabled(!self.add_class.is_empty(), egui::Button::new("OK"))
+                            .add_enabled(
+                                !unused.is_empty(),
+                                egui::Button::new(tr!("unused-remove-all")),
+                            )
                             .clicked()
                         {
-                            match self
-                                .aiprog
-                                .as_mut()
-                                .unwrap()
-                                .add_entry(self.tab, self.add_class.clone())
-                            {
-                                Ok(i) => {
-                                    self.init_hashes();
-                                    self.selected_ai = i;
-                                    let aiprog = self.aiprog.clone().unwrap();
-                                    self.start_task(move || aiprog.to_tree().map(Message::Tree));
-                                }
-                                Err(e) => self.show_error(e),
-                            };
-                            self.show_add = false;
-                            self.add_class = String::new();
+                            remove = true;
                         }
                     });
                 });
-            if !show {
-                self.show_add = false;
+            if let Some(idx) = goto {
+                if let Some(doc) = self.activate(version.id) {
+                    doc.selected_ai = idx;
+                }
+            }
+            if remove {
+                self.show_confirm(
+                    &tr!("confirm-remove-unused"),
+                    Message::RemoveUnused(version),
+                );
+            }
+            if !show || close {
+                self.show_unused = false;
             }
         }
     }
 
-    #[allow(unused_must_use)]
-    fn render_confirm(&mut self, ctx: &egui::CtxRef) {
-        let mut show = self.show_confirm;
-        if self.show_confirm {
-            egui::Window::new("Confirm")
+    fn render_script(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_script {
+            return;
+        }
+        let mut show = self.show_script;
+        let mut run = false;
+        let has_doc = self.doc().is_some();
+        egui::Window::new(tr!("window-script-console"))
+            .open(&mut show)
+            .default_width(500.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 9.0;
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.script)
+                        .code_editor()
+                        .desired_rows(12)
+                        .desired_width(f32::INFINITY)
+                        .hint_text(
+                            "for i in 0..len() {\n    if class_name(i).contains(\"Move\") {\n        \
+                             set_param(i, \"SInst\", \"SpeedRate\", 1.5);\n    }\n}",
+                        ),
+                );
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(has_doc && !self.script.is_empty(), egui::Button::new(tr!("script-run")))
+                        .clicked()
+                    {
+                        run = true;
+                    }
+                    if ui.button(tr!("script-clear-output")).clicked() {
+                        self.script_log.clear();
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        for line in &self.script_log {
+                            ui.monospace(line);
+                        }
+                    });
+            });
+        if run {
+            let doc = self.doc().unwrap();
+            let (version, aiprog) = (doc.version(), doc.aiprog.clone());
+            let script = self.script.clone();
+            self.start_task(move || {
+                run_script(aiprog, &script)
+                    .map(|(aiprog, log)| Message::Script(version, aiprog, log))
+            });
+        }
+        if !show {
+            self.show_script = false;
+        }
+    }
+
+    fn open_search(&mut self) {
+        if let Some(doc) = self.docs.get(self.active) {
+            self.show_search = true;
+            self.search_results = doc.aiprog.search(&self.search);
+        }
+    }
+
+    fn render_search(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_search {
+            return;
+        }
+        if let Some(doc) = self.docs.get(self.active) {
+            let aiprog = &doc.aiprog;
+            let mut show = self.show_search;
+            let mut goto = None;
+            egui::Window::new(tr!("window-search"))
                 .open(&mut show)
+                .default_width(400.0)
                 .collapsible(false)
                 .show(ctx, |ui| {
                     ui.spacing_mut().item_spacing.y = 9.0;
-                    ui.label(self.confirm_text.as_ref().unwrap());
-                    ui.horizontal(|ui| {
-                        if ui.button("Close").clicked() {
-                            self.show_confirm = false;
-                        }
-                        if ui.button("OK").clicked() {
-                            self.show_confirm = false;
-                            self.messengers.0.send(Ok(self
-                                .confirm_msg
-                                .as_ref()
-                                .unwrap()
-                                .to_owned()));
-                        }
-                    });
+                    let res = ui.add(
+                        egui::TextEdit::singleline(&mut self.search)
+                            .id(egui::Id::new("search_text"))
+                            .hint_text(tr!("search-hint"))
+                            .desired_width(f32::INFINITY),
+                    );
+                    if res.changed() {
+                        self.search_results = aiprog.search(&self.search);
+                    }
+                    if !self.search.is_empty() {
+                        ui.label(tr!("search-results", count = self.search_results.len()));
+                    }
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("search_results")
+                                .num_columns(2)
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for hit in self
+                                        .search_results
+                                        .iter()
+                                        .filter(|hit| hit.entry < aiprog.len())
+                                    {
+                                        if ui.small_button(aiprog.entry_label(hit.entry)).clicked()
+                                        {
+                                            goto = Some(hit.entry);
+                                        }
+                                        ui.label(&hit.field);
+                                        ui.end_row();
+                                    }
+                                });
+                        });
                 });
-            if !show || !self.show_confirm {
-                self.confirm_msg = None;
-                self.confirm_text = None;
+            if let Some(idx) = goto {
+                self.docs[self.active].selected_ai = idx;
+            }
+            if !show {
+                self.show_search = false;
             }
         }
     }
 
-    fn render_def(&mut self, ctx: &egui::CtxRef) {
-        if self.show_def {
-            let mut show = self.show_def;
-            let aiprog = self.aiprog.as_ref().unwrap();
-            egui::Window::new(&format!(
-                "AI Def: {}",
-                aiprog.entry_name_from_index(self.selected_ai).unwrap()
+    fn render_references(&mut self, ctx: &egui::CtxRef) {
+        if !self.show_refs {
+            return;
+        }
+        if let Some(doc) = self.docs.get(self.active) {
+            let aiprog = &doc.aiprog;
+            let mut show = self.show_refs;
+            let mut goto = None;
+            let refs = aiprog.references(doc.selected_ai);
+            egui::Window::new(tr!(
+                "window-usages",
+                entry = aiprog.entry_label(doc.selected_ai)
             ))
+            .id(egui::Id::new("references"))
             .open(&mut show)
-            .fixed_size(egui::vec2(425.0, 400.0))
+            .default_width(400.0)
             .collapsible(false)
             .show(ctx, |ui| {
                 ui.spacing_mut().item_spacing.y = 9.0;
-                if let Some(def) = match self.tab {
-                    Category::AI => &AIDEFS.ais,
-                    Category::Action => &AIDEFS.actions,
-                    Category::Behaviour => &AIDEFS.behaviors,
-                    Category::Query => &AIDEFS.querys,
+                if refs.is_empty() {
+                    ui.label(tr!("usages-none"));
                 }
-                .get(aiprog.entry_name_from_index(self.selected_ai).unwrap())
-                {
-                    egui::ScrollArea::vertical().show(ui, |ui| {
-                        let mut text = serde_json::to_string_pretty(def).unwrap();
-                        ui.add_enabled(
-                            false,
-                            egui::TextEdit::multiline(&mut text)
-                                .code_editor()
-                                .desired_rows(20)
-                                .desired_width(ui.available_width()),
-                        );
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("references")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for (kind, refs) in [
+                                    ("ChildIdx", &refs.children),
+                                    ("BehaviorIdx", &refs.behaviours),
+                                ] {
+                                    for (holder, key) in refs {
+                                        if ui.small_button(aiprog.entry_label(*holder)).clicked() {
+                                            goto = Some(*holder);
+                                        }
+                                        ui.label(format!("{} {}", kind, try_name(*key)));
+                                        ui.end_row();
+                                    }
+                                }
+                                for key in &refs.demos {
+                                    ui.label("DemoAIActionIdx");
+                                    ui.label(try_name(*key));
+                                    ui.end_row();
+                                }
+                            });
                     });
-                }
-                if ui.button("Close").clicked() {
-                    self.show_def = false;
+                if ui.button(tr!("button-close")).clicked() {
+                    self.show_refs = false;
                 }
             });
+            if let Some(idx) = goto {
+                self.docs[self.active].selected_ai = idx;
+            }
             if !show {
-                self.show_def = false;
+                self.show_refs = false;
+            }
+        }
+    }
+
+    /// Removes a parameter from an object of the selected entry, asking for
+    /// confirmation first if the parameter is required.
+    #[allow(unused_must_use)]
+    fn remove_param(&mut self, obj: &str, key: u32, required: bool) {
+        let (version, selected) = match self.doc_mut() {
+            Some(doc) => {
+                // So what was typed into the entry before this counts as before
+                doc.commit_editor();
+                (doc.version(), doc.selected_ai)
             }
+            None => return,
+        };
+        let msg = Message::RemoveParam(version, selected, hash_name(obj), key);
+        if required {
+            self.show_confirm(&tr!("confirm-remove-required", param = try_name(key)), msg);
+        } else {
+            self.messengers.0.send(Ok(msg));
         }
     }
 
     fn show_error(&mut self, error: Error) {
+        tracing::error!("{:#}", error);
         self.show_error = true;
         self.error = Some(error.to_string());
     }
@@ -0,0 +1,104 @@
+use crate::i18n::tr;
+use anyhow::{Context, Result};
+use plasticity_core::program::AIProgram;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// How often programs with unsaved changes are backed up
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Lists the original path of each backup in a recovery folder, by position
+const MANIFEST: &str = "files.json";
+
+fn recovery_root() -> PathBuf {
+    std::env::temp_dir().join("plasticity-recovery")
+}
+
+/// Each running instance keeps its backups in its own folder, so one which
+/// crashed doesn't have them overwritten by the next.
+pub fn recovery_dir() -> PathBuf {
+    recovery_root().join(std::process::id().to_string())
+}
+
+/// Replaces this instance's backups with the given programs and the files they
+/// belong to.
+pub fn write_backups(docs: &[(PathBuf, AIProgram)]) -> Result<()> {
+    let dir = recovery_dir();
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    if docs.is_empty() {
+        return Ok(());
+    }
+    fs::create_dir_all(&dir)?;
+    for (i, (_, aiprog)) in docs.iter().enumerate() {
+        fs::write(dir.join(format!("{}.yml", i)), aiprog.0.to_text())?;
+    }
+    // Written last, so a backup interrupted partway is never picked up
+    fs::write(
+        dir.join(MANIFEST),
+        serde_json::to_string(&docs.iter().map(|(file, _)| file).collect::<Vec<_>>())?,
+    )?;
+    Ok(())
+}
+
+fn read_manifest(dir: &Path) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let files: Vec<PathBuf> = serde_json::from_str(&fs::read_to_string(dir.join(MANIFEST))?)?;
+    Ok(files
+        .into_iter()
+        .enumerate()
+        .map(|(i, file)| (file, dir.join(format!("{}.yml", i))))
+        .collect())
+}
+
+/// Finds backups left behind by earlier sessions which crashed or closed with
+/// unsaved changes, as pairs of the original file and its backup.
+pub fn find_backups() -> Vec<(PathBuf, PathBuf)> {
+    let own = recovery_dir();
+    fs::read_dir(recovery_root())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|dir| dir.is_dir() && *dir != own)
+                .filter_map(|dir| read_manifest(&dir).ok())
+                .flatten()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Loads each backup, along with the file as it was last saved if it still
+/// exists.
+pub fn load_backups(
+    backups: &[(PathBuf, PathBuf)],
+) -> Result<Vec<(PathBuf, AIProgram, Option<AIProgram>)>> {
+    backups
+        .iter()
+        .map(|(file, backup)| {
+            let aiprog = AIProgram::new(backup)
+                .with_context(|| tr!("error-restore-backup", file = file.display().to_string()))?;
+            Ok((file.clone(), aiprog, AIProgram::new(file).ok()))
+        })
+        .collect()
+}
+
+/// Deletes the backups left by earlier sessions.
+pub fn discard_backups() -> Result<()> {
+    let own = recovery_dir();
+    if let Ok(entries) = fs::read_dir(recovery_root()) {
+        for dir in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+            if dir.is_dir() && dir != own {
+                fs::remove_dir_all(dir)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Deletes this instance's backups once it has closed normally.
+pub fn clear_backups() {
+    fs::remove_dir_all(recovery_dir()).ok();
+}
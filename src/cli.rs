@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+use plasticity_core::{
+    batch::validate_dir,
+    schema::{json_schema, save_json_schema},
+};
+use std::{fs, path::PathBuf};
+
+const USAGE: &str = "Usage: plasticity validate <folder> [--json <file>]
+       plasticity schema [<file>]
+
+validate checks every AI program and actor pack under the folder, printing a
+report of the problems found. With --json, the report is also written to the
+file as JSON, or printed as JSON instead if the file is -.
+
+schema writes a JSON Schema of AI programs exported to JSON, made from the AI
+defs, to the file, or prints it if no file is given.";
+
+/// Runs the command given on the command line, if there is one, returning the
+/// code to exit with. `None` means there was no command and the editor should
+/// open as usual.
+pub fn run(args: &[String]) -> Option<i32> {
+    match args.first().map(|a| a.as_str()) {
+        Some("validate") => Some(match validate(&args[1..]) {
+            Ok(true) => 0,
+            Ok(false) => 1,
+            Err(e) => {
+                eprintln!("{:#}\n\n{}", e, USAGE);
+                2
+            }
+        }),
+        Some("schema") => Some(match schema(&args[1..]) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("{:#}\n\n{}", e, USAGE);
+                2
+            }
+        }),
+        Some("help" | "--help" | "-h") => {
+            println!("{}", USAGE);
+            Some(0)
+        }
+        _ => None,
+    }
+}
+
+/// Validates a folder, returning whether no problems were found.
+fn validate(args: &[String]) -> Result<bool> {
+    let mut folder = None;
+    let mut json = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = Some(args.next().context("--json needs a file")?),
+            _ if folder.is_none() => folder = Some(PathBuf::from(arg)),
+            _ => anyhow::bail!("Unexpected argument {}", arg),
+        }
+    }
+    let report = validate_dir(&folder.context("No folder given")?)?;
+    match json.map(|j| j.as_str()) {
+        Some("-") => println!("{}", report.to_json()?),
+        Some(file) => {
+            fs::write(file, report.to_json()?)
+                .with_context(|| format!("Failed to write {}", file))?;
+            print!("{}", report.to_text());
+        }
+        None => print!("{}", report.to_text()),
+    }
+    Ok(report.files.iter().all(|file| file.is_empty()))
+}
+
+fn schema(args: &[String]) -> Result<()> {
+    match args {
+        [] => println!("{}", serde_json::to_string_pretty(&json_schema())?),
+        [file] => {
+            save_json_schema(file.as_ref()).with_context(|| format!("Failed to write {}", file))?
+        }
+        _ => anyhow::bail!("Unexpected argument {}", args[1]),
+    }
+    Ok(())
+}
@@ -0,0 +1,177 @@
+#[cfg(target_arch = "wasm32")]
+use crate::web::FileDialog;
+use crate::{
+    app::dialog_button,
+    i18n::tr,
+    settings::{error_color, warning_color},
+};
+use eframe::egui::{self, Ui};
+use plasticity_core::merge::{Change, Touched};
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::FileDialog;
+use std::path::{Path, PathBuf};
+
+/// Compares several mods' versions of one actor's AI program against
+/// vanilla, to see which entries more than one of them changes before
+/// shipping them together.
+pub struct ConflictInspector {
+    base: Option<PathBuf>,
+    mods: Vec<PathBuf>,
+    /// Entries the mods change, once compared
+    pub report: Option<Vec<Touched>>,
+    /// Only list entries more than one mod changes
+    shared_only: bool,
+}
+
+impl Default for ConflictInspector {
+    fn default() -> Self {
+        Self {
+            base: None,
+            mods: vec![],
+            report: None,
+            shared_only: true,
+        }
+    }
+}
+
+fn pick_program() -> Option<PathBuf> {
+    FileDialog::new()
+        .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+        .add_filter("BOTW YAML AI Program", &["yml"])
+        .add_filter("BOTW Actor Pack", &["sbactorpack", "bactorpack"])
+        .pick_file()
+}
+
+/// Names a file by its folder as well, as every mod's copy of a program has
+/// the same file name.
+fn short_name(file: &Path) -> String {
+    let name = file.file_name().unwrap_or_default().to_string_lossy();
+    match file
+        .ancestors()
+        .nth(1)
+        .and_then(|dir| dir.file_name())
+        .map(|dir| dir.to_string_lossy())
+    {
+        Some(dir) => format!("{}/{}", dir, name),
+        None => name.into_owned(),
+    }
+}
+
+impl ConflictInspector {
+    /// Draws the inspector, returning the base and mod files when asked to
+    /// compare them.
+    pub fn ui(&mut self, ui: &mut Ui) -> Option<(PathBuf, Vec<PathBuf>)> {
+        ui.spacing_mut().item_spacing.y = 9.0;
+        let mut compare = None;
+        egui::Grid::new("conflict_files")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(tr!("conflicts-vanilla"));
+                ui.horizontal(|ui| {
+                    ui.label(
+                        self.base
+                            .as_deref()
+                            .map(short_name)
+                            .unwrap_or_else(|| tr!("settings-not-set")),
+                    );
+                    if dialog_button(ui, true, egui::Button::new(tr!("button-browse")).small())
+                        .clicked()
+                    {
+                        if let Some(file) = pick_program() {
+                            self.base = Some(file);
+                        }
+                    }
+                });
+                ui.end_row();
+                let mut remove = None;
+                for (i, file) in self.mods.iter().enumerate() {
+                    ui.label(tr!("conflicts-mod-file", number = i + 1));
+                    ui.horizontal(|ui| {
+                        ui.label(short_name(file))
+                            .on_hover_text(file.display().to_string());
+                        if ui
+                            .small_button("×")
+                            .on_hover_text(tr!("button-remove"))
+                            .clicked()
+                        {
+                            remove = Some(i);
+                        }
+                    });
+                    ui.end_row();
+                }
+                if let Some(i) = remove {
+                    self.mods.remove(i);
+                    self.report = None;
+                }
+            });
+        ui.horizontal(|ui| {
+            if dialog_button(ui, true, egui::Button::new(tr!("conflicts-add-mod"))).clicked() {
+                if let Some(file) = pick_program() {
+                    self.mods.push(file);
+                    self.report = None;
+                }
+            }
+            if ui
+                .add_enabled(
+                    self.base.is_some() && self.mods.len() >= 2,
+                    egui::Button::new(tr!("button-compare")),
+                )
+                .on_hover_text(tr!("conflicts-compare-hover"))
+                .clicked()
+            {
+                compare = self.base.clone().map(|base| (base, self.mods.clone()));
+            }
+        });
+        if let Some(report) = &self.report {
+            ui.separator();
+            let shared: Vec<&Touched> = report.iter().filter(|t| t.touched_by() > 1).collect();
+            let clashes = shared.iter().filter(|t| !t.compatible).count();
+            ui.label(tr!(
+                "conflicts-summary",
+                changed = report.len(),
+                shared = shared.len(),
+                clashes = clashes
+            ));
+            ui.checkbox(&mut self.shared_only, tr!("conflicts-shared-only"));
+            egui::ScrollArea::vertical()
+                .max_height(400.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("conflict_report")
+                        .num_columns(self.mods.len() + 1)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong(tr!("column-entry"));
+                            for i in 0..self.mods.len() {
+                                ui.strong(tr!("conflicts-mod", number = i + 1));
+                            }
+                            ui.end_row();
+                            for touched in report {
+                                if self.shared_only && touched.touched_by() < 2 {
+                                    continue;
+                                }
+                                let label = format!("{} {}", touched.category, touched.label);
+                                if touched.touched_by() < 2 {
+                                    ui.label(label);
+                                } else if touched.compatible {
+                                    ui.colored_label(warning_color(), label)
+                                        .on_hover_text(tr!("conflicts-compatible"));
+                                } else {
+                                    ui.colored_label(error_color(), label)
+                                        .on_hover_text(tr!("conflicts-clash"));
+                                }
+                                for change in &touched.changes {
+                                    ui.label(match change {
+                                        Some(Change::Added) => tr!("conflicts-added"),
+                                        Some(Change::Removed) => tr!("conflicts-removed"),
+                                        Some(Change::Changed) => tr!("conflicts-changed"),
+                                        None => String::new(),
+                                    });
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+        compare
+    }
+}
@@ -0,0 +1,55 @@
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    autosave::{recovery_dir, write_backups},
+    i18n::tr,
+};
+use plasticity_core::program::AIProgram;
+#[cfg(not(target_arch = "wasm32"))]
+use std::panic;
+use std::{path::PathBuf, sync::Mutex};
+
+/// Programs with unsaved changes as of the last edit, for the panic hook to
+/// save, as it can't reach the app itself.
+static UNSAVED: Mutex<Vec<(PathBuf, AIProgram)>> = Mutex::new(Vec::new());
+
+/// Replaces the programs saved if the app crashes.
+pub fn set_unsaved(docs: Vec<(PathBuf, AIProgram)>) {
+    if let Ok(mut unsaved) = UNSAVED.lock() {
+        *unsaved = docs;
+    }
+}
+
+/// Saves unsaved changes for recovery and says what happened when the UI
+/// thread panics, rather than the window just vanishing. Panics on background
+/// threads only lose the task, so they're just logged.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn install_hook() {
+    let default = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default(info);
+        tracing::error!("{}", info);
+        if std::thread::current().name() != Some("main") {
+            return;
+        }
+        // The lock may be held by the code which panicked
+        let saved = match UNSAVED.try_lock() {
+            Ok(unsaved) if unsaved.is_empty() => None,
+            Ok(unsaved) => Some(match write_backups(&unsaved) {
+                Ok(()) => tr!("crash-saved", folder = recovery_dir().display().to_string()),
+                Err(e) => tr!("crash-save-failed-because", error = format!("{:#}", e)),
+            }),
+            Err(_) => Some(tr!("crash-save-failed")),
+        };
+        rfd::MessageDialog::new()
+            .set_level(rfd::MessageLevel::Error)
+            .set_title(&tr!("crash-title"))
+            .set_description(&format!(
+                "{}\n\n{}",
+                info,
+                saved.unwrap_or_else(|| tr!("crash-nothing-unsaved"))
+            ))
+            .set_buttons(rfd::MessageButtons::Ok)
+            .show();
+        std::process::exit(101);
+    }));
+}
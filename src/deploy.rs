@@ -0,0 +1,179 @@
+use crate::i18n::tr;
+use anyhow::{Context, Result};
+use plasticity_core::program::{is_actor_pack, pack_program_path, AIProgram};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Title ID of BOTW on the Switch, which layeredfs mods are placed under
+const SWITCH_TITLE_ID: &str = "01007EF00011E000";
+
+/// How the mod folder being deployed to is laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DeployLayout {
+    /// A BCML mod, with the game's files under `content`
+    Bcml,
+    /// A Cemu graphic pack, with a `rules.txt` and the game's files under
+    /// `content`
+    GraphicPack,
+    /// The root of a Switch SD card, with the game's files under atmosphère's
+    /// layeredfs folder
+    LayeredFs,
+}
+
+impl DeployLayout {
+    pub fn name(&self) -> String {
+        match self {
+            DeployLayout::Bcml => tr!("deploy-layout-bcml"),
+            DeployLayout::GraphicPack => tr!("deploy-layout-graphic-pack"),
+            DeployLayout::LayeredFs => tr!("deploy-layout-layeredfs"),
+        }
+    }
+
+    /// Folder mirroring the game's own files in a mod folder of this layout.
+    fn content_dir(&self, dir: &Path) -> PathBuf {
+        match self {
+            DeployLayout::Bcml | DeployLayout::GraphicPack => dir.join("content"),
+            DeployLayout::LayeredFs => dir
+                .join("atmosphere/contents")
+                .join(SWITCH_TITLE_ID)
+                .join("romfs"),
+        }
+    }
+}
+
+/// Where and how saved files are copied for testing in the game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Deploy {
+    pub dir: Option<PathBuf>,
+    pub layout: DeployLayout,
+    /// Whether to deploy every time a file is saved
+    pub on_save: bool,
+    /// Whether to put loose AI programs into the mod's actor packs which use
+    /// them, rather than copying them as they are
+    pub repack: bool,
+    /// Shell command to run after deploying, such as to restart an emulator
+    pub command: String,
+}
+
+impl Default for Deploy {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            layout: DeployLayout::Bcml,
+            on_save: false,
+            repack: true,
+            command: String::new(),
+        }
+    }
+}
+
+impl Deploy {
+    /// Copies a saved file into the mod folder, then runs the command if there
+    /// is one. Returns the files written.
+    pub fn deploy(&self, file: &Path) -> Result<Vec<PathBuf>> {
+        let dir = self
+            .dir
+            .as_ref()
+            .context("No mod folder set to deploy to")?;
+        let content = self.layout.content_dir(dir);
+        let name = file.file_name().context("Nothing to deploy")?;
+        let written = if is_actor_pack(file) {
+            vec![copy_into(file, &content.join("Actor/Pack").join(name))?]
+        } else if self.repack {
+            self.repack(file, &content)?
+        } else {
+            vec![copy_into(
+                file,
+                &content.join("Actor/AIProgram").join(name),
+            )?]
+        };
+        if self.layout == DeployLayout::GraphicPack {
+            write_rules(dir)?;
+        }
+        if !self.command.trim().is_empty() {
+            shell(&self.command)
+                .spawn()
+                .with_context(|| tr!("error-deploy-command", command = self.command.as_str()))?;
+        }
+        Ok(written)
+    }
+
+    /// Saves a loose AI program into every actor pack in the mod which holds
+    /// one of the same name.
+    fn repack(&self, file: &Path, content: &Path) -> Result<Vec<PathBuf>> {
+        let stem = file.file_stem().unwrap_or_default();
+        let pack_dir = content.join("Actor/Pack");
+        let packs: Vec<PathBuf> = fs::read_dir(&pack_dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| is_actor_pack(path))
+                    .filter(|path| {
+                        pack_program_path(path)
+                            .map(|inner| Path::new(&inner).file_stem() == Some(stem))
+                            .unwrap_or(false)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if packs.is_empty() {
+            anyhow::bail!(
+                "No actor pack in {} uses {}. Copy the actor packs to change there first.",
+                pack_dir.display(),
+                stem.to_string_lossy()
+            );
+        }
+        let aiprog = AIProgram::new(file)?;
+        for pack in &packs {
+            aiprog.save(pack, true)?;
+        }
+        Ok(packs)
+    }
+}
+
+fn copy_into(file: &Path, dest: &Path) -> Result<PathBuf> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(file, dest)
+        .with_context(|| tr!("error-deploy-copy", file = dest.display().to_string()))?;
+    Ok(dest.to_path_buf())
+}
+
+/// Gives a graphic pack the `rules.txt` Cemu needs to list it, if it has none.
+fn write_rules(dir: &Path) -> Result<()> {
+    let rules = dir.join("rules.txt");
+    if !rules.exists() {
+        let name = dir.file_name().unwrap_or_default().to_string_lossy();
+        fs::write(
+            rules,
+            format!(
+                "[Definition]\n\
+                 titleIds = 00050000101C9300,00050000101C9400,00050000101C9500\n\
+                 name = {0}\n\
+                 path = \"The Legend of Zelda: Breath of the Wild/Mods/{0}\"\n\
+                 description = Deployed by Plasticity\n\
+                 version = 4\n",
+                name
+            ),
+        )?;
+    }
+    Ok(())
+}
+
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
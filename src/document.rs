@@ -0,0 +1,459 @@
+use crate::{
+    history::{Edit, History},
+    index::NameIndex,
+    param_history::ParamHistory,
+    sidecar::Sidecar,
+    table::EntryTable,
+    tree::{Tree, TreeDrag, TreeFilter},
+};
+use anyhow::Result;
+use plasticity_core::{
+    animation::AsNames,
+    links::ActorLink,
+    program::{is_compressed, AIProgram, Category},
+    variant::Layout,
+};
+use roead::aamp::{hash_name, ParamList, ParameterList, ParameterObject};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// An open AI program along with all of its editing state.
+pub struct Document {
+    /// Stable identifier for routing background task results, since the
+    /// position of the document among the open tabs can change
+    pub id: usize,
+    pub file: PathBuf,
+    pub aiprog: AIProgram,
+    /// The program as it was last loaded or saved
+    pub init_prog: AIProgram,
+    /// Entries which differ from `init_prog`, kept up to date by
+    /// [`Document::update_changed`]
+    pub changed: BTreeSet<usize>,
+    pub history: History,
+    /// What the editor is changing in place, as it was when last recorded
+    watched: Option<Watched>,
+    pub param_history: ParamHistory,
+    pub tree: Vec<Tree>,
+    /// Trees of root AIs which nothing refers to
+    pub orphans: Vec<Tree>,
+    /// Roots the user has said are meant to have no parent, by name and class
+    /// since their indexes change as entries are added and removed
+    pub marked_roots: Vec<(String, String)>,
+    pub drag: TreeDrag,
+    pub filter: TreeFilter,
+    /// Whether the side panel lists the current tab's entries as a table
+    /// rather than showing the tree
+    pub show_table: bool,
+    pub table: EntryTable,
+    pub index: NameIndex,
+    pub selected_ai: usize,
+    /// Entries picked with Ctrl+click in the tree
+    pub multi_selected: BTreeSet<usize>,
+    pub last_selected: HashMap<Category, usize>,
+    /// Entries jumped away from, most recent last
+    pub back: Vec<usize>,
+    /// Entries gone back from, most recent last
+    pub forward: Vec<usize>,
+    pub tab: Category,
+    /// Whether the demo slot editor is shown instead of the selected entry
+    pub show_demos: bool,
+    /// Whether to yaz0 compress the file when saving
+    pub compress: bool,
+    /// Modification time of the file when it was last loaded or saved
+    pub disk_time: Option<SystemTime>,
+    /// Newer modification time of the file, after something else changed it,
+    /// until the user decides what to do about it
+    pub disk_change: Option<SystemTime>,
+    /// The file as it now is on disk, when the user asked to compare it
+    pub disk_prog: Option<AIProgram>,
+    /// Notes and such kept beside the file
+    pub sidecar: Sidecar,
+    /// The selected entry as YAML, when it's being edited as text
+    pub entry_text: Option<EntryText>,
+    /// AS names from the ASList of the actor pack the program is from, for
+    /// checking the animations its actions play
+    pub as_names: Option<AsNames>,
+    /// Link targets of the actor the program is from, for checking its
+    /// entries' classes have the components they need
+    pub actor_link: Option<ActorLink>,
+    /// How the file lays the program out, if it's from a game other than
+    /// BOTW, for saving it the same way
+    pub layout: Option<Layout>,
+}
+
+/// A document as it was at some point, by its ID and how far its history had
+/// got, so what a background task works out from its program can be checked
+/// to still fit before it's applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DocVersion {
+    pub id: usize,
+    revision: usize,
+}
+
+/// The selected entry and the demo slots as they were when changes to them
+/// were last recorded. The editor's widgets change them in place, so edits
+/// are found by comparing against these once the user is done with a widget.
+struct Watched {
+    entry: usize,
+    before: ParameterList,
+    demos: Option<ParameterObject>,
+}
+
+/// YAML of an entry being edited by hand, until it's applied.
+#[derive(Debug, Clone)]
+pub struct EntryText {
+    pub entry: usize,
+    pub text: String,
+    /// Why the text couldn't be applied, if it couldn't
+    pub error: Option<String>,
+}
+
+/// When a file was last modified, if that can be found out.
+pub fn file_time(file: &Path) -> Option<SystemTime> {
+    std::fs::metadata(file).and_then(|m| m.modified()).ok()
+}
+
+impl Document {
+    pub fn new(id: usize, file: PathBuf, aiprog: AIProgram, selected_ai: usize) -> Self {
+        let mut doc = Document {
+            id,
+            compress: is_compressed(&file),
+            disk_time: file_time(&file),
+            disk_change: None,
+            disk_prog: None,
+            file,
+            init_prog: aiprog.clone(),
+            aiprog,
+            changed: BTreeSet::new(),
+            history: History::default(),
+            watched: None,
+            param_history: ParamHistory::default(),
+            tree: vec![],
+            orphans: vec![],
+            marked_roots: vec![],
+            drag: TreeDrag::default(),
+            filter: TreeFilter::default(),
+            show_table: false,
+            table: EntryTable::default(),
+            index: NameIndex::default(),
+            selected_ai,
+            multi_selected: BTreeSet::new(),
+            last_selected: HashMap::with_capacity(4),
+            back: vec![],
+            forward: vec![],
+            tab: Category::AI,
+            show_demos: false,
+            sidecar: Sidecar::default(),
+            entry_text: None,
+            as_names: None,
+            actor_link: None,
+            layout: None,
+        };
+        doc.index.update(&doc.aiprog);
+        doc
+    }
+
+    fn root_identity(&self, idx: usize) -> (String, String) {
+        (
+            self.aiprog
+                .item_at_index(idx)
+                .objects()
+                .get(hash_name("Def"))
+                .and_then(|def| def.params().get(&hash_name("Name")))
+                .and_then(|name| name.as_string().ok())
+                .unwrap_or_default()
+                .to_owned(),
+            self.aiprog
+                .entry_name_from_index(idx)
+                .unwrap_or_default()
+                .to_owned(),
+        )
+    }
+
+    /// Identifies an entry by its category, class, `Name`, and `GroupName`, for
+    /// attaching things to it which should follow it as entries around it are
+    /// added and removed.
+    pub fn entry_key(&self, idx: usize) -> String {
+        let def = self
+            .aiprog
+            .item_at_index(idx)
+            .objects()
+            .get(hash_name("Def"));
+        let def_string = |key: &str| {
+            def.and_then(|def| def.params().get(&hash_name(key)))
+                .and_then(|v| v.as_string().ok())
+                .unwrap_or_default()
+        };
+        format!(
+            "{}/{}/{}/{}",
+            self.aiprog.category_of(idx),
+            def_string("ClassName"),
+            def_string("GroupName"),
+            def_string("Name")
+        )
+    }
+
+    /// Sets the note on an entry, removing it if empty.
+    pub fn set_note(&mut self, idx: usize, note: String) {
+        let key = self.entry_key(idx);
+        if note.trim().is_empty() {
+            self.sidecar.notes.remove(&key);
+        } else {
+            self.sidecar.notes.insert(key, note);
+        }
+    }
+
+    /// Pinned entries, in the order they were pinned, with their current
+    /// index or `None` for any which can no longer be found.
+    pub fn pinned_entries(&self) -> Vec<(String, Option<usize>)> {
+        if self.sidecar.pins.is_empty() {
+            return vec![];
+        }
+        let keys: Vec<String> = (0..self.aiprog.len()).map(|i| self.entry_key(i)).collect();
+        self.sidecar
+            .pins
+            .iter()
+            .map(|pin| (pin.clone(), keys.iter().position(|key| key == pin)))
+            .collect()
+    }
+
+    pub fn toggle_pin(&mut self, idx: usize) {
+        let key = self.entry_key(idx);
+        if let Some(i) = self.sidecar.pins.iter().position(|pin| *pin == key) {
+            self.sidecar.pins.remove(i);
+        } else {
+            self.sidecar.pins.push(key);
+        }
+    }
+
+    /// Indexes of the AIs currently marked as roots.
+    pub fn marked_root_indexes(&self) -> BTreeSet<usize> {
+        if self.marked_roots.is_empty() {
+            return BTreeSet::new();
+        }
+        (0..self.aiprog.actions_offset())
+            .filter(|i| self.marked_roots.contains(&self.root_identity(*i)))
+            .collect()
+    }
+
+    pub fn toggle_root_mark(&mut self, idx: usize) {
+        let identity = self.root_identity(idx);
+        if let Some(i) = self.marked_roots.iter().position(|r| *r == identity) {
+            self.marked_roots.remove(i);
+        } else {
+            self.marked_roots.push(identity);
+        }
+    }
+
+    pub fn version(&self) -> DocVersion {
+        DocVersion {
+            id: self.id,
+            revision: self.history.revision(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.file
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.aiprog != self.init_prog
+    }
+
+    /// Replaces the working program with an edited copy of it, recording
+    /// what changed to be undone along with anything left to record from the
+    /// editor. The tree has to be rebuilt separately.
+    pub fn restore(&mut self, aiprog: AIProgram) {
+        let edit = Edit::between(&self.aiprog, &aiprog);
+        let edit = match self.editor_edit() {
+            Some(editor) => editor.then(edit),
+            None => edit,
+        };
+        // Earlier values are kept by index
+        if aiprog.len() != self.aiprog.len() {
+            self.param_history.clear();
+        }
+        self.watched = None;
+        self.aiprog = aiprog;
+        self.history.record(edit);
+        self.after_edit();
+    }
+
+    /// Makes a change to a copy of the working program, keeping it and
+    /// recording it to be undone if it succeeds. The tree has to be rebuilt
+    /// separately.
+    pub fn edit<T>(&mut self, change: impl FnOnce(&mut AIProgram) -> Result<T>) -> Result<T> {
+        let mut aiprog = self.aiprog.clone();
+        let result = change(&mut aiprog)?;
+        self.restore(aiprog);
+        Ok(result)
+    }
+
+    /// What the editor has changed in place since it was last recorded, with
+    /// the watched state brought up to date.
+    fn editor_edit(&mut self) -> Option<Edit> {
+        let watched = self.watched.as_mut()?;
+        let mut edit = Edit::default();
+        if let Ok(entry) = self.aiprog.get_item(watched.entry) {
+            if *entry != watched.before {
+                self.param_history
+                    .record(watched.entry, &watched.before, entry);
+                let before = std::mem::replace(&mut watched.before, entry.clone());
+                edit.change_entry(&self.aiprog, watched.entry, before);
+            }
+        }
+        let demos = self.aiprog.0.objects().get(hash_name("DemoAIActionIdx"));
+        if demos != watched.demos.as_ref() {
+            let before = std::mem::replace(&mut watched.demos, demos.cloned());
+            edit.change_object(&self.aiprog, "DemoAIActionIdx", before);
+        }
+        if edit.is_empty() {
+            None
+        } else {
+            Some(edit)
+        }
+    }
+
+    /// Records what the editor has changed in place as an undo step, then
+    /// watches the entry now selected. Returns whether there was anything to
+    /// record.
+    pub fn commit_editor(&mut self) -> bool {
+        let committed = match self.editor_edit() {
+            Some(edit) => {
+                self.history.record(edit);
+                self.update_changed();
+                true
+            }
+            None => false,
+        };
+        if self.watched.as_ref().map(|w| w.entry) != Some(self.selected_ai) {
+            self.watched = self
+                .aiprog
+                .get_item(self.selected_ai)
+                .ok()
+                .map(|entry| Watched {
+                    entry: self.selected_ai,
+                    before: entry.clone(),
+                    demos: self
+                        .aiprog
+                        .0
+                        .objects()
+                        .get(hash_name("DemoAIActionIdx"))
+                        .cloned(),
+                });
+        }
+        committed
+    }
+
+    /// Undoes the last edit, returning whether there was one. The tree has to
+    /// be rebuilt separately.
+    pub fn undo(&mut self) -> bool {
+        self.step(History::undo)
+    }
+
+    /// Redoes the last edit undone, returning whether there was one. The tree
+    /// has to be rebuilt separately.
+    pub fn redo(&mut self) -> bool {
+        self.step(History::redo)
+    }
+
+    fn step(&mut self, step: fn(&mut History, &mut AIProgram) -> bool) -> bool {
+        if let Some(edit) = self.editor_edit() {
+            self.history.record(edit);
+        }
+        self.watched = None;
+        let len = self.aiprog.len();
+        if !step(&mut self.history, &mut self.aiprog) {
+            return false;
+        }
+        if self.aiprog.len() != len {
+            self.param_history.clear();
+        }
+        self.after_edit();
+        true
+    }
+
+    /// Keeps the selection and such within the program after it changes.
+    fn after_edit(&mut self) {
+        let len = self.aiprog.len();
+        self.selected_ai = std::cmp::min(self.selected_ai, len.saturating_sub(1));
+        self.last_selected.retain(|_, i| *i < len);
+        self.multi_selected.retain(|i| *i < len);
+        self.back.retain(|i| *i < len);
+        self.forward.retain(|i| *i < len);
+        self.update_changed();
+    }
+
+    /// Works out again which entries differ from the program as loaded.
+    pub fn update_changed(&mut self) {
+        self.changed = self.aiprog.changed_entries(&self.init_prog);
+    }
+
+    /// Notes whether something else has modified the file since it was loaded
+    /// or saved.
+    pub fn check_disk(&mut self) {
+        let time = file_time(&self.file);
+        if time.is_some() && time != self.disk_time {
+            self.disk_change = time;
+        }
+    }
+
+    /// Keeps the working program as it is despite the file having changed,
+    /// so it will be overwritten on the next save.
+    pub fn keep_mine(&mut self) {
+        if let Some(time) = self.disk_change.take() {
+            self.disk_time = Some(time);
+        }
+        self.disk_prog = None;
+    }
+
+    /// Selects another entry, remembering the current one to go back to.
+    pub fn navigate(&mut self, idx: usize) {
+        if idx != self.selected_ai && idx < self.aiprog.len() {
+            self.back.push(self.selected_ai);
+            self.forward.clear();
+            self.selected_ai = idx;
+        }
+    }
+
+    /// Returns to the entry last jumped away from. Entries since deleted are
+    /// skipped.
+    pub fn go_back(&mut self) {
+        let len = self.aiprog.len();
+        while let Some(idx) = self.back.pop() {
+            if idx < len {
+                self.forward.push(self.selected_ai);
+                self.selected_ai = idx;
+                return;
+            }
+        }
+    }
+
+    pub fn go_forward(&mut self) {
+        let len = self.aiprog.len();
+        while let Some(idx) = self.forward.pop() {
+            if idx < len {
+                self.back.push(self.selected_ai);
+                self.selected_ai = idx;
+                return;
+            }
+        }
+    }
+
+    /// Keeps the category tab in step with the selected entry, leaving the demo
+    /// editor if another entry was picked.
+    pub fn sync_tab(&mut self) {
+        if *self.last_selected.get(&self.tab).unwrap_or(&9999) != self.selected_ai {
+            self.tab = self.aiprog.category_of(self.selected_ai);
+            self.show_demos = false;
+            self.last_selected.insert(self.tab, self.selected_ai);
+        }
+    }
+}
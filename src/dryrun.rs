@@ -0,0 +1,153 @@
+use crate::{i18n::tr, settings::warning_color};
+use eframe::egui::{self, Ui};
+use plasticity_core::{
+    program::AIProgram,
+    trace::{StepReason, TraceStep},
+    util::*,
+};
+use roead::aamp::{hash_name, ParamList, Parameter};
+use std::collections::HashMap;
+
+/// Walks down the tree from a root to show which Action would run, given
+/// results for the queries and choices at the AIs along the way.
+pub struct DryRun {
+    root: usize,
+    /// Result each query is taken to give, by entry index
+    queries: HashMap<usize, bool>,
+    /// Slot taken at each AI the user picked one for
+    choices: HashMap<usize, u32>,
+}
+
+impl DryRun {
+    pub fn new(aiprog: &AIProgram) -> Self {
+        Self {
+            root: aiprog.roots().first().copied().unwrap_or(0),
+            queries: HashMap::new(),
+            choices: HashMap::new(),
+        }
+    }
+
+    pub fn steps(&self, aiprog: &AIProgram) -> Vec<TraceStep> {
+        if self.root >= aiprog.len() {
+            return vec![];
+        }
+        aiprog.trace(self.root, &self.queries, &self.choices)
+    }
+
+    /// Draws the trace, returning an entry clicked to jump to.
+    pub fn ui(&mut self, ui: &mut Ui, aiprog: &AIProgram) -> Option<usize> {
+        let mut goto = None;
+        ui.spacing_mut().item_spacing.y = 9.0;
+        egui::ComboBox::from_label(tr!("dry-run-start"))
+            .width(250.0)
+            .selected_text(aiprog.entry_label(self.root.min(aiprog.len().saturating_sub(1))))
+            .show_ui(ui, |ui| {
+                let roots = aiprog.roots();
+                for i in roots
+                    .iter()
+                    .copied()
+                    .chain((0..aiprog.actions_offset()).filter(|i| !roots.contains(i)))
+                {
+                    ui.selectable_value(&mut self.root, i, aiprog.entry_label(i));
+                }
+            });
+        if aiprog.queries_offset() < aiprog.len() {
+            egui::CollapsingHeader::new(tr!("dry-run-query-results")).show(ui, |ui| {
+                for idx in aiprog.queries_offset()..aiprog.len() {
+                    let result = self.queries.entry(idx).or_insert(false);
+                    ui.checkbox(result, aiprog.entry_label(idx));
+                }
+            });
+        }
+        let steps = self.steps(aiprog);
+        egui::ScrollArea::vertical()
+            .max_height(350.0)
+            .show(ui, |ui| {
+                egui::Grid::new("dry_run")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for step in &steps {
+                            if ui.small_button(aiprog.entry_label(step.entry)).clicked() {
+                                goto = Some(step.entry);
+                            }
+                            match step.slot {
+                                Some(slot) => self.slot_ui(ui, aiprog, step.entry, slot),
+                                None => {
+                                    ui.label("");
+                                }
+                            }
+                            ui.label(match step.reason {
+                                StepReason::Chosen => tr!("dry-run-picked"),
+                                StepReason::Query(query, result) => tr!(
+                                    "dry-run-query",
+                                    query = AIProgram::entry_name(aiprog.item_at_index(query))
+                                        .unwrap_or_default(),
+                                    result = result.to_string()
+                                ),
+                                StepReason::First => tr!("dry-run-first"),
+                                StepReason::End => tr!("dry-run-runs"),
+                                StepReason::Cycle => tr!("dry-run-cycle"),
+                            });
+                            ui.end_row();
+                        }
+                    });
+            });
+        if let Some(last) = steps.last() {
+            if last.slot.is_some() && last.reason != StepReason::Cycle {
+                ui.colored_label(warning_color(), tr!("dry-run-no-child"));
+            }
+        }
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.choices.is_empty(),
+                    egui::Button::new(tr!("dry-run-reset")),
+                )
+                .clicked()
+            {
+                self.choices.clear();
+            }
+            ui.label(tr!("dry-run-guess"));
+        });
+        goto
+    }
+
+    /// Combo for picking which slot of an AI the trace follows.
+    fn slot_ui(&mut self, ui: &mut Ui, aiprog: &AIProgram, entry: usize, slot: u32) {
+        let slots: Vec<(u32, i32)> = aiprog
+            .item_at_index(entry)
+            .objects()
+            .get(hash_name("ChildIdx"))
+            .map(|obj| {
+                obj.params()
+                    .iter()
+                    .filter_map(|(k, v)| match v {
+                        Parameter::Int(i) => Some((*k, *i)),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut picked = slot;
+        egui::ComboBox::from_id_source(("dry_run_slot", entry))
+            .selected_text(translate(&try_name(slot)))
+            .show_ui(ui, |ui| {
+                for (key, child) in slots {
+                    let target = if child >= 0 && (child as usize) < aiprog.len() {
+                        aiprog.entry_label(child as usize)
+                    } else {
+                        tr!("slot-not-set")
+                    };
+                    ui.selectable_value(
+                        &mut picked,
+                        key,
+                        format!("{} → {}", translate(&try_name(key)), target),
+                    );
+                }
+            });
+        if picked != slot {
+            self.choices.insert(entry, picked);
+        }
+    }
+}
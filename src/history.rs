@@ -0,0 +1,355 @@
+use plasticity_core::program::AIProgram;
+use roead::aamp::{hash_name, ParamList, ParameterList, ParameterObject};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Maximum number of undo steps kept in memory
+const MAX_HISTORY: usize = 100;
+
+/// An entry of one of the program's top-level lists, with its key
+type Entry = (u32, ParameterList);
+
+/// One undoable change to the program, kept as only the parts of it which
+/// differ, as they were before and after. Entries are kept by top-level list
+/// and position, so edits have to be undone and redone in the order they
+/// were made.
+#[derive(Debug, Default)]
+pub struct Edit {
+    /// Lengths of the top-level lists which were added, removed, or resized
+    lengths: BTreeMap<u32, (Option<usize>, Option<usize>)>,
+    /// Entries which changed, or are only there on one side
+    entries: BTreeMap<(u32, usize), (Option<Entry>, Option<Entry>)>,
+    /// Top-level objects, such as the demo slots, which changed
+    objects: BTreeMap<u32, (Option<ParameterObject>, Option<ParameterObject>)>,
+}
+
+impl Edit {
+    /// Works out what changed between two versions of a program.
+    pub fn between(before: &AIProgram, after: &AIProgram) -> Self {
+        fn len(list: Option<&ParameterList>) -> Option<usize> {
+            list.map(|list| list.lists().inner().len())
+        }
+        fn entry(list: Option<&ParameterList>, i: usize) -> Option<(u32, &ParameterList)> {
+            list.and_then(|list| list.lists().inner().get_index(i))
+                .map(|(k, v)| (*k, v))
+        }
+        let mut edit = Edit::default();
+        let (old, new) = (before.0.lists().inner(), after.0.lists().inner());
+        for key in old
+            .keys()
+            .chain(new.keys().filter(|k| !old.contains_key(*k)))
+        {
+            let (old, new) = (old.get(key), new.get(key));
+            if len(old) != len(new) {
+                edit.lengths.insert(*key, (len(old), len(new)));
+            }
+            for i in 0..len(old).max(len(new)).unwrap_or_default() {
+                let (old, new) = (entry(old, i), entry(new, i));
+                if old != new {
+                    edit.entries.insert(
+                        (*key, i),
+                        (
+                            old.map(|(k, v)| (k, v.clone())),
+                            new.map(|(k, v)| (k, v.clone())),
+                        ),
+                    );
+                }
+            }
+        }
+        let (old, new) = (before.0.objects().inner(), after.0.objects().inner());
+        for key in old
+            .keys()
+            .chain(new.keys().filter(|k| !old.contains_key(*k)))
+        {
+            let (old, new) = (old.get(key), new.get(key));
+            if old != new {
+                edit.objects.insert(*key, (old.cloned(), new.cloned()));
+            }
+        }
+        edit
+    }
+
+    /// Notes an entry having been changed in place, given how it was before
+    /// and the program as it is now.
+    pub fn change_entry(&mut self, aiprog: &AIProgram, idx: usize, before: ParameterList) {
+        let (category, local) = aiprog.locate(idx);
+        let list = hash_name(category);
+        if let Some((key, after)) = aiprog
+            .0
+            .lists()
+            .get(list)
+            .and_then(|list| list.lists().inner().get_index(local))
+        {
+            self.entries.insert(
+                (list, local),
+                (Some((*key, before)), Some((*key, after.clone()))),
+            );
+        }
+    }
+
+    /// Notes a top-level object having been changed in place, given how it
+    /// was before and the program as it is now.
+    pub fn change_object(
+        &mut self,
+        aiprog: &AIProgram,
+        name: &str,
+        before: Option<ParameterObject>,
+    ) {
+        let key = hash_name(name);
+        self.objects
+            .insert(key, (before, aiprog.0.objects().get(key).cloned()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty() && self.entries.is_empty() && self.objects.is_empty()
+    }
+
+    /// Combines this edit with one made right after it into a single step.
+    pub fn then(mut self, next: Edit) -> Self {
+        fn chain<K: Ord, V>(
+            first: &mut BTreeMap<K, (Option<V>, Option<V>)>,
+            next: BTreeMap<K, (Option<V>, Option<V>)>,
+        ) {
+            for (key, (before, after)) in next {
+                match first.get_mut(&key) {
+                    Some(change) => change.1 = after,
+                    None => {
+                        first.insert(key, (before, after));
+                    }
+                }
+            }
+        }
+        chain(&mut self.entries, next.entries);
+        chain(&mut self.objects, next.objects);
+        for (key, (before, after)) in next.lengths {
+            self.lengths.entry(key).or_insert((before, before)).1 = after;
+        }
+        self.lengths.retain(|_, (before, after)| before != after);
+        self
+    }
+
+    /// Puts the program back how it was before the edit, or forward to how
+    /// it was after.
+    fn apply(&self, aiprog: &mut AIProgram, forward: bool) {
+        fn side<T>(change: &(Option<T>, Option<T>), forward: bool) -> Option<&T> {
+            if forward {
+                change.1.as_ref()
+            } else {
+                change.0.as_ref()
+            }
+        }
+        let lists = aiprog.0.lists_mut().inner_mut();
+        let touched: BTreeSet<&u32> = self
+            .lengths
+            .keys()
+            .chain(self.entries.keys().map(|(list, _)| list))
+            .collect();
+        for key in touched {
+            let len = match self.lengths.get(key) {
+                Some(change) => match side(change, forward) {
+                    Some(len) => Some(*len),
+                    None => {
+                        lists.shift_remove(key);
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            let list = lists.entry(*key).or_default();
+            let mut entries: Vec<Entry> = list.lists_mut().inner_mut().drain(..).collect();
+            if let Some(len) = len {
+                entries.truncate(len);
+            }
+            for ((_, i), change) in self.entries.range((*key, 0)..=(*key, usize::MAX)) {
+                if let Some(entry) = side(change, forward) {
+                    if *i < entries.len() {
+                        entries[*i] = entry.clone();
+                    } else {
+                        entries.push(entry.clone());
+                    }
+                }
+            }
+            list.lists_mut().inner_mut().extend(entries);
+        }
+        let objects = aiprog.0.objects_mut().inner_mut();
+        for (key, change) in &self.objects {
+            match side(change, forward) {
+                Some(obj) => {
+                    objects.insert(*key, obj.clone());
+                }
+                None => {
+                    objects.shift_remove(key);
+                }
+            }
+        }
+    }
+}
+
+/// Undo/redo stack of the edits made to the working AI program.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+    /// Goes up with every edit recorded, undone, or redone, so work based on
+    /// the program at one point can tell whether it has changed since
+    revision: usize,
+}
+
+impl History {
+    /// Records an edit as a new undo step, unless it changed nothing.
+    ///
+    /// Returns whether a step was recorded.
+    pub fn record(&mut self, edit: Edit) -> bool {
+        if edit.is_empty() {
+            return false;
+        }
+        self.undo.push(edit);
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        self.revision += 1;
+        true
+    }
+
+    pub fn revision(&self) -> usize {
+        self.revision
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Steps back one edit, returning whether there was one.
+    pub fn undo(&mut self, aiprog: &mut AIProgram) -> bool {
+        match self.undo.pop() {
+            Some(edit) => {
+                edit.apply(aiprog, false);
+                self.redo.push(edit);
+                self.revision += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Steps forward one edit, returning whether there was one.
+    pub fn redo(&mut self, aiprog: &mut AIProgram) -> bool {
+        match self.redo.pop() {
+            Some(edit) => {
+                edit.apply(aiprog, true);
+                self.undo.push(edit);
+                self.revision += 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use plasticity_core::program::Category;
+    use roead::aamp::Parameter;
+
+    fn fixture() -> AIProgram {
+        AIProgram::from_text(include_str!("../plasticity-core/tests/fixture.yml")).unwrap()
+    }
+
+    /// Sets the `Time` of the fixture's Wait.
+    fn set_time(aiprog: &mut AIProgram, time: i32) {
+        aiprog
+            .item_mut_at_index(2)
+            .objects_mut()
+            .get_mut(hash_name("SInst"))
+            .unwrap()
+            .params_mut()
+            .insert(hash_name("Time"), Parameter::Int(time));
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut aiprog = fixture();
+        set_time(&mut aiprog, 45);
+        let edited = aiprog.clone();
+        let mut history = History::default();
+        assert!(history.record(Edit::between(&fixture(), &aiprog)));
+        assert_eq!(history.revision(), 1);
+        assert!(history.can_undo() && !history.can_redo());
+
+        assert!(history.undo(&mut aiprog));
+        assert_eq!(aiprog, fixture());
+        assert!(!history.can_undo() && history.can_redo());
+        assert!(!history.undo(&mut aiprog));
+
+        assert!(history.redo(&mut aiprog));
+        assert_eq!(aiprog, edited);
+        assert!(!history.redo(&mut aiprog));
+        assert_eq!(history.revision(), 3);
+    }
+
+    #[test]
+    fn unchanged_is_not_recorded() {
+        let edit = Edit::between(&fixture(), &fixture());
+        assert!(edit.is_empty());
+        let mut history = History::default();
+        assert!(!history.record(edit));
+        assert_eq!(history.revision(), 0);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn undo_added_and_deleted_entries() {
+        let mut aiprog = fixture();
+        let mut history = History::default();
+        aiprog
+            .add_entry(Category::Query, "BranchByGameOver".to_owned())
+            .unwrap();
+        history.record(Edit::between(&fixture(), &aiprog));
+        let added = aiprog.clone();
+        aiprog.delete_entry(2).unwrap();
+        history.record(Edit::between(&added, &aiprog));
+        let deleted = aiprog.clone();
+
+        history.undo(&mut aiprog);
+        assert_eq!(aiprog, added);
+        history.undo(&mut aiprog);
+        assert_eq!(aiprog, fixture());
+        history.redo(&mut aiprog);
+        history.redo(&mut aiprog);
+        assert_eq!(aiprog, deleted);
+    }
+
+    #[test]
+    fn edits_in_place_combine() {
+        let mut aiprog = fixture();
+        let before = aiprog.item_at_index(2).clone();
+        set_time(&mut aiprog, 45);
+        let mut first = Edit::default();
+        first.change_entry(&aiprog, 2, before);
+
+        let before = aiprog.item_at_index(2).clone();
+        set_time(&mut aiprog, 60);
+        let demos = aiprog.demos().clone();
+        aiprog
+            .demos_mut()
+            .params_mut()
+            .insert(hash_name("Demo_Idle"), Parameter::Int(2));
+        let mut second = Edit::default();
+        second.change_entry(&aiprog, 2, before);
+        second.change_object(&aiprog, "DemoAIActionIdx", Some(demos));
+
+        let edited = aiprog.clone();
+        let mut history = History::default();
+        assert!(history.record(first.then(second)));
+        history.undo(&mut aiprog);
+        assert_eq!(aiprog, fixture());
+        assert!(!history.can_undo());
+        history.redo(&mut aiprog);
+        assert_eq!(aiprog, edited);
+    }
+}
@@ -0,0 +1,118 @@
+//! Text of the UI in each language it's translated to, kept as Fluent files
+//! in `data/i18n`. Text missing from a translation falls back to English, so
+//! translations can be filled in bit by bit.
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use unic_langid::LanguageIdentifier;
+
+/// A language the UI can be shown in.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    Japanese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Japanese];
+
+    /// Name of the language in itself, for picking it
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Japanese => "日本語",
+        }
+    }
+
+    fn id(&self) -> &'static str {
+        match self {
+            Language::English => "en-US",
+            Language::Japanese => "ja-JP",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("../data/i18n/en.ftl"),
+            Language::Japanese => include_str!("../data/i18n/ja.ftl"),
+        }
+    }
+
+    fn bundle(&self) -> &'static FluentBundle<FluentResource> {
+        match self {
+            Language::English => &ENGLISH,
+            Language::Japanese => &JAPANESE,
+        }
+    }
+}
+
+fn load_bundle(language: Language) -> FluentBundle<FluentResource> {
+    let id: LanguageIdentifier = language.id().parse().unwrap();
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // The marks isolating arguments show up as boxes in egui
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(language.source().to_owned()).unwrap_or_else(
+        |(resource, errors)| {
+            tracing::warn!("Errors in {:?} translation: {:?}", language, errors);
+            resource
+        },
+    );
+    if let Err(errors) = bundle.add_resource(resource) {
+        tracing::warn!("Errors in {:?} translation: {:?}", language, errors);
+    }
+    bundle
+}
+
+lazy_static! {
+    static ref ENGLISH: FluentBundle<FluentResource> = load_bundle(Language::English);
+    static ref JAPANESE: FluentBundle<FluentResource> = load_bundle(Language::Japanese);
+    static ref LANGUAGE: RwLock<Language> = RwLock::new(Language::English);
+}
+
+/// Sets which language [`text`] gives text in.
+pub fn set_language(language: Language) {
+    *LANGUAGE.write().unwrap() = language;
+}
+
+pub fn language() -> Language {
+    *LANGUAGE.read().unwrap()
+}
+
+fn format(
+    bundle: &FluentBundle<FluentResource>,
+    id: &str,
+    args: Option<&FluentArgs<'_>>,
+) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = vec![];
+    let text = bundle
+        .format_pattern(pattern, args, &mut errors)
+        .into_owned();
+    if !errors.is_empty() {
+        tracing::warn!("Errors formatting {}: {:?}", id, errors);
+    }
+    Some(text)
+}
+
+/// The text with an ID in the current language, or in English if it hasn't
+/// been translated, or failing that the ID itself.
+pub fn text(id: &str, args: Option<&FluentArgs<'_>>) -> String {
+    format(language().bundle(), id, args)
+        .or_else(|| format(&ENGLISH, id, args))
+        .unwrap_or_else(|| id.to_owned())
+}
+
+/// Text of the UI in the current language, as `tr!("file-open")` or with
+/// arguments as `tr!("window-usages", entry = label)`.
+macro_rules! tr {
+    ($id:literal) => {
+        $crate::i18n::text($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::text($id, Some(&args))
+    }};
+}
+pub(crate) use tr;
@@ -0,0 +1,98 @@
+use plasticity_core::{program::AIProgram, util::try_name};
+use roead::aamp::{hash_name, ParamList};
+use std::collections::{BTreeSet, HashMap};
+
+/// Names gathered from a program for the editor's combo boxes and
+/// suggestions. It keeps the program it was built from, so it can tell when
+/// that has changed by any means and rebuild itself.
+#[derive(Debug, Default)]
+pub struct NameIndex {
+    /// Class names of the AIs and Actions, by index
+    pub child_names: Vec<String>,
+    /// Names of the behaviors, by index into the behaviors
+    pub behaviour_names: Vec<String>,
+    /// The `Name` of every AI, which the `GroupName` of its children should
+    /// be, with an empty one first
+    pub group_names: Vec<String>,
+    /// Every `ChildIdx` key in use
+    pub slot_names: Vec<String>,
+    /// Every string value used for each SInst or MInst parameter, for
+    /// suggestions when editing
+    pub string_values: HashMap<u32, Vec<String>>,
+    source: Option<AIProgram>,
+}
+
+impl NameIndex {
+    /// Rebuilds the index if the program differs from the one it was built
+    /// from, returning whether it did.
+    pub fn update(&mut self, aiprog: &AIProgram) -> bool {
+        if self.source.as_ref() == Some(aiprog) {
+            return false;
+        }
+        self.build(aiprog);
+        self.source = Some(aiprog.clone());
+        true
+    }
+
+    /// Forces the next [`NameIndex::update`] to rebuild, for when the names
+    /// would be shown differently without the program changing.
+    pub fn invalidate(&mut self) {
+        self.source = None;
+    }
+
+    fn build(&mut self, aiprog: &AIProgram) {
+        self.child_names = (0..aiprog.behaviors_offset())
+            .map(|i| {
+                aiprog
+                    .entry_name_from_index(i)
+                    .unwrap_or_default()
+                    .to_owned()
+            })
+            .collect();
+        self.behaviour_names = aiprog
+            .behaviors()
+            .into_iter()
+            .map(|ai| AIProgram::entry_name(ai).unwrap_or_default())
+            .collect();
+        self.group_names = [String::new()]
+            .into_iter()
+            .chain(aiprog.ais().into_iter().filter_map(|ai| {
+                ai.objects()
+                    .get(hash_name("Def"))?
+                    .params()
+                    .get(&hash_name("Name"))?
+                    .as_string()
+                    .ok()
+                    .map(|s| s.to_string())
+            }))
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect();
+        self.slot_names = aiprog
+            .items()
+            .into_iter()
+            .filter_map(|ai| ai.objects().get(hash_name("ChildIdx")))
+            .flat_map(|children| children.params().keys().map(|k| try_name(*k)))
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect();
+        let mut string_values: HashMap<u32, BTreeSet<String>> = HashMap::new();
+        for ai in aiprog.items() {
+            for obj_name in ["SInst", "MInst"] {
+                if let Some(obj) = ai.objects().get(hash_name(obj_name)) {
+                    for (k, v) in obj.params() {
+                        if let Ok(s) = v.as_string() {
+                            if !s.is_empty() {
+                                string_values.entry(*k).or_default().insert(s.to_owned());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        self.string_values = string_values
+            .into_iter()
+            .map(|(k, values)| (k, values.into_iter().collect()))
+            .collect();
+    }
+}
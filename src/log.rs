@@ -0,0 +1,115 @@
+use eframe::egui::{self, Ui};
+use std::{
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// How many lines the log window keeps
+const MAX_LINES: usize = 5000;
+
+/// Where the log is written when writing it to a file is turned on.
+pub fn log_file() -> PathBuf {
+    std::env::temp_dir().join("plasticity.log")
+}
+
+/// Everything logged while running, kept for the log window and optionally
+/// also written to [`log_file`], for attaching to bug reports.
+#[derive(Debug, Clone, Default)]
+pub struct Log {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    file: Arc<Mutex<Option<File>>>,
+}
+
+impl Log {
+    /// Starts collecting log events from the app and the core library. Only
+    /// the first call takes effect.
+    pub fn init() -> Self {
+        let log = Self::default();
+        let level = if cfg!(debug_assertions) {
+            tracing::Level::DEBUG
+        } else {
+            tracing::Level::INFO
+        };
+        tracing_subscriber::fmt()
+            .with_writer(log.clone())
+            .with_ansi(false)
+            .with_max_level(level)
+            .try_init()
+            .ok();
+        log
+    }
+
+    /// Starts or stops also writing the log to [`log_file`], starting it
+    /// afresh each run.
+    pub fn set_write_file(&self, enabled: bool) -> io::Result<()> {
+        let mut file = self.file.lock().unwrap();
+        match (enabled, file.is_some()) {
+            (true, false) => {
+                *file = Some(
+                    OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(log_file())?,
+                )
+            }
+            (false, true) => *file = None,
+            _ => (),
+        }
+        Ok(())
+    }
+
+    pub fn text(&self) -> String {
+        let lines = self.lines.lock().unwrap();
+        lines.iter().map(|line| format!("{}\n", line)).collect()
+    }
+
+    pub fn clear(&self) {
+        self.lines.lock().unwrap().clear();
+    }
+
+    pub fn ui(&self, ui: &mut Ui) {
+        egui::ScrollArea::vertical()
+            .max_height(400.0)
+            .stick_to_bottom()
+            .show(ui, |ui| {
+                for line in self.lines.lock().unwrap().iter() {
+                    ui.add(egui::Label::new(line).monospace().wrap(true));
+                }
+            });
+    }
+}
+
+impl Write for Log {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            file.write_all(buf)?;
+        }
+        let mut lines = self.lines.lock().unwrap();
+        for line in String::from_utf8_lossy(buf).lines() {
+            if lines.len() == MAX_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line.to_owned());
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.lock().unwrap().as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for Log {
+    type Writer = Log;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
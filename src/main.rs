@@ -2,13 +2,46 @@
 #![cfg_attr(not(debug_assertions), deny(warnings), windows_subsystem = "windows")] // Forbid warnings in release builds
 #![warn(clippy::all, rust_2018_idioms)]
 
+mod aamp;
 mod app;
 mod auto;
-mod program;
+mod autosave;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+mod conflicts;
+mod crash;
+mod deploy;
+mod document;
+mod dryrun;
+mod history;
+mod i18n;
+mod index;
+mod log;
+mod merge;
+mod param_history;
+mod plugin;
+mod port;
+mod project;
+mod reference;
+mod replace;
+mod rows;
+mod script;
+mod session;
+mod settings;
+mod sidecar;
+mod table;
+mod task;
 mod tree;
-mod util;
+#[cfg(target_arch = "wasm32")]
+mod web;
 
+#[cfg(not(target_arch = "wasm32"))]
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+    crash::install_hook();
     let app = app::App::default();
     let native_options = eframe::NativeOptions {
         icon_data: Some(eframe::epi::IconData {
@@ -20,3 +53,8 @@ fn main() {
     };
     eframe::run_native(Box::new(app), native_options);
 }
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    web::start();
+}
@@ -0,0 +1,150 @@
+#[cfg(target_arch = "wasm32")]
+use crate::web::FileDialog;
+use crate::{app::dialog_button, document::DocVersion, i18n::tr};
+use eframe::egui::{self, Ui};
+use plasticity_core::merge::{Conflict, Conflicted, Edit, Merge, Side};
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::FileDialog;
+use std::path::PathBuf;
+
+/// What the merge window asks the app to do.
+pub enum MergeAction {
+    /// Work out the merge of the active program with another version
+    Start(PathBuf, PathBuf),
+    /// Put the merged program in place of the one it was worked out from
+    Apply,
+}
+
+/// Merges the changes another version of the active program made into it,
+/// given the file both were edited from.
+#[derive(Default)]
+pub struct MergeWizard {
+    base: Option<PathBuf>,
+    theirs: Option<PathBuf>,
+    /// The document being merged into, as it was when the merge was worked
+    /// out
+    pub doc: Option<DocVersion>,
+    pub merge: Option<Merge>,
+}
+
+/// What one side of a conflict did, in the user's language.
+fn edit_text(edit: &Edit) -> String {
+    match edit {
+        Edit::Added => tr!("merge-edit-added"),
+        Edit::Removed => tr!("merge-edit-removed"),
+        Edit::Changed => tr!("merge-edit-changed"),
+        Edit::PointedAt(label) => tr!("merge-edit-pointed", label = label.as_str()),
+        Edit::Cleared => tr!("merge-edit-cleared"),
+    }
+}
+
+/// Describes what both sides of a conflict changed and how.
+fn conflict_text(conflict: &Conflict) -> String {
+    let (ours, theirs) = (edit_text(&conflict.ours), edit_text(&conflict.theirs));
+    match &conflict.subject {
+        Conflicted::Entry { category, label } => tr!(
+            "merge-conflict-entry",
+            category = category.to_string(),
+            label = label.as_str(),
+            ours = ours,
+            theirs = theirs
+        ),
+        Conflicted::Demo(key) => tr!(
+            "merge-conflict-demo",
+            demo = key.as_str(),
+            ours = ours,
+            theirs = theirs
+        ),
+    }
+}
+
+fn file_row(ui: &mut Ui, label: String, file: &mut Option<PathBuf>) {
+    ui.label(label);
+    ui.label(
+        file.as_ref()
+            .and_then(|f| f.file_name())
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| tr!("settings-not-set")),
+    );
+    if dialog_button(ui, true, egui::Button::new(tr!("button-browse"))).clicked() {
+        if let Some(picked) = FileDialog::new()
+            .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+            .add_filter("BOTW YAML AI Program", &["yml"])
+            .add_filter("BOTW Actor Pack", &["sbactorpack", "bactorpack"])
+            .pick_file()
+        {
+            *file = Some(picked);
+        }
+    }
+    ui.end_row();
+}
+
+impl MergeWizard {
+    pub fn ui(&mut self, ui: &mut Ui) -> Option<MergeAction> {
+        ui.spacing_mut().item_spacing.y = 9.0;
+        let merge = match self.merge.as_mut() {
+            Some(merge) => merge,
+            None => {
+                ui.label(tr!("merge-intro"));
+                egui::Grid::new("merge_files")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        file_row(ui, tr!("merge-base"), &mut self.base);
+                        file_row(ui, tr!("merge-theirs"), &mut self.theirs);
+                    });
+                let merge = ui
+                    .add_enabled(
+                        self.base.is_some() && self.theirs.is_some(),
+                        egui::Button::new(tr!("merge-merge")),
+                    )
+                    .clicked();
+                return match (&self.base, &self.theirs) {
+                    (Some(base), Some(theirs)) if merge => {
+                        Some(MergeAction::Start(base.clone(), theirs.clone()))
+                    }
+                    _ => None,
+                };
+            }
+        };
+        if merge.conflicts.is_empty() {
+            ui.label(tr!("merge-clean"));
+        } else {
+            ui.label(tr!("merge-conflicts", count = merge.conflicts.len()));
+            ui.horizontal(|ui| {
+                if ui.small_button(tr!("merge-all-ours")).clicked() {
+                    merge.conflicts.iter_mut().for_each(|c| c.take = Side::Ours);
+                }
+                if ui.small_button(tr!("merge-all-theirs")).clicked() {
+                    merge
+                        .conflicts
+                        .iter_mut()
+                        .for_each(|c| c.take = Side::Theirs);
+                }
+            });
+            egui::ScrollArea::vertical()
+                .max_height(400.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("merge_conflicts")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for conflict in merge.conflicts.iter_mut() {
+                                ui.label(conflict_text(conflict));
+                                ui.radio_value(&mut conflict.take, Side::Ours, tr!("merge-ours"));
+                                ui.radio_value(
+                                    &mut conflict.take,
+                                    Side::Theirs,
+                                    tr!("merge-theirs-side"),
+                                );
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+        if ui.button(tr!("button-apply")).clicked() {
+            Some(MergeAction::Apply)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use roead::aamp::{hash_name, ParamList, Parameter, ParameterList};
+use std::collections::HashMap;
+
+/// Maximum number of earlier values kept for each parameter
+const MAX_VALUES: usize = 20;
+
+/// Objects whose parameters have their earlier values kept
+const OBJECTS: [&str; 2] = ["SInst", "MInst"];
+
+/// Earlier values of the numeric parameters edited this session, for going
+/// back a few tweaks without undoing everything done since. Values are kept
+/// by entry index, so they're forgotten when entries are added or removed.
+#[derive(Debug, Default)]
+pub struct ParamHistory(HashMap<(usize, u32, u32), Vec<Parameter>>);
+
+/// Whether a parameter is edited with drag values.
+fn is_numeric(param: &Parameter) -> bool {
+    matches!(
+        param,
+        Parameter::F32(_)
+            | Parameter::Int(_)
+            | Parameter::U32(_)
+            | Parameter::Vec2(_)
+            | Parameter::Vec3(_)
+            | Parameter::Vec4(_)
+            | Parameter::Color(_)
+            | Parameter::Quat(_)
+    )
+}
+
+/// A parameter as a single number, for graphing, if it is one.
+pub fn scalar(param: &Parameter) -> Option<f32> {
+    match param {
+        Parameter::F32(f) => Some(*f),
+        Parameter::Int(i) => Some(*i as f32),
+        Parameter::U32(u) => Some(*u as f32),
+        _ => None,
+    }
+}
+
+impl ParamHistory {
+    /// Keeps the values an entry's numeric parameters had before an edit,
+    /// given the entry before and after it.
+    pub fn record(&mut self, entry: usize, old: &ParameterList, new: &ParameterList) {
+        for obj_name in OBJECTS {
+            let obj_key = hash_name(obj_name);
+            let (old, new) = match (old.objects().get(obj_key), new.objects().get(obj_key)) {
+                (Some(old), Some(new)) => (old, new),
+                _ => continue,
+            };
+            for (key, value) in new.params() {
+                match old.params().get(key) {
+                    Some(prev) if prev != value && is_numeric(prev) => {
+                        let values = self.0.entry((entry, obj_key, *key)).or_default();
+                        values.retain(|v| v != prev);
+                        values.push(prev.clone());
+                        if values.len() > MAX_VALUES {
+                            values.remove(0);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    /// Forgets every earlier value, for when entries have moved.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Earlier values of a parameter of an entry, oldest first.
+    pub fn values(&self, entry: usize, obj_name: &str, key: u32) -> &[Parameter] {
+        self.0
+            .get(&(entry, hash_name(obj_name), key))
+            .map(|values| values.as_slice())
+            .unwrap_or_default()
+    }
+}
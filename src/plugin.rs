@@ -0,0 +1,82 @@
+//! Extensions built into the app by enabling their Cargo feature, so adding
+//! a panel, command, or file format doesn't mean forking the editor. Plugins
+//! are compiled in rather than loaded from dynamic libraries, as Rust has no
+//! stable ABI to load them across.
+//!
+//! To add one, implement [`Plugin`] in a module under `src/plugin/`, gate the
+//! module on a `plugin-<name>` feature, and add it to [`registered`].
+use anyhow::Result;
+use eframe::egui::Ui;
+use plasticity_core::program::AIProgram;
+use std::path::Path;
+
+#[cfg(feature = "plugin-outline")]
+mod outline;
+
+/// A file format a plugin reads or writes whole AI programs as.
+#[derive(Debug, Clone)]
+pub struct Format {
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    pub can_import: bool,
+    pub can_export: bool,
+}
+
+/// An extension to the editor. Everything but [`Plugin::name`] is optional.
+pub trait Plugin {
+    fn name(&self) -> &str;
+
+    /// Labels of the commands listed under the plugin in the Plugins menu.
+    fn commands(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Runs a command, by its position in [`Plugin::commands`], on the active
+    /// program with the given entry selected. Changes it makes can be undone.
+    fn run_command(
+        &mut self,
+        _command: usize,
+        _aiprog: &mut AIProgram,
+        _selected: usize,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Title of a section the plugin adds to the editor, under the
+    /// parameters of the selected entry.
+    fn panel_title(&self) -> Option<&str> {
+        None
+    }
+
+    /// Draws the plugin's editor section, returning whether it changed
+    /// which entries there are or how they're linked, so the tree needs
+    /// rebuilding. Changes to the selected entry are recorded for undo like
+    /// any edit in the editor, but changes to other entries aren't, so are
+    /// better left to a command.
+    fn panel_ui(&mut self, _ui: &mut Ui, _aiprog: &mut AIProgram, _selected: usize) -> bool {
+        false
+    }
+
+    fn formats(&self) -> Vec<Format> {
+        vec![]
+    }
+
+    /// Reads a program from a file in one of the plugin's formats, by its
+    /// position in [`Plugin::formats`].
+    fn import(&self, _format: usize, _file: &Path) -> Result<AIProgram> {
+        anyhow::bail!("{} can't import this format", self.name())
+    }
+
+    fn export(&self, _format: usize, _aiprog: &AIProgram, _file: &Path) -> Result<()> {
+        anyhow::bail!("{} can't export this format", self.name())
+    }
+}
+
+/// Every plugin enabled in this build.
+pub fn registered() -> Vec<Box<dyn Plugin>> {
+    #[allow(unused_mut)]
+    let mut plugins: Vec<Box<dyn Plugin>> = vec![];
+    #[cfg(feature = "plugin-outline")]
+    plugins.push(Box::new(outline::Outline));
+    plugins
+}
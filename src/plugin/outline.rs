@@ -0,0 +1,78 @@
+use super::{Format, Plugin};
+use crate::i18n::tr;
+use anyhow::Result;
+use eframe::egui::{self, Ui};
+use plasticity_core::program::AIProgram;
+use std::{fmt::Write, path::Path};
+
+/// Shows and exports the tree under an entry as indented text, for pasting
+/// into forum posts and issue reports.
+pub struct Outline;
+
+fn outline(aiprog: &AIProgram, root: usize) -> String {
+    fn add(aiprog: &AIProgram, idx: usize, depth: usize, path: &mut Vec<usize>, out: &mut String) {
+        let repeat = path.contains(&idx);
+        writeln!(
+            out,
+            "{}{}{}",
+            "  ".repeat(depth),
+            aiprog.entry_label(idx),
+            if repeat { " (loops back)" } else { "" }
+        )
+        .unwrap();
+        if repeat {
+            return;
+        }
+        path.push(idx);
+        for child in aiprog.children_of(idx) {
+            add(aiprog, child, depth + 1, path, out);
+        }
+        path.pop();
+    }
+    let mut out = String::new();
+    add(aiprog, root, 0, &mut vec![], &mut out);
+    out
+}
+
+impl Plugin for Outline {
+    fn name(&self) -> &str {
+        "Outline"
+    }
+
+    fn panel_title(&self) -> Option<&str> {
+        Some("Outline")
+    }
+
+    fn panel_ui(&mut self, ui: &mut Ui, aiprog: &mut AIProgram, selected: usize) -> bool {
+        if selected >= aiprog.behaviors_offset() {
+            ui.label(tr!("outline-no-children"));
+            return false;
+        }
+        let text = outline(aiprog, selected);
+        ui.add(egui::Label::new(&text).monospace());
+        if ui.button(tr!("button-copy")).clicked() {
+            ui.output().copied_text = text;
+        }
+        false
+    }
+
+    fn formats(&self) -> Vec<Format> {
+        vec![Format {
+            name: "Text Outline",
+            extensions: &["txt"],
+            can_import: false,
+            can_export: true,
+        }]
+    }
+
+    fn export(&self, _format: usize, aiprog: &AIProgram, file: &Path) -> Result<()> {
+        let text: String = aiprog
+            .entry_points()
+            .into_iter()
+            .map(|root| outline(aiprog, root))
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(file, text)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use crate::i18n::tr;
+use anyhow::Result;
+use eframe::egui::{self, Ui};
+use plasticity_core::{program::AIProgram, util::*};
+use roead::aamp::{hash_name, ParamList};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+/// Copies an AI and everything it needs from another program into the one
+/// being edited, such as to give an actor a vanilla enemy's behavior.
+pub struct PortWizard {
+    pub file: PathBuf,
+    source: AIProgram,
+    root: usize,
+    /// The root and everything below it, as [`AIProgram::export_subtree`] gives
+    snippet: Option<AIProgram>,
+    /// Entry and slot to attach the copy to, or none to leave it as a root
+    parent: Option<(usize, u32)>,
+    /// Behaviors of the snippet which the program already has one of the same
+    /// class, with that behavior and whether to use it instead of a copy
+    reuse: BTreeMap<i32, (i32, bool)>,
+}
+
+impl PortWizard {
+    pub fn new(file: PathBuf, source: AIProgram) -> Self {
+        let root = source.roots().first().copied().unwrap_or(0);
+        Self {
+            file,
+            source,
+            root,
+            snippet: None,
+            parent: None,
+            reuse: BTreeMap::new(),
+        }
+    }
+
+    fn pick_root(&mut self, root: usize, target: &AIProgram) {
+        self.root = root;
+        self.snippet = self.source.export_subtree(root).ok();
+        self.reuse = self
+            .snippet
+            .as_ref()
+            .map(|snippet| {
+                (0..snippet.behaviors().len() as i32)
+                    .filter_map(|i| Some((i, (target.match_behavior(snippet, i)?, false))))
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    /// Draws the wizard, returning whether the port was confirmed.
+    pub fn ui(&mut self, ui: &mut Ui, target: &AIProgram) -> bool {
+        if self.snippet.is_none() {
+            self.pick_root(self.root, target);
+        }
+        ui.spacing_mut().item_spacing.y = 9.0;
+        ui.label(tr!(
+            "port-from",
+            file = self.file.file_name().unwrap_or_default().to_string_lossy()
+        ));
+        let mut root = self.root;
+        egui::ComboBox::from_label(tr!("port-root"))
+            .width(250.0)
+            .selected_text(self.source.entry_label(root))
+            .show_ui(ui, |ui| {
+                let roots = self.source.roots();
+                for i in roots
+                    .iter()
+                    .copied()
+                    .chain((0..self.source.actions_offset()).filter(|i| !roots.contains(i)))
+                {
+                    ui.selectable_value(&mut root, i, self.source.entry_label(i));
+                }
+            });
+        if root != self.root {
+            self.pick_root(root, target);
+        }
+        let snippet = match &self.snippet {
+            Some(snippet) => snippet,
+            None => {
+                ui.label(tr!("port-cannot-copy"));
+                return false;
+            }
+        };
+        ui.label(tr!(
+            "port-counts",
+            ais = snippet.ais().len(),
+            actions = snippet.actions().len(),
+            behaviors = snippet.behaviors().len(),
+            queries = snippet.queries().len()
+        ));
+        Self::parent_ui(ui, &mut self.parent, target);
+        if !self.reuse.is_empty() {
+            ui.label(tr!("port-reuse"));
+            egui::Grid::new("port_reuse").num_columns(2).show(ui, |ui| {
+                for (i, (existing, reuse)) in self.reuse.iter_mut() {
+                    ui.label(snippet.entry_label(snippet.behaviors_offset() + *i as usize));
+                    ui.checkbox(
+                        reuse,
+                        tr!(
+                            "port-use",
+                            entry =
+                                target.entry_label(target.behaviors_offset() + *existing as usize)
+                        ),
+                    );
+                    ui.end_row();
+                }
+            });
+        }
+        let shared_queries: Vec<&str> = (snippet.queries_offset()..snippet.len())
+            .filter_map(|i| snippet.entry_name_from_index(i).ok())
+            .filter(|class| {
+                (target.queries_offset()..target.len())
+                    .any(|i| target.entry_name_from_index(i).ok() == Some(*class))
+            })
+            .collect();
+        if !shared_queries.is_empty() {
+            ui.label(tr!(
+                "port-shared-queries",
+                queries = shared_queries.join(", ")
+            ));
+        }
+        ui.button(tr!("port-port")).clicked()
+    }
+
+    fn parent_ui(ui: &mut Ui, parent: &mut Option<(usize, u32)>, target: &AIProgram) {
+        let label = |parent: Option<(usize, u32)>| match parent {
+            Some((idx, slot)) => format!(
+                "{} / {}",
+                target.entry_label(idx),
+                translate(&try_name(slot))
+            ),
+            None => tr!("port-leave-root"),
+        };
+        egui::ComboBox::from_label(tr!("port-attach"))
+            .width(250.0)
+            .selected_text(label(*parent))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(parent, None, label(None));
+                for idx in 0..target.actions_offset() {
+                    if let Some(slots) = target
+                        .item_at_index(idx)
+                        .objects()
+                        .get(hash_name("ChildIdx"))
+                    {
+                        for slot in slots.params().keys() {
+                            let choice = Some((idx, *slot));
+                            ui.selectable_value(parent, choice, label(choice));
+                        }
+                    }
+                }
+            });
+    }
+
+    /// Copies the chosen AI into a program, returning its new index.
+    pub fn apply(&self, target: &mut AIProgram) -> Result<usize> {
+        let snippet = match &self.snippet {
+            Some(snippet) => snippet,
+            None => anyhow::bail!("Nothing to copy"),
+        };
+        let reuse: HashMap<i32, i32> = self
+            .reuse
+            .iter()
+            .filter(|(_, (_, reuse))| *reuse)
+            .map(|(i, (existing, _))| (*i, *existing))
+            .collect();
+        let root = target.merge_snippet(snippet, &reuse)?;
+        if let Some((parent, slot)) = self.parent {
+            target.reparent(root, None, parent, slot)?;
+        }
+        Ok(root)
+    }
+}
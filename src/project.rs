@@ -0,0 +1,155 @@
+use crate::{i18n::tr, task::Progress};
+use anyhow::Result;
+use eframe::egui::{self, Ui};
+use plasticity_core::{
+    batch::{validate_files, Report},
+    program::{find_programs, AIProgram},
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// A mod's content folder, with every AI program and actor pack in it listed
+/// so they can be opened, checked, and saved together.
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub root: PathBuf,
+    pub files: Vec<PathBuf>,
+    filter: String,
+}
+
+pub enum ProjectAction {
+    Open(PathBuf),
+    Validate,
+    SaveAll,
+    Refresh,
+    Close,
+}
+
+impl Project {
+    pub fn new(root: PathBuf) -> Result<Self> {
+        if !root.is_dir() {
+            anyhow::bail!("{} is not a folder", root.display());
+        }
+        Ok(Self {
+            files: find_programs(&root),
+            root,
+            filter: String::new(),
+        })
+    }
+
+    /// Looks through the folder again for files added or removed.
+    pub fn refresh(&mut self) {
+        self.files = find_programs(&self.root);
+    }
+
+    pub fn contains(&self, file: &Path) -> bool {
+        file.starts_with(&self.root)
+    }
+
+    /// Path of a file from the project folder, for display.
+    pub fn relative(&self, file: &Path) -> String {
+        file.strip_prefix(&self.root)
+            .unwrap_or(file)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Draws the file list, given the open files with whether each has unsaved
+    /// changes, and returns whatever was clicked.
+    pub fn ui(
+        &mut self,
+        ui: &mut Ui,
+        open: &HashMap<PathBuf, bool>,
+        active: Option<&Path>,
+    ) -> Option<ProjectAction> {
+        let mut action = None;
+        ui.label(tr!(
+            "project-title",
+            name = self.root.file_name().unwrap_or_default().to_string_lossy()
+        ))
+        .on_hover_text(self.root.to_string_lossy());
+        ui.horizontal(|ui| {
+            if ui
+                .small_button(tr!("tools-validate"))
+                .on_hover_text(tr!("project-validate-hover"))
+                .clicked()
+            {
+                action = Some(ProjectAction::Validate);
+            }
+            if ui
+                .small_button(tr!("project-save-all"))
+                .on_hover_text(tr!("project-save-all-hover"))
+                .clicked()
+            {
+                action = Some(ProjectAction::SaveAll);
+            }
+            if ui
+                .small_button("⟳")
+                .on_hover_text(tr!("project-refresh"))
+                .clicked()
+            {
+                action = Some(ProjectAction::Refresh);
+            }
+            if ui
+                .small_button("✖")
+                .on_hover_text(tr!("file-close-project"))
+                .clicked()
+            {
+                action = Some(ProjectAction::Close);
+            }
+        });
+        ui.add(
+            egui::TextEdit::singleline(&mut self.filter)
+                .hint_text(tr!("hint-filter"))
+                .desired_width(f32::INFINITY),
+        );
+        ui.separator();
+        if self.files.is_empty() {
+            ui.label(tr!("project-empty"));
+        }
+        let filter = self.filter.to_lowercase();
+        egui::ScrollArea::vertical()
+            .id_source("project_files")
+            .show(ui, |ui| {
+                for file in &self.files {
+                    let mut label = self.relative(file);
+                    if !filter.is_empty() && !label.to_lowercase().contains(&filter) {
+                        continue;
+                    }
+                    match open.get(file) {
+                        Some(true) => label.push_str(" •"),
+                        Some(false) => label.push_str(&format!(" {}", tr!("project-open"))),
+                        None => (),
+                    }
+                    if ui
+                        .selectable_label(active == Some(file.as_path()), label)
+                        .clicked()
+                    {
+                        action = Some(ProjectAction::Open(file.clone()));
+                    }
+                }
+            });
+        action
+    }
+
+    /// Runs every check on each file, using the given programs for files
+    /// which are open so unsaved changes are checked too.
+    pub fn validate(&self, open: &HashMap<PathBuf, AIProgram>, progress: &Progress) -> Report {
+        Report {
+            root: self.root.clone(),
+            files: validate_files(
+                &self.files,
+                |file| match open.get(file) {
+                    Some(aiprog) => Ok(aiprog.clone()),
+                    None => AIProgram::new(file),
+                },
+                |done, total| {
+                    progress.set(done, total, tr!("task-files"));
+                    !progress.is_cancelled()
+                },
+            ),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::tree::{Tree, TreeAction, TreeDrag, TreeFilter, TreeView};
+use eframe::egui::{self, Ui};
+use plasticity_core::{program::AIProgram, util::*};
+use roead::aamp::{hash_name, ParamList, Parameter};
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::PathBuf,
+};
+
+/// A second AI program, such as the vanilla version of the one being edited,
+/// which can be browsed but not changed.
+pub struct Reference {
+    pub file: PathBuf,
+    aiprog: AIProgram,
+    tree: Vec<Tree>,
+    selected_ai: usize,
+    drag: TreeDrag,
+}
+
+impl Reference {
+    pub fn new(file: PathBuf, aiprog: AIProgram, tree: Vec<Tree>) -> Self {
+        Self {
+            file,
+            aiprog,
+            tree,
+            selected_ai: 0,
+            drag: TreeDrag::default(),
+        }
+    }
+
+    /// Draws the tree and the selected entry, returning anything picked from a
+    /// node's context menu.
+    pub fn ui(&mut self, ui: &mut Ui) -> Option<TreeAction> {
+        let action = egui::ScrollArea::vertical()
+            .id_source("reference_tree")
+            .max_height(ui.available_height() / 2.0)
+            .show(ui, |ui| {
+                let highlighted = HashSet::new();
+                let mut multi_selected = BTreeSet::new();
+                let filter = TreeFilter::default();
+                let marked_roots = BTreeSet::new();
+                let modified = BTreeSet::new();
+                let pinned = BTreeSet::new();
+                let view = TreeView {
+                    highlighted: &highlighted,
+                    filter: &filter,
+                    marked_roots: &marked_roots,
+                    modified: &modified,
+                    pinned: &pinned,
+                };
+                self.tree.iter_mut().fold(None, |action, t| {
+                    t.ui(
+                        ui,
+                        &mut self.selected_ai,
+                        &mut multi_selected,
+                        &mut self.drag,
+                        &view,
+                    )
+                    .or(action)
+                })
+            });
+        // Nothing can be moved around in here
+        self.drag = TreeDrag::default();
+        ui.separator();
+        if self.selected_ai >= self.aiprog.len() {
+            return action;
+        }
+        ui.heading(self.aiprog.entry_label(self.selected_ai));
+        egui::ScrollArea::vertical()
+            .id_source("reference_entry")
+            .show(ui, |ui| self.entry_ui(ui));
+        action
+    }
+
+    pub fn aiprog(&self) -> &AIProgram {
+        &self.aiprog
+    }
+
+    fn entry_ui(&self, ui: &mut Ui) {
+        let entry = self.aiprog.item_at_index(self.selected_ai);
+        for obj_name in ["Def", "ChildIdx", "SInst", "MInst", "BehaviorIdx"] {
+            let obj = match entry.objects().get(hash_name(obj_name)) {
+                Some(obj) => obj,
+                None => continue,
+            };
+            egui::CollapsingHeader::new(obj_name)
+                .id_source(("reference", obj_name))
+                .default_open(true)
+                .show(ui, |ui| {
+                    egui::Grid::new(("reference_grid", obj_name))
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for (k, v) in obj.params() {
+                                let name = try_name(*k);
+                                ui.label(translate(&name));
+                                ui.label(match (obj_name, v) {
+                                    ("ChildIdx", Parameter::Int(i))
+                                        if *i >= 0 && (*i as usize) < self.aiprog.len() =>
+                                    {
+                                        self.aiprog.entry_label(*i as usize)
+                                    }
+                                    ("BehaviorIdx", Parameter::Int(i))
+                                        if *i >= 0
+                                            && (*i as usize) < self.aiprog.behaviors().len() =>
+                                    {
+                                        self.aiprog.entry_label(
+                                            self.aiprog.behaviors_offset() + *i as usize,
+                                        )
+                                    }
+                                    _ => param_string(v),
+                                });
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{i18n::tr, settings::error_color};
+use eframe::egui::{self, Ui};
+use plasticity_core::{
+    program::AIProgram,
+    replace::{ReplaceHit, ReplaceOptions},
+};
+
+pub enum ReplaceAction {
+    Goto(usize),
+    /// Make the ticked replacements
+    Apply(Vec<ReplaceHit>),
+}
+
+/// Find and replace over the string parameters of a program, listing every
+/// change before any is made so unwanted ones can be unticked.
+#[derive(Debug, Default)]
+pub struct FindReplace {
+    find: String,
+    replace: String,
+    options: ReplaceOptions,
+    /// Changes the current search would make, and whether each is wanted
+    hits: Vec<(ReplaceHit, bool)>,
+    error: Option<String>,
+    /// How many parameters the last replacement changed
+    pub applied: Option<usize>,
+}
+
+impl FindReplace {
+    /// Runs the search again, as after the program changes.
+    pub fn refresh(&mut self, aiprog: &AIProgram) {
+        match aiprog.find_replace(&self.find, &self.replace, self.options) {
+            Ok(hits) => {
+                let skipped: Vec<&ReplaceHit> = self
+                    .hits
+                    .iter()
+                    .filter(|(_, wanted)| !wanted)
+                    .map(|(hit, _)| hit)
+                    .collect();
+                self.hits = hits
+                    .into_iter()
+                    .map(|hit| {
+                        let wanted = !skipped.contains(&&hit);
+                        (hit, wanted)
+                    })
+                    .collect();
+                self.error = None;
+            }
+            Err(e) => {
+                self.hits.clear();
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, aiprog: &AIProgram) -> Option<ReplaceAction> {
+        let mut action = None;
+        ui.spacing_mut().item_spacing.y = 9.0;
+        let mut changed = false;
+        egui::Grid::new("find_replace_fields")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(tr!("replace-find"));
+                changed |= ui
+                    .add(egui::TextEdit::singleline(&mut self.find).id_source("replace_find"))
+                    .changed();
+                ui.end_row();
+                ui.label(tr!("replace-with"));
+                changed |= ui.text_edit_singleline(&mut self.replace).changed();
+                ui.end_row();
+            });
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut self.options.case_sensitive, tr!("replace-match-case"))
+                .changed();
+            changed |= ui
+                .checkbox(&mut self.options.regex, tr!("replace-regex"))
+                .on_hover_text(tr!("replace-regex-hover"))
+                .changed();
+        });
+        if changed {
+            self.applied = None;
+            self.refresh(aiprog);
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(error_color(), error);
+        } else if !self.find.is_empty() {
+            ui.label(tr!("replace-would-change", count = self.hits.len()));
+        }
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                egui::Grid::new("find_replace_hits")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (hit, wanted) in self.hits.iter_mut() {
+                            ui.checkbox(wanted, "");
+                            if ui.small_button(aiprog.entry_label(hit.entry)).clicked() {
+                                action = Some(ReplaceAction::Goto(hit.entry));
+                            }
+                            ui.label(hit.field());
+                            ui.label(format!("{} → {}", hit.before, hit.after));
+                            ui.end_row();
+                        }
+                    });
+            });
+        ui.horizontal(|ui| {
+            let wanted: Vec<ReplaceHit> = self
+                .hits
+                .iter()
+                .filter(|(_, wanted)| *wanted)
+                .map(|(hit, _)| hit.clone())
+                .collect();
+            if ui
+                .add_enabled(
+                    !wanted.is_empty(),
+                    egui::Button::new(tr!("replace-apply", count = wanted.len())),
+                )
+                .clicked()
+            {
+                action = Some(ReplaceAction::Apply(wanted));
+            }
+            if let Some(count) = self.applied {
+                ui.label(tr!("replace-done", count = count));
+            }
+        });
+        action
+    }
+}
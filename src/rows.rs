@@ -0,0 +1,46 @@
+//! Laying out only the rows of long lists and grids which are scrolled into
+//! view, as huge entries would otherwise slow down every frame.
+use eframe::egui::{self, TextStyle, Ui};
+use std::{hash::Hash, ops::Range};
+
+/// Lists with more rows than this only lay out the ones in view
+pub const MANY_ROWS: usize = 100;
+
+/// Height of a row of selectable labels or buttons.
+pub fn list_row_height(ui: &Ui) -> f32 {
+    (ui.fonts().row_height(TextStyle::Button) + 2.0 * ui.spacing().button_padding.y)
+        .max(ui.spacing().interact_size.y)
+}
+
+/// Height to give every row of a grid of fields, enough for any single line
+/// widget, so rows in view can be worked out from how far it's scrolled.
+pub fn grid_row_height(ui: &Ui) -> f32 {
+    let fonts = ui.fonts();
+    let text = [TextStyle::Body, TextStyle::Button, TextStyle::Monospace]
+        .iter()
+        .map(|style| fonts.row_height(*style))
+        .fold(0.0, f32::max);
+    // Text edits have a margin of 2 above and below
+    (text + 4.0).max(ui.spacing().interact_size.y)
+}
+
+/// Shows rows which are all `row_height` high, laying out every one if
+/// there aren't many, or otherwise scrolling them in an area at most
+/// `max_height` high and laying out only those in view.
+pub fn show_rows(
+    ui: &mut Ui,
+    id_source: impl Hash,
+    max_height: f32,
+    row_height: f32,
+    count: usize,
+    add_rows: impl FnOnce(&mut Ui, Range<usize>),
+) {
+    if count <= MANY_ROWS {
+        add_rows(ui, 0..count);
+    } else {
+        egui::ScrollArea::vertical()
+            .id_source(id_source)
+            .max_height(max_height)
+            .show_rows(ui, row_height, count, add_rows);
+    }
+}
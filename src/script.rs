@@ -0,0 +1,237 @@
+use anyhow::{anyhow, Result};
+use plasticity_core::{
+    program::{AIProgram, Category},
+    util::*,
+};
+use rhai::{Dynamic, Engine, EvalAltResult, INT};
+use roead::aamp::{hash_name, ParamList, Parameter};
+use std::{cell::RefCell, rc::Rc};
+
+type ScriptResult<T> = std::result::Result<T, Box<EvalAltResult>>;
+
+fn to_dynamic(param: &Parameter) -> Dynamic {
+    match param {
+        Parameter::Bool(b) => Dynamic::from(*b),
+        Parameter::F32(f) => Dynamic::from(*f as rhai::FLOAT),
+        Parameter::Int(i) => Dynamic::from(*i as INT),
+        Parameter::U32(u) => Dynamic::from(*u as INT),
+        Parameter::String32(s)
+        | Parameter::String64(s)
+        | Parameter::String256(s)
+        | Parameter::StringRef(s) => Dynamic::from(s.clone()),
+        _ => Dynamic::UNIT,
+    }
+}
+
+/// Converts a script value to a parameter, keeping the type of the parameter
+/// it replaces if there is one.
+fn from_dynamic(value: Dynamic, old: Option<&Parameter>) -> ScriptResult<Parameter> {
+    let type_name = value.type_name();
+    let mismatch = || -> Box<EvalAltResult> {
+        format!("Cannot store a {} in this parameter", type_name).into()
+    };
+    Ok(match old {
+        Some(Parameter::Bool(_)) => Parameter::Bool(value.as_bool().map_err(|_| mismatch())?),
+        Some(Parameter::F32(_)) => Parameter::F32(
+            value
+                .as_float()
+                .or_else(|_| value.as_int().map(|i| i as rhai::FLOAT))
+                .map_err(|_| mismatch())? as f32,
+        ),
+        Some(Parameter::Int(_)) => Parameter::Int(value.as_int().map_err(|_| mismatch())? as i32),
+        Some(Parameter::U32(_)) => Parameter::U32(value.as_int().map_err(|_| mismatch())? as u32),
+        Some(Parameter::String64(_)) => {
+            Parameter::String64(value.into_string().map_err(|_| mismatch())?)
+        }
+        Some(Parameter::String256(_)) => {
+            Parameter::String256(value.into_string().map_err(|_| mismatch())?)
+        }
+        Some(Parameter::StringRef(_)) => {
+            Parameter::StringRef(value.into_string().map_err(|_| mismatch())?)
+        }
+        Some(Parameter::String32(_)) | None if value.is_string() => {
+            Parameter::String32(value.into_string().map_err(|_| mismatch())?)
+        }
+        None if value.is_bool() => Parameter::Bool(value.as_bool().unwrap()),
+        None if value.is_int() => Parameter::Int(value.as_int().unwrap() as i32),
+        None if value.is_float() => Parameter::F32(value.as_float().unwrap() as f32),
+        _ => return Err(mismatch()),
+    })
+}
+
+fn parse_category(category: &str) -> ScriptResult<Category> {
+    Ok(match category {
+        "AI" => Category::AI,
+        "Action" => Category::Action,
+        "Behavior" | "Behaviour" => Category::Behaviour,
+        "Query" => Category::Query,
+        _ => return Err(format!("Unknown category {}", category).into()),
+    })
+}
+
+/// Runs a Rhai script against a copy of an AI program, returning the edited
+/// program along with anything the script printed.
+///
+/// Scripts can use `len()`, `category(i)`, `class_name(i)`, `name(i)`,
+/// `get_param(i, obj, name)`, `set_param(i, obj, name, value)`,
+/// `has_param(i, obj, name)`, `remove_param(i, obj, name)`,
+/// `add_entry(category, class)`, and `delete_entry(i)`, where `i` is an
+/// absolute entry index.
+pub fn run_script(aiprog: AIProgram, script: &str) -> Result<(AIProgram, Vec<String>)> {
+    let aiprog = Rc::new(RefCell::new(aiprog));
+    let log = Rc::new(RefCell::new(vec![]));
+    let mut engine = Engine::new();
+    let check = {
+        let aiprog = aiprog.clone();
+        move |i: INT| -> ScriptResult<usize> {
+            if i >= 0 && (i as usize) < aiprog.borrow().len() {
+                Ok(i as usize)
+            } else {
+                Err(format!("Entry index {} out of range", i).into())
+            }
+        }
+    };
+
+    {
+        let log = log.clone();
+        engine.on_print(move |s| log.borrow_mut().push(s.to_owned()));
+    }
+    {
+        let aiprog = aiprog.clone();
+        engine.register_fn("len", move || aiprog.borrow().len() as INT);
+    }
+    {
+        let (aiprog, check) = (aiprog.clone(), check.clone());
+        engine.register_fn("category", move |i: INT| -> ScriptResult<String> {
+            Ok(aiprog.borrow().category_of(check(i)?).to_string())
+        });
+    }
+    {
+        let (aiprog, check) = (aiprog.clone(), check.clone());
+        engine.register_fn("class_name", move |i: INT| -> ScriptResult<String> {
+            let aiprog = aiprog.borrow();
+            Ok(aiprog
+                .entry_name_from_index(check(i)?)
+                .map_err(|e| e.to_string())?
+                .to_owned())
+        });
+    }
+    {
+        let (aiprog, check) = (aiprog.clone(), check.clone());
+        engine.register_fn("name", move |i: INT| -> ScriptResult<String> {
+            let aiprog = aiprog.borrow();
+            Ok(aiprog
+                .item_at_index(check(i)?)
+                .objects()
+                .get(hash_name("Def"))
+                .and_then(|def| def.params().get(&hash_name("Name")))
+                .and_then(|name| name.as_string().ok())
+                .unwrap_or_default()
+                .to_owned())
+        });
+    }
+    {
+        let (aiprog, check) = (aiprog.clone(), check.clone());
+        engine.register_fn(
+            "get_param",
+            move |i: INT, obj: &str, name: &str| -> ScriptResult<Dynamic> {
+                let aiprog = aiprog.borrow();
+                Ok(aiprog
+                    .item_at_index(check(i)?)
+                    .objects()
+                    .get(hash_name(obj))
+                    .and_then(|obj| obj.params().get(&hash_name(name)))
+                    .map(to_dynamic)
+                    .unwrap_or(Dynamic::UNIT))
+            },
+        );
+    }
+    {
+        let (aiprog, check) = (aiprog.clone(), check.clone());
+        engine.register_fn(
+            "has_param",
+            move |i: INT, obj: &str, name: &str| -> ScriptResult<bool> {
+                let aiprog = aiprog.borrow();
+                Ok(aiprog
+                    .item_at_index(check(i)?)
+                    .objects()
+                    .get(hash_name(obj))
+                    .map(|obj| obj.params().contains_key(&hash_name(name)))
+                    .unwrap_or(false))
+            },
+        );
+    }
+    {
+        let (aiprog, check) = (aiprog.clone(), check.clone());
+        engine.register_fn(
+            "set_param",
+            move |i: INT, obj: &str, name: &str, value: Dynamic| -> ScriptResult<()> {
+                let idx = check(i)?;
+                let mut aiprog = aiprog.borrow_mut();
+                let params = aiprog
+                    .item_mut_at_index(idx)
+                    .objects_mut()
+                    .inner_mut()
+                    .entry(hash_name(obj))
+                    .or_default()
+                    .params_mut();
+                let param = from_dynamic(value, params.get(&hash_name(name)))?;
+                NAME_TABLE.write().unwrap().add_name(name);
+                params.insert(hash_name(name), param);
+                Ok(())
+            },
+        );
+    }
+    {
+        let (aiprog, check) = (aiprog.clone(), check.clone());
+        engine.register_fn(
+            "remove_param",
+            move |i: INT, obj: &str, name: &str| -> ScriptResult<()> {
+                let idx = check(i)?;
+                if let Some(obj) = aiprog
+                    .borrow_mut()
+                    .item_mut_at_index(idx)
+                    .objects_mut()
+                    .get_mut(hash_name(obj))
+                {
+                    obj.params_mut().shift_remove(&hash_name(name));
+                }
+                Ok(())
+            },
+        );
+    }
+    {
+        let aiprog = aiprog.clone();
+        engine.register_fn(
+            "add_entry",
+            move |category: &str, class: &str| -> ScriptResult<INT> {
+                let category = parse_category(category)?;
+                aiprog
+                    .borrow_mut()
+                    .add_entry(category, class.to_owned())
+                    .map(|i| i as INT)
+                    .map_err(|e| e.to_string().into())
+            },
+        );
+    }
+    {
+        let (aiprog, check) = (aiprog.clone(), check);
+        engine.register_fn("delete_entry", move |i: INT| -> ScriptResult<()> {
+            let idx = check(i)?;
+            aiprog
+                .borrow_mut()
+                .delete_entry(idx)
+                .map_err(|e| e.to_string().into())
+        });
+    }
+
+    engine
+        .run(script)
+        .map_err(|e| anyhow!("Script error: {}", e))?;
+    drop(engine);
+    let aiprog = Rc::try_unwrap(aiprog)
+        .map_err(|_| anyhow!("Script state still in use"))?
+        .into_inner();
+    let log = log.take();
+    Ok((aiprog, log))
+}
@@ -0,0 +1,89 @@
+use crate::{deploy::Deploy, i18n::tr};
+use anyhow::{Context, Result};
+use plasticity_core::util::{add_names, set_translation};
+use roead::aamp::hash_name;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Maximum number of files kept in the recent files list
+const MAX_RECENT: usize = 10;
+
+/// State persisted between runs through `epi::Storage`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub recent_files: Vec<PathBuf>,
+    /// Whether to open the last file again on startup
+    pub reopen_last: bool,
+    pub last_file: Option<PathBuf>,
+    /// Selected entry in the last file
+    pub last_selected: usize,
+    /// Mod folder open as a project, to open again on startup with the last
+    /// file
+    pub project: Option<PathBuf>,
+    /// Where the game dump was set before it moved to the settings, read only
+    /// to carry it over
+    #[serde(skip_serializing)]
+    pub game_dump: Option<PathBuf>,
+    /// Mod folder saved files are copied into for testing
+    pub deploy: Deploy,
+    /// Names the user has found for hashes missing from the bundled table
+    pub hash_names: BTreeMap<u32, String>,
+    /// Translations the user has added or corrected, by Japanese name
+    pub translations: BTreeMap<String, String>,
+    /// Name and class of the AIs marked as roots in each file
+    pub marked_roots: BTreeMap<PathBuf, Vec<(String, String)>>,
+}
+
+impl Session {
+    pub const KEY: &'static str = "session";
+
+    /// Moves a file to the top of the recent files list.
+    pub fn add_recent(&mut self, file: &Path) {
+        self.recent_files.retain(|f| f != file);
+        self.recent_files.insert(0, file.to_path_buf());
+        self.recent_files.truncate(MAX_RECENT);
+    }
+
+    /// Adds a name to the user's hash table, returning its hash.
+    pub fn add_hash_name(&mut self, name: &str) -> u32 {
+        let hash = hash_name(name);
+        self.hash_names.insert(hash, name.to_owned());
+        add_names([name]);
+        hash
+    }
+
+    /// Adds the names from a table in the same form as the bundled
+    /// `hashes.json`, skipping any which don't hash to their key.
+    pub fn import_hash_names(&mut self, file: &Path) -> Result<()> {
+        let names: BTreeMap<u32, String> = serde_json::from_str(&fs::read_to_string(file)?)
+            .with_context(|| tr!("error-hash-table", file = file.display().to_string()))?;
+        for (_, name) in names.into_iter().filter(|(k, n)| hash_name(n) == *k) {
+            self.add_hash_name(&name);
+        }
+        Ok(())
+    }
+
+    /// Sets the user's translation of a Japanese name. An empty one is removed,
+    /// so the bundled translation applies again.
+    pub fn set_translation(&mut self, name: &str, english: &str) {
+        if english.is_empty() {
+            self.translations.remove(name);
+            set_translation(name, None);
+        } else {
+            self.translations
+                .insert(name.to_owned(), english.to_owned());
+            set_translation(name, Some(english));
+        }
+    }
+
+    /// Writes the user's hash table out for sharing.
+    pub fn export_hash_names(&self, file: &Path) -> Result<()> {
+        fs::write(file, serde_json::to_string_pretty(&self.hash_names)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{
+    deploy::DeployLayout,
+    i18n::{set_language, tr, Language},
+};
+use anyhow::Result;
+use eframe::egui::{Color32, CtxRef, FontDefinitions, Visuals};
+use lazy_static::lazy_static;
+use plasticity_core::util::{
+    set_angle_display, set_index_display, set_name_display, AngleUnit, IndexDisplay, NameDisplay,
+};
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::RwLock};
+
+/// Names of the app and the settings file for finding it in the config folder
+const APP_NAME: &str = "plasticity";
+const CONFIG_NAME: &str = "settings";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Theme {
+    Dark,
+    Light,
+}
+
+/// Colors of the markers for problems and changes, as sRGB, so they can be
+/// told apart without relying on red and green alone.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Colors {
+    /// Checks which passed
+    pub ok: [u8; 3],
+    pub warning: [u8; 3],
+    pub error: [u8; 3],
+    /// Edited entries and parameters, and values which differ
+    pub changed: [u8; 3],
+}
+
+/// A built-in set of [`Colors`], or the user's own.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Palette {
+    Standard,
+    /// The Okabe-Ito colors, which stay distinct with the common kinds of
+    /// colorblindness
+    ColorblindSafe,
+    HighContrast,
+    Custom,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [
+        Palette::Standard,
+        Palette::ColorblindSafe,
+        Palette::HighContrast,
+        Palette::Custom,
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            Palette::Standard => tr!("settings-colors-standard"),
+            Palette::ColorblindSafe => tr!("settings-colors-colorblind"),
+            Palette::HighContrast => tr!("settings-colors-high-contrast"),
+            Palette::Custom => tr!("settings-colors-custom"),
+        }
+    }
+
+    /// The colors of a built-in palette against a theme's background, or
+    /// `None` for the user's own.
+    pub fn colors(&self, theme: Theme) -> Option<Colors> {
+        match self {
+            Palette::Standard => Some(Colors {
+                ok: [0, 255, 0],
+                warning: [255, 255, 0],
+                error: [255, 0, 0],
+                changed: [0x4a, 0x9e, 0xff],
+            }),
+            Palette::ColorblindSafe => Some(Colors {
+                ok: [86, 180, 233],
+                warning: [240, 228, 66],
+                error: [213, 94, 0],
+                changed: [204, 121, 167],
+            }),
+            Palette::HighContrast if theme == Theme::Dark => Some(Colors {
+                ok: [0, 255, 255],
+                warning: [255, 255, 0],
+                error: [255, 0, 255],
+                changed: [255, 255, 255],
+            }),
+            Palette::HighContrast => Some(Colors {
+                ok: [0, 90, 181],
+                warning: [140, 80, 0],
+                error: [190, 0, 90],
+                changed: [0, 0, 0],
+            }),
+            Palette::Custom => None,
+        }
+    }
+}
+
+lazy_static! {
+    static ref COLORS: RwLock<Colors> = RwLock::new(Palette::Standard.colors(Theme::Dark).unwrap());
+}
+
+fn color([r, g, b]: [u8; 3]) -> Color32 {
+    Color32::from_rgb(r, g, b)
+}
+
+pub fn ok_color() -> Color32 {
+    color(COLORS.read().unwrap().ok)
+}
+
+pub fn warning_color() -> Color32 {
+    color(COLORS.read().unwrap().warning)
+}
+
+pub fn error_color() -> Color32 {
+    color(COLORS.read().unwrap().error)
+}
+
+pub fn changed_color() -> Color32 {
+    color(COLORS.read().unwrap().changed)
+}
+
+/// Format new files are saved in by default.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SaveFormat {
+    Binary,
+    Yaml,
+}
+
+impl SaveFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SaveFormat::Binary => "baiprog",
+            SaveFormat::Yaml => "yml",
+        }
+    }
+}
+
+/// The version of the game mods are made for, which decides the endianness
+/// of its files: big endian for the Wii U and little endian for the Switch.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Platform {
+    WiiU,
+    Switch,
+}
+
+impl Platform {
+    /// How a new mod folder to deploy to is most likely laid out.
+    pub fn deploy_layout(&self) -> DeployLayout {
+        match self {
+            Platform::WiiU => DeployLayout::Bcml,
+            Platform::Switch => DeployLayout::LayeredFs,
+        }
+    }
+}
+
+/// Preferences, kept in their own file in the user's config folder so they
+/// can be edited by hand and read without the UI.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Language the UI is shown in
+    pub language: Language,
+    pub theme: Theme,
+    /// Colors of the markers for problems and changes
+    pub palette: Palette,
+    /// Colors used when the palette is [`Palette::Custom`]
+    pub custom_colors: Colors,
+    /// Multiplier for the default font sizes
+    pub font_scale: f32,
+    /// Multiplier for the size of the whole UI, over the screen's own scale
+    pub ui_scale: f32,
+    /// Language to show names from the game in
+    pub name_display: NameDisplay,
+    /// How entries are numbered in the tree and entry pickers
+    pub index_display: IndexDisplay,
+    /// Unit angle parameters are edited in
+    pub angle_display: AngleUnit,
+    /// How many earlier versions of a file to keep when saving over it
+    pub backup_count: usize,
+    /// Whether to also write the log to a file
+    pub log_to_file: bool,
+    /// Unpacked game files to open actors' AI programs from
+    pub game_dump: Option<PathBuf>,
+    /// `gamedata.ssarc`, `Bootup.pack`, or a text list of GameData flags to
+    /// check flag parameters against
+    pub flag_list: Option<PathBuf>,
+    pub save_format: SaveFormat,
+    pub platform: Platform,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            language: Language::English,
+            theme: Theme::Dark,
+            palette: Palette::Standard,
+            custom_colors: Palette::Standard.colors(Theme::Dark).unwrap(),
+            font_scale: 1.2,
+            ui_scale: 1.0,
+            name_display: NameDisplay::English,
+            index_display: IndexDisplay::PerCategory,
+            angle_display: AngleUnit::Degrees,
+            backup_count: 3,
+            log_to_file: false,
+            game_dump: None,
+            flag_list: None,
+            save_format: SaveFormat::Yaml,
+            platform: Platform::WiiU,
+        }
+    }
+}
+
+impl Settings {
+    /// Key the settings were kept under in `epi::Storage` before they had a
+    /// file of their own, for carrying them over.
+    pub const KEY: &'static str = "settings";
+
+    pub fn file() -> Result<PathBuf> {
+        Ok(confy::get_configuration_file_path(APP_NAME, CONFIG_NAME)?)
+    }
+
+    /// Reads the settings file, or `None` if there isn't one yet.
+    pub fn load() -> Result<Option<Self>> {
+        if !Self::file()?.exists() {
+            return Ok(None);
+        }
+        Ok(Some(confy::load(APP_NAME, CONFIG_NAME)?))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        Ok(confy::store(APP_NAME, CONFIG_NAME, self.clone())?)
+    }
+
+    /// Applies the settings to the UI, scaling the given fonts and the screen's
+    /// native pixels per point.
+    pub fn apply(&self, ctx: &CtxRef, fonts: &FontDefinitions, native_ppp: Option<f32>) {
+        ctx.set_visuals(match self.theme {
+            Theme::Dark => Visuals::dark(),
+            Theme::Light => Visuals::light(),
+        });
+        let mut fonts = fonts.clone();
+        fonts.family_and_size.iter_mut().for_each(|(_, (_, size))| {
+            *size *= self.font_scale;
+        });
+        ctx.set_fonts(fonts);
+        ctx.set_pixels_per_point(native_ppp.unwrap_or(1.0) * self.ui_scale);
+        set_name_display(self.name_display);
+        set_index_display(self.index_display);
+        set_angle_display(self.angle_display);
+        set_language(self.language);
+        *COLORS.write().unwrap() = self
+            .palette
+            .colors(self.theme)
+            .unwrap_or(self.custom_colors);
+    }
+}
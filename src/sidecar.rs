@@ -0,0 +1,57 @@
+use crate::i18n::tr;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Editor data about an AI program which the game has no place for, kept in a
+/// `.plasticity.json` file next to it so the program itself is untouched.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sidecar {
+    /// Notes on entries, by [`crate::document::Document::entry_key`]
+    pub notes: BTreeMap<String, String>,
+    /// Entries listed at the top of the side panel, by
+    /// [`crate::document::Document::entry_key`], in the order they were pinned
+    pub pins: Vec<String>,
+}
+
+impl Sidecar {
+    /// Where the sidecar of a file is kept.
+    pub fn path(file: &Path) -> PathBuf {
+        let mut name = file.file_name().unwrap_or_default().to_os_string();
+        name.push(".plasticity.json");
+        file.with_file_name(name)
+    }
+
+    /// Reads the sidecar of a file, or an empty one if it has none.
+    pub fn load(file: &Path) -> Result<Self> {
+        let path = Self::path(file);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        serde_json::from_str(&fs::read_to_string(&path)?)
+            .with_context(|| tr!("error-sidecar", file = path.display().to_string()))
+    }
+
+    /// Writes the sidecar of a file, removing it instead if there's nothing in
+    /// it.
+    pub fn save(&self, file: &Path) -> Result<()> {
+        let path = Self::path(file);
+        if self.is_empty() {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        } else {
+            fs::write(path, serde_json::to_string_pretty(self)?)?;
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty() && self.pins.is_empty()
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{i18n::tr, rows::grid_row_height};
+use eframe::egui::{self, Ui};
+use plasticity_core::program::{AIProgram, Category};
+use roead::aamp::{hash_name, ParamList};
+use std::{cmp::Ordering, collections::BTreeSet};
+
+/// Columns of the entry table, in order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Column {
+    Index,
+    Name,
+    ClassName,
+    GroupName,
+    Children,
+}
+
+const COLUMNS: [Column; 5] = [
+    Column::Index,
+    Column::Name,
+    Column::ClassName,
+    Column::GroupName,
+    Column::Children,
+];
+
+impl Column {
+    fn title(&self) -> String {
+        match self {
+            Column::Index => "#".into(),
+            Column::Name => "Name".into(),
+            Column::ClassName => "ClassName".into(),
+            Column::GroupName => "GroupName".into(),
+            Column::Children => tr!("section-children"),
+        }
+    }
+}
+
+/// One entry as shown in the table.
+struct Row {
+    idx: usize,
+    cells: [String; 5],
+    children: usize,
+}
+
+impl Row {
+    fn new(aiprog: &AIProgram, idx: usize) -> Self {
+        let def = aiprog.item_at_index(idx).objects().get(hash_name("Def"));
+        let def_string = |key: &str| {
+            def.and_then(|def| def.params().get(&hash_name(key)))
+                .and_then(|v| v.as_string().ok())
+                .unwrap_or_default()
+                .to_owned()
+        };
+        let children = aiprog.children_of(idx).len();
+        Self {
+            idx,
+            cells: [
+                aiprog.index_label(idx),
+                def_string("Name"),
+                def_string("ClassName"),
+                def_string("GroupName"),
+                children.to_string(),
+            ],
+            children,
+        }
+    }
+
+    fn cmp(&self, other: &Self, column: Column) -> Ordering {
+        match column {
+            Column::Index => self.idx.cmp(&other.idx),
+            Column::Children => self.children.cmp(&other.children),
+            _ => {
+                let i = column as usize;
+                self.cells[i]
+                    .to_lowercase()
+                    .cmp(&other.cells[i].to_lowercase())
+            }
+        }
+    }
+}
+
+/// A flat list of the entries of one category, as an alternative to the tree
+/// for finding behaviors and queries, which aren't in it.
+#[derive(Debug)]
+pub struct EntryTable {
+    sort: Column,
+    descending: bool,
+    /// Text each column has to contain, ignoring case
+    filters: [String; 5],
+}
+
+impl Default for EntryTable {
+    fn default() -> Self {
+        Self {
+            sort: Column::Index,
+            descending: false,
+            filters: Default::default(),
+        }
+    }
+}
+
+impl EntryTable {
+    /// Draws the column titles, which sort by their column when clicked.
+    fn header_ui(&mut self, ui: &mut Ui) {
+        for column in COLUMNS {
+            let arrow = match (self.sort == column, self.descending) {
+                (true, false) => " ⬆",
+                (true, true) => " ⬇",
+                (false, _) => "",
+            };
+            if ui
+                .selectable_label(self.sort == column, format!("{}{}", column.title(), arrow))
+                .on_hover_text(tr!("table-sort-hover"))
+                .clicked()
+            {
+                if self.sort == column {
+                    self.descending = !self.descending;
+                } else {
+                    self.sort = column;
+                    self.descending = false;
+                }
+            }
+        }
+        ui.end_row();
+    }
+
+    fn row_ui(
+        ui: &mut Ui,
+        row: &Row,
+        selected: usize,
+        multi_selected: &BTreeSet<usize>,
+        clicked: &mut Option<usize>,
+    ) {
+        let highlighted = row.idx == selected || multi_selected.contains(&row.idx);
+        for cell in &row.cells {
+            if ui.selectable_label(highlighted, cell).clicked() {
+                *clicked = Some(row.idx);
+            }
+        }
+        ui.end_row();
+    }
+
+    /// Draws the entries of a category, returning one if its row was clicked.
+    /// Ctrl+click adds or removes a row from the multi-selection, and
+    /// shift+click selects every row shown between the selected one and it.
+    pub fn ui(
+        &mut self,
+        ui: &mut Ui,
+        aiprog: &AIProgram,
+        category: Category,
+        selected: usize,
+        multi_selected: &mut BTreeSet<usize>,
+    ) -> Option<usize> {
+        let start = aiprog.category_offset(category);
+        let end = match category {
+            Category::AI => aiprog.actions_offset(),
+            Category::Action => aiprog.behaviors_offset(),
+            Category::Behaviour => aiprog.queries_offset(),
+            Category::Query => aiprog.len(),
+        };
+        let filters: Vec<String> = self.filters.iter().map(|f| f.to_lowercase()).collect();
+        let mut rows: Vec<Row> = (start..end)
+            .map(|idx| Row::new(aiprog, idx))
+            .filter(|row| {
+                row.cells
+                    .iter()
+                    .zip(filters.iter())
+                    .all(|(cell, filter)| filter.is_empty() || cell.to_lowercase().contains(filter))
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            let order = a.cmp(b, self.sort);
+            if self.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+        let mut clicked = None;
+        // The header and filter rows scroll with the rest, so every row is
+        // made as high as the filters to work out which are in view
+        let row_height = grid_row_height(ui);
+        egui::ScrollArea::both().id_source("entry_table").show_rows(
+            ui,
+            row_height,
+            rows.len() + 2,
+            |ui, range| {
+                egui::Grid::new("entry_table_grid")
+                    .num_columns(COLUMNS.len())
+                    .min_row_height(row_height)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        if range.start == 0 {
+                            self.header_ui(ui);
+                        }
+                        if range.contains(&1) {
+                            for filter in self.filters.iter_mut() {
+                                ui.add(
+                                    egui::TextEdit::singleline(filter)
+                                        .hint_text(tr!("hint-filter"))
+                                        .desired_width(60.0),
+                                );
+                            }
+                            ui.end_row();
+                        }
+                        let shown = range.start.max(2) - 2..range.end.max(2) - 2;
+                        for row in &rows[shown] {
+                            Self::row_ui(ui, row, selected, multi_selected, &mut clicked);
+                        }
+                    });
+            },
+        );
+        if let Some(idx) = clicked {
+            let modifiers = ui.input().modifiers;
+            if modifiers.shift {
+                let position = |i: usize| rows.iter().position(|row| row.idx == i);
+                if let (Some(from), Some(to)) = (position(selected), position(idx)) {
+                    let (from, to) = (from.min(to), from.max(to));
+                    multi_selected.extend(rows[from..=to].iter().map(|row| row.idx));
+                }
+            } else if modifiers.command {
+                // Start a multi-selection from whatever was selected before
+                if multi_selected.is_empty() {
+                    multi_selected.insert(selected);
+                }
+                if !multi_selected.remove(&idx) {
+                    multi_selected.insert(idx);
+                }
+            } else {
+                multi_selected.clear();
+            }
+        }
+        clicked
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{app::Message, i18n::tr};
+use anyhow::Result;
+use eframe::egui::{self, Ui};
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    mpsc::Sender,
+    Arc, Mutex,
+};
+
+/// Runs work on a background thread, or straight away in the browser, which
+/// has no threads.
+pub fn spawn(work: impl FnOnce() + Send + 'static) {
+    #[cfg(not(target_arch = "wasm32"))]
+    std::thread::spawn(work);
+    #[cfg(target_arch = "wasm32")]
+    work();
+}
+
+#[derive(Debug, Default)]
+struct State {
+    done: AtomicUsize,
+    total: AtomicUsize,
+    status: Mutex<String>,
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+}
+
+/// Shared between a background task and the window listing it, for the task
+/// to report how far along it is and to find out if it was cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct Progress(Arc<State>);
+
+impl Progress {
+    /// Reports how many steps are done out of how many, along with what the
+    /// task is doing now.
+    pub fn set(&self, done: usize, total: usize, status: impl Into<String>) {
+        self.0.done.store(done, Ordering::Relaxed);
+        self.0.total.store(total, Ordering::Relaxed);
+        *self.0.status.lock().unwrap() = status.into();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Fails once the task is cancelled, for stopping at the next step with
+    /// `?`. Tasks which never check still have their result thrown away.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            anyhow::bail!("Cancelled");
+        }
+        Ok(())
+    }
+
+    /// How far along the task is, or `None` if it hasn't said.
+    fn fraction(&self) -> Option<f32> {
+        let total = self.0.total.load(Ordering::Relaxed);
+        (total > 0).then(|| self.0.done.load(Ordering::Relaxed) as f32 / total as f32)
+    }
+}
+
+#[derive(Debug)]
+struct Task {
+    name: String,
+    progress: Progress,
+}
+
+/// Work running on background threads, each sending a message back when it's
+/// done. Any number can run at once, and each can be cancelled.
+#[derive(Debug, Default)]
+pub struct Tasks(Vec<Task>);
+
+impl Tasks {
+    pub fn start<F>(&mut self, name: &str, sender: Sender<Result<Message>>, task: F)
+    where
+        F: FnOnce(&Progress) -> Result<Message> + Send + 'static,
+    {
+        let progress = Progress::default();
+        self.0.push(Task {
+            name: name.to_owned(),
+            progress: progress.clone(),
+        });
+        spawn(move || {
+            let result = task(&progress);
+            if !progress.is_cancelled() {
+                sender.send(result).ok();
+            }
+            progress.0.finished.store(true, Ordering::Relaxed);
+        });
+    }
+
+    pub fn is_busy(&self) -> bool {
+        !self.0.is_empty()
+    }
+
+    /// Forgets tasks which have finished or been cancelled.
+    pub fn clean_up(&mut self) {
+        self.0.retain(|task| {
+            !task.progress.0.finished.load(Ordering::Relaxed) && !task.progress.is_cancelled()
+        });
+    }
+
+    /// Lists the running tasks with their progress and a button to cancel
+    /// each.
+    pub fn ui(&mut self, ui: &mut Ui) {
+        for task in &self.0 {
+            ui.horizontal(|ui| {
+                ui.label(&task.name);
+                if ui.small_button(tr!("button-cancel")).clicked() {
+                    task.progress.0.cancelled.store(true, Ordering::Relaxed);
+                }
+            });
+            let status = task.progress.0.status.lock().unwrap().clone();
+            match task.progress.fraction() {
+                Some(fraction) => {
+                    let text = tr!(
+                        "task-progress",
+                        done = task.progress.0.done.load(Ordering::Relaxed),
+                        total = task.progress.0.total.load(Ordering::Relaxed),
+                        status = status
+                    );
+                    ui.add(egui::ProgressBar::new(fraction).text(text.trim_end()))
+                }
+                None => ui.add(egui::ProgressBar::new(0.99).animate(true)),
+            };
+        }
+    }
+}
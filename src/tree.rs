@@ -1,34 +1,339 @@
-use eframe::egui::{CollapsingHeader, Ui};
+use crate::{app::dialog_button, i18n::tr, task::Progress};
+use anyhow::Result;
+use eframe::egui::{
+    popup_below_widget, Button, CollapsingHeader, Color32, Id, Response, Sense, Stroke, Ui,
+};
+use plasticity_core::program::{AIProgram, Category};
+use std::collections::{BTreeSet, HashSet};
 
+#[derive(Debug, Clone)]
+pub struct Tree(
+    pub(crate) String,
+    pub(crate) usize,
+    pub(crate) Vec<Tree>,
+    pub(crate) Category,
+    /// Position of the entry, as [`AIProgram::index_label`] gives it
+    pub(crate) String,
+);
+
+/// Which nodes of the tree to show. Nodes which don't match are still shown
+/// if anything below them does.
+#[derive(Debug, Clone)]
+pub struct TreeFilter {
+    /// Text the name of a node has to contain, ignoring case
+    pub text: String,
+    pub show_ais: bool,
+    pub show_actions: bool,
+}
+
+impl Default for TreeFilter {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            show_ais: true,
+            show_actions: true,
+        }
+    }
+}
+
+impl TreeFilter {
+    pub fn is_active(&self) -> bool {
+        !self.text.is_empty() || !self.show_ais || !self.show_actions
+    }
+
+    fn matches(&self, tree: &Tree) -> bool {
+        let shown = match tree.3 {
+            Category::AI => self.show_ais,
+            Category::Action => self.show_actions,
+            _ => true,
+        };
+        shown && tree.0.to_lowercase().contains(&self.text.to_lowercase())
+    }
+}
+
+/// State of a node being dragged onto another in the tree.
 #[derive(Debug, Default, Clone)]
-pub struct Tree(pub(crate) String, pub(crate) usize, pub(crate) Vec<Tree>);
+pub struct TreeDrag {
+    /// Index of the dragged entry and of the parent it was dragged out of
+    pub source: Option<(usize, Option<usize>)>,
+    /// Index of the entry under the pointer
+    pub target: Option<usize>,
+}
+
+/// Something picked from the right-click menu of a tree node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TreeAction {
+    /// Save the entry and everything below it to a snippet file
+    ExportSubtree(usize),
+    /// Add a snippet file below the entry
+    ImportSubtree(usize),
+    /// Mark or unmark a root AI as one which is meant to have no parent
+    ToggleRoot(usize),
+    /// Put the entry back the way it was when the file was loaded
+    RevertEntry(usize),
+    /// Add the entry to the pinned list, or take it off
+    TogglePin(usize),
+}
+
+/// How the tree should be drawn, apart from what can be changed by clicking
+/// on it.
+pub struct TreeView<'a> {
+    /// Entries to pick out, such as search results
+    pub highlighted: &'a HashSet<usize>,
+    pub filter: &'a TreeFilter,
+    /// Roots the user has said are meant to have no parent
+    pub marked_roots: &'a BTreeSet<usize>,
+    /// Entries changed since the file was loaded
+    pub modified: &'a BTreeSet<usize>,
+    /// Entries in the pinned list
+    pub pinned: &'a BTreeSet<usize>,
+}
+
+/// Selection and drag state passed down through the tree while drawing it.
+struct TreeState<'a> {
+    selected_index: &'a mut usize,
+    /// Entries picked with Ctrl+click, for editing several at once
+    multi_selected: &'a mut BTreeSet<usize>,
+    drag: &'a mut TreeDrag,
+    view: &'a TreeView<'a>,
+    action: Option<TreeAction>,
+}
 
 impl Tree {
-    pub fn ui(&mut self, ui: &mut Ui, selected_index: &mut usize) {
-        self.child_ui(ui, 0, selected_index)
-    }
-
-    fn child_ui(&mut self, ui: &mut Ui, depth: usize, selected_index: &mut usize) {
-        if !self.2.is_empty() {
-            let response = CollapsingHeader::new(&self.0)
-                .default_open(depth < 1)
-                .selectable(true)
-                .selected(*selected_index == self.1)
-                .show(ui, |ui| self.children_ui(ui, depth + 1, selected_index));
-            if response.header_response.clicked() {
-                *selected_index = self.1;
+    /// Builds the tree of every root AI and the entries below it, split into
+    /// those the game can run and orphans which nothing refers to.
+    pub fn from_program(
+        aiprog: &AIProgram,
+        marked_roots: &BTreeSet<usize>,
+        progress: &Progress,
+    ) -> Result<(Vec<Tree>, Vec<Tree>)> {
+        let orphans = aiprog.orphan_roots(marked_roots);
+        let (orphans, roots): (Vec<usize>, Vec<usize>) = aiprog
+            .roots()
+            .into_iter()
+            .partition(|r| orphans.contains(r));
+        let total = roots.len() + orphans.len();
+        let mut done = 0;
+        let mut build = |roots: Vec<usize>| {
+            roots
+                .into_iter()
+                .map(|r| {
+                    progress.check()?;
+                    progress.set(done, total, tr!("task-roots"));
+                    done += 1;
+                    Self::from_entry(aiprog, r, &mut vec![])
+                })
+                .collect::<Result<Vec<Tree>>>()
+        };
+        Ok((build(roots)?, build(orphans)?))
+    }
+
+    fn from_entry(aiprog: &AIProgram, idx: usize, path: &mut Vec<usize>) -> Result<Tree> {
+        let text = AIProgram::entry_name(aiprog.item_at_index(idx))?;
+        // Stop at an entry which is already being expanded further up
+        if path.contains(&idx) {
+            return Ok(Tree(
+                tr!("tree-cycle", entry = text),
+                idx,
+                vec![],
+                aiprog.category_of(idx),
+                aiprog.index_label(idx),
+            ));
+        }
+        path.push(idx);
+        let children = aiprog
+            .children_of(idx)
+            .into_iter()
+            .map(|i| Self::from_entry(aiprog, i, path))
+            .collect::<Result<Vec<Tree>>>()?;
+        path.pop();
+        Ok(Tree(
+            text,
+            idx,
+            children,
+            aiprog.category_of(idx),
+            aiprog.index_label(idx),
+        ))
+    }
+
+    pub fn ui(
+        &mut self,
+        ui: &mut Ui,
+        selected_index: &mut usize,
+        multi_selected: &mut BTreeSet<usize>,
+        drag: &mut TreeDrag,
+        view: &TreeView<'_>,
+    ) -> Option<TreeAction> {
+        if !self.is_visible(view.filter) {
+            return None;
+        }
+        let mut state = TreeState {
+            selected_index,
+            multi_selected,
+            drag,
+            view,
+            action: None,
+        };
+        self.child_ui(ui, 0, None, &mut state);
+        state.action
+    }
+
+    fn is_visible(&self, filter: &TreeFilter) -> bool {
+        !filter.is_active() || filter.matches(self) || self.2.iter().any(|t| t.is_visible(filter))
+    }
+
+    fn child_ui(
+        &mut self,
+        ui: &mut Ui,
+        depth: usize,
+        parent: Option<usize>,
+        state: &mut TreeState<'_>,
+    ) {
+        if state.view.highlighted.contains(&self.1) {
+            ui.scope(|ui| {
+                ui.visuals_mut().override_text_color = Some(Color32::YELLOW);
+                self.node_ui(ui, depth, parent, state);
+            });
+        } else {
+            self.node_ui(ui, depth, parent, state);
+        }
+    }
+
+    fn node_ui(
+        &mut self,
+        ui: &mut Ui,
+        depth: usize,
+        parent: Option<usize>,
+        state: &mut TreeState<'_>,
+    ) {
+        let idx = self.1;
+        let selected = *state.selected_index == idx || state.multi_selected.contains(&idx);
+        let filtered = state.view.filter.is_active();
+        let has_children = self.2.iter().any(|t| t.is_visible(state.view.filter));
+        let modified = state.view.modified.contains(&idx);
+        let label = if modified {
+            format!("{} •", self.0)
+        } else {
+            self.0.clone()
+        };
+        let response = if has_children {
+            // Filtered trees start fully open, without losing what was open
+            // before the filter. The id ignores the change marker so editing
+            // an entry doesn't collapse it.
+            let header = CollapsingHeader::new(label)
+                .default_open(depth < 1 || filtered)
+                .id_source(if filtered {
+                    Id::new((&self.0, "filtered"))
+                } else {
+                    Id::new(self.0.as_str())
+                });
+            let response = header.selectable(true).selected(selected).show(ui, |ui| {
+                // Don't let a highlighted parent colour its children
+                ui.visuals_mut().override_text_color = None;
+                self.children_ui(ui, depth + 1, state)
+            });
+            response.header_response
+        } else {
+            ui.selectable_label(selected, label)
+        };
+        let response = response.on_hover_text(&self.4);
+        if response.clicked() {
+            if ui.input().modifiers.command {
+                // Start a multi-selection from whatever was selected before
+                if state.multi_selected.is_empty() {
+                    state.multi_selected.insert(*state.selected_index);
+                }
+                if !state.multi_selected.remove(&idx) {
+                    state.multi_selected.insert(idx);
+                }
+            } else {
+                state.multi_selected.clear();
             }
-        } else if ui
-            .selectable_label(*selected_index == self.1, &self.0)
-            .clicked()
-        {
-            *selected_index = self.1;
+            *state.selected_index = idx;
         }
+        Self::context_menu(ui, &response, idx, parent.is_none(), modified, state);
+        Self::handle_drag(ui, &response, idx, parent, state.drag);
     }
 
-    fn children_ui(&mut self, ui: &mut Ui, depth: usize, selected_index: &mut usize) {
-        self.2.iter_mut().for_each(|tree| {
-            tree.child_ui(ui, depth, selected_index);
+    fn context_menu(
+        ui: &mut Ui,
+        response: &Response,
+        idx: usize,
+        is_root: bool,
+        modified: bool,
+        state: &mut TreeState<'_>,
+    ) {
+        let popup_id = response.id.with("context_menu");
+        if response.secondary_clicked() {
+            ui.memory().open_popup(popup_id);
+        }
+        popup_below_widget(ui, popup_id, response, |ui| {
+            if dialog_button(ui, true, Button::new(tr!("tree-export-subtree"))).clicked() {
+                state.action = Some(TreeAction::ExportSubtree(idx));
+            }
+            if dialog_button(ui, true, Button::new(tr!("tree-import-subtree"))).clicked() {
+                state.action = Some(TreeAction::ImportSubtree(idx));
+            }
+            if is_root {
+                let marked = state.view.marked_roots.contains(&idx);
+                if ui
+                    .button(if marked {
+                        tr!("tree-unmark-root")
+                    } else {
+                        tr!("tree-mark-root")
+                    })
+                    .clicked()
+                {
+                    state.action = Some(TreeAction::ToggleRoot(idx));
+                }
+            }
+            if modified && ui.button(tr!("tree-revert")).clicked() {
+                state.action = Some(TreeAction::RevertEntry(idx));
+            }
+            let pinned = state.view.pinned.contains(&idx);
+            if ui
+                .button(if pinned {
+                    tr!("button-unpin")
+                } else {
+                    tr!("button-pin")
+                })
+                .clicked()
+            {
+                state.action = Some(TreeAction::TogglePin(idx));
+            }
         });
     }
+
+    fn handle_drag(
+        ui: &mut Ui,
+        response: &Response,
+        idx: usize,
+        parent: Option<usize>,
+        drag: &mut TreeDrag,
+    ) {
+        let response = response.interact(Sense::drag());
+        if response.dragged() && drag.source.is_none() {
+            drag.source = Some((idx, parent));
+        }
+        if let Some((source, _)) = drag.source {
+            if source != idx && ui.rect_contains_pointer(response.rect) {
+                drag.target = Some(idx);
+                ui.painter().rect_stroke(
+                    response.rect,
+                    2.0,
+                    Stroke::new(1.0, ui.visuals().selection.bg_fill),
+                );
+            }
+        }
+    }
+
+    fn children_ui(&mut self, ui: &mut Ui, depth: usize, state: &mut TreeState<'_>) {
+        let parent = Some(self.1);
+        self.2
+            .iter_mut()
+            .filter(|tree| tree.is_visible(state.view.filter))
+            .for_each(|tree| {
+                tree.child_ui(ui, depth, parent, state);
+            });
+    }
 }
@@ -0,0 +1,115 @@
+//! The browser build, which has no file system, threads, or native dialogs.
+//! Programs are opened with the browser's file picker and saved by
+//! downloading them.
+use crate::{
+    app::{App, Message},
+    i18n::tr,
+};
+use anyhow::{anyhow, Context, Result};
+use plasticity_core::program::{is_actor_pack, AIProgram};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::Sender,
+};
+use wasm_bindgen::{JsCast, JsValue};
+
+/// Id of the canvas in `index.html` the app draws to.
+const CANVAS_ID: &str = "plasticity_canvas";
+
+pub fn start() {
+    if let Err(e) = eframe::start_web(CANVAS_ID, Box::new(App::default())) {
+        web_sys::console::error_1(&e);
+    }
+}
+
+/// Stands in for `rfd::FileDialog`, which needs a native window, so the code
+/// behind dialog buttons builds. Those buttons are disabled in the browser
+/// (see `app::dialog_button`), so nothing should get here; if it does, the
+/// pick is cancelled.
+#[derive(Default)]
+pub struct FileDialog;
+
+impl FileDialog {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn add_filter(self, _name: &str, _extensions: &[&str]) -> Self {
+        self
+    }
+
+    pub fn set_file_name(self, _name: &str) -> Self {
+        self
+    }
+
+    pub fn pick_file(self) -> Option<PathBuf> {
+        None
+    }
+
+    pub fn pick_folder(self) -> Option<PathBuf> {
+        None
+    }
+
+    pub fn save_file(self) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// Opens a program picked with the browser's file picker, sending it back
+/// once it's read, as the browser only hands over files asynchronously.
+pub fn open_dialog(sender: Sender<Result<Message>>) {
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Some(handle) = rfd::AsyncFileDialog::new()
+            .add_filter("BOTW Binary AI Program", &["baiprog", "sbaiprog"])
+            .add_filter("BOTW YAML AI Program", &["yml"])
+            .add_filter("BOTW Actor Pack", &["sbactorpack", "bactorpack"])
+            .add_filter("JSON AI Program", &["json"])
+            .pick_file()
+            .await
+        {
+            let file = PathBuf::from(handle.file_name());
+            let data = handle.read().await;
+            let result = AIProgram::from_bytes(&file, &data)
+                .with_context(|| tr!("error-open", file = file.display().to_string()))
+                .map(|aiprog| Message::AIProgram(file, aiprog));
+            sender.send(result).ok();
+        }
+    });
+}
+
+/// Saves a program by downloading it, named and formatted after its file.
+/// Programs from actor packs are downloaded on their own, as the rest of the
+/// pack isn't kept.
+pub fn save(file: &Path, aiprog: &AIProgram, compress: bool) -> Result<()> {
+    let file = if is_actor_pack(file) {
+        file.with_extension(if compress { "sbaiprog" } else { "baiprog" })
+    } else {
+        file.to_owned()
+    };
+    download(&file, &aiprog.to_bytes(&file, compress)?)
+}
+
+fn download(file: &Path, data: &[u8]) -> Result<()> {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(data));
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts).map_err(js_error)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(js_error)?;
+    let link: web_sys::HtmlAnchorElement = web_sys::window()
+        .and_then(|window| window.document())
+        .context("No page to download from")?
+        .create_element("a")
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(|_| anyhow!("Failed to create download link"))?;
+    link.set_href(&url);
+    link.set_download(&file.file_name().unwrap_or_default().to_string_lossy());
+    link.click();
+    web_sys::Url::revoke_object_url(&url).map_err(js_error)
+}
+
+pub fn clipboard_text() -> Result<String> {
+    anyhow::bail!(tr!("error-clipboard-web"))
+}
+
+fn js_error(e: JsValue) -> anyhow::Error {
+    anyhow!("{:?}", e)
+}